@@ -0,0 +1,195 @@
+//! Нормализованный снимок состояния торгового аккаунта.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::exchange_type::ExchangeType;
+use crate::order::OrderResponse;
+use crate::position::Position;
+use crate::private_event::AssetBalance;
+
+/// Балансы, открытые ордера и позиции аккаунта на одной бирже
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub balances: Vec<AssetBalance>,
+    pub open_orders: Vec<OrderResponse>,
+    /// Пусто для спотовых рынков
+    pub positions: Vec<Position>,
+}
+
+impl AssetBalance {
+    /// Разобрать ответ REST запроса балансов аккаунта.
+    ///
+    /// Поддерживаются MEXC и BingX, spot и swap. Активы с нулевым балансом пропускаются.
+    pub fn list_from_json(exchange_type: &ExchangeType, json: &str) -> Result<Vec<Self>, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка парсинга балансов: {}", e))?;
+
+        if value["success"].as_bool() == Some(false)
+            || value["code"].as_i64().is_some_and(|code| code != 0)
+        {
+            return Err(format!("Ошибка API при получении балансов: {}", json));
+        }
+
+        let (balances, asset, free, locked) = match exchange_type {
+            ExchangeType::MexcSpot => (&value["balances"], "asset", "free", "locked"),
+            ExchangeType::MexcSwap => {
+                (&value["data"], "currency", "availableBalance", "frozenBalance")
+            }
+            ExchangeType::BingxSpot => (&value["data"]["balances"], "asset", "free", "locked"),
+            // BingX Swap возвращает один объект баланса маржинального актива
+            ExchangeType::BingxSwap => {
+                (&value["data"]["balance"], "asset", "availableMargin", "freezedMargin")
+            }
+            _ => {
+                return Err(crate::ExchangeError::UnsupportedOperation(format!(
+                    "разбор балансов {:?}",
+                    exchange_type
+                ))
+                .to_string());
+            }
+        };
+
+        let balances = match balances {
+            Value::Array(balances) => balances.iter().collect(),
+            Value::Object(_) => vec![balances],
+            _ => return Err(format!("В ответе отсутствуют балансы: {}", json)),
+        };
+
+        Ok(balances
+            .into_iter()
+            .filter_map(|balance| {
+                Some(AssetBalance {
+                    asset: balance[asset].as_str()?.to_string(),
+                    free: number(&balance[free]),
+                    locked: number(&balance[locked]),
+                })
+            })
+            .filter(|balance| balance.free != 0.0 || balance.locked != 0.0)
+            .collect())
+    }
+}
+
+/// Число из строки или JSON числа; 0, если поле отсутствует
+fn number(value: &Value) -> f64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_f64().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::OrderStatus;
+    use crate::position::PositionSide;
+    use crate::traits::ExchangeClient;
+    use async_trait::async_trait;
+
+    /// REST слой BingX Swap с заранее записанными ответами
+    struct MockBingxSwap;
+
+    #[async_trait]
+    impl ExchangeClient for MockBingxSwap {
+        fn exchange_type(&self) -> ExchangeType {
+            ExchangeType::BingxSwap
+        }
+
+        async fn fetch_l2_snapshot(&self, _symbol: &str) -> Result<String, String> {
+            unreachable!()
+        }
+
+        async fn get_balance(&self, _asset: &str) -> Result<String, String> {
+            unreachable!()
+        }
+
+        async fn create_limit_order(
+            &self,
+            _symbol: &str,
+            _side: &str,
+            _quantity: f64,
+            _price: f64,
+        ) -> Result<String, String> {
+            unreachable!()
+        }
+
+        async fn cancel_order(&self, _symbol: &str, _order_id: &str) -> Result<String, String> {
+            unreachable!()
+        }
+
+        async fn fetch_balances(&self) -> Result<String, String> {
+            Ok(r#"{"code":0,"msg":"","data":{"balance":{"userId":"116","asset":"USDT",
+                "balance":"1000.5","equity":"1002.1","unrealizedProfit":"1.6",
+                "availableMargin":"900.25","usedMargin":"100.25","freezedMargin":"10"}}}"#
+                .to_string())
+        }
+
+        async fn fetch_open_orders(&self, symbol: Option<&str>) -> Result<String, String> {
+            assert_eq!(symbol, None);
+            Ok(r#"{"code":0,"msg":"","data":{"orders":[
+                {"symbol":"BTC-USDT","orderId":1736012449498123456,"side":"BUY",
+                 "positionSide":"LONG","type":"LIMIT","origQty":"0.01","price":"60000",
+                 "status":"PENDING"}]}}"#
+                .to_string())
+        }
+
+        async fn fetch_positions(&self, _symbol: Option<&str>) -> Result<String, String> {
+            Ok(r#"{"code":0,"msg":"","data":[
+                {"symbol":"BTC-USDT","positionId":"12345678","positionSide":"SHORT",
+                 "isolated":true,"positionAmt":"0.02","availableAmt":"0.02",
+                 "unrealizedProfit":"-3.5","realisedProfit":"0","initialMargin":"64.2",
+                 "avgPrice":"64210.5","leverage":20}]}"#
+                .to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_account_snapshot_from_rest_responses() {
+        let snapshot = MockBingxSwap.account_snapshot().await.unwrap();
+        assert_eq!(
+            snapshot,
+            AccountSnapshot {
+                balances: vec![AssetBalance {
+                    asset: "USDT".to_string(),
+                    free: 900.25,
+                    locked: 10.0,
+                }],
+                open_orders: vec![OrderResponse {
+                    symbol: "BTC-USDT".to_string(),
+                    order_id: "1736012449498123456".to_string(),
+                    status: OrderStatus::New,
+                }],
+                positions: vec![Position {
+                    symbol: "BTC-USDT".to_string(),
+                    side: PositionSide::Short,
+                    size: 0.02,
+                    entry_price: 64210.5,
+                    leverage: 20,
+                    unrealized_pnl: -3.5,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_spot_balances() {
+        let mexc = r#"{"makerCommission":0,"canTrade":true,"accountType":"SPOT","balances":[
+            {"asset":"USDT","free":"125.5","locked":"20"},
+            {"asset":"BTC","free":"0","locked":"0"}]}"#;
+        assert_eq!(
+            AssetBalance::list_from_json(&ExchangeType::MexcSpot, mexc).unwrap(),
+            vec![AssetBalance { asset: "USDT".to_string(), free: 125.5, locked: 20.0 }]
+        );
+
+        let mexc_swap = r#"{"success":true,"code":0,"data":[
+            {"currency":"USDT","positionMargin":12.5,"frozenBalance":1.5,
+             "availableBalance":88.0,"equity":102.0}]}"#;
+        assert_eq!(
+            AssetBalance::list_from_json(&ExchangeType::MexcSwap, mexc_swap).unwrap(),
+            vec![AssetBalance { asset: "USDT".to_string(), free: 88.0, locked: 1.5 }]
+        );
+
+        let error = r#"{"code":100001,"msg":"Signature verification failed","data":{}}"#;
+        assert!(AssetBalance::list_from_json(&ExchangeType::BingxSpot, error).is_err());
+    }
+}
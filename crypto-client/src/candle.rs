@@ -0,0 +1,237 @@
+//! Свечи (OHLCV) REST API бирж в едином формате.
+
+use serde_json::Value;
+
+pub use crypto_rest_client::Candle;
+
+use crate::exchange_type::ExchangeType;
+
+/// Разобрать ответ REST эндпоинта свечей биржи.
+///
+/// Поддерживаются массивы MEXC Spot и BingX Spot
+/// (`[openTime, open, high, low, close, volume, ...]`), столбцы MEXC Swap
+/// (`data.time`, `data.open`, ... со временем в секундах) и объекты BingX Swap.
+/// Свечи возвращаются в порядке возрастания времени открытия.
+pub fn list_from_json(exchange_type: &ExchangeType, json: &str) -> Result<Vec<Candle>, String> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| format!("Ошибка парсинга свечей: {}", e))?;
+
+    if value["success"].as_bool() == Some(false)
+        || value["code"].as_i64().is_some_and(|code| code != 0)
+    {
+        return Err(format!("Ошибка API при получении свечей: {}", json));
+    }
+
+    let mut candles = match exchange_type {
+        ExchangeType::MexcSpot => from_rows(&value)?,
+        ExchangeType::BingxSpot => from_rows(&value["data"])?,
+        ExchangeType::MexcSwap => from_mexc_columns(&value["data"])?,
+        ExchangeType::BingxSwap => from_objects(&value["data"])?,
+        _ => return Err(format!("Разбор свечей не поддерживается для {:?}", exchange_type)),
+    };
+    candles.sort_by_key(|candle| candle.open_time);
+    Ok(candles)
+}
+
+fn from_rows(rows: &Value) -> Result<Vec<Candle>, String> {
+    let rows = rows.as_array().ok_or("В ответе отсутствует список свечей")?;
+    rows.iter()
+        .map(|row| {
+            let field = |index: usize| {
+                number(&row[index]).ok_or_else(|| format!("Некорректная свеча: {}", row))
+            };
+            Ok(Candle {
+                open_time: field(0)? as u64,
+                open: field(1)?,
+                high: field(2)?,
+                low: field(3)?,
+                close: field(4)?,
+                volume: field(5)?,
+            })
+        })
+        .collect()
+}
+
+fn from_mexc_columns(data: &Value) -> Result<Vec<Candle>, String> {
+    let column = |name: &str| {
+        data[name].as_array().ok_or_else(|| format!("В ответе свечей отсутствует столбец {}", name))
+    };
+    let (time, open, high, low, close, volume) = (
+        column("time")?,
+        column("open")?,
+        column("high")?,
+        column("low")?,
+        column("close")?,
+        column("vol")?,
+    );
+
+    (0..time.len())
+        .map(|i| {
+            let field = |values: &[Value]| {
+                values.get(i).and_then(number).ok_or_else(|| format!("Некорректная свеча #{}", i))
+            };
+            Ok(Candle {
+                // MEXC Swap отдаёт время в секундах
+                open_time: field(time)? as u64 * 1000,
+                open: field(open)?,
+                high: field(high)?,
+                low: field(low)?,
+                close: field(close)?,
+                volume: field(volume)?,
+            })
+        })
+        .collect()
+}
+
+fn from_objects(data: &Value) -> Result<Vec<Candle>, String> {
+    let items = data.as_array().ok_or("В ответе отсутствует список свечей")?;
+    items
+        .iter()
+        .map(|item| {
+            let field = |name: &str| {
+                number(&item[name]).ok_or_else(|| format!("Некорректная свеча: {}", item))
+            };
+            Ok(Candle {
+                open_time: field("time")? as u64,
+                open: field("open")?,
+                high: field("high")?,
+                low: field("low")?,
+                close: field("close")?,
+                volume: field("volume")?,
+            })
+        })
+        .collect()
+}
+
+/// Обозначение интервала свечей в API биржи по длительности в секундах
+pub(crate) fn interval_name(
+    exchange_type: &ExchangeType,
+    interval_secs: usize,
+) -> Result<&'static str, String> {
+    let name = match exchange_type {
+        ExchangeType::MexcSpot => match interval_secs {
+            60 => Some("1m"),
+            300 => Some("5m"),
+            900 => Some("15m"),
+            1800 => Some("30m"),
+            3600 => Some("60m"),
+            14400 => Some("4h"),
+            86400 => Some("1d"),
+            604800 => Some("1W"),
+            2592000 => Some("1M"),
+            _ => None,
+        },
+        ExchangeType::MexcSwap => match interval_secs {
+            60 => Some("Min1"),
+            300 => Some("Min5"),
+            900 => Some("Min15"),
+            1800 => Some("Min30"),
+            3600 => Some("Min60"),
+            14400 => Some("Hour4"),
+            28800 => Some("Hour8"),
+            86400 => Some("Day1"),
+            604800 => Some("Week1"),
+            2592000 => Some("Month1"),
+            _ => None,
+        },
+        ExchangeType::BingxSpot | ExchangeType::BingxSwap => match interval_secs {
+            60 => Some("1m"),
+            180 => Some("3m"),
+            300 => Some("5m"),
+            900 => Some("15m"),
+            1800 => Some("30m"),
+            3600 => Some("1h"),
+            7200 => Some("2h"),
+            14400 => Some("4h"),
+            21600 => Some("6h"),
+            28800 => Some("8h"),
+            43200 => Some("12h"),
+            86400 => Some("1d"),
+            259200 => Some("3d"),
+            604800 => Some("1w"),
+            2592000 => Some("1M"),
+            _ => None,
+        },
+        _ => None,
+    };
+    name.ok_or_else(|| {
+        format!("Интервал {} с не поддерживается для свечей {:?}", interval_secs, exchange_type)
+    })
+}
+
+/// Число из JSON, которое биржа может передать строкой
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        other => other.as_f64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected() -> Vec<Candle> {
+        vec![
+            Candle {
+                open_time: 1_700_000_000_000,
+                open: 37000.5,
+                high: 37010.0,
+                low: 36990.25,
+                close: 37005.0,
+                volume: 12.5,
+            },
+            Candle {
+                open_time: 1_700_000_060_000,
+                open: 37005.0,
+                high: 37020.0,
+                low: 37000.0,
+                close: 37015.75,
+                volume: 8.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_mexc_klines() {
+        let spot = r#"[
+            [1700000000000,"37000.5","37010","36990.25","37005","12.5",1700000059999,"462531.2"],
+            [1700000060000,"37005","37020","37000","37015.75","8",1700000119999,"296100.1"]]"#;
+        assert_eq!(list_from_json(&ExchangeType::MexcSpot, spot).unwrap(), expected());
+
+        let swap = r#"{"success":true,"code":0,"data":{
+            "time":[1700000000,1700000060],"open":[37000.5,37005],"close":[37005,37015.75],
+            "high":[37010,37020],"low":[36990.25,37000],"vol":[12.5,8],
+            "amount":[462531.2,296100.1],"realOpen":[37000.5,37005],"realClose":[37005,37015.75],
+            "realHigh":[37010,37020],"realLow":[36990.25,37000]}}"#;
+        assert_eq!(list_from_json(&ExchangeType::MexcSwap, swap).unwrap(), expected());
+    }
+
+    #[test]
+    fn test_parse_bingx_klines() {
+        // BingX Spot отдаёт свечи от новых к старым
+        let spot = r#"{"code":0,"timestamp":1700000120000,"data":[
+            [1700000060000,37005,37020,37000,37015.75,8,1700000119999,296100.1],
+            [1700000000000,37000.5,37010,36990.25,37005,12.5,1700000059999,462531.2]]}"#;
+        assert_eq!(list_from_json(&ExchangeType::BingxSpot, spot).unwrap(), expected());
+
+        let swap = r#"{"code":0,"msg":"","data":[
+            {"open":"37005","close":"37015.75","high":"37020","low":"37000","volume":"8",
+             "time":1700000060000},
+            {"open":"37000.5","close":"37005","high":"37010","low":"36990.25","volume":"12.5",
+             "time":1700000000000}]}"#;
+        assert_eq!(list_from_json(&ExchangeType::BingxSwap, swap).unwrap(), expected());
+
+        let error = r#"{"code":100204,"msg":"symbol not exist","data":{}}"#;
+        assert!(list_from_json(&ExchangeType::BingxSpot, error).is_err());
+    }
+
+    #[test]
+    fn test_kline_interval_names() {
+        assert_eq!(interval_name(&ExchangeType::MexcSpot, 3600).unwrap(), "60m");
+        assert_eq!(interval_name(&ExchangeType::MexcSwap, 3600).unwrap(), "Min60");
+        assert_eq!(interval_name(&ExchangeType::BingxSwap, 3600).unwrap(), "1h");
+        assert!(interval_name(&ExchangeType::MexcSpot, 180).is_err());
+        assert!(interval_name(&ExchangeType::BinanceSpot, 60).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Данные рынка с ценами и количествами в [`Decimal`] (фича `decimal`).
+//!
+//! Строковые значения биржи разбираются напрямую в [`Decimal`], минуя `f64`, поэтому
+//! цены с мелким шагом и количества сверяются с данными биржи без ошибок округления.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::orderbook::levels_with;
+use crate::ws_client::WsMessage;
+
+/// [`OrderBookUpdate`](crate::OrderBookUpdate) с уровнями в [`Decimal`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecimalOrderBookUpdate {
+    pub symbol: String,
+    /// Уровни покупки `(цена, количество)`; количество 0 удаляет уровень
+    pub bids: Vec<(Decimal, Decimal)>,
+    /// Уровни продажи `(цена, количество)`; количество 0 удаляет уровень
+    pub asks: Vec<(Decimal, Decimal)>,
+    /// Полный срез стакана, а не изменения
+    pub is_snapshot: bool,
+    /// Версия стакана после обновления, если биржа её передаёт
+    pub version: Option<u64>,
+    /// Первая версия, вошедшая в обновление, если биржа передаёт диапазон
+    pub from_version: Option<u64>,
+    /// Контрольная сумма стакана после применения обновления
+    pub checksum: Option<u32>,
+    /// Время события в миллисекундах Unix
+    pub time: u64,
+}
+
+impl WsMessage {
+    /// Разобрать сообщение стакана в [`DecimalOrderBookUpdate`].
+    ///
+    /// Поддерживаются те же сообщения, что и в
+    /// [`as_orderbook_update`](Self::as_orderbook_update).
+    pub fn as_orderbook_update_decimal(&self) -> Option<DecimalOrderBookUpdate> {
+        let update = self.as_orderbook_update()?;
+        let depth = self.data.get("d")?;
+        Some(DecimalOrderBookUpdate {
+            symbol: update.symbol,
+            bids: levels_with(&depth["bids"], decimal),
+            asks: levels_with(&depth["asks"], decimal),
+            is_snapshot: update.is_snapshot,
+            version: update.version,
+            from_version: update.from_version,
+            checksum: update.checksum,
+            time: update.time,
+        })
+    }
+}
+
+/// Десятичное число из строки или JSON числа без промежуточного `f64`
+fn decimal(value: &Value) -> Option<Decimal> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    Decimal::from_str_exact(&text).or_else(|_| Decimal::from_scientific(&text)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::exchange_type::ExchangeType;
+    use crate::ws_client::WsClientWrapper;
+
+    #[test]
+    fn test_decimal_levels_round_trip_exactly() {
+        let update = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSpot,
+            r#"{"c":"spot@public.increase.depth.v3.api@PEPEUSDT","d":{
+                "asks":[{"p":"0.000000123","v":"81000000.5"}],
+                "bids":[{"p":"0.000000122","v":"0"}],"r":"7"},"s":"PEPEUSDT","t":1}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap()
+        .as_orderbook_update_decimal()
+        .unwrap();
+
+        let (price, quantity) = update.asks[0];
+        assert_eq!(price, Decimal::from_str("0.000000123").unwrap());
+        assert_eq!(price.to_string(), "0.000000123");
+        assert_eq!(quantity.to_string(), "81000000.5");
+        assert_eq!(update.bids, vec![(Decimal::from_str("0.000000122").unwrap(), Decimal::ZERO)]);
+        assert_eq!(update.version, Some(7));
+
+        assert_eq!(decimal(&serde_json::json!(1e-7)), Decimal::from_str("0.0000001").ok());
+        assert_eq!(decimal(&Value::Null), None);
+    }
+}
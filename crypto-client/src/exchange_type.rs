@@ -1,5 +1,8 @@
+use crypto_market_type::MarketType;
+use serde::{Deserialize, Serialize};
+
 /// Перечисление всех поддерживаемых типов клиентов криптобирж
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ExchangeType {
     // Binance экосистема
     BinanceSpot,
@@ -37,6 +40,17 @@ pub enum ExchangeType {
     ZbgSpot,
 }
 
+/// Описание биржи для выбора в интерфейсе настройки
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExchangeInfo {
+    pub exchange_type: ExchangeType,
+    pub display_name: &'static str,
+    pub supports_websocket: bool,
+    /// Создание и отмена ордеров через REST
+    pub supports_rest_trading: bool,
+    pub market_type: MarketType,
+}
+
 impl ExchangeType {
     /// Получить строковое представление биржи
     pub fn as_str(&self) -> &'static str {
@@ -73,6 +87,65 @@ impl ExchangeType {
         }
     }
 
+    /// Название биржи для отображения пользователю
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ExchangeType::BinanceSpot => "Binance Spot",
+            ExchangeType::BinanceLinear => "Binance USDT-M Futures",
+            ExchangeType::BinanceInverse => "Binance COIN-M Futures",
+            ExchangeType::BinanceOption => "Binance Options",
+            ExchangeType::OkxSpot => "OKX",
+            ExchangeType::BybitLinear => "Bybit",
+            ExchangeType::HuobiSpot => "Huobi Spot",
+            ExchangeType::KucoinSpot => "KuCoin Spot",
+            ExchangeType::MexcSpot => "MEXC Spot",
+            ExchangeType::MexcSwap => "MEXC Futures",
+            ExchangeType::BingxSpot => "BingX Spot",
+            ExchangeType::BingxSwap => "BingX Perpetual",
+            ExchangeType::BitfinexSpot => "Bitfinex",
+            ExchangeType::BitgetSpot => "Bitget Spot",
+            ExchangeType::BitgetSwap => "Bitget Futures",
+            ExchangeType::BithumbSpot => "Bithumb",
+            ExchangeType::BitmexSwap => "BitMEX",
+            ExchangeType::BitstampSpot => "Bitstamp",
+            ExchangeType::BitzSpot => "Bit-Z Spot",
+            ExchangeType::BitzSwap => "Bit-Z Swap",
+            ExchangeType::CoinbaseProSpot => "Coinbase Pro",
+            ExchangeType::DeribitOptions => "Deribit",
+            ExchangeType::FtxSpot => "FTX",
+            ExchangeType::GateSpot => "Gate.io",
+            ExchangeType::KrakenSpot => "Kraken Spot",
+            ExchangeType::KrakenFutures => "Kraken Futures",
+            ExchangeType::ZbSpot => "ZB Spot",
+            ExchangeType::ZbSwap => "ZB Swap",
+            ExchangeType::ZbgSpot => "ZBG",
+        }
+    }
+
+    /// Основной тип рынка, с которым работает клиент биржи
+    pub fn market_type(&self) -> MarketType {
+        match self {
+            ExchangeType::BinanceLinear
+            | ExchangeType::BybitLinear
+            | ExchangeType::MexcSwap
+            | ExchangeType::BingxSwap
+            | ExchangeType::BitgetSwap
+            | ExchangeType::BitzSwap
+            | ExchangeType::ZbSwap => MarketType::LinearSwap,
+            ExchangeType::BinanceInverse | ExchangeType::BitmexSwap => MarketType::InverseSwap,
+            ExchangeType::KrakenFutures => MarketType::InverseFuture,
+            ExchangeType::BinanceOption | ExchangeType::DeribitOptions => {
+                MarketType::EuropeanOption
+            }
+            _ => MarketType::Spot,
+        }
+    }
+
+    /// Поддерживает ли REST клиент биржи создание и отмену ордеров
+    pub fn supports_rest_trading(&self) -> bool {
+        matches!(self, ExchangeType::MexcSpot | ExchangeType::BingxSpot)
+    }
+
     /// Проверить, поддерживает ли биржа WebSocket соединения
     pub fn supports_websocket(&self) -> bool {
         matches!(
@@ -96,6 +169,73 @@ impl ExchangeType {
         )
     }
 
+    /// Публичные WebSocket каналы, поддерживаемые клиентом биржи
+    /// (`"trades"`, `"orderbook"`, `"ticker"`)
+    pub fn supported_channels(&self) -> &'static [&'static str] {
+        match self {
+            // ticker MEXC Spot приходит из канала miniTicker
+            ExchangeType::MexcSpot
+            | ExchangeType::MexcSwap
+            | ExchangeType::BingxSpot
+            | ExchangeType::BingxSwap => &["trades", "orderbook", "ticker"],
+            _ => &[],
+        }
+    }
+
+    /// Известный лимит подписок на одно WebSocket соединение биржи.
+    ///
+    /// `None`, если биржа не публикует лимит.
+    pub fn max_ws_subscriptions(&self) -> Option<usize> {
+        match self {
+            // MEXC Spot: не более 30 подписок на соединение
+            ExchangeType::MexcSpot => Some(30),
+            // Binance: не более 1024 потоков на соединение
+            ExchangeType::BinanceSpot
+            | ExchangeType::BinanceLinear
+            | ExchangeType::BinanceInverse
+            | ExchangeType::BinanceOption => Some(1024),
+            _ => None,
+        }
+    }
+
+    /// Поддерживает ли биржа изменение ордера на месте, с сохранением места в очереди
+    pub fn supports_order_amend(&self) -> bool {
+        // MEXC не предоставляет эндпоинт изменения ордера, а cancelReplace BingX Spot
+        // отменяет ордер и создаёт новый
+        false
+    }
+
+    /// Команда подписки на сделки по всем символам, если биржа публикует такой поток.
+    ///
+    /// MEXC и BingX отдают сделки только по конкретной паре.
+    pub fn all_trades_command(&self) -> Option<&'static str> {
+        match self {
+            // Тема trade без символа — сделки по всем инструментам
+            ExchangeType::BitmexSwap => Some(r#"{"op":"subscribe","args":["trade"]}"#),
+            _ => None,
+        }
+    }
+
+    /// Команда подписки на периодические срезы `levels` лучших уровней стакана.
+    ///
+    /// MEXC Spot публикует срезы глубиной 5, 10 и 20 уровней; для остальных бирж и
+    /// глубин возвращает `None`.
+    pub fn depth_snapshot_command(&self, symbol: &str, levels: u32) -> Option<String> {
+        match (self, levels) {
+            (ExchangeType::MexcSpot, 5 | 10 | 20) => Some(format!(
+                r#"{{"method":"SUBSCRIPTION","params":["spot@public.limit.depth.v3.api@{}@{}"]}}"#,
+                symbol.replace('_', ""),
+                levels
+            )),
+            _ => None,
+        }
+    }
+
+    /// Есть ли у рынка биржи позиции (фьючерсы и бессрочные контракты)
+    pub fn has_positions(&self) -> bool {
+        matches!(self, ExchangeType::MexcSwap | ExchangeType::BingxSwap)
+    }
+
     /// Получить все доступные типы бирж
     pub fn all() -> Vec<ExchangeType> {
         vec![
@@ -130,4 +270,18 @@ impl ExchangeType {
             ExchangeType::ZbgSpot,
         ]
     }
+
+    /// Все биржи с описанием возможностей, например для выпадающего списка в UI
+    pub fn catalog() -> Vec<ExchangeInfo> {
+        Self::all()
+            .into_iter()
+            .map(|exchange_type| ExchangeInfo {
+                display_name: exchange_type.display_name(),
+                supports_websocket: exchange_type.supports_websocket(),
+                supports_rest_trading: exchange_type.supports_rest_trading(),
+                market_type: exchange_type.market_type(),
+                exchange_type,
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,74 @@
+//! Типизированная ставка финансирования бессрочных контрактов.
+
+use serde_json::Value;
+
+pub use crypto_rest_client::FundingRate;
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ChannelType, WsMessage};
+
+impl WsMessage {
+    /// Разобрать сообщение канала ставки финансирования в [`FundingRate`].
+    ///
+    /// Поддерживается `push.funding.rate` MEXC Swap; исходный кадр остаётся в `data`.
+    /// Для остальных сообщений возвращает `None`.
+    pub fn as_funding_rate(&self) -> Option<FundingRate> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSwap, ChannelType::FundingRate) => mexc_swap_funding(&self.data),
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// `push.funding.rate` MEXC Swap передаёт ставку в `fundingRate` (в старом формате — `rate`)
+fn mexc_swap_funding(data: &Value) -> Option<FundingRate> {
+    let funding = data.get("data")?;
+    Some(FundingRate {
+        symbol: data["symbol"].as_str().or_else(|| funding["symbol"].as_str())?.to_string(),
+        rate: number(&funding["fundingRate"]).or_else(|| number(&funding["rate"]))?,
+        next_settle_time: funding["nextSettleTime"].as_u64().unwrap_or_default(),
+        time: data["ts"].as_u64().unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    #[test]
+    fn test_parse_mexc_swap_funding_rate() {
+        let message = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSwap,
+            r#"{"channel":"push.funding.rate","symbol":"BTC_USDT",
+                "data":{"fundingRate":"0.0001","nextSettleTime":1716019200000,"symbol":"BTC_USDT"},
+                "ts":1716000000123}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap();
+
+        assert_eq!(message.channel, ChannelType::FundingRate);
+        assert_eq!(message.symbol, "BTC_USDT");
+        assert_eq!(
+            message.as_funding_rate(),
+            Some(FundingRate {
+                symbol: "BTC_USDT".to_string(),
+                rate: 0.0001,
+                next_settle_time: 1716019200000,
+                time: 1716000000123,
+            })
+        );
+        // Исходный кадр доступен без изменений
+        assert_eq!(message.data["data"]["fundingRate"], "0.0001");
+        assert_eq!(message.as_ticker(), None);
+    }
+}
@@ -7,20 +7,47 @@ pub use crypto_market_type::MarketType;
 use serde::{Deserialize, Serialize};
 
 // Модули
+pub mod account;
+pub mod candle;
 pub mod config;
+#[cfg(feature = "decimal")]
+pub mod decimal;
 pub mod exchange_type;
+pub mod funding;
+pub mod order;
+pub mod orderbook;
+pub mod position;
+pub mod private_event;
+pub mod replay;
 pub mod rest_client;
+pub mod symbol;
+pub mod ticker;
+pub mod trade;
 pub mod traits;
 pub mod ws_client;
 
 // Экспорт основных типов и структур
+pub use account::AccountSnapshot;
+pub use candle::Candle;
 pub use config::ExchangeConfig;
-pub use exchange_type::ExchangeType;
+#[cfg(feature = "decimal")]
+pub use decimal::DecimalOrderBookUpdate;
+pub use exchange_type::{ExchangeInfo, ExchangeType};
+pub use funding::FundingRate;
+pub use order::{CancelReplaceResponse, OrderResponse, OrderStatus};
+pub use orderbook::{OrderBook, OrderBookManager, OrderBookUpdate};
+pub use position::{Position, PositionSide};
+pub use private_event::{AssetBalance, FillAggregator, OrderUpdate, PrivateEvent, TradeFill};
+pub use replay::{RecordedFrame, ReplayWsClient};
 pub use rest_client::{CryptoRestClient, ExchangeClientFactory, RestClientWrapper};
+pub use symbol::{SymbolNormalizer, SymbolOverrides};
+pub use ticker::{BboQuote, ConsolidatedBbo, Ticker};
+pub use trade::{Trade, TradeSide};
 pub use traits::{ExchangeClient, SubscriptionManager, WebSocketClient};
 pub use ws_client::{
-    ChannelType, ConnectionState, CryptoWsClient, SubscriptionConfig, WsClientFactory,
-    WsClientWrapper, WsMessage,
+    ChannelType, ConnectionState, CryptoWsClient, DepthMode, MessageReader,
+    ServiceMessagePredicate, SubscriptionConfig, SubscriptionDiff, SupervisorPolicy,
+    WsClientFactory, WsClientWrapper, WsMessage,
 };
 
 /// Результат операции с биржей
@@ -35,14 +62,20 @@ pub enum ExchangeError {
     NetworkError(String),
     /// Ошибка аутентификации
     AuthError(String),
-    /// Ошибка API биржи
-    ApiError(String),
+    /// Ошибка API биржи с кодом ошибки, если его удалось извлечь из ответа
+    ApiError { code: Option<i64>, message: String },
     /// Ошибка парсинга данных
     ParseError(String),
     /// Биржа не поддерживается
     UnsupportedExchange(String),
+    /// Операция не поддерживается биржей
+    UnsupportedOperation(String),
     /// WebSocket ошибка
     WebSocketError(String),
+    /// Новая подписка превысила бы лимит подписок на одно соединение биржи
+    SubscriptionLimitReached { exchange: ExchangeType, limit: usize },
+    /// Биржа не подтвердила и не отклонила подписку за отведённое время
+    SubscriptionTimeout { exchange: ExchangeType, channel: String, symbol: String },
     /// Общая ошибка
     GeneralError(String),
 }
@@ -53,12 +86,24 @@ impl std::fmt::Display for ExchangeError {
             ExchangeError::ConfigError(msg) => write!(f, "Ошибка конфигурации: {}", msg),
             ExchangeError::NetworkError(msg) => write!(f, "Ошибка сети: {}", msg),
             ExchangeError::AuthError(msg) => write!(f, "Ошибка аутентификации: {}", msg),
-            ExchangeError::ApiError(msg) => write!(f, "Ошибка API: {}", msg),
+            ExchangeError::ApiError { code: Some(code), message } => {
+                write!(f, "Ошибка API ({}): {}", code, message)
+            }
+            ExchangeError::ApiError { code: None, message } => write!(f, "Ошибка API: {}", message),
             ExchangeError::ParseError(msg) => write!(f, "Ошибка парсинга: {}", msg),
             ExchangeError::UnsupportedExchange(msg) => {
                 write!(f, "Биржа не поддерживается: {}", msg)
             }
+            ExchangeError::UnsupportedOperation(msg) => {
+                write!(f, "Операция не поддерживается: {}", msg)
+            }
             ExchangeError::WebSocketError(msg) => write!(f, "WebSocket ошибка: {}", msg),
+            ExchangeError::SubscriptionLimitReached { exchange, limit } => {
+                write!(f, "Достигнут лимит подписок {:?}: {}", exchange, limit)
+            }
+            ExchangeError::SubscriptionTimeout { exchange, channel, symbol } => {
+                write!(f, "Нет подтверждения подписки {:?} на {} {}", exchange, channel, symbol)
+            }
             ExchangeError::GeneralError(msg) => write!(f, "Общая ошибка: {}", msg),
         }
     }
@@ -66,12 +111,158 @@ impl std::fmt::Display for ExchangeError {
 
 impl std::error::Error for ExchangeError {}
 
+/// Признаки сетевой ошибки, при которой запрос не был отправлен: не удалось
+/// разрешить адрес или установить соединение
+const REQUEST_NOT_SENT_MARKERS: &[&str] =
+    &["error trying to connect", "dns error", "failed to lookup address", "connection refused"];
+
+/// Коды MEXC/BingX, означающие недостаточный баланс или позицию
+const INSUFFICIENT_BALANCE_CODES: &[i64] = &[
+    10101,  // MEXC Spot: Insufficient balance
+    30004,  // MEXC Spot: Insufficient position
+    30005,  // MEXC Spot: Oversold
+    2005,   // MEXC Futures: Balance insufficient
+    100202, // BingX Spot: Insufficient balance
+    101204, // BingX Swap: Insufficient margin
+];
+
+/// Коды MEXC/BingX, означающие неизвестную или неподдерживаемую торговую пару
+const INVALID_SYMBOL_CODES: &[i64] = &[
+    -1121,  // MEXC Spot: Invalid symbol
+    10007,  // MEXC Spot: Symbol not support api
+    30014,  // MEXC Spot: Invalid symbol
+    1001,   // MEXC Futures: Contract does not exist
+    100204, // BingX Spot: Symbol does not exist
+    109400, // BingX Swap: Symbol does not exist
+];
+
+/// Коды MEXC/BingX, означающие, что ордер не найден
+const ORDER_NOT_FOUND_CODES: &[i64] = &[
+    -2011,  // MEXC Spot: Unknown order sent
+    -2013,  // MEXC Spot: Order does not exist
+    2009,   // MEXC Futures: Order does not exist
+    80016,  // BingX Swap: Order does not exist
+    100404, // BingX Spot: Order does not exist
+];
+
+impl ExchangeError {
+    /// Создать `ApiError` из текста ошибки, извлекая код из JSON тела ответа биржи.
+    ///
+    /// Тело может быть как самим сообщением, так и частью строки вида
+    /// `MEXC API error (400 Bad Request): {"code":10101,"msg":"Insufficient balance"}`.
+    pub fn from_api_error(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let code = message.find('{').and_then(|start| {
+            serde_json::from_str::<serde_json::Value>(&message[start..])
+                .ok()
+                .and_then(|body| body.get("code").and_then(|code| code.as_i64()))
+        });
+        ExchangeError::ApiError { code, message }
+    }
+
+    /// Код ошибки биржи, если он известен
+    pub fn api_code(&self) -> Option<i64> {
+        match self {
+            ExchangeError::ApiError { code, .. } => *code,
+            _ => None,
+        }
+    }
+
+    /// Ордер отклонён из-за недостаточного баланса или позиции
+    pub fn is_insufficient_balance(&self) -> bool {
+        self.matches_api_code(INSUFFICIENT_BALANCE_CODES)
+    }
+
+    /// Торговая пара неизвестна бирже или не поддерживается API
+    pub fn is_invalid_symbol(&self) -> bool {
+        self.matches_api_code(INVALID_SYMBOL_CODES)
+    }
+
+    /// Ордер не найден (уже исполнен, отменён или никогда не существовал)
+    pub fn is_order_not_found(&self) -> bool {
+        self.matches_api_code(ORDER_NOT_FOUND_CODES)
+    }
+
+    /// Классифицировать текст ошибки REST клиента так же, как при преобразовании
+    /// [`crypto_rest_client::Error`]: сбои отправки запроса становятся `NetworkError`,
+    /// ошибки ключей и подписи — `AuthError`, остальное разбирается как ответ биржи
+    /// через [`Self::from_api_error`].
+    pub fn classify(message: impl Into<String>) -> Self {
+        crypto_rest_client::Error(message.into()).into()
+    }
+
+    /// Временная ошибка, после которой запрос можно безопасно повторить: запрос не
+    /// был отправлен (см. [`Self::is_request_not_sent`]), 429 Too Many Requests или
+    /// 503 Service Unavailable. Отказы биржи по существу (баланс, символ и т.п.) и
+    /// потерянные ответы (см. [`Self::is_outcome_unknown`]) повторять нельзя.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ExchangeError::NetworkError(_) => self.is_request_not_sent(),
+            ExchangeError::ApiError { message, .. } => {
+                message.contains("(429") || message.contains("(503")
+            }
+            _ => false,
+        }
+    }
+
+    /// Запрос не дошёл до биржи: ошибка DNS или установки соединения
+    pub fn is_request_not_sent(&self) -> bool {
+        match self {
+            ExchangeError::NetworkError(message) => {
+                let lower = message.to_lowercase();
+                REQUEST_NOT_SENT_MARKERS.iter().any(|marker| lower.contains(marker))
+            }
+            _ => false,
+        }
+    }
+
+    /// Запрос отправлен, но ответ потерян (таймаут, разрыв соединения, 502/504 от
+    /// шлюза): биржа могла его выполнить, результат неизвестен
+    pub fn is_outcome_unknown(&self) -> bool {
+        match self {
+            ExchangeError::NetworkError(_) => !self.is_request_not_sent(),
+            ExchangeError::ApiError { message, .. } => {
+                message.contains("(502") || message.contains("(504")
+            }
+            _ => false,
+        }
+    }
+
+    /// Таймаут запроса: биржа могла успеть обработать его, результат неизвестен
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            ExchangeError::NetworkError(message) => {
+                let lower = message.to_lowercase();
+                lower.contains("timed out") || lower.contains("timeout")
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_api_code(&self, codes: &[i64]) -> bool {
+        self.api_code().map(|code| codes.contains(&code)).unwrap_or(false)
+    }
+}
+
 impl From<String> for ExchangeError {
     fn from(msg: String) -> Self {
         ExchangeError::GeneralError(msg)
     }
 }
 
+/// Ошибка REST клиента сохраняет текст, а вариант выбирается по её категории;
+/// отказ биржи разбирается через [`ExchangeError::from_api_error`]
+impl From<crypto_rest_client::Error> for ExchangeError {
+    fn from(err: crypto_rest_client::Error) -> Self {
+        match err.kind() {
+            crypto_rest_client::ErrorKind::Network => ExchangeError::NetworkError(err.0),
+            crypto_rest_client::ErrorKind::Auth => ExchangeError::AuthError(err.0),
+            crypto_rest_client::ErrorKind::Parse => ExchangeError::ParseError(err.0),
+            crypto_rest_client::ErrorKind::Api => ExchangeError::from_api_error(err.0),
+        }
+    }
+}
+
 /// Конфигурация клиента для нескольких бирж
 #[derive(Debug, Clone)]
 pub struct MultiExchangeConfig {
@@ -126,6 +317,7 @@ impl CryptoClient {
     /// Создать клиент из конфигурации
     pub async fn from_config(config: MultiExchangeConfig) -> ExchangeResult<Self> {
         let mut client = Self::new();
+        client.rest_client.set_retry_attempts(config.retry_attempts);
 
         for (exchange_type, exchange_config) in config.exchanges {
             // Добавляем REST клиент
@@ -147,6 +339,61 @@ impl CryptoClient {
         Ok(client)
     }
 
+    /// Создать клиент из конфигурации, пропуская биржи, которые не удалось добавить.
+    ///
+    /// В отличие от [`CryptoClient::from_config`], ошибка одной биржи не прерывает
+    /// создание клиента: биржа не добавляется ни в REST, ни в WebSocket клиент,
+    /// а ошибка возвращается вместе с клиентом.
+    pub async fn from_config_lenient(
+        config: MultiExchangeConfig,
+    ) -> (Self, Vec<(ExchangeType, ExchangeError)>) {
+        let mut client = Self::new();
+        client.rest_client.set_retry_attempts(config.retry_attempts);
+        let mut failures = Vec::new();
+
+        for (exchange_type, exchange_config) in config.exchanges {
+            if let Err(e) =
+                client.rest_client.add_exchange(exchange_type.clone(), exchange_config.clone())
+            {
+                log::warn!("Биржа {:?} пропущена: {}", exchange_type, e);
+                failures.push((exchange_type, ExchangeError::ConfigError(e)));
+                continue;
+            }
+
+            if exchange_type.supports_websocket()
+                && let Err(e) =
+                    client.ws_client.add_exchange(exchange_type.clone(), exchange_config).await
+            {
+                log::warn!("Биржа {:?} пропущена: {}", exchange_type, e);
+                client.rest_client.remove_exchange(&exchange_type);
+                failures.push((exchange_type, ExchangeError::ConfigError(e)));
+            }
+        }
+
+        (client, failures)
+    }
+
+    /// Проверить API ключи всех бирж REST клиента до запуска стратегии.
+    ///
+    /// Для каждой биржи возвращается `Ok(true)`, если ключи приняты, `Ok(false)`, если
+    /// отклонены, и ошибку, если проверить не удалось (например, `NetworkError`).
+    /// Биржи упорядочены по имени.
+    pub async fn validate_all_credentials(&self) -> Vec<(ExchangeType, ExchangeResult<bool>)> {
+        let mut exchanges = self.rest_client.get_available_exchanges();
+        exchanges.sort_by_key(|exchange| exchange.as_str());
+
+        let mut results = Vec::with_capacity(exchanges.len());
+        for exchange_type in exchanges {
+            let result = self
+                .rest_client
+                .validate_credentials(&exchange_type)
+                .await
+                .map_err(ExchangeError::classify);
+            results.push((exchange_type, result));
+        }
+        results
+    }
+
     /// Получить список доступных бирж для REST API
     pub fn get_rest_exchanges(&self) -> Vec<ExchangeType> {
         self.rest_client.get_available_exchanges()
@@ -217,10 +464,198 @@ mod tests {
         assert_eq!(config.retry_attempts, 5);
     }
 
+    #[tokio::test]
+    async fn test_from_config_lenient_reports_failures() {
+        let config = MultiExchangeConfig::new()
+            .add_exchange(ExchangeType::BinanceSpot, ExchangeConfig::new(None, None))
+            .add_exchange(ExchangeType::ZbgSpot, ExchangeConfig::new(None, None));
+
+        let (client, failures) = CryptoClient::from_config_lenient(config.clone()).await;
+        assert!(client.is_rest_available(&ExchangeType::BinanceSpot));
+        assert!(!client.is_rest_available(&ExchangeType::ZbgSpot));
+        assert_eq!(client.ws_client.client_count(), 1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, ExchangeType::ZbgSpot);
+        assert!(matches!(failures[0].1, ExchangeError::ConfigError(_)));
+
+        assert!(CryptoClient::from_config(config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_all_credentials() {
+        let mut client = CryptoClient::new();
+        client
+            .rest_client
+            .add_exchange(ExchangeType::MexcSpot, ExchangeConfig::new(None, None))
+            .unwrap();
+        client
+            .rest_client
+            .add_exchange(
+                ExchangeType::BingxSpot,
+                ExchangeConfig::new(Some("key".to_string()), Some("secret".to_string()))
+                    .with_proxy(Some("http://127.0.0.1:9".to_string())),
+            )
+            .unwrap();
+
+        let results = client.validate_all_credentials().await;
+        assert_eq!(results.len(), 2);
+        // Ключи не заданы — отказ, а не ошибка
+        assert_eq!(results[1].0, ExchangeType::MexcSpot);
+        assert!(matches!(results[1].1, Ok(false)));
+        // Недоступный прокси — сетевая ошибка
+        assert_eq!(results[0].0, ExchangeType::BingxSpot);
+        assert!(matches!(results[0].1, Err(ExchangeError::NetworkError(_))), "{:?}", results[0].1);
+    }
+
     #[test]
     fn test_crypto_client_creation() {
         let client = CryptoClient::new();
         assert_eq!(client.rest_client.exchange_count(), 0);
         assert_eq!(client.ws_client.client_count(), 0);
     }
+
+    #[test]
+    fn test_api_error_predicates() {
+        let insufficient = ExchangeError::from_api_error(
+            r#"MEXC API error (400 Bad Request): {"code":30004,"msg":"Insufficient position"}"#,
+        );
+        assert_eq!(insufficient.api_code(), Some(30004));
+        assert!(insufficient.is_insufficient_balance());
+        assert!(!insufficient.is_order_not_found());
+
+        let invalid_symbol = ExchangeError::from_api_error(
+            r#"MEXC API error (400 Bad Request): {"code":-1121,"msg":"Invalid symbol."}"#,
+        );
+        assert!(invalid_symbol.is_invalid_symbol());
+
+        let not_found = ExchangeError::from_api_error(
+            r#"MEXC API error (400 Bad Request): {"code":-2011,"msg":"Unknown order sent."}"#,
+        );
+        assert!(not_found.is_order_not_found());
+
+        let bingx = ExchangeError::from_api_error(
+            r#"{"code":100202,"msg":"Insufficient balance","data":{}}"#,
+        );
+        assert!(bingx.is_insufficient_balance());
+
+        let without_body = ExchangeError::from_api_error("API Error: 502 Bad Gateway");
+        assert_eq!(without_body.api_code(), None);
+        assert!(!without_body.is_insufficient_balance());
+        assert!(!ExchangeError::NetworkError("timeout".to_string()).is_order_not_found());
+    }
+
+    #[test]
+    fn test_error_retry_classification() {
+        let refused = ExchangeError::classify(
+            "Request error: error sending request for url (https://api.mexc.com/api/v3/order): \
+             error trying to connect: tcp connect error: Connection refused",
+        );
+        assert!(refused.is_retryable());
+        assert!(refused.is_request_not_sent());
+        assert!(!refused.is_outcome_unknown());
+        assert!(!refused.is_timeout());
+
+        let timeout = ExchangeError::classify(
+            "Request error: error sending request for url (https://api.mexc.com/api/v3/order): \
+             operation timed out",
+        );
+        assert!(!timeout.is_retryable());
+        assert!(timeout.is_outcome_unknown());
+        assert!(timeout.is_timeout());
+
+        // Соединение оборвалось после отправки: биржа могла принять запрос
+        let reset = ExchangeError::classify(
+            "Request error: error sending request for url (https://api.mexc.com/api/v3/order): \
+             connection closed before message completed",
+        );
+        assert!(!reset.is_retryable());
+        assert!(reset.is_outcome_unknown());
+
+        let gateway = ExchangeError::classify("MEXC API error (504 Gateway Timeout): ");
+        assert!(!gateway.is_retryable());
+        assert!(gateway.is_outcome_unknown());
+
+        let reject = ExchangeError::classify(
+            r#"MEXC API error (400 Bad Request): {"code":10101,"msg":"Insufficient balance"}"#,
+        );
+        assert!(reject.is_insufficient_balance());
+        assert!(!reject.is_retryable());
+
+        assert!(!reject.is_outcome_unknown());
+
+        let rate_limited = ExchangeError::classify("MEXC API error (429 Too Many Requests): {}");
+        assert!(rate_limited.is_retryable());
+        assert!(!rate_limited.is_outcome_unknown());
+    }
+
+    #[test]
+    fn test_rest_error_conversion() {
+        fn convert(message: &str) -> ExchangeError {
+            crypto_rest_client::Error(message.to_string()).into()
+        }
+
+        let network = "Request error: error sending request for url \
+            (https://open-api.bingx.com/openApi/spot/v1/trade/order): operation timed out";
+        assert!(matches!(convert(network), ExchangeError::NetworkError(m) if m == network));
+
+        let auth = r#"MEXC API error (400 Bad Request): {"code":700002,"msg":"Signature for this request is not valid."}"#;
+        assert!(matches!(convert(auth), ExchangeError::AuthError(m) if m == auth));
+        let missing_keys = "API key and secret are required";
+        assert!(matches!(convert(missing_keys), ExchangeError::AuthError(_)));
+
+        let parse: crypto_rest_client::Error =
+            serde_json::from_str::<serde_json::Value>("{").unwrap_err().into();
+        assert!(matches!(ExchangeError::from(parse), ExchangeError::ParseError(m) if m.contains("EOF")));
+
+        let api = r#"{"code":100202,"msg":"Insufficient balance","data":{}}"#;
+        let error = convert(api);
+        assert_eq!(error.api_code(), Some(100202));
+        assert!(error.is_insufficient_balance());
+        assert!(matches!(error, ExchangeError::ApiError { message, .. } if message == api));
+    }
+
+    #[test]
+    fn test_order_status_parsing() {
+        let cases = [
+            ("NEW", OrderStatus::New),
+            ("PARTIALLY_FILLED", OrderStatus::PartiallyFilled),
+            ("FILLED", OrderStatus::Filled),
+            ("CANCELED", OrderStatus::Canceled),
+            ("CANCELLED", OrderStatus::Canceled),
+            ("PARTIALLY_CANCELED", OrderStatus::PartiallyCanceled),
+            ("REJECTED", OrderStatus::Rejected),
+            ("EXPIRED", OrderStatus::Expired),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(raw.parse::<OrderStatus>().unwrap(), expected);
+            let deserialized: OrderStatus = serde_json::from_str(&format!("\"{}\"", raw)).unwrap();
+            assert_eq!(deserialized, expected);
+        }
+
+        let unknown: OrderStatus = "PENDING_CANCEL_V2".parse().unwrap();
+        assert_eq!(unknown, OrderStatus::Unknown("PENDING_CANCEL_V2".to_string()));
+        assert_eq!(unknown.as_str(), "PENDING_CANCEL_V2");
+        assert!(!unknown.is_final());
+        assert!(OrderStatus::Filled.is_final());
+    }
+
+    #[test]
+    fn test_order_response_from_json() {
+        let mexc = OrderResponse::from_json(
+            r#"{"symbol":"BTCUSDT","orderId":"C02__443776","status":"PARTIALLY_FILLED"}"#,
+        )
+        .unwrap();
+        assert_eq!(mexc.symbol, "BTCUSDT");
+        assert_eq!(mexc.order_id, "C02__443776");
+        assert_eq!(mexc.status, OrderStatus::PartiallyFilled);
+
+        let bingx = OrderResponse::from_json(
+            r#"{"code":0,"data":{"order":{"orderId":1736011869,"status":"CANCELLED"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(bingx.order_id, "1736011869");
+        assert_eq!(bingx.status, OrderStatus::Canceled);
+
+        assert!(OrderResponse::from_json(r#"{"code":0,"data":{}}"#).is_err());
+    }
 }
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::convert::Infallible;
+use std::str::FromStr;
+
+/// Статус ордера на бирже
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OrderStatus {
+    /// Ордер принят и ожидает исполнения
+    New,
+    /// Ордер исполнен частично
+    PartiallyFilled,
+    /// Ордер исполнен полностью
+    Filled,
+    /// Ордер отменён
+    Canceled,
+    /// Ордер частично исполнен, остаток отменён
+    PartiallyCanceled,
+    /// Ордер отклонён биржей
+    Rejected,
+    /// Срок действия ордера истёк
+    Expired,
+    /// Статус, неизвестный клиенту (сохраняется исходная строка биржи)
+    Unknown(String),
+}
+
+impl OrderStatus {
+    /// Получить строковое представление статуса в формате биржи
+    pub fn as_str(&self) -> &str {
+        match self {
+            OrderStatus::New => "NEW",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Canceled => "CANCELED",
+            OrderStatus::PartiallyCanceled => "PARTIALLY_CANCELED",
+            OrderStatus::Rejected => "REJECTED",
+            OrderStatus::Expired => "EXPIRED",
+            OrderStatus::Unknown(status) => status,
+        }
+    }
+
+    /// Ордер больше не может быть исполнен
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled
+                | OrderStatus::Canceled
+                | OrderStatus::PartiallyCanceled
+                | OrderStatus::Rejected
+                | OrderStatus::Expired
+        )
+    }
+}
+
+impl From<&str> for OrderStatus {
+    fn from(s: &str) -> Self {
+        match s.trim().to_uppercase().as_str() {
+            "NEW" | "PENDING" | "LIVE" => OrderStatus::New,
+            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
+            "FILLED" => OrderStatus::Filled,
+            "CANCELED" | "CANCELLED" => OrderStatus::Canceled,
+            "PARTIALLY_CANCELED" => OrderStatus::PartiallyCanceled,
+            "REJECTED" | "FAILED" => OrderStatus::Rejected,
+            "EXPIRED" => OrderStatus::Expired,
+            _ => OrderStatus::Unknown(s.to_string()),
+        }
+    }
+}
+
+impl FromStr for OrderStatus {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(OrderStatus::from(s))
+    }
+}
+
+impl std::fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for OrderStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for OrderStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let status = String::deserialize(deserializer)?;
+        Ok(OrderStatus::from(status.as_str()))
+    }
+}
+
+/// Ответ биржи с информацией об ордере
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderResponse {
+    /// Торговая пара
+    pub symbol: String,
+    /// Идентификатор ордера на бирже
+    pub order_id: String,
+    /// Статус ордера
+    pub status: OrderStatus,
+}
+
+impl OrderResponse {
+    /// Разобрать JSON ответ биржи об ордере.
+    ///
+    /// Поддерживает плоский формат MEXC (`{"symbol":..,"orderId":..,"status":..}`)
+    /// и формат BingX, где ордер вложен в `data` или `data.order`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка парсинга ордера: {}", e))?;
+
+        let order = [&value["data"]["order"], &value["data"], &value]
+            .into_iter()
+            .find(|candidate| candidate.get("orderId").is_some())
+            .ok_or_else(|| format!("В ответе отсутствует orderId: {}", json))?;
+
+        Ok(Self::from_value(order))
+    }
+
+    fn from_value(order: &Value) -> Self {
+        // orderId приходит как строкой, так и числом
+        let order_id = match &order["orderId"] {
+            Value::String(id) => id.clone(),
+            id => id.to_string(),
+        };
+
+        let status = order["status"]
+            .as_str()
+            .map(OrderStatus::from)
+            .unwrap_or_else(|| OrderStatus::Unknown(String::new()));
+
+        OrderResponse {
+            symbol: order["symbol"].as_str().unwrap_or_default().to_string(),
+            order_id,
+            status,
+        }
+    }
+
+    /// Разобрать список открытых ордеров.
+    ///
+    /// Поддерживает массив MEXC Spot, `data` MEXC Swap с числовым `state`
+    /// и `data.orders` BingX.
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, String> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| format!("Ошибка парсинга списка ордеров: {}", e))?;
+
+        let orders = [&value, &value["data"], &value["data"]["orders"]]
+            .into_iter()
+            .find_map(Value::as_array)
+            .ok_or_else(|| format!("В ответе отсутствует список ордеров: {}", json))?;
+
+        Ok(orders
+            .iter()
+            .map(|order| {
+                let mut response = Self::from_value(order);
+                // MEXC Swap: 1 — в обработке, 2 — не исполнен, 3 — исполнен, 4 — отменён
+                if let Some(state) = order["state"].as_i64() {
+                    response.status = match state {
+                        1 | 2 => OrderStatus::New,
+                        3 => OrderStatus::Filled,
+                        4 => OrderStatus::Canceled,
+                        state => OrderStatus::Unknown(state.to_string()),
+                    };
+                }
+                response
+            })
+            .collect())
+    }
+}
+
+/// Результат атомарной отмены ордера с выставлением нового (cancel-replace)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CancelReplaceResponse {
+    /// Отменённый ордер
+    pub cancelled: OrderResponse,
+    /// Новый ордер
+    pub new_order: OrderResponse,
+}
+
+impl CancelReplaceResponse {
+    /// Разобрать ответ BingX `cancelReplace` с `orderCancelResponse` и `orderOpenResponse`.
+    ///
+    /// Если биржа вернула ошибку или новый ордер не был создан, возвращается ошибка
+    /// с исходным ответом.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| format!("Ошибка парсинга ответа cancel-replace: {}", e))?;
+
+        if value["code"].as_i64().is_some_and(|code| code != 0) {
+            return Err(format!("Cancel-replace отклонён биржей: {}", json));
+        }
+
+        let data = &value["data"];
+        let order = |key: &str| {
+            if data[key].is_object() {
+                OrderResponse::from_json(&data[key].to_string())
+            } else {
+                Err(format!("В ответе cancel-replace отсутствует {}: {}", key, json))
+            }
+        };
+
+        Ok(CancelReplaceResponse {
+            cancelled: order("orderCancelResponse")?,
+            new_order: order("orderOpenResponse")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bingx_cancel_replace_response() {
+        let json = r#"{"code":0,"msg":"","debugMsg":"","data":{
+            "cancelResult":{"code":0,"msg":"","result":true},
+            "openResult":{"code":0,"msg":"","result":true},
+            "orderOpenResponse":{"symbol":"BTC-USDT","orderId":1802040102451200001,
+                "transactTime":1700000000123,"price":"61000","origQty":"0.002",
+                "executedQty":"0","status":"PENDING","type":"LIMIT","side":"SELL"},
+            "orderCancelResponse":{"symbol":"BTC-USDT","orderId":1802040102451200000,
+                "price":"60000","origQty":"0.002","executedQty":"0",
+                "status":"CANCELED","type":"LIMIT","side":"BUY"}}}"#;
+
+        let response = CancelReplaceResponse::from_json(json).unwrap();
+        assert_eq!(response.cancelled.order_id, "1802040102451200000");
+        assert_eq!(response.cancelled.status, OrderStatus::Canceled);
+        assert_eq!(response.new_order.order_id, "1802040102451200001");
+        assert_eq!(response.new_order.status, OrderStatus::New);
+        assert_eq!(response.new_order.symbol, "BTC-USDT");
+
+        let rejected = r#"{"code":100400,"msg":"order not exist","data":{}}"#;
+        assert!(CancelReplaceResponse::from_json(rejected).is_err());
+        let not_opened = r#"{"code":0,"data":{"orderCancelResponse":{"orderId":1}}}"#;
+        assert!(CancelReplaceResponse::from_json(not_opened).unwrap_err().contains("orderOpen"));
+    }
+
+    #[test]
+    fn test_parse_open_orders() {
+        let mexc_spot = r#"[{"symbol":"BTCUSDT","orderId":"C02__443776347957968896",
+            "price":"60000","origQty":"0.001","status":"NEW","side":"BUY"}]"#;
+        let orders = OrderResponse::list_from_json(mexc_spot).unwrap();
+        assert_eq!(orders[0].order_id, "C02__443776347957968896");
+        assert_eq!(orders[0].status, OrderStatus::New);
+
+        let mexc_swap = r#"{"success":true,"code":0,"data":[
+            {"orderId":"739113577038255616","symbol":"BTC_USDT","price":60000,"vol":2,
+             "side":1,"orderType":1,"state":2}]}"#;
+        let orders = OrderResponse::list_from_json(mexc_swap).unwrap();
+        assert_eq!(orders[0].symbol, "BTC_USDT");
+        assert_eq!(orders[0].status, OrderStatus::New);
+
+        let empty = r#"{"code":0,"data":{"orders":[]}}"#;
+        assert!(OrderResponse::list_from_json(empty).unwrap().is_empty());
+        assert!(OrderResponse::list_from_json(r#"{"code":100001,"msg":"x"}"#).is_err());
+    }
+}
@@ -0,0 +1,622 @@
+//! Локальный стакан, собираемый из снимков и инкрементальных обновлений.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ChannelType, WsMessage};
+
+/// Количество лучших уровней каждой стороны, участвующих в контрольной сумме
+const CHECKSUM_DEPTH: usize = 25;
+
+/// Максимум обновлений пары, накапливаемых в ожидании снимка
+const MAX_BUFFERED_UPDATES: usize = 1000;
+
+/// Обновление стакана: полный срез или изменения уровней
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookUpdate {
+    pub symbol: String,
+    /// Уровни покупки `(цена, количество)`; количество 0 удаляет уровень
+    pub bids: Vec<(f64, f64)>,
+    /// Уровни продажи `(цена, количество)`; количество 0 удаляет уровень
+    pub asks: Vec<(f64, f64)>,
+    /// Полный срез стакана, а не изменения
+    pub is_snapshot: bool,
+    /// Версия стакана после обновления, если биржа её передаёт
+    pub version: Option<u64>,
+    /// Первая версия, вошедшая в обновление, если биржа передаёт диапазон
+    /// (агрегированная глубина MEXC); по умолчанию обновление покрывает одну версию
+    pub from_version: Option<u64>,
+    /// Контрольная сумма стакана после применения обновления
+    pub checksum: Option<u32>,
+    /// Время события в миллисекундах Unix
+    pub time: u64,
+}
+
+impl WsMessage {
+    /// Разобрать сообщение стакана в [`OrderBookUpdate`].
+    ///
+    /// Поддерживаются `increase.depth` и `limit.depth` MEXC Spot. Для остальных
+    /// сообщений возвращает `None`.
+    pub fn as_orderbook_update(&self) -> Option<OrderBookUpdate> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSpot, ChannelType::Orderbook) => {
+                mexc_spot_depth(&self.symbol, &self.data, false)
+            }
+            (ExchangeType::MexcSpot, ChannelType::OrderbookSnapshot) => {
+                mexc_spot_depth(&self.symbol, &self.data, true)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// Уровни стакана в двух формах: объекты WS MEXC Spot `[{"p": "50000.00", "v": "1.2"}]`
+/// и массивы REST `[["50000.00", "1.2"]]` (MEXC Swap добавляет третьим элементом
+/// число ордеров)
+fn levels(levels: &Value) -> Vec<(f64, f64)> {
+    levels_with(levels, number)
+}
+
+/// Уровни стакана с разбором цены и количества через `parse`
+pub(crate) fn levels_with<T>(levels: &Value, parse: impl Fn(&Value) -> Option<T>) -> Vec<(T, T)> {
+    levels
+        .as_array()
+        .map(|levels| {
+            levels
+                .iter()
+                .filter_map(|level| match level {
+                    Value::Array(level) => Some((parse(level.first()?)?, parse(level.get(1)?)?)),
+                    level => Some((parse(&level["p"])?, parse(&level["v"])?)),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn mexc_spot_depth(symbol: &str, data: &Value, is_snapshot: bool) -> Option<OrderBookUpdate> {
+    let depth = data.get("d")?;
+    Some(OrderBookUpdate {
+        symbol: symbol.to_string(),
+        bids: levels(&depth["bids"]),
+        asks: levels(&depth["asks"]),
+        is_snapshot,
+        version: number(&depth["r"])
+            .or_else(|| number(&depth["toVersion"]))
+            .map(|version| version as u64),
+        from_version: number(&depth["fromVersion"]).map(|version| version as u64),
+        checksum: number(&depth["checksum"]).map(|checksum| checksum as i64 as u32),
+        time: data["t"].as_u64().unwrap_or_default(),
+    })
+}
+
+impl OrderBookUpdate {
+    /// Разобрать ответ REST эндпоинта стакана биржи в снимок (`is_snapshot = true`).
+    ///
+    /// Поддерживаются MEXC Spot (`bids`/`asks` в корне), MEXC Swap и BingX
+    /// (`data.bids`/`data.asks`). `symbol` сохраняется как передан. Порядок уровней
+    /// в ответах бирж не гарантирован, поэтому они упорядочиваются от лучшей цены,
+    /// а уровни с нулевым количеством отбрасываются.
+    pub fn from_rest_json(
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        json: &str,
+    ) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка парсинга стакана: {}", e))?;
+
+        if value["success"].as_bool() == Some(false)
+            || value["code"].as_i64().is_some_and(|code| code != 0)
+        {
+            return Err(format!("Ошибка API при получении стакана: {}", json));
+        }
+
+        let (depth, version, time) = match exchange_type {
+            ExchangeType::MexcSpot => (&value, &value["lastUpdateId"], &value["timestamp"]),
+            ExchangeType::MexcSwap => {
+                (&value["data"], &value["data"]["version"], &value["data"]["timestamp"])
+            }
+            ExchangeType::BingxSpot => (&value["data"], &Value::Null, &value["data"]["ts"]),
+            ExchangeType::BingxSwap => (&value["data"], &Value::Null, &value["data"]["T"]),
+            _ => return Err(format!("Разбор стакана не поддерживается для {:?}", exchange_type)),
+        };
+        if !depth["bids"].is_array() || !depth["asks"].is_array() {
+            return Err(format!("В ответе отсутствуют уровни стакана: {}", json));
+        }
+
+        let mut bids = levels(&depth["bids"]);
+        let mut asks = levels(&depth["asks"]);
+        bids.retain(|level| level.1 > 0.0);
+        asks.retain(|level| level.1 > 0.0);
+        bids.sort_by(|a, b| b.0.total_cmp(&a.0));
+        asks.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Self {
+            symbol: symbol.to_string(),
+            bids,
+            asks,
+            is_snapshot: true,
+            version: number(version).map(|version| version as u64),
+            from_version: None,
+            checksum: None,
+            time: number(time).map(|time| time as u64).unwrap_or_default(),
+        })
+    }
+}
+
+/// Стакан одной торговой пары
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderBook {
+    /// Уровни покупки по убыванию цены
+    pub bids: Vec<(f64, f64)>,
+    /// Уровни продажи по возрастанию цены
+    pub asks: Vec<(f64, f64)>,
+    pub version: Option<u64>,
+}
+
+impl OrderBook {
+    /// Лучшие цены покупки и продажи
+    pub fn best_bid_ask(&self) -> (Option<f64>, Option<f64>) {
+        (self.bids.first().map(|level| level.0), self.asks.first().map(|level| level.0))
+    }
+
+    /// Контрольная сумма CRC32 лучших 25 уровней.
+    ///
+    /// Уровни чередуются `bid:qty:ask:qty`, цены и количества записываются в
+    /// кратчайшем десятичном виде (`50000`, а не `50000.00`).
+    pub fn checksum(&self) -> u32 {
+        let mut parts = Vec::with_capacity(CHECKSUM_DEPTH * 4);
+        for i in 0..CHECKSUM_DEPTH {
+            for side in [&self.bids, &self.asks] {
+                if let Some((price, quantity)) = side.get(i) {
+                    parts.push(format!("{}:{}", price, quantity));
+                }
+            }
+        }
+        crc32fast::hash(parts.join(":").as_bytes())
+    }
+
+    fn apply(&mut self, update: &OrderBookUpdate) {
+        if update.is_snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+        for &(price, quantity) in &update.bids {
+            Self::set_level(&mut self.bids, price, quantity, |a, b| b.total_cmp(a));
+        }
+        for &(price, quantity) in &update.asks {
+            Self::set_level(&mut self.asks, price, quantity, f64::total_cmp);
+        }
+        if update.version.is_some() {
+            self.version = update.version;
+        }
+    }
+
+    fn set_level(
+        side: &mut Vec<(f64, f64)>,
+        price: f64,
+        quantity: f64,
+        order: impl Fn(&f64, &f64) -> Ordering,
+    ) {
+        match side.binary_search_by(|(level, _)| order(level, &price)) {
+            Ok(index) if quantity == 0.0 => {
+                side.remove(index);
+            }
+            Ok(index) => side[index].1 = quantity,
+            Err(index) if quantity != 0.0 => side.insert(index, (price, quantity)),
+            Err(_) => {}
+        }
+    }
+}
+
+/// Локальные стаканы нескольких пар.
+///
+/// При расхождении контрольной суммы или пропуске версии стакан пары
+/// сбрасывается и ожидает новый снимок: обновления до него накапливаются, а
+/// пара попадает в [`pending_snapshots`](Self::pending_snapshots). После снимка
+/// накопленные обновления не новее снимка отбрасываются, остальные применяются.
+#[derive(Debug, Default)]
+pub struct OrderBookManager {
+    books: HashMap<String, OrderBook>,
+    pending_snapshots: HashSet<String>,
+    /// Обновления, пришедшие до снимка
+    buffered: HashMap<String, Vec<OrderBookUpdate>>,
+}
+
+impl OrderBookManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Стакан пары, если он синхронизирован
+    pub fn book(&self, symbol: &str) -> Option<&OrderBook> {
+        self.books.get(symbol)
+    }
+
+    /// Применить снимок или обновление стакана.
+    ///
+    /// Возвращает `false`, если обновление отложено в ожидании снимка, устарело,
+    /// следует за пропущенной версией или контрольная сумма после него не сошлась.
+    pub fn apply(&mut self, update: &OrderBookUpdate) -> bool {
+        if update.is_snapshot {
+            return self.apply_snapshot(update);
+        }
+        if self.pending_snapshots.contains(&update.symbol) {
+            trace!("OrderBookManager: обновление {} отложено до снимка", update.symbol);
+            self.buffer(update);
+            return false;
+        }
+
+        let current = self.books.get(&update.symbol).and_then(|book| book.version);
+        match (current, update.version) {
+            (Some(current), Some(version)) if version <= current => {
+                trace!(
+                    "OrderBookManager: устаревшее обновление {} (версия {}, стакан {})",
+                    update.symbol, version, current
+                );
+                return false;
+            }
+            // Обновление с диапазоном версий непрерывно, если начинается не позже
+            // следующей за текущей версии
+            (Some(current), Some(version))
+                if update.from_version.unwrap_or(version) > current + 1 =>
+            {
+                warn!(
+                    "OrderBookManager: пропуск версий стакана {} ({} -> {}), запрашиваем снимок",
+                    update.symbol,
+                    current,
+                    update.from_version.unwrap_or(version)
+                );
+                self.books.remove(&update.symbol);
+                self.pending_snapshots.insert(update.symbol.clone());
+                self.buffer(update);
+                return false;
+            }
+            _ => {}
+        }
+
+        self.books.entry(update.symbol.clone()).or_default().apply(update);
+        match update.checksum {
+            Some(checksum) => self.verify_checksum(&update.symbol, checksum),
+            None => true,
+        }
+    }
+
+    /// Заменить стакан снимком и применить накопленные после него обновления
+    fn apply_snapshot(&mut self, snapshot: &OrderBookUpdate) -> bool {
+        let symbol = &snapshot.symbol;
+        self.pending_snapshots.remove(symbol);
+        let buffered = self.buffered.remove(symbol).unwrap_or_default();
+
+        let mut book = OrderBook::default();
+        book.apply(snapshot);
+        self.books.insert(symbol.clone(), book);
+        if let Some(checksum) = snapshot.checksum
+            && !self.verify_checksum(symbol, checksum)
+        {
+            return false;
+        }
+
+        // Обновления, которые уже вошли в снимок, отбрасываются
+        let newer = buffered.iter().filter(|update| match (update.version, snapshot.version) {
+            (Some(version), Some(snapshot_version)) => version > snapshot_version,
+            _ => update.time >= snapshot.time,
+        });
+        for update in newer {
+            self.apply(update);
+        }
+        !self.needs_snapshot(symbol)
+    }
+
+    fn buffer(&mut self, update: &OrderBookUpdate) {
+        let buffered = self.buffered.entry(update.symbol.clone()).or_default();
+        if buffered.len() >= MAX_BUFFERED_UPDATES {
+            buffered.remove(0);
+        }
+        buffered.push(update.clone());
+    }
+
+    /// Принудительно пересинхронизировать стакан пары по REST снимку.
+    ///
+    /// Стакан сбрасывается, и до применения снимка обновления накапливаются.
+    /// `snapshot` обычно [`CryptoRestClient::fetch_orderbook`](crate::CryptoRestClient::fetch_orderbook);
+    /// его `symbol` заменяется на `symbol`, так как формат символа REST может
+    /// отличаться от WebSocket. При ошибке загрузки пара остаётся в
+    /// [`pending_snapshots`](Self::pending_snapshots).
+    pub async fn resync(
+        &mut self,
+        symbol: &str,
+        snapshot: impl Future<Output = Result<OrderBookUpdate, String>>,
+    ) -> Result<bool, String> {
+        self.request_snapshot(symbol);
+
+        let mut snapshot = snapshot.await?;
+        snapshot.symbol = symbol.to_string();
+        snapshot.is_snapshot = true;
+        info!("OrderBookManager: стакан {} пересинхронизирован по снимку", symbol);
+        Ok(self.apply(&snapshot))
+    }
+
+    /// Сбросить стакан пары и накапливать её обновления до снимка
+    pub(crate) fn request_snapshot(&mut self, symbol: &str) {
+        self.books.remove(symbol);
+        self.pending_snapshots.insert(symbol.to_string());
+    }
+
+    /// Сверить контрольную сумму стакана пары.
+    ///
+    /// При расхождении стакан сбрасывается и запрашивается повторный снимок.
+    pub fn verify_checksum(&mut self, symbol: &str, checksum: u32) -> bool {
+        let Some(book) = self.books.get(symbol) else {
+            return false;
+        };
+        let actual = book.checksum();
+        if actual == checksum {
+            return true;
+        }
+
+        warn!(
+            "OrderBookManager: контрольная сумма стакана {} не совпала (ожидалась {}, получена {}), запрашиваем снимок",
+            symbol, checksum, actual
+        );
+        self.books.remove(symbol);
+        self.pending_snapshots.insert(symbol.to_string());
+        false
+    }
+
+    /// Ожидает ли пара повторного снимка
+    pub fn needs_snapshot(&self, symbol: &str) -> bool {
+        self.pending_snapshots.contains(symbol)
+    }
+
+    /// Пары, для которых нужно загрузить снимок (например, через
+    /// [`CryptoRestClient::fetch_l2_snapshot`](crate::CryptoRestClient::fetch_l2_snapshot)).
+    ///
+    /// Пары остаются в списке до применения снимка.
+    pub fn pending_snapshots(&self) -> Vec<String> {
+        let mut symbols: Vec<_> = self.pending_snapshots.iter().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    fn parse(raw: &str) -> OrderBookUpdate {
+        WsClientWrapper::parse_message_with_predicates(ExchangeType::MexcSpot, raw, &[])
+            .into_message()
+            .unwrap()
+            .as_orderbook_update()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_rest_depth_snapshots() {
+        let spot = OrderBookUpdate::from_rest_json(
+            &ExchangeType::MexcSpot,
+            "BTCUSDT",
+            r#"{"lastUpdateId":3407459756,"bids":[["50000.00","1.2"],["49999.50","0.3"]],
+                "asks":[["50001.00","0.5"]],"timestamp":1716000000123}"#,
+        )
+        .unwrap();
+        assert!(spot.is_snapshot);
+        assert_eq!(spot.symbol, "BTCUSDT");
+        assert_eq!(spot.bids, vec![(50000.0, 1.2), (49999.5, 0.3)]);
+        assert_eq!(spot.asks, vec![(50001.0, 0.5)]);
+        assert_eq!(spot.version, Some(3407459756));
+        assert_eq!(spot.time, 1716000000123);
+
+        let swap = OrderBookUpdate::from_rest_json(
+            &ExchangeType::MexcSwap,
+            "BTC_USDT",
+            r#"{"success":true,"code":0,"data":{"asks":[[50001.5,120,3],[50001,80,2]],
+                "bids":[[50000,40,1]],"version":9876,"timestamp":1716000000456}}"#,
+        )
+        .unwrap();
+        assert_eq!(swap.asks, vec![(50001.0, 80.0), (50001.5, 120.0)]);
+        assert_eq!(swap.bids, vec![(50000.0, 40.0)]);
+        assert_eq!(swap.version, Some(9876));
+
+        // BingX Spot отдаёт asks от худшей цены к лучшей
+        let bingx = OrderBookUpdate::from_rest_json(
+            &ExchangeType::BingxSpot,
+            "BTC-USDT",
+            r#"{"code":0,"timestamp":1716000000789,"data":{"bids":[["50000.0","0.8"]],
+                "asks":[["50002.0","0.4"],["50001.0","0.6"]],"ts":1716000000780}}"#,
+        )
+        .unwrap();
+        assert_eq!(bingx.asks, vec![(50001.0, 0.6), (50002.0, 0.4)]);
+        assert_eq!(bingx.version, None);
+        assert_eq!(bingx.time, 1716000000780);
+
+        let bingx_swap = OrderBookUpdate::from_rest_json(
+            &ExchangeType::BingxSwap,
+            "BTC-USDT",
+            r#"{"code":0,"msg":"","data":{"T":1716000000999,"bids":[["50000.1","12.5"]],
+                "asks":[["50000.2","3"]]}}"#,
+        )
+        .unwrap();
+        assert_eq!(bingx_swap.bids, vec![(50000.1, 12.5)]);
+        assert_eq!(bingx_swap.time, 1716000000999);
+
+        let err = OrderBookUpdate::from_rest_json(
+            &ExchangeType::BingxSwap,
+            "BTC-USDT",
+            r#"{"code":109400,"msg":"symbol not exist"}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Ошибка API"), "{}", err);
+    }
+
+    #[test]
+    fn test_rest_depth_snapshot_normalized() {
+        let book = OrderBookUpdate::from_rest_json(
+            &ExchangeType::MexcSpot,
+            "BTCUSDT",
+            r#"{"lastUpdateId":42,"bids":[["49999.50","0.3"],["50000.00","0"],["50000.50","1"]],
+                "asks":[["50003.00","0.2"],["50001.00","0.00"],["50002.00","0.7"]]}"#,
+        )
+        .unwrap();
+        assert_eq!(book.bids, vec![(50000.5, 1.0), (49999.5, 0.3)]);
+        assert_eq!(book.asks, vec![(50002.0, 0.7), (50003.0, 0.2)]);
+        assert_eq!(book.version, Some(42));
+    }
+
+    #[test]
+    fn test_checksum_mismatch_forces_resnapshot() {
+        let mut manager = OrderBookManager::new();
+        let snapshot = parse(
+            r#"{"c":"spot@public.limit.depth.v3.api@BTCUSDT@20","d":{
+                "asks":[{"p":"50001.00","v":"0.5"},{"p":"50002.00","v":"1"}],
+                "bids":[{"p":"50000.00","v":"1.2"}],"r":"100"},"s":"BTCUSDT","t":1}"#,
+        );
+        assert!(snapshot.is_snapshot);
+        assert!(manager.apply(&snapshot));
+
+        let book = manager.book("BTC_USDT").unwrap();
+        assert_eq!(book.best_bid_ask(), (Some(50000.0), Some(50001.0)));
+        assert_eq!(book.checksum(), crc32fast::hash(b"50000:1.2:50001:0.5:50002:1"),);
+
+        // Обновление с верной контрольной суммой применяется
+        let mut expected = book.clone();
+        expected.apply(&OrderBookUpdate {
+            bids: vec![(50000.5, 2.0)],
+            asks: vec![(50001.0, 0.0)],
+            is_snapshot: false,
+            ..snapshot.clone()
+        });
+        let valid = parse(&format!(
+            r#"{{"c":"spot@public.increase.depth.v3.api@BTCUSDT","d":{{
+                "asks":[{{"p":"50001.00","v":"0"}}],"bids":[{{"p":"50000.50","v":"2"}}],
+                "r":"101","checksum":{}}},"t":2}}"#,
+            expected.checksum()
+        ));
+        assert_eq!(valid.checksum, Some(expected.checksum()));
+        assert!(manager.apply(&valid));
+        assert_eq!(
+            manager.book("BTC_USDT").unwrap().best_bid_ask(),
+            (Some(50000.5), Some(50002.0))
+        );
+
+        // Подделанная контрольная сумма сбрасывает стакан
+        let corrupted = parse(
+            r#"{"c":"spot@public.increase.depth.v3.api@BTCUSDT","d":{
+                "asks":[],"bids":[{"p":"49999.00","v":"3"}],"r":"102","checksum":12345},"t":3}"#,
+        );
+        assert!(!manager.apply(&corrupted));
+        assert!(manager.book("BTC_USDT").is_none());
+        assert!(manager.needs_snapshot("BTC_USDT"));
+        assert_eq!(manager.pending_snapshots(), vec!["BTC_USDT".to_string()]);
+
+        // До нового снимка обновления не применяются
+        assert!(!manager.apply(&OrderBookUpdate { checksum: None, ..corrupted }));
+        assert!(manager.book("BTC_USDT").is_none());
+
+        // Отложенное обновление уже вошло в новый снимок и отбрасывается
+        assert!(manager.apply(&OrderBookUpdate { version: Some(102), ..snapshot }));
+        assert!(!manager.needs_snapshot("BTC_USDT"));
+        let book = manager.book("BTC_USDT").unwrap();
+        assert_eq!(book.version, Some(102));
+        assert_eq!(book.best_bid_ask(), (Some(50000.0), Some(50001.0)));
+    }
+
+    #[tokio::test]
+    async fn test_resync_after_version_gap() {
+        let diff = |version: u64, bid: f64| OrderBookUpdate {
+            symbol: "BTC_USDT".to_string(),
+            bids: vec![(bid, 1.0)],
+            asks: vec![],
+            is_snapshot: false,
+            version: Some(version),
+            from_version: None,
+            checksum: None,
+            time: version,
+        };
+        let mut manager = OrderBookManager::new();
+        assert!(manager.apply(&OrderBookUpdate {
+            bids: vec![(50000.0, 1.0)],
+            asks: vec![(50001.0, 1.0)],
+            is_snapshot: true,
+            ..diff(100, 0.0)
+        }));
+        assert!(manager.apply(&diff(101, 49999.0)));
+        assert!(!manager.apply(&diff(101, 49998.0)), "устаревшее обновление применено");
+
+        // Пропущена версия 102: стакан сброшен, обновления копятся до снимка
+        assert!(!manager.apply(&diff(103, 49997.0)));
+        assert!(manager.needs_snapshot("BTC_USDT"));
+        assert!(manager.book("BTC_USDT").is_none());
+        assert!(!manager.apply(&diff(104, 49996.0)));
+        assert!(!manager.apply(&diff(105, 49995.0)));
+
+        let err = manager
+            .resync("BTC_USDT", async { Err("Request error: timeout".to_string()) })
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Request error: timeout");
+        assert_eq!(manager.pending_snapshots(), vec!["BTC_USDT".to_string()]);
+
+        // Снимок REST в формате символа биржи, версия 104
+        let snapshot = OrderBookUpdate::from_rest_json(
+            &ExchangeType::MexcSpot,
+            "BTCUSDT",
+            r#"{"lastUpdateId":104,"bids":[["50000.00","2"]],"asks":[["50001.00","1"]],
+                "timestamp":104}"#,
+        )
+        .unwrap();
+        assert!(manager.resync("BTC_USDT", async { Ok(snapshot) }).await.unwrap());
+        assert!(!manager.needs_snapshot("BTC_USDT"));
+
+        // Версии 103 и 104 уже в снимке, применена только 105
+        let book = manager.book("BTC_USDT").unwrap();
+        assert_eq!(book.version, Some(105));
+        assert_eq!(book.bids, vec![(50000.0, 2.0), (49995.0, 1.0)]);
+        assert!(manager.apply(&diff(106, 49994.0)));
+    }
+
+    #[test]
+    fn test_version_range_contiguity() {
+        let ranged = |from: u64, to: u64| {
+            parse(&format!(
+                r#"{{"c":"spot@public.increase.depth.v3.api@BTCUSDT","d":{{
+                    "asks":[],"bids":[{{"p":"50000.00","v":"{}"}}],
+                    "fromVersion":{},"toVersion":{}}},"t":{}}}"#,
+                to, from, to, to
+            ))
+        };
+        let update = ranged(101, 103);
+        assert_eq!((update.from_version, update.version), (Some(101), Some(103)));
+
+        let mut manager = OrderBookManager::new();
+        assert!(manager.apply(&OrderBookUpdate {
+            is_snapshot: true,
+            version: Some(100),
+            from_version: None,
+            ..ranged(0, 0)
+        }));
+        assert!(manager.apply(&update));
+        // Диапазон частично перекрывает текущую версию: пропусков нет
+        assert!(manager.apply(&ranged(102, 105)));
+        assert!(!manager.apply(&ranged(104, 105)), "устаревшее обновление применено");
+        assert_eq!(manager.book("BTC_USDT").unwrap().version, Some(105));
+
+        // Версия 106 не вошла ни в одно обновление
+        assert!(!manager.apply(&ranged(107, 110)));
+        assert!(manager.needs_snapshot("BTC_USDT"));
+    }
+}
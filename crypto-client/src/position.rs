@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ChannelType, WsMessage};
+
+/// Направление позиции
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+/// Открытая позиция на фьючерсном рынке
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Контракт, например `BTC_USDT`
+    pub symbol: String,
+    /// Направление позиции
+    pub side: PositionSide,
+    /// Размер позиции в контрактах
+    pub size: f64,
+    /// Средняя цена входа
+    pub entry_price: f64,
+    /// Кредитное плечо
+    pub leverage: u32,
+    /// Нереализованный PnL; 0, если биржа его не вернула
+    pub unrealized_pnl: f64,
+}
+
+impl Position {
+    /// Разобрать ответ MEXC Contract `open_positions` или BingX Swap `positions`
+    /// в список позиций.
+    ///
+    /// `positionType` MEXC: 1 — long, 2 — short. Ответ с `success: false` или ненулевым
+    /// `code` возвращается как ошибка с кодом и сообщением биржи.
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>, String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Ошибка парсинга позиций: {}", e))?;
+
+        if value["success"].as_bool() == Some(false)
+            || value["code"].as_i64().is_some_and(|code| code != 0)
+        {
+            return Err(format!(
+                "Ошибка API при получении позиций ({}): {}",
+                value["code"],
+                value["message"].as_str().or_else(|| value["msg"].as_str()).unwrap_or_default()
+            ));
+        }
+
+        let positions = value["data"]
+            .as_array()
+            .ok_or_else(|| format!("В ответе отсутствует список позиций: {}", json))?;
+
+        positions
+            .iter()
+            .map(|position| match position.get("positionSide") {
+                Some(_) => Self::from_bingx_value(position),
+                None => Self::from_mexc_value(position),
+            })
+            .collect()
+    }
+
+    fn from_bingx_value(position: &Value) -> Result<Self, String> {
+        let side = match position["positionSide"].as_str() {
+            Some("LONG") => PositionSide::Long,
+            Some("SHORT") => PositionSide::Short,
+            other => return Err(format!("Неизвестный тип позиции: {:?}", other)),
+        };
+
+        Ok(Position {
+            symbol: position["symbol"].as_str().unwrap_or_default().to_string(),
+            side,
+            size: number(&position["positionAmt"]).abs(),
+            entry_price: number(&position["avgPrice"]),
+            leverage: number(&position["leverage"]) as u32,
+            unrealized_pnl: number(&position["unrealizedProfit"]),
+        })
+    }
+
+    fn from_mexc_value(position: &Value) -> Result<Self, String> {
+        let side = match position["positionType"].as_i64() {
+            Some(1) => PositionSide::Long,
+            Some(2) => PositionSide::Short,
+            other => return Err(format!("Неизвестный тип позиции: {:?}", other)),
+        };
+
+        Ok(Position {
+            symbol: position["symbol"].as_str().unwrap_or_default().to_string(),
+            side,
+            size: position["holdVol"].as_f64().unwrap_or_default(),
+            entry_price: position["holdAvgPrice"]
+                .as_f64()
+                .or_else(|| position["openAvgPrice"].as_f64())
+                .unwrap_or_default(),
+            leverage: position["leverage"].as_u64().unwrap_or_default() as u32,
+            unrealized_pnl: position["unrealised"].as_f64().unwrap_or_default(),
+        })
+    }
+}
+
+impl WsMessage {
+    /// Разобрать сообщение канала [`ChannelType::Positions`] в [`Position`].
+    ///
+    /// Поддерживается `push.personal.position` MEXC Swap; у закрытой позиции размер 0.
+    /// Для остальных сообщений возвращает `None`.
+    pub fn as_position_update(&self) -> Option<Position> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSwap, ChannelType::Positions) => {
+                Position::from_mexc_value(&self.data["data"]).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа; 0, если поле отсутствует
+fn number(value: &Value) -> f64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_f64().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    const MEXC_OPEN_POSITIONS: &str = r#"{"success":true,"code":0,"data":[
+        {"positionId":1394650,"symbol":"BTC_USDT","positionType":1,"openType":1,"state":1,
+         "holdVol":12,"frozenVol":0,"closeVol":0,"holdAvgPrice":64210.5,"openAvgPrice":64210.5,
+         "closeAvgPrice":0,"liquidatePrice":58120.3,"oim":38.52,"im":38.52,"holdFee":0,
+         "realised":-0.0462,"unrealised":1.74,"leverage":20,"createTime":1715770402000,
+         "updateTime":1715770402000,"autoAddIm":false},
+        {"positionId":1394651,"symbol":"ETH_USDT","positionType":2,"openType":2,"state":1,
+         "holdVol":3,"frozenVol":0,"closeVol":0,"holdAvgPrice":2950.1,"openAvgPrice":2950.1,
+         "leverage":10,"createTime":1715770403000,"updateTime":1715770403000}]}"#;
+
+    #[test]
+    fn test_parse_mexc_open_positions() {
+        let positions = Position::list_from_json(MEXC_OPEN_POSITIONS).unwrap();
+        assert_eq!(
+            positions,
+            vec![
+                Position {
+                    symbol: "BTC_USDT".to_string(),
+                    side: PositionSide::Long,
+                    size: 12.0,
+                    entry_price: 64210.5,
+                    leverage: 20,
+                    unrealized_pnl: 1.74,
+                },
+                Position {
+                    symbol: "ETH_USDT".to_string(),
+                    side: PositionSide::Short,
+                    size: 3.0,
+                    entry_price: 2950.1,
+                    leverage: 10,
+                    unrealized_pnl: 0.0,
+                },
+            ]
+        );
+
+        assert!(
+            Position::list_from_json(r#"{"success":true,"code":0,"data":[]}"#).unwrap().is_empty()
+        );
+        let error = Position::list_from_json(
+            r#"{"success":false,"code":602,"message":"Signature verification failed!"}"#,
+        )
+        .unwrap_err();
+        assert!(error.contains("602"));
+    }
+
+    #[test]
+    fn test_parse_mexc_swap_private_frames() {
+        let parse = |frame: &str| {
+            WsClientWrapper::parse_message_with_predicates(ExchangeType::MexcSwap, frame, &[])
+                .into_message()
+                .unwrap()
+        };
+
+        let position = parse(
+            r#"{"channel":"push.personal.position","data":{"positionId":1394650,
+                "symbol":"BTC_USDT","positionType":2,"openType":1,"state":1,"holdVol":5,
+                "frozenVol":0,"closeVol":0,"holdAvgPrice":64210.5,"openAvgPrice":64210.5,
+                "closeAvgPrice":0,"liquidatePrice":70120.3,"oim":16.05,"im":16.05,"holdFee":0,
+                "realised":-0.0193,"leverage":20,"autoAddIm":false},"ts":1715770402000}"#,
+        );
+        assert_eq!(position.channel, ChannelType::Positions);
+        assert_eq!(position.symbol, "BTC_USDT");
+        assert_eq!(
+            position.as_position_update(),
+            Some(Position {
+                symbol: "BTC_USDT".to_string(),
+                side: PositionSide::Short,
+                size: 5.0,
+                entry_price: 64210.5,
+                leverage: 20,
+                unrealized_pnl: 0.0,
+            })
+        );
+
+        let asset = parse(
+            r#"{"channel":"push.personal.asset","data":{"currency":"USDT","positionMargin":16.05,
+                "availableBalance":483.2,"cashBalance":483.2,"frozenBalance":0.5,
+                "equity":499.25,"unrealized":0,"bonus":0},"ts":1715770402000}"#,
+        );
+        assert_eq!(asset.channel, ChannelType::AccountBalance);
+        assert_eq!(asset.symbol, "ACCOUNT");
+        assert_eq!(asset.as_position_update(), None);
+
+        let order = parse(
+            r#"{"channel":"push.personal.order","data":{"orderId":"102015012431820288",
+                "symbol":"ETH_USDT","positionId":0,"price":2950.1,"vol":3,"leverage":10,
+                "side":3,"category":1,"orderType":1,"dealAvgPrice":0,"dealVol":0,"state":2,
+                "externalOid":"_m_2228b23a75204e1982b301e44d439cbb"},"ts":1715770403000}"#,
+        );
+        assert_eq!(order.channel, ChannelType::Orders);
+        assert_eq!(order.symbol, "ETH_USDT");
+    }
+}
@@ -0,0 +1,395 @@
+//! Типизированные события приватных каналов: сделки по своим ордерам,
+//! изменения баланса и обновления ордеров.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::exchange_type::ExchangeType;
+use crate::order::OrderStatus;
+use crate::ws_client::{ChannelType, WsMessage};
+
+/// Событие аккаунта, разобранное из приватного WebSocket сообщения
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PrivateEvent {
+    /// Исполнение (полное или частичное) своего ордера
+    Fill(TradeFill),
+    /// Новые значения балансов активов
+    BalanceUpdate(Vec<AssetBalance>),
+    /// Изменение состояния ордера
+    OrderUpdate(OrderUpdate),
+}
+
+/// Сделка по собственному ордеру
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeFill {
+    pub symbol: String,
+    pub order_id: String,
+    pub trade_id: String,
+    /// `BUY` или `SELL`
+    pub side: String,
+    pub price: f64,
+    pub quantity: f64,
+    pub fee: f64,
+    pub fee_currency: String,
+    pub is_maker: bool,
+    /// Время сделки в миллисекундах Unix
+    pub time: u64,
+}
+
+/// Баланс одного актива после изменения
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetBalance {
+    pub asset: String,
+    /// Доступно для торговли
+    pub free: f64,
+    /// Заблокировано в ордерах
+    pub locked: f64,
+}
+
+/// Обновление состояния ордера
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderUpdate {
+    pub symbol: String,
+    pub order_id: String,
+    /// `BUY` или `SELL`
+    pub side: String,
+    pub status: OrderStatus,
+    pub price: f64,
+    pub quantity: f64,
+    pub filled_quantity: f64,
+    /// Цена последнего исполнения, если биржа её передаёт
+    pub last_price: Option<f64>,
+}
+
+/// Объединяет частичные исполнения одного ордера в одну сделку.
+///
+/// Исполнения с одинаковым `order_id`, пришедшие в течение окна от первого из них,
+/// суммируются по количеству и комиссии, а цена становится средневзвешенной по объёму.
+#[derive(Debug, Clone)]
+pub struct FillAggregator {
+    window: Duration,
+    /// Ожидающие исполнения в порядке первого поступления
+    pending: Vec<(Instant, TradeFill)>,
+}
+
+impl FillAggregator {
+    pub fn new(window: Duration) -> Self {
+        Self { window, pending: Vec::new() }
+    }
+
+    /// Добавить исполнение, объединив его с ожидающим по тому же ордеру
+    pub fn push(&mut self, fill: TradeFill, now: Instant) {
+        match self.pending.iter_mut().find(|(_, pending)| pending.order_id == fill.order_id) {
+            Some((_, pending)) => {
+                let quantity = pending.quantity + fill.quantity;
+                if quantity > 0.0 {
+                    pending.price =
+                        (pending.price * pending.quantity + fill.price * fill.quantity) / quantity;
+                }
+                pending.quantity = quantity;
+                pending.fee += fill.fee;
+                pending.is_maker &= fill.is_maker;
+                pending.trade_id = fill.trade_id;
+                pending.time = pending.time.max(fill.time);
+            }
+            None => self.pending.push((now, fill)),
+        }
+    }
+
+    /// Извлечь самое раннее исполнение, окно которого истекло к `now`
+    pub fn pop_expired(&mut self, now: Instant) -> Option<TradeFill> {
+        let index = self
+            .pending
+            .iter()
+            .position(|(first_seen, _)| now.duration_since(*first_seen) >= self.window)?;
+        Some(self.pending.remove(index).1)
+    }
+
+    /// Извлечь все ожидающие исполнения, не дожидаясь окончания окна
+    pub fn flush(&mut self) -> Vec<TradeFill> {
+        self.pending.drain(..).map(|(_, fill)| fill).collect()
+    }
+}
+
+impl WsMessage {
+    /// Разобрать приватное сообщение в [`PrivateEvent`].
+    ///
+    /// Поддерживаются приватные сделки, баланс и `executionReport` MEXC Spot, а также
+    /// ордера, сделки и баланс OKX. Для публичных сообщений и неизвестных форматов
+    /// возвращает `None`.
+    pub fn as_private_event(&self) -> Option<PrivateEvent> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSpot, ChannelType::PrivateDeals) => {
+                mexc_private_deal(&self.data, &self.symbol).map(PrivateEvent::Fill)
+            }
+            (ExchangeType::MexcSpot, ChannelType::AccountBalance) => {
+                mexc_account(&self.data).map(|balance| PrivateEvent::BalanceUpdate(vec![balance]))
+            }
+            (_, ChannelType::Orders) => self.as_order_update().map(PrivateEvent::OrderUpdate),
+            (ExchangeType::OkxSpot, ChannelType::PrivateDeals) => {
+                okx_fill(&self.data["data"][0]).map(PrivateEvent::Fill)
+            }
+            (ExchangeType::OkxSpot, ChannelType::AccountBalance)
+                if self.data["arg"]["channel"] == "account" =>
+            {
+                okx_account(&self.data["data"][0]).map(PrivateEvent::BalanceUpdate)
+            }
+            _ => None,
+        }
+    }
+
+    /// Разобрать сообщение канала ордеров в [`OrderUpdate`].
+    ///
+    /// Поддерживаются `executionReport` MEXC Spot и `orders` OKX. Для остальных
+    /// сообщений возвращает `None`.
+    pub fn as_order_update(&self) -> Option<OrderUpdate> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSpot, ChannelType::Orders) => {
+                mexc_execution_report(&self.data["d"], &self.symbol)
+            }
+            (ExchangeType::OkxSpot, ChannelType::Orders) => okx_order(&self.data["data"][0]),
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа; биржи передают цены и объёмы строками
+fn number(value: &Value) -> f64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_f64().unwrap_or_default(),
+    }
+}
+
+fn string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// `privateDeals` MEXC: `tradeType` 1 — покупка, 2 — продажа
+fn mexc_private_deal(data: &Value, symbol: &str) -> Option<TradeFill> {
+    let deal = data.get("privateDeals")?;
+    let side = match deal["tradeType"].as_i64()? {
+        1 => "BUY",
+        2 => "SELL",
+        _ => return None,
+    };
+
+    Some(TradeFill {
+        symbol: data["symbol"].as_str().unwrap_or(symbol).to_string(),
+        order_id: string(&deal["orderId"]),
+        trade_id: string(&deal["tradeId"]),
+        side: side.to_string(),
+        price: number(&deal["price"]),
+        quantity: number(&deal["quantity"]),
+        fee: number(&deal["feeAmount"]),
+        fee_currency: string(&deal["feeCurrency"]),
+        is_maker: deal["isMaker"].as_bool().unwrap_or_default(),
+        time: deal["time"].as_u64().unwrap_or_default(),
+    })
+}
+
+/// `executionReport` MEXC Spot: `i` — ордер, `X` — статус, `S` — сторона (`BUY`/`SELL`
+/// или 1/2), `p`/`q` — цена и количество ордера, `z` — исполненное количество,
+/// `L` — цена последнего исполнения
+fn mexc_execution_report(report: &Value, symbol: &str) -> Option<OrderUpdate> {
+    let side = match &report["S"] {
+        Value::Number(side) if side.as_i64() == Some(1) => "BUY".to_string(),
+        Value::Number(side) if side.as_i64() == Some(2) => "SELL".to_string(),
+        Value::String(side) => side.to_uppercase(),
+        _ => return None,
+    };
+    let last_price = number(&report["L"]);
+
+    Some(OrderUpdate {
+        symbol: symbol.to_string(),
+        order_id: Some(string(&report["i"])).filter(|id| !id.is_empty())?,
+        side,
+        status: OrderStatus::from(report["X"].as_str().unwrap_or_default()),
+        price: number(&report["p"]),
+        quantity: number(&report["q"]),
+        filled_quantity: number(&report["z"]),
+        last_price: (last_price > 0.0).then_some(last_price),
+    })
+}
+
+/// `privateAccount` MEXC содержит итоговый баланс одного актива
+fn mexc_account(data: &Value) -> Option<AssetBalance> {
+    let account = data.get("privateAccount")?;
+    Some(AssetBalance {
+        asset: account["vcoinName"].as_str()?.to_string(),
+        free: number(&account["balanceAmount"]),
+        locked: number(&account["frozenAmount"]),
+    })
+}
+
+fn okx_order(order: &Value) -> Option<OrderUpdate> {
+    Some(OrderUpdate {
+        symbol: order["instId"].as_str()?.to_string(),
+        order_id: order["ordId"].as_str()?.to_string(),
+        side: string(&order["side"]).to_uppercase(),
+        status: OrderStatus::from(order["state"].as_str().unwrap_or_default()),
+        price: number(&order["px"]),
+        quantity: number(&order["sz"]),
+        filled_quantity: number(&order["accFillSz"]),
+        last_price: order["fillPx"].as_str().and_then(|price| price.parse().ok()),
+    })
+}
+
+/// Комиссия OKX отрицательна, когда списывается со счёта
+fn okx_fill(fill: &Value) -> Option<TradeFill> {
+    Some(TradeFill {
+        symbol: fill["instId"].as_str()?.to_string(),
+        order_id: fill["ordId"].as_str()?.to_string(),
+        trade_id: string(&fill["tradeId"]),
+        side: string(&fill["side"]).to_uppercase(),
+        price: number(&fill["fillPx"]),
+        quantity: number(&fill["fillSz"]),
+        fee: -number(&fill["fee"]),
+        fee_currency: string(&fill["feeCcy"]),
+        is_maker: fill["execType"] == "M",
+        time: number(&fill["ts"]) as u64,
+    })
+}
+
+fn okx_account(account: &Value) -> Option<Vec<AssetBalance>> {
+    let details = account["details"].as_array()?;
+    Some(
+        details
+            .iter()
+            .filter_map(|detail| {
+                Some(AssetBalance {
+                    asset: detail["ccy"].as_str()?.to_string(),
+                    free: number(&detail["availBal"]),
+                    locked: number(&detail["frozenBal"]),
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    fn parse(exchange: ExchangeType, raw: &str) -> WsMessage {
+        WsClientWrapper::parse_message_with_predicates(exchange, raw, &[]).into_message().unwrap()
+    }
+
+    #[test]
+    fn test_mexc_private_deal_and_account_events() {
+        let deal = parse(
+            ExchangeType::MexcSpot,
+            r#"{"channel":"spot@private.deals.v3.api.pb","symbol":"MXUSDT","sendTime":1736417034332,
+                "privateDeals":{"price":"3.6962","quantity":"1","amount":"3.6962","tradeType":2,
+                "isMaker":false,"isSelfTrade":false,"tradeId":"505979017439002624X1",
+                "clientOrderId":"","orderId":"C02__505979017439002624115",
+                "feeAmount":"0.0003998377369698171","feeCurrency":"MX","time":1736417034280}}"#,
+        );
+        assert_eq!(
+            deal.as_private_event(),
+            Some(PrivateEvent::Fill(TradeFill {
+                symbol: "MXUSDT".to_string(),
+                order_id: "C02__505979017439002624115".to_string(),
+                trade_id: "505979017439002624X1".to_string(),
+                side: "SELL".to_string(),
+                price: 3.6962,
+                quantity: 1.0,
+                fee: 0.0003998377369698171,
+                fee_currency: "MX".to_string(),
+                is_maker: false,
+                time: 1736417034280,
+            }))
+        );
+
+        let account = parse(
+            ExchangeType::MexcSpot,
+            r#"{"channel":"spot@private.account.v3.api.pb","createTime":1736417034305,
+                "sendTime":1736417034307,"privateAccount":{"vcoinName":"USDT",
+                "coinId":"128f589271cb4951b03e71e6323eb7be","balanceAmount":"21.94210356004384",
+                "balanceAmountChange":"10","frozenAmount":"1.5","frozenAmountChange":"0",
+                "type":"CONTRACT_TRANSFER","time":1736416910000}}"#,
+        );
+        assert_eq!(
+            account.as_private_event(),
+            Some(PrivateEvent::BalanceUpdate(vec![AssetBalance {
+                asset: "USDT".to_string(),
+                free: 21.94210356004384,
+                locked: 1.5,
+            }]))
+        );
+
+        let public = parse(
+            ExchangeType::MexcSpot,
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","s":"BTCUSDT",
+                "d":{"deals":[{"p":"50000","v":"0.1","S":1,"t":1640995200000}]},"t":1640995200000}"#,
+        );
+        assert_eq!(public.as_private_event(), None);
+    }
+
+    #[test]
+    fn test_fill_aggregation_vwap() {
+        let fill = |trade_id: &str, price: f64, quantity: f64, time: u64| TradeFill {
+            symbol: "BTCUSDT".to_string(),
+            order_id: "C02__1".to_string(),
+            trade_id: trade_id.to_string(),
+            side: "BUY".to_string(),
+            price,
+            quantity,
+            fee: 0.01,
+            fee_currency: "USDT".to_string(),
+            is_maker: true,
+            time,
+        };
+
+        let start = Instant::now();
+        let window = Duration::from_millis(500);
+        let mut aggregator = FillAggregator::new(window);
+        aggregator.push(fill("1", 100.0, 1.0, 1000), start);
+        aggregator.push(fill("2", 101.0, 2.0, 1001), start + Duration::from_millis(100));
+        aggregator.push(fill("3", 104.0, 1.0, 1002), start + Duration::from_millis(200));
+        let mut other = fill("4", 50.0, 1.0, 1003);
+        other.order_id = "C02__2".to_string();
+        aggregator.push(other, start + Duration::from_millis(300));
+
+        assert_eq!(aggregator.pop_expired(start + Duration::from_millis(400)), None);
+        let aggregated = aggregator.pop_expired(start + window).unwrap();
+        assert_eq!(aggregated.quantity, 4.0);
+        // (100 * 1 + 101 * 2 + 104 * 1) / 4
+        assert_eq!(aggregated.price, 101.5);
+        assert!((aggregated.fee - 0.03).abs() < 1e-12);
+        assert_eq!(aggregated.trade_id, "3");
+        assert_eq!(aggregated.time, 1002);
+        assert_eq!(aggregator.pop_expired(start + window), None);
+        assert_eq!(aggregator.flush().len(), 1);
+    }
+
+    #[test]
+    fn test_okx_order_update_event() {
+        let order = parse(
+            ExchangeType::OkxSpot,
+            r#"{"arg":{"channel":"orders","instType":"SPOT","instId":"BTC-USDT"},
+                "data":[{"instId":"BTC-USDT","ordId":"680800019749904384","px":"60000",
+                "sz":"0.002","side":"buy","state":"partially_filled","accFillSz":"0.001"}]}"#,
+        );
+        assert_eq!(
+            order.as_private_event(),
+            Some(PrivateEvent::OrderUpdate(OrderUpdate {
+                symbol: "BTC-USDT".to_string(),
+                order_id: "680800019749904384".to_string(),
+                side: "BUY".to_string(),
+                status: OrderStatus::PartiallyFilled,
+                price: 60000.0,
+                quantity: 0.002,
+                filled_quantity: 0.001,
+                last_price: None,
+            }))
+        );
+    }
+}
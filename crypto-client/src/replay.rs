@@ -0,0 +1,135 @@
+//! Запись сырых WebSocket кадров в JSONL и их воспроизведение через обычный
+//! конвейер парсинга. Позволяет сохранить поток с продакшена и детерминированно
+//! прогнать его через парсер.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ParseOutcome, WsClientWrapper, WsMessage};
+
+/// Сырой кадр, сохранённый при записи потока
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// Биржа, от которой получен кадр
+    pub exchange: ExchangeType,
+    /// Время получения кадра в миллисекундах Unix
+    pub received_at: u64,
+    /// Текст кадра в том виде, в котором он пришёл из транспорта
+    pub frame: String,
+}
+
+/// Дописывает кадры в JSONL файл, по одной строке на кадр
+pub(crate) struct FrameRecorder {
+    file: File,
+}
+
+impl FrameRecorder {
+    pub(crate) fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Не удалось открыть файл записи {}: {}", path.display(), e))?;
+        Ok(Self { file })
+    }
+
+    pub(crate) fn record(&mut self, exchange: &ExchangeType, frame: &str) -> Result<(), String> {
+        let record = RecordedFrame {
+            exchange: exchange.clone(),
+            received_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            frame: frame.to_string(),
+        };
+        let mut line = serde_json::to_string(&record)
+            .map_err(|e| format!("Ошибка сериализации кадра: {}", e))?;
+        line.push('\n');
+        // Одна запись на кадр, чтобы при аварийном завершении файл не обрывался посреди строки
+        self.file.write_all(line.as_bytes()).map_err(|e| format!("Ошибка записи кадра: {}", e))
+    }
+}
+
+/// Воспроизводит записанный поток кадров через парсер [`WsMessage`].
+///
+/// Файл читается построчно, поэтому размер записи не ограничен памятью.
+pub struct ReplayWsClient {
+    lines: Lines<BufReader<File>>,
+    speed: f64,
+    last_received_at: Option<u64>,
+}
+
+impl ReplayWsClient {
+    /// Открыть JSONL файл, созданный [`CryptoWsClient::start_recording`](crate::CryptoWsClient::start_recording)
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| format!("Не удалось открыть запись {}: {}", path.display(), e))?;
+        Ok(Self { lines: BufReader::new(file).lines(), speed: 1.0, last_received_at: None })
+    }
+
+    /// Установить скорость воспроизведения: 1.0 — исходные интервалы между кадрами,
+    /// 10.0 — в десять раз быстрее, 0 — без задержек
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Следующий записанный кадр без парсинга; `None` в конце записи
+    pub fn next_frame(&mut self) -> Result<Option<RecordedFrame>, String> {
+        for line in self.lines.by_ref() {
+            let line = line.map_err(|e| format!("Ошибка чтения записи: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame = serde_json::from_str(&line)
+                .map_err(|e| format!("Некорректная строка записи: {} - {}", e, line))?;
+            return Ok(Some(frame));
+        }
+        Ok(None)
+    }
+
+    /// Следующее сообщение записи, разобранное так же, как живой поток.
+    ///
+    /// Служебные и нераспознанные кадры пропускаются. `timestamp` сообщения берётся
+    /// из записи. Возвращает `None`, когда запись закончилась.
+    pub async fn next_message(&mut self) -> Result<Option<WsMessage>, String> {
+        while let Some(record) = self.next_frame()? {
+            self.wait_original_interval(record.received_at).await;
+
+            match WsClientWrapper::parse_message_with_predicates(
+                record.exchange.clone(),
+                &record.frame,
+                &[],
+            ) {
+                ParseOutcome::Message(mut message) => {
+                    message.timestamp = record.received_at;
+                    return Ok(Some(message));
+                }
+                ParseOutcome::Service => {}
+                ParseOutcome::Error(e) => {
+                    log::trace!("ReplayWsClient: кадр пропущен ({}): {}", e, record.frame);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    async fn wait_original_interval(&mut self, received_at: u64) {
+        let previous = self.last_received_at.replace(received_at);
+        if self.speed <= 0.0 {
+            return;
+        }
+        if let Some(previous) = previous {
+            let interval = received_at.saturating_sub(previous) as f64 / self.speed;
+            if interval > 0.0 {
+                tokio::time::sleep(Duration::from_secs_f64(interval / 1000.0)).await;
+            }
+        }
+    }
+}
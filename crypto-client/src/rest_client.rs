@@ -1,10 +1,25 @@
 use async_trait::async_trait;
 use crypto_rest_client::*;
+use log::warn;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::candle;
 use crate::config::ExchangeConfig;
 use crate::exchange_type::ExchangeType;
 use crate::traits::ExchangeClient;
+use crate::{
+    AccountSnapshot, CancelReplaceResponse, Candle, ExchangeError, MarketType, MultiExchangeConfig,
+    OrderBookUpdate, Position,
+};
+
+/// Базовая задержка перед повторной отправкой ордера
+const ORDER_RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
 
 /// Обёртка для различных REST клиентов
 pub enum RestClientWrapper {
@@ -137,7 +152,9 @@ impl ExchangeClient for RestClientWrapper {
     ) -> Result<String, String> {
         let result = match self {
             RestClientWrapper::MexcSpot(client) => {
-                client.create_order(symbol, side, quantity, price).await
+                client
+                    .create_order(symbol, side, quantity, price, None, TimeInForce::Gtc, false)
+                    .await
             }
             RestClientWrapper::BingxSpot(client) => {
                 client.create_order(symbol, side, quantity, Some(price), "LIMIT").await
@@ -164,6 +181,157 @@ impl ExchangeClient for RestClientWrapper {
         result.map_err(|e| e.to_string())
     }
 
+    async fn cancel_replace_order(
+        &self,
+        symbol: &str,
+        cancel_order_id: &str,
+        new_side: &str,
+        new_quantity: f64,
+        new_price: f64,
+    ) -> Result<String, String> {
+        let result = match self {
+            RestClientWrapper::BingxSpot(client) => {
+                client
+                    .cancel_replace_order(
+                        symbol,
+                        cancel_order_id,
+                        new_side,
+                        new_quantity,
+                        new_price,
+                    )
+                    .await
+            }
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "cancel-replace ордера на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    async fn fetch_balances(&self) -> Result<String, String> {
+        let result = match self {
+            RestClientWrapper::MexcSpot(client) => client.get_account_balance("").await,
+            RestClientWrapper::MexcSwap(client) => client.fetch_assets().await,
+            RestClientWrapper::BingxSpot(client) => client.get_account_balance(None).await,
+            RestClientWrapper::BingxSwap(client) => client.get_account_balance().await,
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение балансов на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    async fn fetch_open_orders(&self, symbol: Option<&str>) -> Result<String, String> {
+        let result = match self {
+            RestClientWrapper::MexcSpot(client) => match symbol {
+                Some(symbol) => client.fetch_open_orders(symbol).await,
+                // MEXC Spot отдаёт открытые ордера только по конкретной паре
+                None => {
+                    return Err(ExchangeError::UnsupportedOperation(
+                        "открытые ордера MEXC Spot без указания пары".to_string(),
+                    )
+                    .to_string());
+                }
+            },
+            RestClientWrapper::MexcSwap(client) => client.fetch_open_orders(symbol).await,
+            RestClientWrapper::BingxSpot(client) => client.fetch_open_orders(symbol).await,
+            RestClientWrapper::BingxSwap(client) => client.fetch_open_orders(symbol).await,
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение открытых ордеров на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    async fn fetch_positions(&self, symbol: Option<&str>) -> Result<String, String> {
+        let result = match self {
+            RestClientWrapper::MexcSwap(client) => client.fetch_positions(symbol).await,
+            RestClientWrapper::BingxSwap(client) => client.get_position(symbol).await,
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение позиций на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    async fn fetch_klines(
+        &self,
+        symbol: &str,
+        interval_secs: usize,
+        limit: u32,
+    ) -> Result<String, String> {
+        let interval = candle::interval_name(&self.exchange_type(), interval_secs);
+        let result = match self {
+            RestClientWrapper::MexcSpot(_) => {
+                MexcSpotRestClient::fetch_klines(symbol, interval?, limit).await
+            }
+            RestClientWrapper::MexcSwap(_) => {
+                // MEXC Swap принимает не лимит, а начало периода в секундах
+                let now = chrono::Utc::now().timestamp() as u64;
+                let start = now.saturating_sub(interval_secs as u64 * limit as u64);
+                MexcSwapRestClient::fetch_klines(symbol, interval?, Some(start), None).await
+            }
+            RestClientWrapper::BingxSpot(_) => {
+                BingxSpotRestClient::fetch_klines(symbol, interval?, limit).await
+            }
+            RestClientWrapper::BingxSwap(_) => {
+                BingxSwapRestClient::fetch_klines(symbol, interval?, limit).await
+            }
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение свечей на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    async fn fetch_funding_rate_history(
+        &self,
+        symbol: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<FundingRate>, String> {
+        let result = match self {
+            RestClientWrapper::MexcSwap(_) => {
+                MexcSwapRestClient::fetch_funding_rate_history(symbol, start, end, limit).await
+            }
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение истории ставок финансирования на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
     async fn get_listen_key(&self) -> Result<String, String> {
         let result = match self {
             RestClientWrapper::MexcSpot(client) => client.get_listen_key().await,
@@ -177,6 +345,89 @@ impl ExchangeClient for RestClientWrapper {
     }
 }
 
+impl RestClientWrapper {
+    /// Создать лимитный ордер с клиентским ID, по которому его можно найти через
+    /// [`Self::query_order`]
+    async fn create_limit_order_with_client_id(
+        &self,
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+        client_order_id: &str,
+    ) -> Result<String, String> {
+        let result = match self {
+            RestClientWrapper::MexcSpot(client) => {
+                client
+                    .create_order_with_client_id(
+                        symbol,
+                        side,
+                        quantity,
+                        price,
+                        None,
+                        TimeInForce::Gtc,
+                        false,
+                        Some(client_order_id),
+                    )
+                    .await
+            }
+            RestClientWrapper::BingxSpot(client) => {
+                client
+                    .create_order_with_client_id(
+                        symbol,
+                        side,
+                        quantity,
+                        Some(price),
+                        "LIMIT",
+                        Some(client_order_id),
+                    )
+                    .await
+            }
+            _ => return self.create_limit_order(symbol, side, quantity, price).await,
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    /// Найти ордер по клиентскому ID: `Ok(None)`, если биржа его не знает
+    async fn query_order(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<Option<String>, String> {
+        let result = match self {
+            RestClientWrapper::MexcSpot(client) => {
+                client.query_order(symbol, client_order_id).await
+            }
+            RestClientWrapper::BingxSpot(client) => {
+                client.get_order_status(symbol, None, Some(client_order_id.to_string())).await
+            }
+            _ => {
+                return Err(ExchangeError::UnsupportedOperation(format!(
+                    "получение ордера на {:?}",
+                    self.exchange_type()
+                ))
+                .to_string());
+            }
+        };
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) if ExchangeError::classify(e.to_string()).is_order_not_found() => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        // BingX сообщает об ошибке кодом в теле ответа с HTTP 200
+        let error = ExchangeError::from_api_error(response.as_str());
+        match error.api_code() {
+            Some(code) if code != 0 && error.is_order_not_found() => Ok(None),
+            Some(code) if code != 0 => Err(response),
+            _ => Ok(Some(response)),
+        }
+    }
+}
+
 /// Фабрика для создания клиентов бирж
 pub struct ExchangeClientFactory;
 
@@ -302,12 +553,25 @@ impl ExchangeClientFactory {
 /// Основной унифицированный REST клиент для всех криптовалютных бирж
 pub struct CryptoRestClient {
     clients: HashMap<ExchangeType, RestClientWrapper>,
+    /// Кэш списков инструментов: рынок биржи -> (символ -> тип рынка)
+    market_listings: Mutex<HashMap<ExchangeType, HashMap<String, MarketType>>>,
+    /// Количество повторов отправки ордера при временных ошибках
+    retry_attempts: u32,
 }
 
 impl CryptoRestClient {
     /// Создание нового пустого клиента
     pub fn new() -> Self {
-        Self { clients: HashMap::new() }
+        Self {
+            clients: HashMap::new(),
+            market_listings: Mutex::new(HashMap::new()),
+            retry_attempts: MultiExchangeConfig::default().retry_attempts,
+        }
+    }
+
+    /// Установить количество повторов отправки ордера при временных ошибках
+    pub fn set_retry_attempts(&mut self, attempts: u32) {
+        self.retry_attempts = attempts;
     }
 
     /// Добавить биржу в клиент
@@ -351,6 +615,13 @@ impl CryptoRestClient {
     }
 
     /// Создать лимитный ордер для указанной биржи
+    ///
+    /// Ордер отправляется с клиентским ID (MEXC Spot, BingX Spot). Если запрос не дошёл
+    /// до биржи, отправка повторяется до `retry_attempts` раз с задержкой и случайным
+    /// разбросом; отказы биржи не повторяются. Если ответ потерян после отправки
+    /// (таймаут, разрыв соединения), ордер ищется по клиентскому ID: найденный
+    /// возвращается как результат, а если проверить не удалось — возвращается ошибка
+    /// о неизвестном результате отправки.
     pub async fn create_limit_order(
         &self,
         exchange_type: &ExchangeType,
@@ -360,7 +631,25 @@ impl CryptoRestClient {
         price: f64,
     ) -> Result<String, String> {
         match self.clients.get(exchange_type) {
-            Some(client) => client.create_limit_order(symbol, side, quantity, price).await,
+            Some(client) => {
+                let client_order_id = new_client_order_id();
+                let client_order_id = client_order_id.as_str();
+                place_order_with_retry(
+                    self.retry_attempts,
+                    ORDER_RETRY_BASE_DELAY,
+                    || {
+                        client.create_limit_order_with_client_id(
+                            symbol,
+                            side,
+                            quantity,
+                            price,
+                            client_order_id,
+                        )
+                    },
+                    || client.query_order(symbol, client_order_id),
+                )
+                .await
+            }
             None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
         }
     }
@@ -378,6 +667,192 @@ impl CryptoRestClient {
         }
     }
 
+    /// Изменить цену и количество ордера без отмены и повторного создания.
+    ///
+    /// Поддерживается не всеми биржами, см. [`ExchangeType::supports_order_amend`]; пока
+    /// ни MEXC, ни BingX не умеют изменять ордер на месте. Повторяется так же, как [`create_limit_order`](Self::create_limit_order), но
+    /// без поиска ордера: при потерянном ответе возвращается ошибка о неизвестном
+    /// результате.
+    pub async fn amend_order(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        order_id: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+    ) -> Result<String, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => {
+                place_order_with_retry(
+                    self.retry_attempts,
+                    ORDER_RETRY_BASE_DELAY,
+                    || client.amend_order(symbol, order_id, side, quantity, price),
+                    lookup_unsupported,
+                )
+                .await
+            }
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Атомарно отменить ордер и выставить новый, без окна без котировки.
+    ///
+    /// Поддерживается только BingX Spot; на остальных биржах возвращается ошибка
+    /// «Операция не поддерживается». Новый ордер встаёт в конец очереди. Повторяется
+    /// так же, как [`amend_order`](Self::amend_order).
+    pub async fn cancel_replace_order(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        cancel_order_id: &str,
+        new_side: &str,
+        new_quantity: f64,
+        new_price: f64,
+    ) -> Result<CancelReplaceResponse, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => CancelReplaceResponse::from_json(
+                &place_order_with_retry(
+                    self.retry_attempts,
+                    ORDER_RETRY_BASE_DELAY,
+                    || {
+                        client.cancel_replace_order(
+                            symbol,
+                            cancel_order_id,
+                            new_side,
+                            new_quantity,
+                            new_price,
+                        )
+                    },
+                    lookup_unsupported,
+                )
+                .await?,
+            ),
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Получить открытые позиции фьючерсного аккаунта
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи (пока поддерживается только MEXC Swap)
+    /// * `symbol` - Контракт; `None` — позиции по всем контрактам
+    pub async fn fetch_positions(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: Option<&str>,
+    ) -> Result<Vec<Position>, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => Position::list_from_json(&client.fetch_positions(symbol).await?),
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Получить балансы, открытые ордера и позиции аккаунта одним снимком
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи (MEXC Swap, BingX Spot и Swap; MEXC Spot не отдаёт
+    ///   открытые ордера без указания пары)
+    pub async fn account_snapshot(
+        &self,
+        exchange_type: &ExchangeType,
+    ) -> Result<AccountSnapshot, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => client.account_snapshot().await,
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Получить свечи в едином формате [`Candle`]
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи (MEXC и BingX, spot и swap)
+    /// * `symbol` - Торговая пара в формате биржи
+    /// * `interval_secs` - Длительность свечи в секундах, например 60 для 1 минуты
+    /// * `limit` - Количество последних свечей
+    pub async fn fetch_candles(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        interval_secs: usize,
+        limit: u32,
+    ) -> Result<Vec<Candle>, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => {
+                let json = client.fetch_klines(symbol, interval_secs, limit).await?;
+                let mut candles = candle::list_from_json(exchange_type, &json)?;
+                let extra = candles.len().saturating_sub(limit as usize);
+                candles.drain(..extra);
+                Ok(candles)
+            }
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Получить историю ставок финансирования в едином формате [`FundingRate`]
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи (пока поддерживается только MEXC Swap)
+    /// * `symbol` - Контракт в формате биржи
+    /// * `start`, `end` - Границы периода в миллисекундах Unix
+    /// * `limit` - Количество последних ставок; `None` — все ставки периода
+    pub async fn fetch_funding_rate_history(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<FundingRate>, String> {
+        match self.clients.get(exchange_type) {
+            Some(client) => client.fetch_funding_rate_history(symbol, start, end, limit).await,
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
+    /// Получить снимок стакана в едином формате [`OrderBookUpdate`]
+    ///
+    /// Снимок подходит как начальное состояние для [`OrderBookManager`](crate::OrderBookManager)
+    /// перед применением WebSocket обновлений.
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи (MEXC и BingX, spot и swap)
+    /// * `symbol` - Торговая пара в формате биржи
+    /// * `limit` - Количество лучших уровней каждой стороны
+    pub async fn fetch_orderbook(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        limit: usize,
+    ) -> Result<OrderBookUpdate, String> {
+        let json = self.fetch_l2_snapshot(exchange_type, symbol).await?;
+        let mut snapshot = OrderBookUpdate::from_rest_json(exchange_type, symbol, &json)?;
+        snapshot.bids.truncate(limit);
+        snapshot.asks.truncate(limit);
+        Ok(snapshot)
+    }
+
+    /// Проверить API ключи биржи лёгким подписанным запросом.
+    ///
+    /// `Ok(false)` означает, что биржа отклонила ключи (или они не заданы); сетевые и
+    /// прочие ошибки возвращаются как `Err`. MEXC Spot проверяется через
+    /// [`MexcSpotRestClient::validate_credentials`], остальные биржи — запросом балансов.
+    pub async fn validate_credentials(&self, exchange_type: &ExchangeType) -> Result<bool, String> {
+        match self.clients.get(exchange_type) {
+            Some(RestClientWrapper::MexcSpot(client)) => {
+                client.validate_credentials().await.map_err(|e| e.to_string())
+            }
+            Some(client) => match client.fetch_balances().await {
+                Ok(_) => Ok(true),
+                Err(e) => match ExchangeError::classify(e.clone()) {
+                    ExchangeError::AuthError(_) => Ok(false),
+                    _ => Err(e),
+                },
+            },
+            None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
+        }
+    }
+
     /// Получить список доступных бирж
     pub fn get_available_exchanges(&self) -> Vec<ExchangeType> {
         self.clients.keys().cloned().collect()
@@ -413,6 +888,225 @@ impl CryptoRestClient {
             None => Err(format!("Клиент для биржи {:?} не настроен", exchange_type)),
         }
     }
+
+    /// Определить тип рынка символа на бирже
+    ///
+    /// Символ ищется сначала в списке инструментов рынка `exchange_type`, затем на
+    /// соседнем рынке той же биржи (spot <-> swap). Списки загружаются из exchangeInfo
+    /// и эндпоинта контрактов один раз и кэшируются.
+    pub async fn resolve_market_type(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Result<MarketType, String> {
+        let markets = match exchange_type {
+            ExchangeType::MexcSpot | ExchangeType::MexcSwap => {
+                [ExchangeType::MexcSpot, ExchangeType::MexcSwap]
+            }
+            ExchangeType::BingxSpot | ExchangeType::BingxSwap => {
+                [ExchangeType::BingxSpot, ExchangeType::BingxSwap]
+            }
+            _ => {
+                return Err(format!(
+                    "Определение типа рынка не поддерживается для биржи {:?}",
+                    exchange_type
+                ));
+            }
+        };
+        // Рынок, указанный вызывающим, проверяется первым
+        let (own, sibling): (Vec<_>, Vec<_>) =
+            markets.into_iter().partition(|market| market == exchange_type);
+        let symbol = symbol.trim().to_uppercase();
+
+        for market in own.into_iter().chain(sibling) {
+            let loaded = self
+                .market_listings
+                .lock()
+                .map_err(|_| "Ошибка блокировки кэша типов рынка")?
+                .contains_key(&market);
+            if !loaded {
+                let listing = Self::fetch_market_listing(&market).await?;
+                self.market_listings
+                    .lock()
+                    .map_err(|_| "Ошибка блокировки кэша типов рынка")?
+                    .insert(market.clone(), listing);
+            }
+
+            let market_type = self
+                .market_listings
+                .lock()
+                .map_err(|_| "Ошибка блокировки кэша типов рынка")?
+                .get(&market)
+                .and_then(|listing| listing.get(&symbol).copied());
+            if let Some(market_type) = market_type {
+                return Ok(market_type);
+            }
+        }
+
+        Err(format!("Символ {} не найден на рынках биржи {:?}", symbol, exchange_type))
+    }
+
+    /// Загрузить список инструментов рынка биржи
+    async fn fetch_market_listing(
+        market: &ExchangeType,
+    ) -> Result<HashMap<String, MarketType>, String> {
+        match market {
+            ExchangeType::MexcSpot => {
+                let json =
+                    MexcSpotRestClient::fetch_exchange_info().await.map_err(|e| e.to_string())?;
+                Self::parse_mexc_spot_exchange_info(&json)
+            }
+            ExchangeType::MexcSwap => {
+                let json = MexcSwapRestClient::fetch_contract_details()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Self::parse_mexc_swap_contracts(&json)
+            }
+            ExchangeType::BingxSpot => {
+                let symbols =
+                    BingxSpotRestClient::fetch_all_symbols().await.map_err(|e| e.to_string())?;
+                Ok(Self::listing_from_symbols(&symbols, MarketType::Spot))
+            }
+            ExchangeType::BingxSwap => {
+                // BingX Perpetual Futures - контракты с маржой в USDT
+                let symbols =
+                    BingxSwapRestClient::fetch_all_symbols().await.map_err(|e| e.to_string())?;
+                Ok(Self::listing_from_symbols(&symbols, MarketType::LinearSwap))
+            }
+            _ => Err(format!("Список инструментов для {:?} не поддерживается", market)),
+        }
+    }
+
+    /// Разобрать ответ MEXC `/api/v3/exchangeInfo`
+    fn parse_mexc_spot_exchange_info(json: &str) -> Result<HashMap<String, MarketType>, String> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| format!("Ошибка парсинга exchangeInfo MEXC: {}", e))?;
+        let symbols =
+            value["symbols"].as_array().ok_or("В exchangeInfo MEXC отсутствует список symbols")?;
+        Ok(Self::listing_from_symbols(symbols, MarketType::Spot))
+    }
+
+    /// Разобрать ответ MEXC `/api/v1/contract/detail`
+    ///
+    /// Контракт линейный, если маржа и расчеты ведутся в валюте котировки.
+    fn parse_mexc_swap_contracts(json: &str) -> Result<HashMap<String, MarketType>, String> {
+        let value: Value = serde_json::from_str(json)
+            .map_err(|e| format!("Ошибка парсинга контрактов MEXC: {}", e))?;
+        let contracts =
+            value["data"].as_array().ok_or("В ответе контрактов MEXC отсутствует поле data")?;
+
+        Ok(contracts
+            .iter()
+            .filter_map(|contract| {
+                let symbol = contract["symbol"].as_str()?;
+                let market_type = if contract["settleCoin"] == contract["quoteCoin"] {
+                    MarketType::LinearSwap
+                } else {
+                    MarketType::InverseSwap
+                };
+                Some((symbol.to_uppercase(), market_type))
+            })
+            .collect())
+    }
+
+    fn listing_from_symbols(
+        symbols: &[Value],
+        market_type: MarketType,
+    ) -> HashMap<String, MarketType> {
+        symbols
+            .iter()
+            .filter_map(|item| item["symbol"].as_str())
+            .map(|symbol| (symbol.to_uppercase(), market_type))
+            .collect()
+    }
+}
+
+/// Отправить ордер, повторяя попытку только если запрос не дошёл до биржи.
+///
+/// Если ответ потерян после отправки, биржа могла принять ордер, и слепой повтор
+/// создал бы дубликат. Тогда `lookup` ищет ордер по клиентскому ID: найденный
+/// возвращается как результат, ненайденный отправляется повторно. Если проверить не
+/// удалось, возвращается ошибка о неизвестном результате отправки.
+async fn place_order_with_retry<F, Fut, L, LFut>(
+    retry_attempts: u32,
+    base_delay: Duration,
+    mut place: F,
+    mut lookup: L,
+) -> Result<String, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+    L: FnMut() -> LFut,
+    LFut: Future<Output = Result<Option<String>, String>>,
+{
+    let mut attempt = 0;
+    loop {
+        let message = match place().await {
+            Ok(response) => return Ok(response),
+            Err(message) => message,
+        };
+
+        let error = ExchangeError::classify(message.as_str());
+        if error.is_outcome_unknown() {
+            match lookup().await {
+                Ok(Some(order)) => {
+                    warn!("Ответ на отправку ордера потерян ({}), ордер найден на бирже", message);
+                    return Ok(order);
+                }
+                Ok(None) => {
+                    warn!(
+                        "Ответ на отправку ордера потерян ({}), ордер на бирже не найден",
+                        message
+                    )
+                }
+                Err(lookup_error) => {
+                    return Err(format!(
+                        "Результат отправки ордера неизвестен: биржа могла принять ордер ({}), \
+                         проверка не удалась: {}",
+                        message, lookup_error
+                    ));
+                }
+            }
+        } else if !error.is_retryable() {
+            return Err(message);
+        }
+        if attempt >= retry_attempts {
+            return Err(message);
+        }
+
+        attempt += 1;
+        let delay = jittered_backoff(base_delay, attempt);
+        warn!(
+            "Временная ошибка при отправке ордера ({}), повтор {}/{} через {:?}",
+            message, attempt, retry_attempts, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Проверка для операций, которые нельзя найти по клиентскому ID: результат после
+/// потерянного ответа остаётся неизвестным
+async fn lookup_unsupported() -> Result<Option<String>, String> {
+    Err("операцию нельзя найти по клиентскому ID".to_string())
+}
+
+/// Клиентский ID ордера, общий для всех попыток отправки: по нему ордер ищется после
+/// потерянного ответа, а повторный ордер с тем же ID биржа отклоняет
+fn new_client_order_id() -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(millis);
+    format!("cc{:x}{:016x}", millis, hasher.finish())
+}
+
+/// Экспоненциальная задержка со случайным разбросом в диапазоне [половина, целое],
+/// чтобы клиенты не повторяли запросы одновременно
+fn jittered_backoff(base_delay: Duration, attempt: u32) -> Duration {
+    let backoff = base_delay.saturating_mul(1 << attempt.saturating_sub(1).min(6));
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(attempt);
+    let jitter = hasher.finish() % 1000;
+    backoff / 2 + backoff.mul_f64(jitter as f64 / 2000.0)
 }
 
 impl Default for CryptoRestClient {
@@ -420,3 +1114,280 @@ impl Default for CryptoRestClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEXC_EXCHANGE_INFO: &str = r#"{"timezone":"CST","serverTime":1755487578276,"symbols":[
+        {"symbol":"BTCUSDT","status":"1","baseAsset":"BTC","quoteAsset":"USDT"},
+        {"symbol":"ETHUSDT","status":"1","baseAsset":"ETH","quoteAsset":"USDT"}]}"#;
+
+    const MEXC_CONTRACT_DETAIL: &str = r#"{"success":true,"code":0,"data":[
+        {"symbol":"BTC_USDT","baseCoin":"BTC","quoteCoin":"USDT","settleCoin":"USDT"},
+        {"symbol":"BTC_USD","baseCoin":"BTC","quoteCoin":"USD","settleCoin":"BTC"}]}"#;
+
+    const NETWORK_ERROR: &str = "Request error: error sending request for url \
+        (https://api.mexc.com/api/v3/order): error trying to connect: Connection refused";
+
+    const TIMEOUT_ERROR: &str = "Request error: error sending request for url \
+        (https://api.mexc.com/api/v3/order): operation timed out";
+
+    const ORDER: &str = r#"{"orderId":"C02__1"}"#;
+
+    #[tokio::test]
+    async fn test_order_retry_on_network_error() {
+        let mut calls = 0;
+        let result = place_order_with_retry(
+            3,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                let response =
+                    if calls < 3 { Err(NETWORK_ERROR.to_string()) } else { Ok(ORDER.to_string()) };
+                async move { response }
+            },
+            || async {
+                panic!("запрос не был отправлен, проверять нечего")
+            },
+        )
+        .await;
+        assert_eq!(result.unwrap(), ORDER);
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let result = place_order_with_retry(
+            2,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                async { Err::<String, _>(NETWORK_ERROR.to_string()) }
+            },
+            lookup_unsupported,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_order_no_retry_on_reject() {
+        let reject =
+            r#"MEXC API error (400 Bad Request): {"code":10101,"msg":"Insufficient balance"}"#;
+
+        let mut calls = 0;
+        let result = place_order_with_retry(
+            3,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                async move { Err::<String, _>(reject.to_string()) }
+            },
+            lookup_unsupported,
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), reject);
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn test_order_lookup_after_lost_response() {
+        let reset = "Request error: error sending request for url \
+            (https://api.mexc.com/api/v3/order): connection closed before message completed";
+
+        // Ордер принят биржей: возвращается найденный ордер без повторной отправки
+        for error in [TIMEOUT_ERROR, reset] {
+            let mut calls = 0;
+            let result = place_order_with_retry(
+                3,
+                Duration::from_millis(1),
+                || {
+                    calls += 1;
+                    async move { Err::<String, _>(error.to_string()) }
+                },
+                || async { Ok(Some(ORDER.to_string())) },
+            )
+            .await;
+            assert_eq!(result.unwrap(), ORDER);
+            assert_eq!(calls, 1);
+        }
+
+        // Ордера на бирже нет: отправка повторяется
+        let mut calls = 0;
+        let result = place_order_with_retry(
+            3,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                let response =
+                    if calls < 2 { Err(TIMEOUT_ERROR.to_string()) } else { Ok(ORDER.to_string()) };
+                async move { response }
+            },
+            || async { Ok(None) },
+        )
+        .await;
+        assert_eq!(result.unwrap(), ORDER);
+        assert_eq!(calls, 2);
+
+        // Проверить не удалось: результат неизвестен, повтора нет
+        let mut calls = 0;
+        let result = place_order_with_retry(
+            3,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                async { Err::<String, _>(TIMEOUT_ERROR.to_string()) }
+            },
+            lookup_unsupported,
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(error.starts_with("Результат отправки ордера неизвестен"), "{}", error);
+        assert!(error.contains("operation timed out"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_client_order_id_format() {
+        let first = new_client_order_id();
+        let second = new_client_order_id();
+        assert_ne!(first, second);
+        // MEXC принимает до 32 символов [A-Za-z0-9_-], BingX — до 40
+        assert!(first.len() <= 32);
+        assert!(first.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_catalog_matches_implementations() {
+        let catalog = ExchangeType::catalog();
+        let all = ExchangeType::all();
+        assert_eq!(catalog.len(), all.len());
+        assert!(
+            all.iter().all(|exchange| catalog.iter().any(|info| info.exchange_type == *exchange))
+        );
+
+        for info in &catalog {
+            assert!(!info.display_name.is_empty());
+            assert_eq!(info.supports_websocket, info.exchange_type.supports_websocket());
+
+            if info.supports_rest_trading {
+                continue;
+            }
+            // Без поддержки торговли REST клиент отклоняет ордер до обращения к бирже
+            let config = ExchangeConfig::new(None, None);
+            let Ok(client) =
+                ExchangeClientFactory::create_client(info.exchange_type.clone(), config)
+            else {
+                // REST клиента для биржи нет вовсе
+                continue;
+            };
+            let error = client.create_limit_order("BTCUSDT", "BUY", 1.0, 1.0).await.unwrap_err();
+            assert!(error.contains("не поддерживается"), "{:?}: {}", info.exchange_type, error);
+        }
+
+        let trading: Vec<_> = catalog.iter().filter(|info| info.supports_rest_trading).collect();
+        assert_eq!(trading.len(), 2);
+
+        let mexc_swap = catalog.iter().find(|info| info.exchange_type == ExchangeType::MexcSwap);
+        assert_eq!(mexc_swap.unwrap().market_type, MarketType::LinearSwap);
+    }
+
+    #[tokio::test]
+    async fn test_amend_order_unsupported_exchange() {
+        // cancelReplace BingX Spot теряет место в очереди и изменением не считается
+        assert!(!ExchangeType::BingxSpot.supports_order_amend());
+        assert!(!ExchangeType::MexcSpot.supports_order_amend());
+
+        let mut client = CryptoRestClient::new();
+        client.add_exchange(ExchangeType::MexcSpot, ExchangeConfig::new(None, None)).unwrap();
+        let error = client
+            .amend_order(&ExchangeType::MexcSpot, "BTCUSDT", "C02__1", "BUY", 1.0, 50000.0)
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Операция не поддерживается: изменение ордера на MexcSpot");
+
+        client.add_exchange(ExchangeType::BingxSpot, ExchangeConfig::new(None, None)).unwrap();
+        let error = client
+            .amend_order(&ExchangeType::BingxSpot, "BTC-USDT", "1", "BUY", 1.0, 50000.0)
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Операция не поддерживается: изменение ордера на BingxSpot");
+
+        let error = client
+            .cancel_replace_order(&ExchangeType::MexcSpot, "BTCUSDT", "C02__1", "SELL", 1.0, 1.0)
+            .await
+            .unwrap_err();
+        assert_eq!(error, "Операция не поддерживается: cancel-replace ордера на MexcSpot");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_positions_routing() {
+        let mut client = CryptoRestClient::new();
+        client.add_exchange(ExchangeType::MexcSpot, ExchangeConfig::new(None, None)).unwrap();
+        client.add_exchange(ExchangeType::MexcSwap, ExchangeConfig::new(None, None)).unwrap();
+
+        let error = client.fetch_positions(&ExchangeType::MexcSpot, None).await.unwrap_err();
+        assert_eq!(error, "Операция не поддерживается: получение позиций на MexcSpot");
+        let error =
+            client.fetch_positions(&ExchangeType::MexcSwap, Some("BTC_USDT")).await.unwrap_err();
+        assert!(error.contains("API key and secret are required"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_candles_routing() {
+        let mut client = CryptoRestClient::new();
+        client.add_exchange(ExchangeType::BinanceSpot, ExchangeConfig::new(None, None)).unwrap();
+        client.add_exchange(ExchangeType::MexcSpot, ExchangeConfig::new(None, None)).unwrap();
+
+        let error =
+            client.fetch_candles(&ExchangeType::BinanceSpot, "BTCUSDT", 60, 10).await.unwrap_err();
+        assert_eq!(error, "Операция не поддерживается: получение свечей на BinanceSpot");
+        // MEXC Spot не поддерживает 3-минутные свечи, запрос не отправляется
+        let error =
+            client.fetch_candles(&ExchangeType::MexcSpot, "BTCUSDT", 180, 10).await.unwrap_err();
+        assert!(error.contains("Интервал 180"));
+        assert!(client.fetch_candles(&ExchangeType::BingxSpot, "BTC-USDT", 60, 10).await.is_err());
+    }
+
+    #[test]
+    fn test_jittered_backoff_bounds() {
+        let base = Duration::from_millis(200);
+        for attempt in 1..=4 {
+            let full = base * (1 << (attempt - 1));
+            let delay = jittered_backoff(base, attempt);
+            assert!(delay >= full / 2 && delay <= full);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_market_type_from_cached_listings() {
+        let client = CryptoRestClient::new();
+        {
+            let mut listings = client.market_listings.lock().unwrap();
+            listings.insert(
+                ExchangeType::MexcSpot,
+                CryptoRestClient::parse_mexc_spot_exchange_info(MEXC_EXCHANGE_INFO).unwrap(),
+            );
+            listings.insert(
+                ExchangeType::MexcSwap,
+                CryptoRestClient::parse_mexc_swap_contracts(MEXC_CONTRACT_DETAIL).unwrap(),
+            );
+        }
+
+        // Символ контракта находится через соседний рынок той же биржи
+        let market_type =
+            client.resolve_market_type(&ExchangeType::MexcSpot, "btc_usdt").await.unwrap();
+        assert_eq!(market_type, MarketType::LinearSwap);
+        assert_eq!(
+            client.resolve_market_type(&ExchangeType::MexcSwap, "BTCUSDT").await.unwrap(),
+            MarketType::Spot
+        );
+        assert_eq!(
+            client.resolve_market_type(&ExchangeType::MexcSwap, "BTC_USD").await.unwrap(),
+            MarketType::InverseSwap
+        );
+
+        assert!(client.resolve_market_type(&ExchangeType::MexcSpot, "DOGE_BTC").await.is_err());
+        assert!(client.resolve_market_type(&ExchangeType::KrakenSpot, "XBTUSD").await.is_err());
+    }
+}
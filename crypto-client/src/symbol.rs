@@ -0,0 +1,120 @@
+//! Разбор биржевых символов на базовый и котируемый актив.
+
+use std::collections::HashMap;
+
+use crate::exchange_type::ExchangeType;
+
+/// Ручные разбиения символов: (биржа, сырой символ) -> (base, quote)
+pub type SymbolOverrides = HashMap<(ExchangeType, String), (String, String)>;
+
+/// Котируемые активы, по которым делится символ без разделителя
+const QUOTE_ASSETS: &[&str] = &["USDT", "USDC", "FDUSD", "BUSD", "USD", "BTC", "ETH", "EUR"];
+
+/// Нормализатор символов с таблицей ручных разбиений.
+///
+/// Таблица проверяется раньше эвристики, поэтому через неё задаются символы с неоднозначным
+/// разбиением: `1000PEPEUSDT`, плечевые токены, индексы.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolNormalizer {
+    overrides: SymbolOverrides,
+}
+
+impl SymbolNormalizer {
+    /// Создать нормализатор без ручных разбиений
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавить таблицу ручных разбиений
+    pub fn with_overrides(mut self, overrides: SymbolOverrides) -> Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
+    /// Добавить ручное разбиение одного символа
+    pub fn with_override(
+        mut self,
+        exchange_type: ExchangeType,
+        symbol: &str,
+        base: &str,
+        quote: &str,
+    ) -> Self {
+        self.overrides
+            .insert((exchange_type, symbol.to_string()), (base.to_string(), quote.to_string()));
+        self
+    }
+
+    /// Разбить символ биржи на (base, quote).
+    ///
+    /// Сначала ищется ручное разбиение, затем символ делится по разделителю (`BTC_USDT`,
+    /// `BTC-USDT`, `BTC/USDT`) или по известному котируемому активу в конце (`BTCUSDT`).
+    /// Возвращает `None`, если символ не удалось разобрать.
+    pub fn normalize_symbol(
+        &self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Option<(String, String)> {
+        if let Some(pair) = self.overrides.get(&(exchange_type.clone(), symbol.to_string())) {
+            return Some(pair.clone());
+        }
+        split_symbol(symbol)
+    }
+}
+
+/// Эвристическое разбиение символа
+fn split_symbol(symbol: &str) -> Option<(String, String)> {
+    let symbol = symbol.to_uppercase();
+
+    if let Some((base, quote)) = symbol.split_once(['_', '-', '/']) {
+        if base.is_empty() || quote.is_empty() {
+            return None;
+        }
+        return Some((base.to_string(), quote.to_string()));
+    }
+
+    // Самый длинный подходящий актив: FDUSD раньше USD
+    QUOTE_ASSETS
+        .iter()
+        .filter(|quote| symbol.len() > quote.len() && symbol.ends_with(*quote))
+        .max_by_key(|quote| quote.len())
+        .map(|quote| (symbol[..symbol.len() - quote.len()].to_string(), quote.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(base: &str, quote: &str) -> Option<(String, String)> {
+        Some((base.to_string(), quote.to_string()))
+    }
+
+    #[test]
+    fn test_override_takes_precedence_over_split() {
+        let normalizer = SymbolNormalizer::new();
+        assert_eq!(
+            normalizer.normalize_symbol(&ExchangeType::MexcSpot, "1000PEPEUSDT"),
+            pair("1000PEPE", "USDT")
+        );
+        assert_eq!(
+            normalizer.normalize_symbol(&ExchangeType::MexcSwap, "BTC_USDT"),
+            pair("BTC", "USDT")
+        );
+        assert_eq!(
+            normalizer.normalize_symbol(&ExchangeType::BingxSpot, "ETHFDUSD"),
+            pair("ETH", "FDUSD")
+        );
+        assert_eq!(normalizer.normalize_symbol(&ExchangeType::MexcSpot, "BTCTRY"), None);
+
+        let normalizer =
+            normalizer.with_override(ExchangeType::MexcSpot, "1000PEPEUSDT", "PEPE", "USDT");
+        assert_eq!(
+            normalizer.normalize_symbol(&ExchangeType::MexcSpot, "1000PEPEUSDT"),
+            pair("PEPE", "USDT")
+        );
+        // Разбиение привязано к бирже
+        assert_eq!(
+            normalizer.normalize_symbol(&ExchangeType::BingxSpot, "1000PEPEUSDT"),
+            pair("1000PEPE", "USDT")
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Типизированная 24-часовая статистика по торговой паре из публичных ticker каналов.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ChannelType, WsMessage};
+
+/// Тикер: последняя цена и статистика за 24 часа
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ticker {
+    pub symbol: String,
+    pub last_price: f64,
+    /// Изменение цены за период в долях (0.01 = +1%)
+    pub price_change_rate: f64,
+    pub high: f64,
+    pub low: f64,
+    /// Объём в базовой валюте (для MEXC Swap — в контрактах)
+    pub volume: f64,
+    /// Оборот в валюте котировки
+    pub quote_volume: f64,
+    /// Лучшая цена покупки, если биржа передаёт её в тикере
+    pub best_bid: Option<f64>,
+    /// Лучшая цена продажи, если биржа передаёт её в тикере
+    pub best_ask: Option<f64>,
+    /// Время события в миллисекундах Unix
+    pub time: u64,
+}
+
+/// Лучшая цена одной стороны сводного BBO и биржа, с которой она получена
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BboQuote {
+    pub exchange: ExchangeType,
+    pub price: f64,
+    /// Время тикера в миллисекундах Unix
+    pub time: u64,
+}
+
+/// Сводные лучшие цены по символу на всех подключённых биржах
+/// (см. [`CryptoWsClient::consolidated_bbo`](crate::CryptoWsClient::consolidated_bbo))
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedBbo {
+    /// Символ в нормализованном виде (`BTCUSDT`)
+    pub symbol: String,
+    /// Наибольшая цена покупки
+    pub bid: Option<BboQuote>,
+    /// Наименьшая цена продажи
+    pub ask: Option<BboQuote>,
+}
+
+impl ConsolidatedBbo {
+    /// Разница между лучшей продажей и лучшей покупкой; отрицательна, если цены
+    /// бирж пересекаются
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask.as_ref()?.price - self.bid.as_ref()?.price)
+    }
+}
+
+impl WsMessage {
+    /// Разобрать сообщение ticker канала в [`Ticker`].
+    ///
+    /// Поддерживаются miniTicker MEXC Spot и `push.ticker` MEXC Swap. Для остальных
+    /// сообщений возвращает `None`.
+    pub fn as_ticker(&self) -> Option<Ticker> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSpot, ChannelType::Ticker) => mexc_mini_ticker(&self.data),
+            (ExchangeType::MexcSwap, ChannelType::Ticker) => mexc_swap_ticker(&self.data),
+            _ => None,
+        }
+    }
+}
+
+impl WsMessage {
+    /// Индексная цена из сообщения канала [`ChannelType::IndexPrice`].
+    ///
+    /// Поддерживается `push.index.price` MEXC Swap. Для остальных сообщений
+    /// возвращает `None`.
+    pub fn as_index_price(&self) -> Option<f64> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSwap, ChannelType::IndexPrice) => {
+                self.data.get("data").map(|data| number(&data["price"]))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа
+fn number(value: &Value) -> f64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        value => value.as_f64().unwrap_or_default(),
+    }
+}
+
+/// miniTicker MEXC Spot: `volume` — оборот в валюте котировки, `quantity` — объём
+/// в базовой валюте. Лучших цен miniTicker не содержит
+fn mexc_mini_ticker(data: &Value) -> Option<Ticker> {
+    let ticker = data.get("d")?;
+    Some(Ticker {
+        symbol: ticker["symbol"].as_str()?.to_string(),
+        last_price: number(&ticker["price"]),
+        price_change_rate: number(&ticker["rate"]),
+        high: number(&ticker["high"]),
+        low: number(&ticker["low"]),
+        volume: number(&ticker["quantity"]),
+        quote_volume: number(&ticker["volume"]),
+        best_bid: None,
+        best_ask: None,
+        time: data["t"].as_u64().unwrap_or_default(),
+    })
+}
+
+fn mexc_swap_ticker(data: &Value) -> Option<Ticker> {
+    let ticker = data.get("data")?;
+    Some(Ticker {
+        symbol: data["symbol"].as_str().or_else(|| ticker["symbol"].as_str())?.to_string(),
+        last_price: number(&ticker["lastPrice"]),
+        price_change_rate: number(&ticker["riseFallRate"]),
+        high: number(&ticker["high24Price"]),
+        low: number(&ticker["lower24Price"]),
+        volume: number(&ticker["volume24"]),
+        quote_volume: number(&ticker["amount24"]),
+        best_bid: ticker.get("bid1").map(number),
+        best_ask: ticker.get("ask1").map(number),
+        time: ticker["timestamp"].as_u64().or_else(|| data["ts"].as_u64()).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    #[test]
+    fn test_parse_mexc_spot_mini_ticker() {
+        let message = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSpot,
+            r#"{"c":"spot@public.miniTicker.v3.api@BTCUSDT@UTC+0","d":{"symbol":"BTCUSDT",
+                "price":"64125.37","rate":"0.0123","zonedRate":"0.0098","high":"64500",
+                "low":"62870.01","volume":"512345678.9","quantity":"8012.35"},
+                "s":"BTCUSDT","t":1716000000123}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap();
+
+        assert_eq!(message.channel, ChannelType::Ticker);
+        assert_eq!(message.symbol, "BTC_USDT");
+        assert_eq!(
+            message.as_ticker(),
+            Some(Ticker {
+                symbol: "BTCUSDT".to_string(),
+                last_price: 64125.37,
+                price_change_rate: 0.0123,
+                high: 64500.0,
+                low: 62870.01,
+                volume: 8012.35,
+                quote_volume: 512345678.9,
+                best_bid: None,
+                best_ask: None,
+                time: 1716000000123,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_mexc_swap_index_price() {
+        let message = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSwap,
+            r#"{"channel":"push.index.price","symbol":"BTC_USDT",
+                "data":{"price":64118.3,"symbol":"BTC_USDT"},"ts":1716000000123}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap();
+
+        assert_eq!(message.channel, ChannelType::IndexPrice);
+        assert_eq!(message.symbol, "BTC_USDT");
+        assert_eq!(message.as_index_price(), Some(64118.3));
+        assert_eq!(message.as_ticker(), None);
+
+        // Маркировочная цена не выдаётся за индексную
+        let fair = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSwap,
+            r#"{"channel":"push.ticker","symbol":"BTC_USDT",
+                "data":{"fairPrice":64120.1,"indexPrice":64118.3,"lastPrice":64121},"ts":1}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap();
+        assert_eq!(fair.as_index_price(), None);
+    }
+}
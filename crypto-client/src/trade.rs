@@ -0,0 +1,85 @@
+//! Типизированные публичные сделки из WebSocket каналов.
+
+use serde_json::Value;
+
+pub use crypto_rest_client::{Trade, TradeSide};
+
+use crate::exchange_type::ExchangeType;
+use crate::ws_client::{ChannelType, WsMessage};
+
+impl WsMessage {
+    /// Разобрать сообщение канала сделок в список [`Trade`].
+    ///
+    /// Поддерживаются `deals` MEXC Spot (в одном кадре может быть несколько сделок).
+    /// Формат совпадает с
+    /// [`MexcSpotRestClient::fetch_trades_typed`](crypto_rest_client::MexcSpotRestClient::fetch_trades_typed),
+    /// поэтому история и поток сделок обрабатываются одним кодом. Для остальных
+    /// сообщений возвращает `None`.
+    pub fn as_trades(&self) -> Option<Vec<Trade>> {
+        match (&self.exchange, &self.channel) {
+            (ExchangeType::MexcSpot, ChannelType::Trades) => {
+                mexc_spot_deals(&self.symbol, &self.data)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Число из строки или JSON числа
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// `deals` MEXC Spot: `S` — сторона тейкера (1 — покупка, 2 — продажа)
+fn mexc_spot_deals(symbol: &str, data: &Value) -> Option<Vec<Trade>> {
+    data["d"]["deals"]
+        .as_array()?
+        .iter()
+        .map(|deal| {
+            Some(Trade {
+                symbol: symbol.to_string(),
+                trade_id: None,
+                price: number(&deal["p"])?,
+                quantity: number(&deal["v"])?,
+                side: match deal["S"].as_i64()? {
+                    1 => TradeSide::Buy,
+                    _ => TradeSide::Sell,
+                },
+                time: deal["t"].as_u64()?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::WsClientWrapper;
+
+    #[test]
+    fn test_parse_mexc_spot_deals() {
+        let trades = WsClientWrapper::parse_message_with_predicates(
+            ExchangeType::MexcSpot,
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","d":{"deals":[
+                {"S":2,"p":"64125.37","t":1716000000123,"v":"0.0123"},
+                {"S":1,"p":"64125.38","t":1716000000456,"v":"0.5"}],
+                "e":"spot@public.deals.v3.api"},"s":"BTCUSDT","t":1716000000460}"#,
+            &[],
+        )
+        .into_message()
+        .unwrap()
+        .as_trades()
+        .unwrap();
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].side, TradeSide::Sell);
+        assert_eq!(trades[0].price, 64125.37);
+        assert_eq!(trades[0].quantity, 0.0123);
+        assert_eq!(trades[0].time, 1716000000123);
+        assert_eq!(trades[1].side, TradeSide::Buy);
+        assert_eq!(trades[1].symbol, trades[0].symbol);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::exchange_type::ExchangeType;
+use crate::{AccountSnapshot, AssetBalance, FundingRate, OrderResponse, Position};
 use async_trait::async_trait;
 
 /// Базовый трейт для всех клиентов бирж
@@ -25,6 +26,120 @@ pub trait ExchangeClient: Send + Sync {
     /// Отменить ордер
     async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<String, String>;
 
+    /// Изменить цену и количество лимитного ордера одним запросом
+    /// (см. [`ExchangeType::supports_order_amend`])
+    async fn amend_order(
+        &self,
+        _symbol: &str,
+        _order_id: &str,
+        _side: &str,
+        _quantity: f64,
+        _price: f64,
+    ) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "изменение ордера на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Атомарно отменить ордер и выставить новый (сырой JSON биржи)
+    async fn cancel_replace_order(
+        &self,
+        _symbol: &str,
+        _cancel_order_id: &str,
+        _new_side: &str,
+        _new_quantity: f64,
+        _new_price: f64,
+    ) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "cancel-replace ордера на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Получить открытые позиции фьючерсного аккаунта (сырой JSON биржи)
+    async fn fetch_positions(&self, _symbol: Option<&str>) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "получение позиций на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Получить балансы всех активов аккаунта (сырой JSON биржи)
+    async fn fetch_balances(&self) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "получение балансов на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Получить открытые ордера (сырой JSON биржи)
+    ///
+    /// `symbol` — торговая пара; `None` — ордера по всем парам, если биржа это позволяет.
+    async fn fetch_open_orders(&self, _symbol: Option<&str>) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "получение открытых ордеров на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Получить нормализованный снимок аккаунта: балансы, открытые ордера и позиции
+    ///
+    /// Собирается из [`fetch_balances`](Self::fetch_balances),
+    /// [`fetch_open_orders`](Self::fetch_open_orders) и, для рынков с позициями,
+    /// [`fetch_positions`](Self::fetch_positions).
+    async fn account_snapshot(&self) -> Result<AccountSnapshot, String> {
+        let exchange_type = self.exchange_type();
+        let balances = AssetBalance::list_from_json(&exchange_type, &self.fetch_balances().await?)?;
+        let open_orders = OrderResponse::list_from_json(&self.fetch_open_orders(None).await?)?;
+        let positions = if exchange_type.has_positions() {
+            Position::list_from_json(&self.fetch_positions(None).await?)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(AccountSnapshot { balances, open_orders, positions })
+    }
+
+    /// Получить свечи (сырой JSON биржи)
+    ///
+    /// `interval_secs` — длительность свечи в секундах, например 60 для 1 минуты.
+    async fn fetch_klines(
+        &self,
+        _symbol: &str,
+        _interval_secs: usize,
+        _limit: u32,
+    ) -> Result<String, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "получение свечей на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
+    /// Получить историю ставок финансирования бессрочного контракта
+    ///
+    /// `start` и `end` — границы периода в миллисекундах Unix, `limit` — сколько
+    /// последних ставок оставить. Ставки упорядочены от старых к новым.
+    async fn fetch_funding_rate_history(
+        &self,
+        _symbol: &str,
+        _start: Option<u64>,
+        _end: Option<u64>,
+        _limit: Option<u32>,
+    ) -> Result<Vec<FundingRate>, String> {
+        Err(crate::ExchangeError::UnsupportedOperation(format!(
+            "получение истории ставок финансирования на {:?}",
+            self.exchange_type()
+        ))
+        .to_string())
+    }
+
     /// Получить listen_key для WebSocket приватных данных
     ///
     /// # Возвращает
@@ -59,6 +174,20 @@ pub trait WebSocketClient: Send + Sync {
     /// Подписаться на тикеры
     async fn subscribe_ticker(&mut self, symbol: &str) -> Result<(), String>;
 
+    /// Подписаться на индексную цену бессрочного контракта
+    async fn subscribe_index_price(&mut self, _symbol: &str) -> Result<(), String> {
+        Err("Подписка на индексную цену не поддерживается для этой биржи".to_string())
+    }
+
+    /// Подписаться на свечи (kline)
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара
+    /// * `interval` - Интервал свечи в секундах (например, 60 для 1 минуты)
+    async fn subscribe_kline(&mut self, _symbol: &str, _interval: usize) -> Result<(), String> {
+        Err("Подписка на свечи не поддерживается для этой биржи".to_string())
+    }
+
     /// Подписаться на баланс аккаунта
     ///
     /// # Параметры
@@ -73,6 +202,14 @@ pub trait WebSocketClient: Send + Sync {
     ///   Если None, клиент попытается подписаться без аутентификации
     async fn subscribe_private_deals(&mut self, listen_key: Option<&str>) -> Result<(), String>;
 
+    /// Подписаться на изменения позиций фьючерсного аккаунта
+    ///
+    /// # Параметры
+    /// * `listen_key` - Опциональный ключ для приватных каналов
+    async fn subscribe_positions(&mut self, _listen_key: Option<&str>) -> Result<(), String> {
+        Err("Подписка на позиции не поддерживается для этой биржи".to_string())
+    }
+
     /// Получить следующее сообщение
     async fn next_message(&mut self) -> Result<Option<Self::Message>, String>;
 
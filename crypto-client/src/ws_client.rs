@@ -1,42 +1,134 @@
 use async_trait::async_trait;
-use crypto_ws_client::mexc::MexcUserDataStreamWSClient;
+use crypto_rest_client::MexcSpotRestClient;
+use crypto_ws_client::mexc::{ListenKeyStatus, MexcUserDataStreamWSClient};
 use crypto_ws_client::{
-    BingxSpotWSClient, BingxSwapWSClient, MexcSpotWSClient, MexcSwapWSClient, WSClient,
+    BingxSpotWSClient, BingxSwapWSClient, ConnectionState as TransportState, FrameSink,
+    HealthStatus, MexcSpotWSClient, MexcSwapWSClient, ReconnectHook, WSClient, per_message_debug,
+    per_message_info,
 };
+use indexmap::IndexMap;
 use log::*;
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc as async_mpsc;
+use tokio::sync::{Mutex as AsyncMutex, Semaphore, watch};
+use tokio::task::{JoinHandle, JoinSet};
 
 use crate::config::ExchangeConfig;
 use crate::exchange_type::ExchangeType;
+use crate::orderbook::{OrderBookManager, OrderBookUpdate};
+use crate::private_event::{FillAggregator, PrivateEvent};
+use crate::replay::FrameRecorder;
+use crate::ticker::{BboQuote, ConsolidatedBbo, Ticker};
 use crate::traits::{SubscriptionManager, WebSocketClient};
+use crate::{ExchangeError, ExchangeResult};
+
+/// Максимальный интервал без heartbeat, после которого соединение считается нездоровым
+const HEARTBEAT_TIMEOUT_SECS: i64 = 120;
+
+/// Интервалы kline (в секундах), которые принимает MEXC Swap: Min1..Min60, Hour4, Hour8, Day1,
+/// Week1, Month1
+const MEXC_SWAP_KLINE_INTERVALS: &[usize] =
+    &[60, 300, 900, 1800, 3600, 14400, 28800, 86400, 604800, 2592000];
+
+/// Емкость буфера сообщений одной биржи по умолчанию
+pub const DEFAULT_BUFFER_CAPACITY: usize = 100_000;
+
+/// Число одновременных подключений в [`CryptoWsClient::connect_exchanges`] по умолчанию
+pub const DEFAULT_MAX_CONCURRENT_CONNECTS: usize = 4;
+
+/// Время ожидания подтверждения подписки по умолчанию
+pub const DEFAULT_SUBSCRIPTION_ACK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Типы каналов подписки
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ChannelType {
     Orderbook,
+    /// Полный срез top-N стакана (не инкрементальное обновление)
+    OrderbookSnapshot,
     Trades,
     Ticker,
     Kline,
     AccountBalance,
     Orders,
     PrivateDeals,
+    /// Изменения открытых позиций фьючерсного аккаунта
+    Positions,
+    /// Ставка финансирования бессрочного контракта
+    FundingRate,
+    /// Индексная цена (отдельно от маркировочной)
+    IndexPrice,
+    /// Синтетическое сообщение о полученном pong (см.
+    /// [`CryptoWsClient::with_heartbeats`]); `data["heartbeat"]["latency_ms"]` —
+    /// задержка ping-pong в миллисекундах
+    Heartbeat,
+    /// Бинарный кадр, который не удалось декодировать (см.
+    /// [`CryptoWsClient::with_undecodable_passthrough`]);
+    /// `data["undecodable_frame"]["raw_frame"]` — исходные байты в base64
+    Unknown,
+    /// Синтетическое сообщение о смене состояния подключения биржи (см.
+    /// [`CryptoWsClient::with_connection_status_messages`]); новое состояние
+    /// возвращает [`WsMessage::as_connection_state`]
+    ConnectionStatus,
 }
 
 impl ChannelType {
     pub fn as_str(&self) -> &'static str {
         match self {
             ChannelType::Orderbook => "orderbook",
+            ChannelType::OrderbookSnapshot => "orderbook_snapshot",
             ChannelType::Trades => "trades",
             ChannelType::Ticker => "ticker",
             ChannelType::Kline => "kline",
             ChannelType::AccountBalance => "balance",
             ChannelType::Orders => "orders",
             ChannelType::PrivateDeals => "private_deals",
+            ChannelType::Positions => "positions",
+            ChannelType::FundingRate => "funding_rate",
+            ChannelType::IndexPrice => "index_price",
+            ChannelType::Heartbeat => "heartbeat",
+            ChannelType::Unknown => "unknown",
+            ChannelType::ConnectionStatus => "connection_status",
         }
     }
+
+    /// Канал по имени из [`as_str`](Self::as_str)
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            ChannelType::Orderbook,
+            ChannelType::OrderbookSnapshot,
+            ChannelType::Trades,
+            ChannelType::Ticker,
+            ChannelType::Kline,
+            ChannelType::AccountBalance,
+            ChannelType::Orders,
+            ChannelType::PrivateDeals,
+            ChannelType::Positions,
+            ChannelType::FundingRate,
+            ChannelType::IndexPrice,
+            ChannelType::Heartbeat,
+            ChannelType::Unknown,
+            ChannelType::ConnectionStatus,
+        ]
+        .into_iter()
+        .find(|channel| channel.as_str() == name)
+    }
+
+    /// Канал данных аккаунта, требующий аутентификации
+    pub fn is_private(&self) -> bool {
+        matches!(
+            self,
+            ChannelType::AccountBalance
+                | ChannelType::Orders
+                | ChannelType::PrivateDeals
+                | ChannelType::Positions
+        )
+    }
 }
 
 /// Конфигурация для подписки
@@ -47,6 +139,28 @@ pub struct SubscriptionConfig {
     pub interval: Option<String>, // для kline
 }
 
+/// Режим подписки на стакан в [`CryptoWsClient::subscribe_orderbook`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthMode {
+    /// Инкрементальные изменения (`increase.depth` MEXC Spot); стакан ведётся
+    /// через [`OrderBookManager`](crate::OrderBookManager)
+    #[default]
+    Incremental,
+    /// Периодические полные срезы лучших уровней (`limit.depth@N` MEXC Spot),
+    /// вести стакан не нужно
+    Snapshot(u32),
+}
+
+impl DepthMode {
+    /// Канал, в который попадают сообщения стакана в этом режиме
+    pub fn channel(&self) -> ChannelType {
+        match self {
+            DepthMode::Incremental => ChannelType::Orderbook,
+            DepthMode::Snapshot(_) => ChannelType::OrderbookSnapshot,
+        }
+    }
+}
+
 /// Сообщение от WebSocket
 #[derive(Debug, Clone)]
 pub struct WsMessage {
@@ -55,8 +169,39 @@ pub struct WsMessage {
     pub symbol: String,
     pub data: Value,
     pub timestamp: u64,
+    /// Монотонное время получения фрейма в наносекундах (см. [`monotonic_nanos`]).
+    /// Подходит для измерения задержек и упорядочивания событий, но не для wall-clock.
+    pub received_at_ns: u64,
+    /// Время события на бирже в миллисекундах Unix, если кадр его содержит
+    pub event_time: Option<u64>,
+    /// Теги подписок, к которым относится сообщение (см.
+    /// [`CryptoWsClient::subscribe_tagged`])
+    pub tags: Vec<String>,
+    /// Исходный бинарный кадр (protobuf MEXC), если включено
+    /// [`CryptoWsClient::with_raw_frames`]; позволяет заново декодировать сообщение
+    /// обновлённой схемой
+    pub raw_bytes: Option<Vec<u8>>,
 }
 
+/// Время события из кадра биржи в миллисекундах Unix.
+///
+/// Поддерживаются `ts` (MEXC Swap, BingX), `sendTime` и `t` (MEXC Spot), `E` (BingX User
+/// Data Stream) и `data[0].ts` (OKX).
+fn frame_event_time(data: &Value) -> Option<u64> {
+    let time = |value: &Value| match value {
+        Value::String(s) => s.parse().ok(),
+        value => value.as_u64(),
+    };
+    ["ts", "sendTime", "t", "E"]
+        .iter()
+        .find_map(|key| data.get(key).and_then(time))
+        .or_else(|| time(&data["data"][0]["ts"]))
+}
+
+/// Монотонная метка времени в наносекундах; ею же транспорт отмечает время
+/// получения кадра
+pub use crypto_ws_client::monotonic_nanos;
+
 /// Состояние подключения
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -67,6 +212,83 @@ pub enum ConnectionState {
     Error(String),
 }
 
+impl ConnectionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionState::Disconnected => "disconnected",
+            ConnectionState::Connecting => "connecting",
+            ConnectionState::Connected => "connected",
+            ConnectionState::Reconnecting => "reconnecting",
+            ConnectionState::Error(_) => "error",
+        }
+    }
+
+    /// Состояние по состоянию транспорта соединения
+    fn from_transport(state: TransportState) -> Self {
+        match state {
+            TransportState::Disconnected => ConnectionState::Disconnected,
+            TransportState::Connecting => ConnectionState::Connecting,
+            TransportState::Connected => ConnectionState::Connected,
+            TransportState::Reconnecting => ConnectionState::Reconnecting,
+            TransportState::Failed(error) => ConnectionState::Error(error),
+        }
+    }
+}
+
+impl WsMessage {
+    /// Состояние подключения из сообщения [`ChannelType::ConnectionStatus`], для
+    /// остальных сообщений `None`
+    pub fn as_connection_state(&self) -> Option<ConnectionState> {
+        if self.channel != ChannelType::ConnectionStatus {
+            return None;
+        }
+        let status = &self.data["connection_status"];
+        Some(match status["state"].as_str()? {
+            "disconnected" => ConnectionState::Disconnected,
+            "connecting" => ConnectionState::Connecting,
+            "connected" => ConnectionState::Connected,
+            "reconnecting" => ConnectionState::Reconnecting,
+            "error" => ConnectionState::Error(status["error"].as_str()?.to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// Параметры супервизора переподключения [`CryptoWsClient::spawn_supervisor`]
+#[derive(Debug, Clone)]
+pub struct SupervisorPolicy {
+    /// Период проверки отключившихся бирж
+    pub check_interval: Duration,
+    /// Задержка после первой неудачной попытки; удваивается с каждой следующей
+    pub initial_backoff: Duration,
+    /// Верхняя граница задержки между попытками
+    pub max_backoff: Duration,
+    /// Перезапускать биржи, фоновая задача `run()` которых завершилась сама (например,
+    /// исчерпав попытки переподключения), через указанную паузу. `None` — только
+    /// помечать такие биржи ошибкой (см. [`CryptoWsClient::mark_stopped_exchanges`])
+    pub restart_stopped: Option<Duration>,
+}
+
+impl Default for SupervisorPolicy {
+    fn default() -> Self {
+        Self {
+            check_interval: Duration::from_secs(5),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            restart_stopped: None,
+        }
+    }
+}
+
+impl SupervisorPolicy {
+    /// Задержка перед следующей попыткой после `failures` неудачных подряд
+    fn backoff(&self, failures: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1 << failures.saturating_sub(1).min(16))
+            .min(self.max_backoff)
+    }
+}
+
 /// Менеджер подписок
 #[derive(Debug, Default)]
 pub struct SubscriptionManagerImpl {
@@ -98,42 +320,391 @@ impl SubscriptionManager for SubscriptionManagerImpl {
     }
 }
 
-/// Потокобезопасный канал для получения сообщений
-struct MessageChannel {
-    sender: std::sync::mpsc::Sender<String>,
-    receiver: Arc<Mutex<std::sync::mpsc::Receiver<String>>>,
+impl SubscriptionManagerImpl {
+    /// Запомнить подписку конкретной биржи
+    fn add_exchange_subscription(
+        &mut self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+    ) {
+        self.subscriptions
+            .entry(exchange_type.clone())
+            .or_default()
+            .insert((channel.to_string(), symbol.to_string()));
+    }
+
+    /// Подписки конкретной биржи в детерминированном порядке
+    fn exchange_subscriptions(&self, exchange_type: &ExchangeType) -> Vec<(String, String)> {
+        let mut subs: Vec<_> = self
+            .subscriptions
+            .get(exchange_type)
+            .map(|subs| subs.iter().cloned().collect())
+            .unwrap_or_default();
+        subs.sort();
+        subs
+    }
+
+    /// Забыть подписку конкретной биржи
+    fn remove_exchange_subscription(
+        &mut self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+    ) {
+        if let Some(subs) = self.subscriptions.get_mut(exchange_type) {
+            subs.remove(&(channel.to_string(), symbol.to_string()));
+        }
+    }
+
+    /// Забыть все подписки биржи
+    fn remove_exchange(&mut self, exchange_type: &ExchangeType) {
+        self.subscriptions.remove(exchange_type);
+    }
 }
 
-impl MessageChannel {
-    fn new() -> Self {
-        let (tx, rx) = std::sync::mpsc::channel();
-        debug!("MessageChannel::new: создан новый канал сообщений");
-        Self { sender: tx, receiver: Arc::new(Mutex::new(rx)) }
+/// Расхождение между подписками менеджера и подписками транспортного уровня
+///
+/// Подписки представлены парами `(channel, symbol)`, где `channel` совпадает с
+/// [`ChannelType::as_str`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionDiff {
+    /// Учтены менеджером, но отсутствуют на транспорте: данные по ним не придут
+    /// или не восстановятся после переподключения
+    pub missing_in_transport: Vec<(String, String)>,
+    /// Активны на транспорте, но неизвестны менеджеру
+    pub missing_in_manager: Vec<(String, String)>,
+    /// Подписки, повторно отправленные в режиме восстановления
+    pub repaired: Vec<(String, String)>,
+}
+
+impl SubscriptionDiff {
+    /// Оба представления подписок совпадают
+    pub fn is_consistent(&self) -> bool {
+        self.missing_in_transport.is_empty() && self.missing_in_manager.is_empty()
+    }
+}
+
+/// Пользовательский признак служебного сообщения, получает распарсенный JSON кадра
+pub type ServiceMessagePredicate = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+
+/// Подписка, которую можно повторить на другом соединении биржи
+#[derive(Clone, Copy)]
+enum SubscriptionRequest<'a> {
+    /// Публичный канал по имени в менеджере подписок
+    Channel,
+    /// Свечи с интервалом в секундах
+    Kline(usize),
+    /// Готовая команда транспорта
+    Command(&'a str),
+}
+
+/// Ответ биржи на подписку
+#[derive(Debug, PartialEq, Eq)]
+enum SubscriptionAck {
+    Confirmed,
+    /// Отказ из-за лимита подписок на соединение (только с автошардингом)
+    ConnectionLimit,
+}
+
+/// Результат разбора сырого кадра
+#[derive(Debug)]
+pub(crate) enum ParseOutcome {
+    /// Кадр с данными
+    Message(WsMessage),
+    /// Служебный кадр: подтверждение подписки, pong и т.п.
+    Service,
+    /// Кадр не удалось разобрать
+    Error(String),
+}
+
+#[cfg(test)]
+impl ParseOutcome {
+    /// Сообщение с данными, если кадр его содержит
+    pub(crate) fn into_message(self) -> Option<WsMessage> {
+        match self {
+            ParseOutcome::Message(message) => Some(message),
+            _ => None,
+        }
+    }
+}
+
+/// Привести символ к виду, не зависящему от формата биржи (BTC_USDT, BTC-USDT -> BTCUSDT)
+fn normalize_subscription_symbol(symbol: &str) -> String {
+    symbol.chars().filter(|c| !matches!(c, '_' | '-' | '/')).collect::<String>().to_uppercase()
+}
+
+/// Ограниченный буфер полученных сообщений с политикой drop-oldest
+struct MessageBuffer {
+    queue: Mutex<VecDeque<(u64, String)>>,
+    capacity: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl MessageBuffer {
+    fn push(&self, msg: (u64, String)) {
+        let mut queue = match self.queue.lock() {
+            Ok(queue) => queue,
+            Err(_) => {
+                error!("MessageBuffer::push: ошибка блокировки mutex буфера");
+                return;
+            }
+        };
+        let capacity = self.capacity.load(Ordering::Relaxed).max(1);
+        while queue.len() >= capacity {
+            queue.pop_front();
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!(
+                    "MessageBuffer::push: буфер заполнен ({} сообщений), всего отброшено {} старых сообщений",
+                    capacity, dropped
+                );
+            }
+        }
+        queue.push_back(msg);
     }
 
-    fn try_recv(&self) -> Option<String> {
-        if let Ok(receiver) = self.receiver.lock() {
-            match receiver.try_recv() {
-                Ok(msg) => {
-                    debug!("MessageChannel::try_recv: получено сообщение из канала");
+    /// Вернуть сообщение в начало буфера, сохранив его время получения
+    fn push_front(&self, msg: (u64, String)) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push_front(msg);
+        }
+    }
+
+    /// Получить сообщение вместе с монотонным временем его получения в наносекундах
+    fn try_recv(&self) -> Option<(u64, String)> {
+        if let Ok(mut queue) = self.queue.lock() {
+            match queue.pop_front() {
+                Some(msg) => {
+                    per_message_debug!("MessageBuffer::try_recv: получено сообщение из канала");
                     Some(msg)
                 }
-                Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    trace!("MessageChannel::try_recv: канал пуст");
-                    None
-                }
-                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                    warn!("MessageChannel::try_recv: канал отключен");
+                None => {
+                    trace!("MessageBuffer::try_recv: канал пуст");
                     None
                 }
             }
         } else {
-            error!("MessageChannel::try_recv: ошибка блокировки mutex канала");
+            error!("MessageBuffer::try_recv: ошибка блокировки mutex канала");
             None
         }
     }
 }
 
+/// Потокобезопасный канал для получения сообщений
+///
+/// Транспорт передаёт кадры через [`frame_sink`](Self::frame_sink) сразу в
+/// ограниченный буфер вместе с монотонной меткой времени, поставленной в цикле
+/// чтения сокета, поэтому ни очереди, ни задержка опроса через `try_recv` не
+/// искажают время прихода фрейма. Если потребитель не успевает забирать сообщения,
+/// самые старые отбрасываются и учитываются в счетчике.
+///
+/// Буфер защищен `Mutex` и атомиками, поэтому компилятор сам выводит
+/// `Send`/`Sync` для [`WsClientWrapper`] без `unsafe impl`.
+struct MessageChannel {
+    buffer: Arc<MessageBuffer>,
+}
+
+impl MessageChannel {
+    fn new() -> Self {
+        let buffer = Arc::new(MessageBuffer {
+            queue: Mutex::new(VecDeque::new()),
+            capacity: AtomicUsize::new(DEFAULT_BUFFER_CAPACITY),
+            dropped: AtomicU64::new(0),
+        });
+        debug!("MessageChannel::new: создан новый канал сообщений");
+        Self { buffer }
+    }
+
+    /// Приёмник кадров, который передаётся в конструктор клиента транспорта.
+    ///
+    /// Хранит слабую ссылку на буфер: после удаления канала приёмник возвращает
+    /// `false`, и `run()` транспорта завершается.
+    fn frame_sink(&self) -> FrameSink {
+        let buffer: Weak<MessageBuffer> = Arc::downgrade(&self.buffer);
+        Arc::new(move |received_at_ns, msg| match buffer.upgrade() {
+            Some(buffer) => {
+                buffer.push((received_at_ns, msg));
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Положить кадр в буфер с текущей меткой времени, как это делает транспорт
+    #[cfg(test)]
+    fn push(&self, msg: impl Into<String>) {
+        self.buffer.push((monotonic_nanos(), msg.into()));
+    }
+
+    /// Установить максимальное количество сообщений в буфере
+    fn set_capacity(&self, capacity: usize) {
+        self.buffer.capacity.store(capacity.max(1), Ordering::Relaxed);
+    }
+
+    /// Количество сообщений, отброшенных из-за переполнения буфера
+    fn dropped_count(&self) -> u64 {
+        self.buffer.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Получить сообщение вместе с монотонным временем его получения в наносекундах
+    fn try_recv(&self) -> Option<(u64, String)> {
+        self.buffer.try_recv()
+    }
+
+    /// Извлечь из буфера первый кадр, для которого `extract` вернул значение.
+    ///
+    /// Остальные кадры остаются в буфере в прежнем порядке.
+    fn take_first<T>(&self, extract: impl Fn(&str) -> Option<T>) -> Option<T> {
+        let mut queue = self.buffer.queue.lock().ok()?;
+        let (index, value) =
+            queue.iter().enumerate().find_map(|(i, (_, msg))| Some((i, extract(msg)?)))?;
+        queue.remove(index);
+        Some(value)
+    }
+}
+
+/// Приёмник сообщений одного соединения, которым можно пользоваться без `&mut`
+/// доступа к клиенту.
+///
+/// Разделяет буфер с [`MessageChannel`] соединения, поэтому чтение через
+/// [`MessageReader`] не мешает подпискам и переподключениям.
+#[derive(Clone)]
+struct MessageSource {
+    /// Правила разбора кадров (User Data Stream MEXC разбирается как MexcSpot)
+    parse_as: ExchangeType,
+    buffer: Arc<MessageBuffer>,
+    /// Учёт кадра, который не удалось разобрать, в метриках транспорта
+    record_parse_failure: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl MessageSource {
+    /// Получить следующее сообщение, дополнительно отбрасывая кадры, которые
+    /// пользовательские предикаты считают служебными.
+    ///
+    /// Если `awaiting_ack` задан, ответ биржи на подписку остаётся в буфере для
+    /// [`CryptoWsClient`], ожидающего подтверждения.
+    fn next_message(
+        &self,
+        service_predicates: &[ServiceMessagePredicate],
+        recorder: Option<&mut FrameRecorder>,
+        awaiting_ack: Option<&ExchangeType>,
+    ) -> Option<WsMessage> {
+        let Some((received_at_ns, raw_message)) = self.buffer.try_recv() else {
+            trace!("MessageSource::next_message: новых сообщений нет");
+            return None;
+        };
+        per_message_debug!(
+            "MessageSource::next_message: получено сырое сообщение: {}",
+            raw_message
+        );
+
+        if let Some(exchange_type) = awaiting_ack
+            && WsClientWrapper::subscription_ack(exchange_type, &raw_message).is_some()
+        {
+            trace!("MessageSource::next_message: ответ на подписку оставлен для подтверждения");
+            self.buffer.push_front((received_at_ns, raw_message));
+            return None;
+        }
+        if let Err(e) = recorder.map_or(Ok(()), |r| r.record(&self.parse_as, &raw_message)) {
+            warn!("MessageSource::next_message: {}", e);
+        }
+        match WsClientWrapper::parse_message_with_predicates(
+            self.parse_as.clone(),
+            &raw_message,
+            service_predicates,
+        ) {
+            ParseOutcome::Message(mut ws_message) => {
+                ws_message.received_at_ns = received_at_ns;
+                Some(ws_message)
+            }
+            ParseOutcome::Service => {
+                trace!(
+                    "MessageSource::next_message: пропущено служебное сообщение: {}",
+                    raw_message
+                );
+                None
+            }
+            ParseOutcome::Error(e) => {
+                warn!(
+                    "MessageSource::next_message: ошибка парсинга сообщения: {} - {}",
+                    e, raw_message
+                );
+                (self.record_parse_failure)();
+                None
+            }
+        }
+    }
+
+    /// Отбросить все накопленные сообщения, вернуть их количество
+    fn drain(&self) -> usize {
+        let mut dropped = 0;
+        while self.buffer.try_recv().is_some() {
+            dropped += 1;
+        }
+        dropped
+    }
+}
+
+/// Причина [`ConnectionState::Error`] биржи, фоновая задача `run()` которой завершилась
+const RUN_TASK_STOPPED: &str = "фоновая задача run() завершилась";
+
+/// Запустить `run()` клиента фоновой задачей, которая прерывается сигналом `shutdown`.
+///
+/// По завершении задачи клиент помечается остановленным, чтобы его можно было
+/// запустить снова, а [`CryptoWsClient::mark_stopped_exchanges`] не считал биржу
+/// подключённой.
+fn spawn_run_task(
+    label: &'static str,
+    run: impl Future<Output = ()> + Send + 'static,
+    is_running: Arc<Mutex<bool>>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    tokio::spawn(async move {
+        info!("{} WebSocket: запуск фоновой задачи", label);
+        tokio::select! {
+            _ = run => warn!("{} WebSocket: фоновая задача run() завершилась", label),
+            Ok(_) = shutdown.wait_for(|stop| *stop) => {
+                info!("{} WebSocket: фоновая задача остановлена по сигналу завершения", label)
+            }
+        }
+        if let Ok(mut running) = is_running.lock() {
+            *running = false;
+        }
+    });
+}
+
+/// Выполнить `connect` для всех элементов, допуская не более `limit` одновременных
+/// вызовов. Результаты возвращаются в порядке завершения.
+async fn connect_concurrently<T, R, F, Fut>(items: Vec<T>, limit: usize, connect: F) -> Vec<R>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(limit.max(1)));
+    let mut tasks = JoinSet::new();
+    for item in items {
+        let semaphore = semaphore.clone();
+        let connect = connect(item);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            connect.await
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                error!("connect_concurrently: задача подключения завершилась с ошибкой: {}", e)
+            }
+        }
+    }
+    results
+}
+
 /// Обёртка для различных WebSocket клиентов
 pub enum WsClientWrapper {
     MexcSpot {
@@ -170,6 +741,23 @@ pub enum WsClientWrapper {
     Kraken,      // TODO: из crypto-ws-client
     Gate,        // TODO: из crypto-ws-client
     Placeholder, // Временный вариант для компиляции
+    /// Клиент без сетевого подключения для тестов: сообщения подаются через канал
+    #[cfg(test)]
+    Mock {
+        exchange: ExchangeType,
+        message_channel: MessageChannel,
+        is_running: Arc<Mutex<bool>>,
+        health: Option<HealthStatus>,
+        /// Кадры, которые не удалось разобрать (см. [`HealthStatus::parse_failures`])
+        parse_failures: Arc<AtomicU64>,
+        subscriptions: Vec<String>,
+        /// Вызовы `subscribe_*` в порядке отправки: `channel:symbol`
+        subscribe_calls: Vec<String>,
+        /// Отписки в порядке отправки: `channel:symbol`
+        unsubscribe_calls: Vec<String>,
+        /// Хук переподключения (см. [`set_on_reconnect`](Self::set_on_reconnect))
+        on_reconnect: Arc<Mutex<Option<ReconnectHook>>>,
+    },
 }
 
 impl WsClientWrapper {
@@ -182,9 +770,8 @@ impl WsClientWrapper {
             "WsClientWrapper::new_mexc_user_data_stream: канал для MEXC User Data Stream создан"
         );
 
-        let client = Arc::new(
-            MexcUserDataStreamWSClient::new(listen_key, channel.sender.clone(), None).await,
-        );
+        let client =
+            Arc::new(MexcUserDataStreamWSClient::new(listen_key, channel.frame_sink(), None).await);
         debug!("WsClientWrapper::new_mexc_user_data_stream: MEXC User Data Stream WSClient создан");
 
         Ok(WsClientWrapper::MexcUserDataStream {
@@ -204,7 +791,7 @@ impl WsClientWrapper {
                 let channel = MessageChannel::new();
                 debug!("WsClientWrapper::new: канал для MEXC Spot создан");
 
-                let client = Arc::new(MexcSpotWSClient::new(channel.sender.clone(), None).await);
+                let client = Arc::new(MexcSpotWSClient::new(channel.frame_sink(), None).await);
                 debug!("WsClientWrapper::new: MEXC Spot WSClient создан");
 
                 Ok(WsClientWrapper::MexcSpot {
@@ -218,7 +805,7 @@ impl WsClientWrapper {
                 let channel = MessageChannel::new();
                 debug!("WsClientWrapper::new: канал для MEXC Swap создан");
 
-                let client = Arc::new(MexcSwapWSClient::new(channel.sender.clone(), None).await);
+                let client = Arc::new(MexcSwapWSClient::new(channel.frame_sink(), None).await);
                 debug!("WsClientWrapper::new: MEXC Swap WSClient создан");
 
                 Ok(WsClientWrapper::MexcSwap {
@@ -232,7 +819,7 @@ impl WsClientWrapper {
                 let channel = MessageChannel::new();
                 debug!("WsClientWrapper::new: канал для BingX Spot создан");
 
-                let client = Arc::new(BingxSpotWSClient::new(channel.sender.clone(), None).await);
+                let client = Arc::new(BingxSpotWSClient::new(channel.frame_sink(), None).await);
                 debug!("WsClientWrapper::new: BingX Spot WSClient создан");
 
                 Ok(WsClientWrapper::BingxSpot {
@@ -246,7 +833,7 @@ impl WsClientWrapper {
                 let channel = MessageChannel::new();
                 debug!("WsClientWrapper::new: канал для BingX Swap создан");
 
-                let client = Arc::new(BingxSwapWSClient::new(channel.sender.clone(), None).await);
+                let client = Arc::new(BingxSwapWSClient::new(channel.frame_sink(), None).await);
                 debug!("WsClientWrapper::new: BingX Swap WSClient создан");
 
                 Ok(WsClientWrapper::BingxSwap {
@@ -262,18 +849,44 @@ impl WsClientWrapper {
         }
     }
 
+    /// Создать тестовый клиент без сетевого подключения
+    #[cfg(test)]
+    fn new_mock(exchange: ExchangeType) -> Self {
+        WsClientWrapper::Mock {
+            exchange,
+            message_channel: MessageChannel::new(),
+            is_running: Arc::new(Mutex::new(false)),
+            health: None,
+            parse_failures: Arc::new(AtomicU64::new(0)),
+            subscriptions: Vec::new(),
+            subscribe_calls: Vec::new(),
+            unsubscribe_calls: Vec::new(),
+            on_reconnect: Arc::new(Mutex::new(None)),
+        }
+    }
+
     /// Запустить WebSocket клиент в фоновом режиме
     pub async fn start_background_task(&mut self) -> Result<(), String> {
+        // Отправитель сразу удаляется, поэтому сигнал завершения не придёт никогда
+        self.start_background_task_with_shutdown(watch::channel(false).1).await
+    }
+
+    /// Запустить WebSocket клиент в фоновом режиме до сигнала `shutdown`
+    async fn start_background_task_with_shutdown(
+        &mut self,
+        shutdown: watch::Receiver<bool>,
+    ) -> Result<(), String> {
         match self {
             WsClientWrapper::MexcSpot { client, is_running, .. } => {
                 let mut running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
                 if !*running {
                     let client_arc = Arc::clone(client);
-                    tokio::spawn(async move {
-                        info!("MEXC Spot WebSocket: запуск фоновой задачи");
-                        client_arc.run().await;
-                        info!("MEXC Spot WebSocket: фоновая задача завершена");
-                    });
+                    spawn_run_task(
+                        "MEXC Spot",
+                        async move { client_arc.run().await },
+                        is_running.clone(),
+                        shutdown,
+                    );
                     *running = true;
                     info!("MEXC Spot WebSocket клиент запущен в фоновом режиме");
                 } else {
@@ -286,11 +899,12 @@ impl WsClientWrapper {
                 if !*running {
                     // Запускаем MEXC User Data Stream клиент в фоновом режиме
                     let client_arc = Arc::clone(client);
-                    tokio::spawn(async move {
-                        info!("MEXC User Data Stream WebSocket: запуск фоновой задачи");
-                        client_arc.run().await;
-                        info!("MEXC User Data Stream WebSocket: фоновая задача завершена");
-                    });
+                    spawn_run_task(
+                        "MEXC User Data Stream",
+                        async move { client_arc.run().await },
+                        is_running.clone(),
+                        shutdown,
+                    );
                     *running = true;
                     info!("MEXC User Data Stream WebSocket клиент запущен в фоновом режиме");
                 } else {
@@ -302,11 +916,12 @@ impl WsClientWrapper {
                 let mut running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
                 if !*running {
                     let client_arc = Arc::clone(client);
-                    tokio::spawn(async move {
-                        info!("MEXC Swap WebSocket: запуск фоновой задачи");
-                        client_arc.run().await;
-                        info!("MEXC Swap WebSocket: фоновая задача завершена");
-                    });
+                    spawn_run_task(
+                        "MEXC Swap",
+                        async move { client_arc.run().await },
+                        is_running.clone(),
+                        shutdown,
+                    );
                     *running = true;
                     info!("MEXC Swap WebSocket клиент запущен в фоновом режиме");
                 } else {
@@ -318,11 +933,12 @@ impl WsClientWrapper {
                 let mut running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
                 if !*running {
                     let client_arc = Arc::clone(client);
-                    tokio::spawn(async move {
-                        info!("BingX Spot WebSocket: запуск фоновой задачи");
-                        client_arc.run().await;
-                        info!("BingX Spot WebSocket: фоновая задача завершена");
-                    });
+                    spawn_run_task(
+                        "BingX Spot",
+                        async move { client_arc.run().await },
+                        is_running.clone(),
+                        shutdown,
+                    );
                     *running = true;
                     info!("BingX Spot WebSocket клиент запущен в фоновом режиме");
                 } else {
@@ -334,11 +950,12 @@ impl WsClientWrapper {
                 let mut running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
                 if !*running {
                     let client_arc = Arc::clone(client);
-                    tokio::spawn(async move {
-                        info!("BingX Swap WebSocket: запуск фоновой задачи");
-                        client_arc.run().await;
-                        info!("BingX Swap WebSocket: фоновая задача завершена");
-                    });
+                    spawn_run_task(
+                        "BingX Swap",
+                        async move { client_arc.run().await },
+                        is_running.clone(),
+                        shutdown,
+                    );
                     *running = true;
                     info!("BingX Swap WebSocket клиент запущен в фоновом режиме");
                 } else {
@@ -347,66 +964,457 @@ impl WsClientWrapper {
                 Ok(())
             }
             WsClientWrapper::Placeholder => Ok(()),
+            #[cfg(test)]
+            WsClientWrapper::Mock { is_running, .. } => {
+                let mut running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
+                if !*running {
+                    // Имитация run(): задача живёт до сигнала завершения
+                    spawn_run_task("Mock", std::future::pending(), is_running.clone(), shutdown);
+                    *running = true;
+                }
+                Ok(())
+            }
             _ => Err("WebSocket клиенты пока не реализованы".to_string()),
         }
     }
 
-    /// Получить следующее сообщение (неблокирующий вызов)
-    pub fn try_recv_message(&mut self) -> Option<String> {
-        let result = match self {
-            WsClientWrapper::MexcSpot { message_channel, .. } => {
-                trace!("try_recv_message: проверяем канал MEXC Spot");
-                message_channel.try_recv()
-            }
-            WsClientWrapper::MexcUserDataStream { message_channel, .. } => {
-                trace!("try_recv_message: проверяем канал MEXC User Data Stream");
-                message_channel.try_recv()
-            }
-            WsClientWrapper::MexcSwap { message_channel, .. } => {
-                trace!("try_recv_message: проверяем канал MEXC Swap");
-                message_channel.try_recv()
-            }
-            WsClientWrapper::BingxSpot { message_channel, .. } => {
-                trace!("try_recv_message: проверяем канал BingX Spot");
-                message_channel.try_recv()
-            }
-            WsClientWrapper::BingxSwap { message_channel, .. } => {
-                trace!("try_recv_message: проверяем канал BingX Swap");
-                message_channel.try_recv()
-            }
-            _ => {
-                trace!("try_recv_message: неподдерживаемый тип клиента");
-                None
-            }
-        };
+    fn message_channel(&self) -> Option<&MessageChannel> {
+        match self {
+            WsClientWrapper::MexcSpot { message_channel, .. }
+            | WsClientWrapper::MexcUserDataStream { message_channel, .. }
+            | WsClientWrapper::MexcSwap { message_channel, .. }
+            | WsClientWrapper::BingxSpot { message_channel, .. }
+            | WsClientWrapper::BingxSwap { message_channel, .. } => Some(message_channel),
+            #[cfg(test)]
+            WsClientWrapper::Mock { message_channel, .. } => Some(message_channel),
+            _ => None,
+        }
+    }
 
-        if let Some(ref msg) = result {
-            debug!("try_recv_message: получено сообщение длиной {} символов", msg.len());
-        } else {
-            trace!("try_recv_message: сообщений в канале нет");
+    /// Установить емкость буфера входящих сообщений
+    pub fn set_buffer_capacity(&self, capacity: usize) {
+        if let Some(channel) = self.message_channel() {
+            channel.set_capacity(capacity);
         }
+    }
 
-        result
+    /// Включить сообщения [`ChannelType::Heartbeat`] на каждый pong транспорта
+    pub fn set_heartbeat_messages(&self, enabled: bool) {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.set_heartbeat_messages(enabled),
+            WsClientWrapper::MexcUserDataStream { client, .. } => {
+                client.set_heartbeat_messages(enabled)
+            }
+            WsClientWrapper::MexcSwap { client, .. } => client.set_heartbeat_messages(enabled),
+            WsClientWrapper::BingxSpot { client, .. } => client.set_heartbeat_messages(enabled),
+            WsClientWrapper::BingxSwap { client, .. } => client.set_heartbeat_messages(enabled),
+            _ => {}
+        }
     }
-}
 
-// Реализуем Send и Sync для WsClientWrapper
-unsafe impl Send for WsClientWrapper {}
-unsafe impl Sync for WsClientWrapper {}
+    /// Сохранять в [`WsMessage::raw_bytes`] исходные бинарные кадры
+    pub fn set_raw_frames(&self, enabled: bool) {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.set_raw_frames(enabled),
+            WsClientWrapper::MexcUserDataStream { client, .. } => client.set_raw_frames(enabled),
+            WsClientWrapper::MexcSwap { client, .. } => client.set_raw_frames(enabled),
+            WsClientWrapper::BingxSpot { client, .. } => client.set_raw_frames(enabled),
+            WsClientWrapper::BingxSwap { client, .. } => client.set_raw_frames(enabled),
+            _ => {}
+        }
+    }
 
-#[async_trait]
-impl WebSocketClient for WsClientWrapper {
-    type Message = WsMessage;
+    /// Число потоков декодирования бинарных кадров (см. [`WSClient::set_parse_workers`])
+    pub fn set_parse_workers(&self, workers: usize) {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.set_parse_workers(workers),
+            WsClientWrapper::MexcUserDataStream { client, .. } => client.set_parse_workers(workers),
+            WsClientWrapper::MexcSwap { client, .. } => client.set_parse_workers(workers),
+            WsClientWrapper::BingxSpot { client, .. } => client.set_parse_workers(workers),
+            WsClientWrapper::BingxSwap { client, .. } => client.set_parse_workers(workers),
+            _ => {}
+        }
+    }
 
-    async fn connect(&mut self) -> Result<(), String> {
-        // Запускаем фоновую задачу для обработки WebSocket соединения
-        self.start_background_task().await
+    /// Выдавать нераспознанные бинарные кадры сообщениями [`ChannelType::Unknown`]
+    pub fn set_undecodable_passthrough(&self, enabled: bool) {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.set_undecodable_passthrough(enabled),
+            WsClientWrapper::MexcUserDataStream { client, .. } => {
+                client.set_undecodable_passthrough(enabled)
+            }
+            WsClientWrapper::MexcSwap { client, .. } => client.set_undecodable_passthrough(enabled),
+            WsClientWrapper::BingxSpot { client, .. } => {
+                client.set_undecodable_passthrough(enabled)
+            }
+            WsClientWrapper::BingxSwap { client, .. } => {
+                client.set_undecodable_passthrough(enabled)
+            }
+            _ => {}
+        }
     }
 
-    async fn disconnect(&mut self) -> Result<(), String> {
+    /// Количество сообщений, отброшенных из-за переполнения буфера
+    pub fn dropped_messages(&self) -> u64 {
+        self.message_channel().map(|channel| channel.dropped_count()).unwrap_or(0)
+    }
+
+    /// Жизненный цикл listenKey User Data Stream MEXC (см.
+    /// [`MexcUserDataStreamWSClient::listen_key_status`]); для остальных клиентов `None`
+    pub fn listen_key_status(&self) -> Option<ListenKeyStatus> {
         match self {
-            WsClientWrapper::MexcSpot { client, is_running, .. } => {
-                let should_close = {
+            WsClientWrapper::MexcUserDataStream { client, .. } => Some(client.listen_key_status()),
+            _ => None,
+        }
+    }
+
+    /// Запустить задачу, продлевающую listenKey User Data Stream MEXC каждые `interval`.
+    ///
+    /// Каждое успешное продление через
+    /// [`MexcSpotRestClient::keep_alive_listen_key`] отмечается в
+    /// [`listen_key_status`](Self::listen_key_status). При ошибке задача пишет
+    /// предупреждение и повторяет попытку через `interval`, поэтому остановку продления
+    /// видно по неменяющемуся `last_refreshed`. Задача завершается, когда клиент User
+    /// Data Stream удалён.
+    pub fn spawn_listen_key_keepalive(
+        &self,
+        rest_client: Arc<MexcSpotRestClient>,
+        listen_key: String,
+        interval: Duration,
+    ) -> Result<JoinHandle<()>, String> {
+        let WsClientWrapper::MexcUserDataStream { client, .. } = self else {
+            return Err(
+                "Продление listenKey поддерживается только для MEXC User Data Stream".to_string()
+            );
+        };
+        let client = Arc::downgrade(client);
+
+        Ok(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                match rest_client.keep_alive_listen_key(&listen_key).await {
+                    Ok(_) => {
+                        client.record_listen_key_refresh();
+                        debug!("WsClientWrapper: listenKey продлён");
+                    }
+                    Err(e) => warn!("WsClientWrapper: не удалось продлить listenKey: {}", e),
+                }
+            }
+        }))
+    }
+
+    /// Получить состояние транспортного соединения, если клиент его отслеживает
+    pub fn health_status(&self) -> Option<HealthStatus> {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.health_status(),
+            WsClientWrapper::MexcUserDataStream { client, .. } => Some(client.health_status()),
+            WsClientWrapper::MexcSwap { client, .. } => client.health_status(),
+            WsClientWrapper::BingxSpot { client, .. } => client.health_status(),
+            WsClientWrapper::BingxSwap { client, .. } => client.health_status(),
+            #[cfg(test)]
+            WsClientWrapper::Mock { health, parse_failures, .. } => {
+                health.clone().map(|mut health| {
+                    health.parse_failures = parse_failures.load(Ordering::Relaxed);
+                    health
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Приёмник сообщений соединения для [`MessageReader`]
+    fn message_source(&self) -> Option<MessageSource> {
+        fn source(
+            parse_as: ExchangeType,
+            message_channel: &MessageChannel,
+            record_parse_failure: impl Fn() + Send + Sync + 'static,
+        ) -> MessageSource {
+            MessageSource {
+                parse_as,
+                buffer: message_channel.buffer.clone(),
+                record_parse_failure: Arc::new(record_parse_failure),
+            }
+        }
+
+        match self {
+            WsClientWrapper::MexcSpot { client, message_channel, .. } => {
+                let client = client.clone();
+                Some(source(ExchangeType::MexcSpot, message_channel, move || {
+                    client.record_parse_failure()
+                }))
+            }
+            // User Data Stream разбирается по правилам MexcSpot
+            WsClientWrapper::MexcUserDataStream { client, message_channel, .. } => {
+                let client = client.clone();
+                Some(source(ExchangeType::MexcSpot, message_channel, move || {
+                    client.record_parse_failure()
+                }))
+            }
+            WsClientWrapper::MexcSwap { client, message_channel, .. } => {
+                let client = client.clone();
+                Some(source(ExchangeType::MexcSwap, message_channel, move || {
+                    client.record_parse_failure()
+                }))
+            }
+            WsClientWrapper::BingxSpot { client, message_channel, .. } => {
+                let client = client.clone();
+                Some(source(ExchangeType::BingxSpot, message_channel, move || {
+                    client.record_parse_failure()
+                }))
+            }
+            WsClientWrapper::BingxSwap { client, message_channel, .. } => {
+                let client = client.clone();
+                Some(source(ExchangeType::BingxSwap, message_channel, move || {
+                    client.record_parse_failure()
+                }))
+            }
+            #[cfg(test)]
+            WsClientWrapper::Mock { exchange, message_channel, parse_failures, .. } => {
+                let parse_failures = parse_failures.clone();
+                Some(source(exchange.clone(), message_channel, move || {
+                    parse_failures.fetch_add(1, Ordering::Relaxed);
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Задать хук, вызываемый транспортом после переподключения до восстановления
+    /// подписок (см. [`WSClient::set_on_reconnect`])
+    pub fn set_on_reconnect(&self, hook: ReconnectHook) {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.set_on_reconnect(hook),
+            WsClientWrapper::MexcUserDataStream { client, .. } => client.set_on_reconnect(hook),
+            WsClientWrapper::MexcSwap { client, .. } => client.set_on_reconnect(hook),
+            WsClientWrapper::BingxSpot { client, .. } => client.set_on_reconnect(hook),
+            WsClientWrapper::BingxSwap { client, .. } => client.set_on_reconnect(hook),
+            #[cfg(test)]
+            WsClientWrapper::Mock { on_reconnect, .. } => {
+                *on_reconnect.lock().unwrap() = Some(hook);
+            }
+            _ => {}
+        }
+    }
+
+    /// WebSocket адрес, к которому подключен клиент, с учётом переопределения
+    pub fn connection_url(&self) -> Option<&str> {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.connection_url(),
+            WsClientWrapper::MexcUserDataStream { client, .. } => Some(client.connection_url()),
+            WsClientWrapper::MexcSwap { client, .. } => client.connection_url(),
+            WsClientWrapper::BingxSpot { client, .. } => client.connection_url(),
+            WsClientWrapper::BingxSwap { client, .. } => client.connection_url(),
+            _ => None,
+        }
+    }
+
+    /// Соединение деградировало: клиент остановлен, транспорт переподключается
+    /// или heartbeat не приходил дольше [`HEARTBEAT_TIMEOUT_SECS`]
+    fn is_degraded(&self) -> bool {
+        if !self.is_connected() {
+            return true;
+        }
+        self.health_status().is_some_and(|health| {
+            health.state != TransportState::Connected
+                || chrono::Utc::now().timestamp() - health.last_ping > HEARTBEAT_TIMEOUT_SECS
+        })
+    }
+
+    /// Отправить готовую команду подписки транспорту; она восстановится при переподключении
+    async fn send_subscription(&mut self, command: &str) -> Result<(), String> {
+        let commands = [command.to_string()];
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.send(&commands).await,
+            WsClientWrapper::MexcSwap { client, .. } => client.send(&commands).await,
+            WsClientWrapper::BingxSpot { client, .. } => client.send(&commands).await,
+            WsClientWrapper::BingxSwap { client, .. } => client.send(&commands).await,
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscriptions, .. } => subscriptions.extend(commands),
+            _ => return Err("Клиент не принимает команды подписки".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Подписаться на публичный канал по его имени в менеджере подписок
+    async fn subscribe_channel(&mut self, channel: &str, symbol: &str) -> Result<(), String> {
+        match channel {
+            "orderbook" => self.subscribe_orderbook(symbol).await,
+            "trades" => self.subscribe_trades(symbol).await,
+            "ticker" => self.subscribe_ticker(symbol).await,
+            "index_price" => self.subscribe_index_price(symbol).await,
+            _ => Err(format!("Канал {} нельзя повторить на резервном соединении", channel)),
+        }
+    }
+
+    /// Отписаться от публичного канала по его имени в менеджере подписок
+    async fn unsubscribe_channel(&mut self, channel: &str, symbol: &str) -> Result<(), String> {
+        // Имена топиков транспорта для каждой биржи
+        let topic = match (channel, &*self) {
+            ("orderbook", _) => "depth",
+            ("ticker", _) => "ticker",
+            ("trades", WsClientWrapper::MexcSpot { .. }) => "deals",
+            ("trades", WsClientWrapper::MexcSwap { .. }) => "deal",
+            ("trades", _) => "trade",
+            _ => return Err(format!("Отписка от канала {} не поддерживается", channel)),
+        };
+        let topics = [(topic.to_string(), symbol.to_string())];
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => client.unsubscribe(&topics).await,
+            WsClientWrapper::MexcSwap { client, .. } => client.unsubscribe(&topics).await,
+            WsClientWrapper::BingxSpot { client, .. } => client.unsubscribe(&topics).await,
+            WsClientWrapper::BingxSwap { client, .. } => client.unsubscribe(&topics).await,
+            #[cfg(test)]
+            WsClientWrapper::Mock { unsubscribe_calls, .. } => {
+                unsubscribe_calls.push(format!("{}:{}", channel, symbol))
+            }
+            _ => return Err("Клиент не поддерживает отписку".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Повторить подписку на этом соединении
+    async fn resubscribe(
+        &mut self,
+        request: SubscriptionRequest<'_>,
+        channel: &str,
+        symbol: &str,
+    ) -> Result<(), String> {
+        match request {
+            SubscriptionRequest::Channel => self.subscribe_channel(channel, symbol).await,
+            SubscriptionRequest::Kline(interval) => self.subscribe_kline(symbol, interval).await,
+            SubscriptionRequest::Command(command) => self.send_subscription(command).await,
+        }
+    }
+
+    /// Сырые команды подписки, которые транспорт восстановит при переподключении
+    pub fn transport_subscriptions(&self) -> Option<Vec<String>> {
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => Some(client.active_subscriptions()),
+            WsClientWrapper::MexcUserDataStream { client, .. } => {
+                Some(client.active_subscriptions())
+            }
+            WsClientWrapper::MexcSwap { client, .. } => Some(client.active_subscriptions()),
+            WsClientWrapper::BingxSpot { client, .. } => Some(client.active_subscriptions()),
+            WsClientWrapper::BingxSwap { client, .. } => Some(client.active_subscriptions()),
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscriptions, .. } => Some(subscriptions.clone()),
+            _ => None,
+        }
+    }
+
+    /// Разобрать команду подписки транспорта в пару `(channel, symbol)`
+    ///
+    /// Поддерживаются форматы MEXC Spot (`params`), MEXC Swap (`method` + `param.symbol`)
+    /// и BingX (`dataType`). Символ нормализуется для сравнения с подписками менеджера.
+    fn parse_transport_subscription(command: &str) -> Option<(String, String)> {
+        let value: Value = serde_json::from_str(command).ok()?;
+
+        let (topic, symbol) = if let Some(topic) =
+            value.get("params").and_then(|p| p.as_array()).and_then(|p| p.first()?.as_str())
+        {
+            // spot@public.deals.v3.api@BTCUSDT, spot@private.account.v3.api.pb
+            (topic.to_string(), topic.split('@').nth(2).unwrap_or("ACCOUNT").to_string())
+        } else if let Some(method) = value.get("method").and_then(|m| m.as_str()) {
+            // {"method":"sub.deal","param":{"symbol":"BTC_USDT"}}
+            let symbol = value.get("param")?.get("symbol")?.as_str()?;
+            (method.to_string(), symbol.to_string())
+        } else {
+            // {"id":"...","dataType":"BTC-USDT@depth"}
+            let data_type = value.get("dataType")?.as_str()?;
+            let (symbol, topic) = data_type.split_once('@')?;
+            (topic.to_string(), symbol.to_string())
+        };
+
+        // Регистр в темах не важен: `spot@public.miniTicker.v3.api@BTCUSDT@UTC+0`
+        let topic = topic.to_ascii_lowercase();
+        let channel = if topic.contains("private.deals") {
+            ChannelType::PrivateDeals
+        } else if topic.contains("account") {
+            ChannelType::AccountBalance
+        } else if topic.contains("index.price") {
+            ChannelType::IndexPrice
+        } else if topic.contains("limit.depth") {
+            ChannelType::OrderbookSnapshot
+        } else if topic.contains("depth") {
+            ChannelType::Orderbook
+        } else if topic.contains("deal") || topic.contains("trade") {
+            ChannelType::Trades
+        } else if topic.contains("ticker") {
+            ChannelType::Ticker
+        } else if topic.contains("kline") {
+            ChannelType::Kline
+        } else {
+            return None;
+        };
+
+        Some((channel.as_str().to_string(), normalize_subscription_symbol(&symbol)))
+    }
+
+    /// Получить следующее сообщение (неблокирующий вызов)
+    pub fn try_recv_message(&mut self) -> Option<String> {
+        self.try_recv_stamped_message().map(|(_, msg)| msg)
+    }
+
+    /// Получить следующее сообщение вместе с монотонным временем получения в наносекундах
+    fn try_recv_stamped_message(&mut self) -> Option<(u64, String)> {
+        let result = match self {
+            WsClientWrapper::MexcSpot { message_channel, .. } => {
+                trace!("try_recv_message: проверяем канал MEXC Spot");
+                message_channel.try_recv()
+            }
+            WsClientWrapper::MexcUserDataStream { message_channel, .. } => {
+                trace!("try_recv_message: проверяем канал MEXC User Data Stream");
+                message_channel.try_recv()
+            }
+            WsClientWrapper::MexcSwap { message_channel, .. } => {
+                trace!("try_recv_message: проверяем канал MEXC Swap");
+                message_channel.try_recv()
+            }
+            WsClientWrapper::BingxSpot { message_channel, .. } => {
+                trace!("try_recv_message: проверяем канал BingX Spot");
+                message_channel.try_recv()
+            }
+            WsClientWrapper::BingxSwap { message_channel, .. } => {
+                trace!("try_recv_message: проверяем канал BingX Swap");
+                message_channel.try_recv()
+            }
+            #[cfg(test)]
+            WsClientWrapper::Mock { message_channel, .. } => message_channel.try_recv(),
+            _ => {
+                trace!("try_recv_message: неподдерживаемый тип клиента");
+                None
+            }
+        };
+
+        if let Some((_, ref msg)) = result {
+            per_message_debug!(
+                "try_recv_message: получено сообщение длиной {} символов",
+                msg.len()
+            );
+        } else {
+            trace!("try_recv_message: сообщений в канале нет");
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl WebSocketClient for WsClientWrapper {
+    type Message = WsMessage;
+
+    async fn connect(&mut self) -> Result<(), String> {
+        // Запускаем фоновую задачу для обработки WebSocket соединения
+        self.start_background_task().await
+    }
+
+    async fn disconnect(&mut self) -> Result<(), String> {
+        match self {
+            WsClientWrapper::MexcSpot { client, is_running, .. } => {
+                let should_close = {
                     let running = is_running.lock().map_err(|_| "Ошибка блокировки mutex")?;
                     *running
                 };
@@ -471,6 +1479,11 @@ impl WebSocketClient for WsClientWrapper {
                 Ok(())
             }
             WsClientWrapper::Placeholder => Ok(()),
+            #[cfg(test)]
+            WsClientWrapper::Mock { is_running, .. } => {
+                *is_running.lock().map_err(|_| "Ошибка блокировки mutex")? = false;
+                Ok(())
+            }
             _ => Err("WebSocket клиенты пока не реализованы".to_string()),
         }
     }
@@ -514,6 +1527,11 @@ impl WebSocketClient for WsClientWrapper {
                 debug!("subscribe_orderbook: пропуск placeholder клиента");
                 Ok(())
             }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push(format!("orderbook:{}", symbol));
+                Ok(())
+            }
             _ => {
                 warn!("subscribe_orderbook: неподдерживаемый тип клиента");
                 Err("WebSocket клиенты пока не реализованы".to_string())
@@ -559,6 +1577,11 @@ impl WebSocketClient for WsClientWrapper {
                 debug!("subscribe_trades: пропуск placeholder клиента");
                 Ok(())
             }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push(format!("trades:{}", symbol));
+                Ok(())
+            }
             _ => {
                 warn!("subscribe_trades: неподдерживаемый тип клиента");
                 Err("WebSocket клиенты пока не реализованы".to_string())
@@ -570,9 +1593,11 @@ impl WebSocketClient for WsClientWrapper {
         info!("subscribe_ticker: начинаем подписку на ticker для символа {}", symbol);
 
         match self {
-            WsClientWrapper::MexcSpot { .. } => {
-                warn!("subscribe_ticker: MEXC Spot не поддерживает ticker канал");
-                Err("MEXC Spot не поддерживает ticker канал".to_string())
+            WsClientWrapper::MexcSpot { client, .. } => {
+                info!("subscribe_ticker: подписка на miniTicker для MEXC Spot: {}", symbol);
+                client.subscribe_ticker(&[symbol.to_string()]).await;
+                info!("subscribe_ticker: подписка на MEXC Spot ticker выполнена");
+                Ok(())
             }
             WsClientWrapper::MexcUserDataStream { .. } => {
                 warn!(
@@ -602,6 +1627,11 @@ impl WebSocketClient for WsClientWrapper {
                 debug!("subscribe_ticker: пропуск placeholder клиента");
                 Ok(())
             }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push(format!("ticker:{}", symbol));
+                Ok(())
+            }
             _ => {
                 warn!("subscribe_ticker: неподдерживаемый тип клиента");
                 Err("WebSocket клиенты пока не реализованы".to_string())
@@ -609,6 +1639,83 @@ impl WebSocketClient for WsClientWrapper {
         }
     }
 
+    async fn subscribe_index_price(&mut self, symbol: &str) -> Result<(), String> {
+        info!("subscribe_index_price: начинаем подписку на индексную цену {}", symbol);
+
+        match self {
+            WsClientWrapper::MexcSwap { client, .. } => {
+                // push.index.price приходит отдельно от маркировочной push.fair.price
+                client.subscribe(&[("index.price".to_string(), symbol.to_string())]).await;
+                info!("subscribe_index_price: подписка на MEXC Swap index.price выполнена");
+                Ok(())
+            }
+            WsClientWrapper::Placeholder => {
+                debug!("subscribe_index_price: пропуск placeholder клиента");
+                Ok(())
+            }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push(format!("index_price:{}", symbol));
+                Ok(())
+            }
+            _ => {
+                warn!("subscribe_index_price: биржа не публикует индексную цену отдельным каналом");
+                Err("Подписка на индексную цену не поддерживается для этой биржи".to_string())
+            }
+        }
+    }
+
+    async fn subscribe_kline(&mut self, symbol: &str, interval: usize) -> Result<(), String> {
+        info!("subscribe_kline: начинаем подписку на kline {} с интервалом {}с", symbol, interval);
+        let symbol_interval = [(symbol.to_string(), interval)];
+
+        match self {
+            WsClientWrapper::MexcSpot { client, .. } => {
+                info!("subscribe_kline: подписка на kline для MEXC Spot: {}", symbol);
+                client.subscribe_candlestick(&symbol_interval).await;
+                Ok(())
+            }
+            WsClientWrapper::MexcUserDataStream { .. } => {
+                warn!(
+                    "subscribe_kline: MEXC User Data Stream предназначен только для приватных данных"
+                );
+                Err("MEXC User Data Stream не поддерживает публичные каналы как kline".to_string())
+            }
+            WsClientWrapper::MexcSwap { client, .. } => {
+                // Транслятор MEXC Swap паникует на неизвестном интервале, проверяем заранее
+                if !MEXC_SWAP_KLINE_INTERVALS.contains(&interval) {
+                    return Err(format!("MEXC Swap не поддерживает интервал kline {}с", interval));
+                }
+                info!("subscribe_kline: подписка на kline для MEXC Swap: {}", symbol);
+                client.subscribe_candlestick(&symbol_interval).await;
+                Ok(())
+            }
+            WsClientWrapper::BingxSpot { client, .. } => {
+                info!("subscribe_kline: подписка на kline для BingX Spot: {}", symbol);
+                client.subscribe_candlestick(&symbol_interval).await;
+                Ok(())
+            }
+            WsClientWrapper::BingxSwap { client, .. } => {
+                info!("subscribe_kline: подписка на kline для BingX Swap: {}", symbol);
+                client.subscribe_candlestick(&symbol_interval).await;
+                Ok(())
+            }
+            WsClientWrapper::Placeholder => {
+                debug!("subscribe_kline: пропуск placeholder клиента");
+                Ok(())
+            }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push(format!("kline:{}", symbol));
+                Ok(())
+            }
+            _ => {
+                warn!("subscribe_kline: неподдерживаемый тип клиента");
+                Err("WebSocket клиенты пока не реализованы".to_string())
+            }
+        }
+    }
+
     async fn subscribe_account_balance(&mut self, _listen_key: Option<&str>) -> Result<(), String> {
         info!("subscribe_account_balance: начинаем подписку на баланс аккаунта");
 
@@ -649,6 +1756,11 @@ impl WebSocketClient for WsClientWrapper {
                 debug!("subscribe_account_balance: пропуск placeholder клиента");
                 Ok(())
             }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push("balance".to_string());
+                Ok(())
+            }
             _ => {
                 warn!("subscribe_account_balance: неподдерживаемый тип клиента");
                 Err("WebSocket клиенты пока не реализованы".to_string())
@@ -696,6 +1808,11 @@ impl WebSocketClient for WsClientWrapper {
                 debug!("subscribe_private_deals: пропуск placeholder клиента");
                 Ok(())
             }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push("private_deals".to_string());
+                Ok(())
+            }
             _ => {
                 warn!("subscribe_private_deals: неподдерживаемый тип клиента");
                 Err("WebSocket клиенты пока не реализованы".to_string())
@@ -703,67 +1820,44 @@ impl WebSocketClient for WsClientWrapper {
         }
     }
 
-    async fn next_message(&mut self) -> Result<Option<Self::Message>, String> {
-        debug!("WsClientWrapper::next_message: вызван метод получения следующего сообщения");
-
-        // Проверяем наличие новых сообщений
-        if let Some(raw_message) = self.try_recv_message() {
-            debug!("WsClientWrapper::next_message: получено сырое сообщение: {}", raw_message);
+    async fn subscribe_positions(&mut self, _listen_key: Option<&str>) -> Result<(), String> {
+        info!("subscribe_positions: начинаем подписку на позиции аккаунта");
 
-            // Определяем тип биржи перед парсингом для избежания проблем с заимствованием
-            let exchange_type = match self {
-                WsClientWrapper::MexcSpot { .. } => {
-                    debug!("WsClientWrapper::next_message: обрабатываем сообщение для MEXC Spot");
-                    ExchangeType::MexcSpot
-                }
-                WsClientWrapper::MexcUserDataStream { .. } => {
-                    info!(
-                        "WsClientWrapper::next_message: обрабатываем сообщение для MEXC User Data Stream"
-                    );
-                    ExchangeType::MexcSpot // User Data Stream использует тот же тип парсинга, что и MexcSpot
-                }
-                WsClientWrapper::MexcSwap { .. } => {
-                    debug!("WsClientWrapper::next_message: обрабатываем сообщение для MEXC Swap");
-                    ExchangeType::MexcSwap
-                }
-                WsClientWrapper::BingxSpot { .. } => {
-                    debug!("WsClientWrapper::next_message: обрабатываем сообщение для BingX Spot");
-                    ExchangeType::BingxSpot
-                }
-                WsClientWrapper::BingxSwap { .. } => {
-                    debug!("WsClientWrapper::next_message: обрабатываем сообщение для BingX Swap");
-                    ExchangeType::BingxSwap
-                }
-                _ => {
-                    warn!("WsClientWrapper::next_message: неподдерживаемый тип биржи");
-                    return Err("Неподдерживаемый тип биржи".to_string());
-                }
-            };
-            // Парсим сообщение
-            match Self::parse_message_static(exchange_type, &raw_message) {
-                Ok(ws_message) => Ok(Some(ws_message)),
-                Err(e) => {
-                    // Логируем только реальные ошибки, не служебные сообщения
-                    if e == "Служебное сообщение" {
-                        trace!(
-                            "WsClientWrapper::next_message: пропущено служебное сообщение: {}",
-                            raw_message
-                        );
-                    } else {
-                        warn!(
-                            "WsClientWrapper::next_message: ошибка парсинга сообщения: {} - {}",
-                            e, raw_message
-                        );
-                    }
-                    Ok(None)
-                }
+        match self {
+            WsClientWrapper::MexcSpot { .. }
+            | WsClientWrapper::MexcUserDataStream { .. }
+            | WsClientWrapper::BingxSpot { .. } => {
+                warn!("subscribe_positions: у спотового рынка нет позиций");
+                Err("Подписка на позиции доступна только для фьючерсных рынков".to_string())
+            }
+            WsClientWrapper::MexcSwap { .. } => {
+                warn!("subscribe_positions: MEXC Swap требует приватный ключ для позиций");
+                Err("MEXC Swap требует приватный ключ для подписки на позиции".to_string())
+            }
+            WsClientWrapper::BingxSwap { .. } => {
+                warn!("subscribe_positions: BingX Swap требует приватный ключ для позиций");
+                Err("BingX Swap требует приватный ключ для подписки на позиции".to_string())
+            }
+            WsClientWrapper::Placeholder => {
+                debug!("subscribe_positions: пропуск placeholder клиента");
+                Ok(())
+            }
+            #[cfg(test)]
+            WsClientWrapper::Mock { subscribe_calls, .. } => {
+                subscribe_calls.push("positions".to_string());
+                Ok(())
+            }
+            _ => {
+                warn!("subscribe_positions: неподдерживаемый тип клиента");
+                Err("WebSocket клиенты пока не реализованы".to_string())
             }
-        } else {
-            trace!("WsClientWrapper::next_message: новых сообщений нет");
-            Ok(None)
         }
     }
 
+    async fn next_message(&mut self) -> Result<Option<Self::Message>, String> {
+        Ok(self.message_source().and_then(|source| source.next_message(&[], None, None)))
+    }
+
     fn is_connected(&self) -> bool {
         match self {
             WsClientWrapper::MexcSpot { is_running, .. } => {
@@ -782,6 +1876,10 @@ impl WebSocketClient for WsClientWrapper {
                 is_running.lock().map(|r| *r).unwrap_or(false)
             }
             WsClientWrapper::Placeholder => false,
+            #[cfg(test)]
+            WsClientWrapper::Mock { is_running, .. } => {
+                is_running.lock().map(|r| *r).unwrap_or(false)
+            }
             _ => false,
         }
     }
@@ -789,17 +1887,37 @@ impl WebSocketClient for WsClientWrapper {
 
 impl WsClientWrapper {
     /// Парсит сырое WebSocket сообщение в структурированный формат
-    fn parse_message_static(
+    #[cfg(test)]
+    fn parse_message_static(exchange_type: ExchangeType, raw_message: &str) -> ParseOutcome {
+        Self::parse_message_with_predicates(exchange_type, raw_message, &[])
+    }
+
+    /// Парсит сообщение, считая служебными также кадры, подходящие под любой из предикатов
+    pub(crate) fn parse_message_with_predicates(
         exchange_type: ExchangeType,
         raw_message: &str,
-    ) -> Result<WsMessage, String> {
-        debug!(
+        service_predicates: &[ServiceMessagePredicate],
+    ) -> ParseOutcome {
+        match Self::try_parse_message(exchange_type, raw_message, service_predicates) {
+            Ok(Some(message)) => ParseOutcome::Message(message),
+            Ok(None) => ParseOutcome::Service,
+            Err(e) => ParseOutcome::Error(e),
+        }
+    }
+
+    /// Разбор кадра; `Ok(None)` — служебное сообщение
+    fn try_parse_message(
+        exchange_type: ExchangeType,
+        raw_message: &str,
+        service_predicates: &[ServiceMessagePredicate],
+    ) -> Result<Option<WsMessage>, String> {
+        per_message_debug!(
             "parse_message_static: начинаем парсинг сообщения длиной {} символов",
             raw_message.len()
         );
 
         // Парсим JSON
-        let data: Value = serde_json::from_str(raw_message).map_err(|e| {
+        let mut data: Value = serde_json::from_str(raw_message).map_err(|e| {
             error!(
                 "parse_message_static: ошибка парсинга JSON: {} - сообщение: {}",
                 e, raw_message
@@ -807,28 +1925,76 @@ impl WsClientWrapper {
             format!("Ошибка парсинга JSON: {}", e)
         })?;
 
-        debug!(
+        // Исходные байты бинарного кадра (см. `CryptoWsClient::with_raw_frames`)
+        let raw_bytes = crypto_ws_client::take_raw_frame(&mut data);
+        let message = Self::parse_frame(exchange_type, data, raw_message, service_predicates)?;
+        Ok(message.map(|message| WsMessage { raw_bytes, ..message }))
+    }
+
+    /// Разбор кадра, уже распарсенного как JSON
+    fn parse_frame(
+        exchange_type: ExchangeType,
+        data: Value,
+        raw_message: &str,
+        service_predicates: &[ServiceMessagePredicate],
+    ) -> Result<Option<WsMessage>, String> {
+
+        per_message_debug!(
             "parse_message_static: JSON успешно распаршен, содержит {} полей",
             data.as_object().map(|o| o.len()).unwrap_or(0)
         );
 
+        // Синтетические кадры транспорта: heartbeat (см. `WSClient::set_heartbeat_messages`)
+        // и нераспознанный бинарный кадр (см. `WSClient::set_undecodable_passthrough`)
+        let transport_channel = if data.get("heartbeat").is_some() {
+            Some(ChannelType::Heartbeat)
+        } else if data.get("undecodable_frame").is_some() {
+            Some(ChannelType::Unknown)
+        } else {
+            None
+        };
+        if let Some(channel) = transport_channel {
+            return Ok(Some(WsMessage {
+                exchange: exchange_type,
+                channel,
+                symbol: String::new(),
+                event_time: frame_event_time(&data),
+                tags: Vec::new(),
+                raw_bytes: None,
+                data,
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+                received_at_ns: monotonic_nanos(),
+            }));
+        }
+
         // Проверяем, является ли это служебным сообщением
-        if Self::is_service_message(&exchange_type, &data) {
-            debug!("parse_message_static: пропускаем служебное сообщение: {}", raw_message);
-            return Err("Служебное сообщение".to_string());
+        if Self::is_service_message(&exchange_type, &data)
+            || service_predicates.iter().any(|predicate| predicate(&data))
+        {
+            per_message_debug!(
+                "parse_message_static: пропускаем служебное сообщение: {}",
+                raw_message
+            );
+            return Ok(None);
         }
 
         // Сначала проверяем, является ли это приватным сообщением
-        debug!("parse_message_static: проверяем является ли сообщение приватным");
+        per_message_debug!("parse_message_static: проверяем является ли сообщение приватным");
         if Self::is_private_message(&exchange_type, &data) {
-            info!("parse_message_static: обнаружено приватное сообщение, начинаем парсинг");
+            per_message_info!(
+                "parse_message_static: обнаружено приватное сообщение, начинаем парсинг"
+            );
             match Self::parse_private_message(exchange_type, &data, raw_message) {
                 Ok(ws_message) => {
-                    info!(
+                    per_message_info!(
                         "parse_message_static: приватное сообщение успешно обработано: channel={:?}, symbol={}",
-                        ws_message.channel, ws_message.symbol
+                        ws_message.channel,
+                        ws_message.symbol
                     );
-                    return Ok(ws_message);
+                    return Ok(Some(ws_message));
                 }
                 Err(e) => {
                     error!(
@@ -841,108 +2007,120 @@ impl WsClientWrapper {
         }
 
         // Затем обрабатываем как обычное публичное сообщение
-        debug!("parse_message_static: обрабатываем как публичное сообщение");
+        per_message_debug!("parse_message_static: обрабатываем как публичное сообщение");
         let (channel_type, symbol) = Self::extract_channel_and_symbol(&exchange_type, &data)?;
 
-        debug!(
+        per_message_debug!(
             "parse_message_static: публичное сообщение обработано: channel={:?}, symbol={}",
-            channel_type, symbol
+            channel_type,
+            symbol
         );
 
-        Ok(WsMessage {
+        Ok(Some(WsMessage {
             exchange: exchange_type,
             channel: channel_type,
             symbol,
+            event_time: frame_event_time(&data),
+            tags: Vec::new(),
+            raw_bytes: None,
             data,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as u64,
-        })
+            received_at_ns: monotonic_nanos(),
+        }))
     }
 
     /// Проверяет, является ли сообщение приватным
     fn is_private_message(exchange_type: &ExchangeType, data: &Value) -> bool {
-        debug!("is_private_message: проверяем сообщение для биржи {:?}", exchange_type);
+        per_message_debug!("is_private_message: проверяем сообщение для биржи {:?}", exchange_type);
 
         match *exchange_type {
             ExchangeType::MexcSpot => {
-                debug!("is_private_message: анализируем MEXC Spot сообщение");
+                per_message_debug!("is_private_message: анализируем MEXC Spot сообщение");
 
                 // Проверяем User Data Stream формат: {"channel": "spot@private.*.api.pb", ...}
                 if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
-                    debug!("is_private_message: найден канал 'channel': {}", channel_str);
+                    per_message_debug!(
+                        "is_private_message: найден канал 'channel': {}",
+                        channel_str
+                    );
                     if channel_str.contains("private") {
-                        info!(
+                        per_message_info!(
                             "is_private_message: обнаружен приватный канал в User Data Stream: {}",
                             channel_str
                         );
                         return true;
                     }
                 } else {
-                    debug!("is_private_message: поле 'channel' не найдено или не является строкой");
+                    per_message_debug!(
+                        "is_private_message: поле 'channel' не найдено или не является строкой"
+                    );
                 }
 
                 // Проверяем новый wrapper формат с privateDeals
                 if data.get("privateDeals").is_some() {
-                    info!("is_private_message: обнаружен privateDeals в данных");
+                    per_message_info!("is_private_message: обнаружен privateDeals в данных");
                     return true;
                 } else {
-                    debug!("is_private_message: поле 'privateDeals' не найдено");
+                    per_message_debug!("is_private_message: поле 'privateDeals' не найдено");
                 }
 
                 // Проверяем приватные поля в данных
                 if data.get("privateAccount").is_some() {
-                    info!("is_private_message: обнаружен privateAccount в данных");
+                    per_message_info!("is_private_message: обнаружен privateAccount в данных");
                     return true;
                 } else {
-                    debug!("is_private_message: поле 'privateAccount' не найдено");
+                    per_message_debug!("is_private_message: поле 'privateAccount' не найдено");
                 }
 
                 // Проверяем старый формат где приватные сделки путаются с публичными
                 if let Some(d_data) = data.get("d") {
-                    debug!("is_private_message: найдено поле 'd', проверяем содержимое");
+                    per_message_debug!(
+                        "is_private_message: найдено поле 'd', проверяем содержимое"
+                    );
                     if let Some(symbol_in_d) = d_data.get("symbol").and_then(|v| v.as_str()) {
-                        debug!("is_private_message: найден symbol в d: {}", symbol_in_d);
+                        per_message_debug!(
+                            "is_private_message: найден symbol в d: {}",
+                            symbol_in_d
+                        );
                         if symbol_in_d.contains("private.deals") {
-                            info!(
+                            per_message_info!(
                                 "is_private_message: обнаружена приватная сделка в поле d.symbol: {}",
                                 symbol_in_d
                             );
                             return true;
                         }
                     } else {
-                        debug!(
+                        per_message_debug!(
                             "is_private_message: поле 'd.symbol' не найдено или не является строкой"
                         );
                     }
                 } else {
-                    debug!("is_private_message: поле 'd' не найдено");
+                    per_message_debug!("is_private_message: поле 'd' не найдено");
                 }
 
-                debug!("is_private_message: MEXC Spot сообщение не является приватным");
+                per_message_debug!("is_private_message: MEXC Spot сообщение не является приватным");
                 false
             }
             ExchangeType::MexcSwap => {
-                debug!("is_private_message: анализируем MEXC Swap сообщение");
-                // Для MEXC Swap приватные сообщения обычно содержат "private" в канале
-                if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
-                    if channel_str.contains("private") || channel_str.contains("account") {
-                        debug!(
-                            "is_private_message: обнаружен приватный канал MEXC Swap: {}",
-                            channel_str
-                        );
-                        return true;
-                    }
+                per_message_debug!("is_private_message: анализируем MEXC Swap сообщение");
+                let private = Self::mexc_swap_private_channel(data).is_some();
+                if private {
+                    per_message_debug!(
+                        "is_private_message: обнаружен приватный канал MEXC Swap: {}",
+                        data["channel"]
+                    );
                 }
-                false
+                private
             }
             ExchangeType::BingxSpot | ExchangeType::BingxSwap => {
-                debug!("is_private_message: анализируем BingX сообщение");
+                per_message_debug!("is_private_message: анализируем BingX сообщение");
                 // Для BingX приватные сообщения обычно содержат "account" или "private" в dataType
                 if let Some(data_type) = data.get("dataType").and_then(|v| v.as_str()) {
                     if data_type.contains("account") || data_type.contains("private") {
-                        debug!(
+                        per_message_debug!(
                             "is_private_message: обнаружен приватный dataType BingX: {}",
                             data_type
                         );
@@ -951,37 +2129,90 @@ impl WsClientWrapper {
                 }
                 false
             }
+            ExchangeType::OkxSpot => {
+                // OKX: {"arg": {"channel": "orders", ...}, "data": [...]}
+                let private = Self::okx_private_channel(data).is_some();
+                if private {
+                    per_message_debug!(
+                        "is_private_message: обнаружен приватный канал OKX: {}",
+                        data["arg"]
+                    );
+                }
+                private
+            }
             _ => {
-                debug!("is_private_message: неподдерживаемый тип биржи: {:?}", exchange_type);
+                per_message_debug!(
+                    "is_private_message: неподдерживаемый тип биржи: {:?}",
+                    exchange_type
+                );
                 false
             }
         }
     }
 
+    /// Тип приватного канала OKX по `arg.channel`.
+    ///
+    /// Кадры без `data` (подтверждения подписки и логина) приватными данными не считаются.
+    fn okx_private_channel(data: &Value) -> Option<ChannelType> {
+        data.get("data")?;
+        match data["arg"]["channel"].as_str()? {
+            "orders" => Some(ChannelType::Orders),
+            "fills" => Some(ChannelType::PrivateDeals),
+            "account" => Some(ChannelType::AccountBalance),
+            "positions" => Some(ChannelType::Positions),
+            _ => None,
+        }
+    }
+
+    /// Тип приватного канала MEXC Swap по `channel`.
+    ///
+    /// Приватные пуши контрактов приходят в каналах `push.personal.*`; прочие события
+    /// аккаунта (риск ликвидации, ADL) относятся к балансу. Каналы с `account`/`private`
+    /// в имени поддерживаются для совместимости.
+    fn mexc_swap_private_channel(data: &Value) -> Option<ChannelType> {
+        match data.get("channel")?.as_str()? {
+            "push.personal.position" => Some(ChannelType::Positions),
+            "push.personal.order" => Some(ChannelType::Orders),
+            "push.personal.order.deal" => Some(ChannelType::PrivateDeals),
+            channel
+                if channel.starts_with("push.personal.")
+                    || channel.contains("account")
+                    || channel.contains("private") =>
+            {
+                Some(ChannelType::AccountBalance)
+            }
+            _ => None,
+        }
+    }
+
     /// Парсит приватное сообщение
     fn parse_private_message(
         exchange_type: ExchangeType,
         data: &Value,
         _raw_message: &str,
     ) -> Result<WsMessage, String> {
-        debug!("parse_private_message: начинаем парсинг для биржи {:?}", exchange_type);
+        per_message_debug!("parse_private_message: начинаем парсинг для биржи {:?}", exchange_type);
         
         match exchange_type {
             ExchangeType::MexcSpot => {
-                debug!("parse_private_message: обрабатываем MEXC Spot приватное сообщение");
+                per_message_debug!(
+                    "parse_private_message: обрабатываем MEXC Spot приватное сообщение"
+                );
                 
                 // Обрабатываем различные форматы приватных сообщений MEXC
 
                 // 1. User Data Stream формат: {"channel": "spot@private.deals.v3.api.pb", "privateDeals": {...}}
                 if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
-                    debug!("parse_private_message: найден канал: {}", channel_str);
+                    per_message_debug!("parse_private_message: найден канал: {}", channel_str);
                     
                     if channel_str.contains("private.deals") {
-                        info!("parse_private_message: обрабатываем User Data Stream private.deals");
+                        per_message_info!(
+                            "parse_private_message: обрабатываем User Data Stream private.deals"
+                        );
                         
                         // Проверяем наличие privateDeals
                         if data.get("privateDeals").is_some() {
-                            debug!("parse_private_message: найдено поле privateDeals");
+                            per_message_debug!("parse_private_message: найдено поле privateDeals");
                         } else {
                             warn!("parse_private_message: отсутствует поле privateDeals в private.deals канале");
                         }
@@ -990,18 +2221,28 @@ impl WsClientWrapper {
                             .get("symbol")
                             .and_then(|v| v.as_str())
                             .or_else(|| {
-                                debug!("parse_private_message: символ не найден в корневом объекте, ищем в privateDeals");
+                                per_message_debug!(
+                                    "parse_private_message: символ не найден в корневом объекте, ищем в privateDeals"
+                                );
                                 // Пытаемся извлечь символ из данных privateDeals
                                 data.get("privateDeals")
                                     .and_then(|pd| pd.get("feeCurrency"))
                                     .and_then(|v| v.as_str())
                                     .and_then(|fee_currency| {
-                                        debug!("parse_private_message: найден feeCurrency: {}", fee_currency);
+                                        per_message_debug!(
+                                            "parse_private_message: найден feeCurrency: {}",
+                                            fee_currency
+                                        );
                                         if fee_currency == "MX" { 
-                                            debug!("parse_private_message: feeCurrency=MX, используем MXUSDT");
+                                            per_message_debug!(
+                                                "parse_private_message: feeCurrency=MX, используем MXUSDT"
+                                            );
                                             Some("MXUSDT") 
                                         } else { 
-                                            debug!("parse_private_message: feeCurrency={}, не можем определить символ", fee_currency);
+                                            per_message_debug!(
+                                                "parse_private_message: feeCurrency={}, не можем определить символ",
+                                                fee_currency
+                                            );
                                             None 
                                         }
                                     })
@@ -1009,7 +2250,7 @@ impl WsClientWrapper {
                             .unwrap_or("UNKNOWN")
                             .to_string();
 
-                        info!(
+                        per_message_info!(
                             "parse_private_message: обработана приватная сделка User Data Stream для символа: {}",
                             symbol
                         );
@@ -1023,11 +2264,15 @@ impl WsClientWrapper {
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
                                 .as_millis() as u64,
+                            received_at_ns: monotonic_nanos(),
+                            event_time: frame_event_time(data),
+                            tags: Vec::new(),
+                            raw_bytes: None,
                         });
                     }
 
                     if channel_str.contains("private.account") || channel_str.contains("account") {
-                        info!(
+                        per_message_info!(
                             "parse_private_message: обработаны данные приватного аккаунта User Data Stream"
                         );
 
@@ -1040,17 +2285,28 @@ impl WsClientWrapper {
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
                                 .as_millis() as u64,
+                            received_at_ns: monotonic_nanos(),
+                            event_time: frame_event_time(data),
+                            tags: Vec::new(),
+                            raw_bytes: None,
                         });
                     }
                     
-                    debug!("parse_private_message: канал {} не содержит private.deals или private.account", channel_str);
+                    per_message_debug!(
+                        "parse_private_message: канал {} не содержит private.deals или private.account",
+                        channel_str
+                    );
                 } else {
-                    debug!("parse_private_message: поле 'channel' не найдено, проверяем wrapper формат");
+                    per_message_debug!(
+                        "parse_private_message: поле 'channel' не найдено, проверяем wrapper формат"
+                    );
                 }
 
                 // 2. Новый wrapper формат с privateDeals
                 if data.get("privateDeals").is_some() {
-                    debug!("parse_private_message: обрабатываем wrapper формат с privateDeals");
+                    per_message_debug!(
+                        "parse_private_message: обрабатываем wrapper формат с privateDeals"
+                    );
                     
                     let symbol = data
                         .get("symbol")
@@ -1058,7 +2314,7 @@ impl WsClientWrapper {
                         .unwrap_or("UNKNOWN")
                         .to_string();
 
-                    info!(
+                    per_message_info!(
                         "parse_private_message: обработана приватная сделка wrapper формат для символа: {}",
                         symbol
                     );
@@ -1072,20 +2328,33 @@ impl WsClientWrapper {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_millis() as u64,
+                        received_at_ns: monotonic_nanos(),
+                        event_time: frame_event_time(data),
+                        tags: Vec::new(),
+                        raw_bytes: None,
                     });
                 } else {
-                    debug!("parse_private_message: поле 'privateDeals' не найдено, проверяем смешанный формат");
+                    per_message_debug!(
+                        "parse_private_message: поле 'privateDeals' не найдено, проверяем смешанный формат"
+                    );
                 }
 
                 // 3. Старый смешанный формат где приватные сделки попадают в публичный канал
                 if let Some(d_data) = data.get("d") {
-                    debug!("parse_private_message: проверяем смешанный формат с полем 'd'");
+                    per_message_debug!(
+                        "parse_private_message: проверяем смешанный формат с полем 'd'"
+                    );
                     
                     if let Some(symbol_in_d) = d_data.get("symbol").and_then(|v| v.as_str()) {
-                        debug!("parse_private_message: найден symbol в d: {}", symbol_in_d);
+                        per_message_debug!(
+                            "parse_private_message: найден symbol в d: {}",
+                            symbol_in_d
+                        );
                         
                         if symbol_in_d.contains("private.deals") {
-                            debug!("parse_private_message: обрабатываем смешанный формат с private.deals");
+                            per_message_debug!(
+                                "parse_private_message: обрабатываем смешанный формат с private.deals"
+                            );
                             
                             // Извлекаем реальный символ из поля quantity
                             let symbol = d_data
@@ -1114,20 +2383,29 @@ impl WsClientWrapper {
                                     .duration_since(std::time::UNIX_EPOCH)
                                     .unwrap_or_default()
                                     .as_millis() as u64,
+                                received_at_ns: monotonic_nanos(),
+                                event_time: frame_event_time(data),
+                                tags: Vec::new(),
+                                raw_bytes: None,
                             });
                         } else {
-                            debug!("parse_private_message: symbol в d не содержит private.deals: {}", symbol_in_d);
+                            per_message_debug!(
+                                "parse_private_message: symbol в d не содержит private.deals: {}",
+                                symbol_in_d
+                            );
                         }
                     } else {
-                        debug!("parse_private_message: symbol в d не найден или не является строкой");
+                        per_message_debug!(
+                            "parse_private_message: symbol в d не найден или не является строкой"
+                        );
                     }
                 } else {
-                    debug!("parse_private_message: поле 'd' не найдено");
+                    per_message_debug!("parse_private_message: поле 'd' не найдено");
                 }
 
                 // 4. Другие приватные данные аккаунта
                 if data.get("privateAccount").is_some() {
-                    info!("parse_private_message: обработаны данные privateAccount");
+                    per_message_info!("parse_private_message: обработаны данные privateAccount");
 
                     return Ok(WsMessage {
                         exchange: exchange_type,
@@ -1138,42 +2416,55 @@ impl WsClientWrapper {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_millis() as u64,
+                        received_at_ns: monotonic_nanos(),
+                        event_time: frame_event_time(data),
+                        tags: Vec::new(),
+                        raw_bytes: None,
                     });
                 } else {
-                    debug!("parse_private_message: поле 'privateAccount' не найдено");
+                    per_message_debug!("parse_private_message: поле 'privateAccount' не найдено");
                 }
 
                 error!("parse_private_message: ни один формат приватного сообщения MEXC не подошел");
                 Err("Неизвестный формат приватного сообщения MEXC".to_string())
             }
             ExchangeType::MexcSwap => {
-                let channel_str = data.get("channel").and_then(|v| v.as_str()).unwrap_or("");
-
-                if channel_str.contains("account") || channel_str.contains("private") {
-                    info!(
-                        "parse_private_message: обработано приватное сообщение MEXC Swap: {}",
-                        channel_str
-                    );
+                let channel = Self::mexc_swap_private_channel(data).ok_or_else(|| {
+                    "Неизвестный формат приватного сообщения MEXC Swap".to_string()
+                })?;
+
+                // Баланс относится к аккаунту, позиции, ордера и сделки - к контракту
+                let symbol = match channel {
+                    ChannelType::AccountBalance => "ACCOUNT".to_string(),
+                    _ => data["data"]["symbol"].as_str().unwrap_or("UNKNOWN").to_string(),
+                };
 
-                    return Ok(WsMessage {
-                        exchange: exchange_type,
-                        channel: ChannelType::AccountBalance,
-                        symbol: "ACCOUNT".to_string(),
-                        data: data.clone(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_millis() as u64,
-                    });
-                }
+                per_message_info!(
+                    "parse_private_message: обработано приватное сообщение MEXC Swap {} для {}",
+                    channel.as_str(),
+                    symbol
+                );
 
-                Err("Неизвестный формат приватного сообщения MEXC Swap".to_string())
+                Ok(WsMessage {
+                    exchange: exchange_type,
+                    channel,
+                    symbol,
+                    data: data.clone(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                    received_at_ns: monotonic_nanos(),
+                    event_time: frame_event_time(data),
+                    tags: Vec::new(),
+                    raw_bytes: None,
+                })
             }
             ExchangeType::BingxSpot | ExchangeType::BingxSwap => {
                 let data_type = data.get("dataType").and_then(|v| v.as_str()).unwrap_or("");
 
                 if data_type.contains("account") || data_type.contains("private") {
-                    info!(
+                    per_message_info!(
                         "parse_private_message: обработано приватное сообщение BingX: {}",
                         data_type
                     );
@@ -1187,11 +2478,52 @@ impl WsClientWrapper {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_millis() as u64,
+                        received_at_ns: monotonic_nanos(),
+                        event_time: frame_event_time(data),
+                        tags: Vec::new(),
+                        raw_bytes: None,
                     });
                 }
 
                 Err("Неизвестный формат приватного сообщения BingX".to_string())
             }
+            ExchangeType::OkxSpot => {
+                let channel = Self::okx_private_channel(data)
+                    .ok_or_else(|| "Неизвестный формат приватного сообщения OKX".to_string())?;
+
+                // Инструмент указан в arg для orders/fills/positions, иначе берём из данных
+                let symbol = match channel {
+                    ChannelType::AccountBalance if data["arg"]["channel"] == "account" => {
+                        "ACCOUNT".to_string()
+                    }
+                    _ => data["arg"]["instId"]
+                        .as_str()
+                        .or_else(|| data["data"][0]["instId"].as_str())
+                        .unwrap_or("UNKNOWN")
+                        .to_string(),
+                };
+
+                per_message_info!(
+                    "parse_private_message: обработано приватное сообщение OKX {} для {}",
+                    channel.as_str(),
+                    symbol
+                );
+
+                Ok(WsMessage {
+                    exchange: exchange_type,
+                    channel,
+                    symbol,
+                    data: data.clone(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as u64,
+                    received_at_ns: monotonic_nanos(),
+                    event_time: frame_event_time(data),
+                    tags: Vec::new(),
+                    raw_bytes: None,
+                })
+            }
             _ => Err("Неподдерживаемый тип биржи для приватных сообщений".to_string()),
         }
     }
@@ -1206,11 +2538,11 @@ impl WsClientWrapper {
                 // Проверяем формат User Data Stream: {"channel": "spot@private.account.v3.api.pb", ...}
                 if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
                     if channel_str.contains("private.account") || channel_str.contains("account") {
-                        info!("Обнаружено сообщение User Data Stream: {}", channel_str);
+                        per_message_info!("Обнаружено сообщение User Data Stream: {}", channel_str);
                         return Ok((ChannelType::AccountBalance, "ACCOUNT".to_string()));
                     }
                     if channel_str.contains("private.deals") {
-                        info!(
+                        per_message_info!(
                             "Обнаружено сообщение приватных сделок User Data Stream: {}",
                             channel_str
                         );
@@ -1222,7 +2554,7 @@ impl WsClientWrapper {
                             .or_else(|| data.get("symbol").and_then(|v| v.as_str()))
                             .unwrap_or("UNKNOWN")
                             .to_string();
-                        info!("Извлечен символ для приватных сделок: {}", symbol);
+                        per_message_info!("Извлечен символ для приватных сделок: {}", symbol);
                         return Ok((ChannelType::PrivateDeals, symbol));
                     }
                 }
@@ -1239,7 +2571,7 @@ impl WsClientWrapper {
 
                         if d_symbol.contains("private.deals") {
                             // Это приватная сделка - извлекаем реальный символ из поля quantity
-                            info!(
+                            per_message_info!(
                                 "Обнаружена приватная сделка в формате MEXC v3: symbol в d = {}",
                                 d_symbol
                             );
@@ -1258,7 +2590,7 @@ impl WsClientWrapper {
                                 .unwrap_or("UNKNOWN")
                                 .to_string();
 
-                            info!("Извлечен символ для приватной сделки: {}", symbol);
+                            per_message_info!("Извлечен символ для приватной сделки: {}", symbol);
                             return Ok((ChannelType::PrivateDeals, symbol));
                         } else {
                             // Обычные публичные сделки
@@ -1274,13 +2606,17 @@ impl WsClientWrapper {
                                     .and_then(|v| v.as_str())
                                     .unwrap_or("UNKNOWN")
                                     .to_string();
-                                debug!(
+                                per_message_debug!(
                                     "extract_channel_and_symbol: канал deals без символа, извлечен символ из данных: {}",
                                     symbol
                                 );
                                 Ok((ChannelType::Trades, symbol))
                             }
                         }
+                    } else if channel_str.contains("limit.depth") {
+                        // spot@public.limit.depth.v3.api@BTCUSDT@20 - полный срез top-N уровней
+                        let symbol = Self::extract_mexc_symbol_from_channel(channel_str)?;
+                        Ok((ChannelType::OrderbookSnapshot, symbol))
                     } else if channel_str.contains("depth")
                         || channel_str.contains("increase.depth")
                     {
@@ -1289,14 +2625,23 @@ impl WsClientWrapper {
                     } else if channel_str.contains("kline") {
                         let symbol = Self::extract_mexc_symbol_from_channel(channel_str)?;
                         Ok((ChannelType::Kline, symbol))
-                    } else if channel_str.contains("balance")
-                        || channel_str.contains("account")
-                        || channel_str.contains("executionReport")
-                    {
+                    } else if channel_str.contains("miniTicker") {
+                        // spot@public.miniTicker.v3.api@BTCUSDT@UTC+0 - 24ч статистика по паре
+                        let symbol = Self::extract_mexc_symbol_from_channel(channel_str)?;
+                        Ok((ChannelType::Ticker, symbol))
+                    } else if channel_str.contains("executionReport") {
+                        // spot@private.executionReport.v3.api - изменение состояния своего ордера
+                        let symbol = data
+                            .get("s")
+                            .or_else(|| data.get("d").and_then(|d| d.get("s")))
+                            .and_then(|v| v.as_str())
+                            .map_or_else(|| "UNKNOWN".to_string(), Self::mexc_spot_symbol);
+                        Ok((ChannelType::Orders, symbol))
+                    } else if channel_str.contains("balance") || channel_str.contains("account") {
                         // Для баланса аккаунта и приватных каналов символ не требуется
                         Ok((ChannelType::AccountBalance, "ACCOUNT".to_string()))
                     } else {
-                        debug!(
+                        per_message_debug!(
                             "extract_channel_and_symbol: неизвестный канал MEXC Spot: {}",
                             channel_str
                         );
@@ -1305,7 +2650,7 @@ impl WsClientWrapper {
                 } else {
                     // Если нет ни "channel", ни "c", проверяем другие поля для User Data Stream
                     if data.get("privateAccount").is_some() || data.get("createTime").is_some() {
-                        info!(
+                        per_message_info!(
                             "Обнаружено сообщение User Data Stream по полям privateAccount/createTime"
                         );
                         return Ok((ChannelType::AccountBalance, "ACCOUNT".to_string()));
@@ -1316,12 +2661,18 @@ impl WsClientWrapper {
             ExchangeType::MexcSwap => {
                 // MEXC Swap формат: {"channel": "push.deal", "symbol": "BTC_USDT", "data": {...}, "ts": timestamp}
                 if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
-                    let channel_type = if channel_str.contains("deal") {
+                    let channel_type = if channel_str.contains("funding") {
+                        ChannelType::FundingRate
+                    } else if channel_str.contains("index.price") {
+                        ChannelType::IndexPrice
+                    } else if channel_str.contains("deal") {
                         ChannelType::Trades
                     } else if channel_str.contains("depth") {
                         ChannelType::Orderbook
                     } else if channel_str.contains("ticker") {
                         ChannelType::Ticker
+                    } else if channel_str.contains("kline") {
+                        ChannelType::Kline
                     } else if channel_str.contains("balance") || channel_str.contains("account") {
                         ChannelType::AccountBalance
                     } else {
@@ -1344,8 +2695,24 @@ impl WsClientWrapper {
                 }
             }
             ExchangeType::BingxSpot | ExchangeType::BingxSwap => {
-                // BingX формат: {"dataType": "BTC-USDT@depth", "data": {...}, "ts": timestamp}
-                if let Some(data_type) = data.get("dataType").and_then(|v| v.as_str()) {
+                // BingX присылает данные в двух конвертах:
+                // {"code": 0, "dataType": "BTC-USDT@depth", "data": {...}, "ts": timestamp}
+                // и {"dataType": "BTC-USDT@trade", "data": [...]}; в части ответов dataType
+                // вложен в data: {"code": 0, "data": {"dataType": "...", "data": {...}}}
+                if let Some(code) = data.get("code").and_then(|v| v.as_i64())
+                    && code != 0
+                {
+                    return Err(format!(
+                        "Ошибка BingX {}: {}",
+                        code,
+                        data["msg"].as_str().unwrap_or_default()
+                    ));
+                }
+                let data_type = [&data["dataType"], &data["data"]["dataType"]]
+                    .into_iter()
+                    .filter_map(|v| v.as_str())
+                    .find(|data_type| !data_type.is_empty());
+                if let Some(data_type) = data_type {
                     let parts: Vec<&str> = data_type.split('@').collect();
                     if parts.len() != 2 {
                         return Err(format!("Неправильный формат dataType BingX: {}", data_type));
@@ -1386,7 +2753,13 @@ impl WsClientWrapper {
     /// Извлекает символ из канала MEXC
     fn extract_mexc_symbol_from_channel(channel: &str) -> Result<String, String> {
         // Формат: "spot@public.deals.v3.api@BTCUSDT"
-        let parts: Vec<&str> = channel.split('@').collect();
+        // Некоторые каналы содержат завершающий параметр после символа:
+        // "spot@public.limit.depth.v3.api@BTCUSDT@20" (уровни),
+        // "spot@public.kline.v3.api@BTCUSDT@Min15" (интервал)
+        let mut parts: Vec<&str> = channel.split('@').collect();
+        if parts.len() >= 4 && Self::is_mexc_channel_param(parts[parts.len() - 1]) {
+            parts.pop();
+        }
         if parts.len() < 3 {
             return Err(format!("Канал MEXC не содержит символа: {}", channel));
         }
@@ -1398,8 +2771,12 @@ impl WsClientWrapper {
             return Err(format!("Канал MEXC не содержит символа в конце: {}", channel));
         }
 
-        // Конвертируем BTCUSDT обратно в BTC_USDT
-        let symbol = if symbol_part.len() >= 6 && symbol_part.ends_with("USDT") {
+        Ok(Self::mexc_spot_symbol(symbol_part))
+    }
+
+    /// Конвертирует символ MEXC Spot BTCUSDT обратно в BTC_USDT
+    fn mexc_spot_symbol(symbol_part: &str) -> String {
+        if symbol_part.len() >= 6 && symbol_part.ends_with("USDT") {
             let base = &symbol_part[..symbol_part.len() - 4];
             format!("{}_USDT", base)
         } else if symbol_part.len() >= 7 && symbol_part.ends_with("USDC") {
@@ -1407,9 +2784,24 @@ impl WsClientWrapper {
             format!("{}_USDC", base)
         } else {
             symbol_part.to_string()
-        };
+        }
+    }
 
-        Ok(symbol)
+    /// Проверяет, является ли сегмент канала MEXC параметром (число уровней, интервал kline
+    /// или часовой пояс miniTicker)
+    fn is_mexc_channel_param(segment: &str) -> bool {
+        if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            return true;
+        }
+        if segment == "24H" || segment.starts_with("UTC") {
+            return true;
+        }
+        ["Min", "Hour", "Day", "Week", "Month"].iter().any(|prefix| {
+            segment
+                .strip_prefix(prefix)
+                .map(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or(false)
+        })
     }
 
     /// Проверяет, является ли сообщение служебным (не содержащим торговых данных)
@@ -1423,9 +2815,11 @@ impl WsClientWrapper {
                     data.get("msg").and_then(|v| v.as_str()),
                 ) {
                     // Это ответ на подписку или служебное сообщение
-                    debug!(
+                    per_message_debug!(
                         "is_service_message: обнаружено служебное сообщение MEXC Spot: id={}, code={}, msg={}",
-                        id, code, msg
+                        id,
+                        code,
+                        msg
                     );
                     return true;
                 }
@@ -1433,7 +2827,7 @@ impl WsClientWrapper {
                 // Проверяем User Data Stream сообщения - они НЕ являются служебными
                 if let Some(channel_str) = data.get("channel").and_then(|v| v.as_str()) {
                     if channel_str.contains("private") {
-                        debug!(
+                        per_message_debug!(
                             "is_service_message: обнаружено User Data Stream приватное сообщение - НЕ служебное: {}",
                             channel_str
                         );
@@ -1445,7 +2839,7 @@ impl WsClientWrapper {
                 if data.get("privateDeals").is_some() 
                     || data.get("privateAccount").is_some() 
                     || data.get("createTime").is_some() {
-                    debug!(
+                    per_message_debug!(
                         "is_service_message: обнаружено сообщение с приватными данными - НЕ служебное"
                     );
                     return false;
@@ -1453,7 +2847,9 @@ impl WsClientWrapper {
 
                 // Проверяем, есть ли поле "c" с данными канала (публичные сообщения)
                 if data.get("c").is_none() && data.get("d").is_none() {
-                    debug!("is_service_message: сообщение MEXC Spot не содержит данных канала");
+                    per_message_debug!(
+                        "is_service_message: сообщение MEXC Spot не содержит данных канала"
+                    );
                     return true;
                 }
 
@@ -1465,16 +2861,19 @@ impl WsClientWrapper {
                     data.get("id").and_then(|v| v.as_i64()),
                     data.get("code").and_then(|v| v.as_i64()),
                 ) {
-                    debug!(
+                    per_message_debug!(
                         "is_service_message: обнаружено служебное сообщение MEXC Swap: id={}, code={}",
-                        id, code
+                        id,
+                        code
                     );
                     return true;
                 }
 
                 // Проверяем наличие основных полей
                 if data.get("channel").is_none() && data.get("data").is_none() {
-                    debug!("is_service_message: сообщение MEXC Swap не содержит данных канала");
+                    per_message_debug!(
+                        "is_service_message: сообщение MEXC Swap не содержит данных канала"
+                    );
                     return true;
                 }
 
@@ -1484,14 +2883,18 @@ impl WsClientWrapper {
                 // Проверяем служебные сообщения BingX
                 if let Some(result) = data.get("result") {
                     if result.as_bool() == Some(true) || result.as_bool() == Some(false) {
-                        debug!("is_service_message: обнаружено служебное сообщение BingX");
+                        per_message_debug!(
+                            "is_service_message: обнаружено служебное сообщение BingX"
+                        );
                         return true;
                     }
                 }
 
                 // Проверяем наличие основных полей
                 if data.get("dataType").is_none() && data.get("data").is_none() {
-                    debug!("is_service_message: сообщение BingX не содержит данных канала");
+                    per_message_debug!(
+                        "is_service_message: сообщение BingX не содержит данных канала"
+                    );
                     return true;
                 }
 
@@ -1500,6 +2903,68 @@ impl WsClientWrapper {
             _ => false,
         }
     }
+
+    /// Распознать ответ биржи на запрос подписки.
+    ///
+    /// Возвращает `Some(Ok(()))` для подтверждения, `Some(Err(..))` для отказа и
+    /// `None`, если кадр не является ответом на подписку.
+    fn subscription_ack(
+        exchange_type: &ExchangeType,
+        raw_message: &str,
+    ) -> Option<Result<(), String>> {
+        let data: Value = serde_json::from_str(raw_message).ok()?;
+        match exchange_type {
+            // {"id":0,"code":0,"msg":"spot@public.deals.v3.api@BTCUSDT"}
+            ExchangeType::MexcSpot => {
+                let code = data.get("code")?.as_i64()?;
+                let msg = data.get("msg")?.as_str()?;
+                data.get("id")?;
+                if code == 0 && !msg.contains("Not Subscribed") {
+                    Some(Ok(()))
+                } else {
+                    Some(Err(msg.to_string()))
+                }
+            }
+            // {"channel":"rs.sub.deal","data":"success"} или {"channel":"rs.error","data":"..."}
+            ExchangeType::MexcSwap => {
+                let channel = data.get("channel")?.as_str()?;
+                let result = data["data"].as_str().unwrap_or_default();
+                if channel == "rs.error" {
+                    Some(Err(result.to_string()))
+                } else if channel.starts_with("rs.sub.") {
+                    if result == "success" { Some(Ok(())) } else { Some(Err(result.to_string())) }
+                } else {
+                    None
+                }
+            }
+            // {"id":"...","code":0,"msg":"","dataType":"","data":null}
+            ExchangeType::BingxSpot | ExchangeType::BingxSwap => {
+                data.get("id")?;
+                let code = data.get("code")?.as_i64()?;
+                if !data["data"].is_null() {
+                    return None;
+                }
+                if code == 0 {
+                    Some(Ok(()))
+                } else {
+                    Some(Err(format!("{} {}", code, data["msg"].as_str().unwrap_or_default())))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Отказ в подписке из-за лимита подписок на одно соединение
+    fn is_subscription_limit_rejection(exchange_type: &ExchangeType, reason: &str) -> bool {
+        match exchange_type {
+            // "Not Subscribed successfully! [...].  Reason： Subscription limit exceeded"
+            ExchangeType::MexcSpot => {
+                let reason = reason.to_lowercase();
+                reason.contains("limit") || reason.contains("maximum")
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Фабрика для создания WebSocket клиентов
@@ -1536,26 +3001,459 @@ impl WsClientFactory {
     }
 }
 
+/// Размер окна дедупликации сообщений основного и резервных соединений
+const STANDBY_DEDUP_WINDOW: usize = 10_000;
+
+/// Резервные соединения одной биржи.
+///
+/// Основное соединение хранится в `CryptoWsClient::clients`, здесь — только его
+/// идентификатор и резервные клиенты с их идентификаторами.
+#[derive(Default)]
+struct StandbyGroup {
+    primary_id: String,
+    standby: Vec<(String, WsClientWrapper)>,
+}
+
+/// Окно уже выданных сообщений основного и резервных соединений биржи
+#[derive(Default)]
+struct DedupWindow {
+    seen: VecDeque<u64>,
+    seen_set: HashSet<u64>,
+}
+
+impl DedupWindow {
+    /// Отметить сообщение как выданное; `false`, если такое уже приходило
+    /// по другому соединению в пределах окна дедупликации
+    fn first_seen(&mut self, message: &WsMessage) -> bool {
+        let mut hasher = DefaultHasher::new();
+        message.channel.as_str().hash(&mut hasher);
+        message.symbol.hash(&mut hasher);
+        message.data.to_string().hash(&mut hasher);
+        let key = hasher.finish();
+
+        if !self.seen_set.insert(key) {
+            return false;
+        }
+        self.seen.push_back(key);
+        if self.seen.len() > STANDBY_DEDUP_WINDOW
+            && let Some(oldest) = self.seen.pop_front()
+        {
+            self.seen_set.remove(&oldest);
+        }
+        true
+    }
+}
+
+/// Приёмники соединений одной биржи
+#[derive(Default)]
+struct ExchangeSources {
+    /// Основное соединение и шарды: у каждого свой набор подписок
+    connections: Vec<MessageSource>,
+    /// Резервные соединения повторяют подписки основного
+    standby: Vec<MessageSource>,
+}
+
+/// Состояние чтения сообщений, общее для [`CryptoWsClient`] и его [`MessageReader`]
+#[derive(Default)]
+struct ReaderState {
+    /// Приёмники подключенных бирж в порядке их добавления
+    sources: IndexMap<ExchangeType, ExchangeSources>,
+    dedup: HashMap<ExchangeType, DedupWindow>,
+    paused_exchanges: HashSet<ExchangeType>,
+    service_predicates: HashMap<ExchangeType, Vec<ServiceMessagePredicate>>,
+    recorder: Option<FrameRecorder>,
+    /// Последний тикер по (бирже, символу), обновляется при чтении сообщений
+    last_tickers: HashMap<(ExchangeType, String), Ticker>,
+    /// Максимальная задержка между событием на бирже и получением сообщения
+    max_message_age: Option<Duration>,
+    /// Количество сообщений, отброшенных как устаревшие
+    stale_messages: u64,
+    /// Теги подписок: (биржа, канал, нормализованный символ) -> теги
+    subscription_tags: HashMap<(ExchangeType, String, String), Vec<String>>,
+    /// Биржи, на которых `CryptoWsClient` ждёт ответа на подписку, и число ожиданий
+    awaiting_ack: HashMap<ExchangeType, usize>,
+    /// Сообщения, отложенные [`CryptoWsClient::collect`]; отдаются первыми
+    deferred: VecDeque<WsMessage>,
+}
+
+/// Ожидание ответа биржи на подписку: пока оно активно, [`MessageReader`] оставляет
+/// ответы на подписку в буфере. Снимается и при отмене ожидания.
+struct AwaitingAck {
+    reader: MessageReader,
+    exchange_type: ExchangeType,
+}
+
+impl AwaitingAck {
+    fn new(reader: &MessageReader, exchange_type: &ExchangeType) -> Self {
+        *reader.state().awaiting_ack.entry(exchange_type.clone()).or_default() += 1;
+        Self { reader: reader.clone(), exchange_type: exchange_type.clone() }
+    }
+}
+
+impl Drop for AwaitingAck {
+    fn drop(&mut self) {
+        let mut state = self.reader.state();
+        if let Some(count) = state.awaiting_ack.get_mut(&self.exchange_type) {
+            *count = count.saturating_sub(1);
+        }
+    }
+}
+
+/// Читатель сообщений [`CryptoWsClient`], которому не нужен `&mut` доступ к клиенту.
+///
+/// Получается через [`CryptoWsClient::message_reader`] и разделяет с клиентом буферы
+/// соединений, паузы, предикаты служебных кадров, теги и запись кадров. Читатель
+/// можно передать в отдельную задачу и получать сообщения, пока клиент в другой
+/// задаче подписывается и отписывается. Набор читаемых соединений обновляется при
+/// подключении, отключении и переключении на резервное соединение.
+#[derive(Clone, Default)]
+pub struct MessageReader {
+    state: Arc<Mutex<ReaderState>>,
+}
+
+impl MessageReader {
+    fn state(&self) -> std::sync::MutexGuard<'_, ReaderState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Получить следующее сообщение из всех подключенных бирж (неблокирующий вызов)
+    pub fn next_message(&self) -> Option<WsMessage> {
+        let mut guard = self.state();
+        let state = &mut *guard;
+
+        if let Some(message) = state.deferred.pop_front() {
+            return Some(message);
+        }
+
+        for (exchange_type, sources) in &state.sources {
+            per_message_debug!(
+                "MessageReader::next_message: проверяем сообщения от биржи {:?}",
+                exchange_type
+            );
+
+            if state.paused_exchanges.contains(exchange_type) {
+                // Соединение живо, но сообщения приостановленной биржи отбрасываются
+                let dropped: usize = sources.connections.iter().map(MessageSource::drain).sum();
+                if dropped > 0 {
+                    trace!(
+                        "MessageReader::next_message: отброшено {} сообщений приостановленной биржи {:?}",
+                        dropped, exchange_type
+                    );
+                }
+                for source in &sources.standby {
+                    source.drain();
+                }
+                continue;
+            }
+
+            let predicates =
+                state.service_predicates.get(exchange_type).map(Vec::as_slice).unwrap_or(&[]);
+            let awaiting_ack = state
+                .awaiting_ack
+                .get(exchange_type)
+                .is_some_and(|&n| n > 0)
+                .then_some(exchange_type);
+            let mut dedup = if sources.standby.is_empty() {
+                None
+            } else {
+                Some(state.dedup.entry(exchange_type.clone()).or_default())
+            };
+
+            for source in &sources.connections {
+                // Устаревшие сообщения пропускаются подряд, чтобы не вернуть None при
+                // свежих сообщениях в буфере
+                let received = loop {
+                    match source.next_message(predicates, state.recorder.as_mut(), awaiting_ack) {
+                        Some(message)
+                            if CryptoWsClient::is_stale(&message, state.max_message_age) =>
+                        {
+                            state.stale_messages += 1;
+                            trace!(
+                                "MessageReader::next_message: устаревшее сообщение {:?} отброшено",
+                                exchange_type
+                            );
+                        }
+                        received => break received,
+                    }
+                };
+                if let Some(mut message) = received {
+                    if dedup.as_mut().is_none_or(|dedup| dedup.first_seen(&message)) {
+                        CryptoWsClient::remember_ticker(&mut state.last_tickers, &message);
+                        CryptoWsClient::apply_tags(&state.subscription_tags, &mut message);
+                        return Some(message);
+                    }
+                    trace!(
+                        "MessageReader::next_message: дубликат сообщения {:?} пропущен",
+                        exchange_type
+                    );
+                }
+            }
+
+            // Резервные соединения восполняют то, что основное пропустило
+            if let Some(dedup) = dedup
+                && let Some(mut message) = sources.standby.iter().find_map(|source| {
+                    std::iter::from_fn(|| source.next_message(predicates, None, None))
+                        .find(|message| dedup.first_seen(message))
+                })
+            {
+                if CryptoWsClient::is_stale(&message, state.max_message_age) {
+                    state.stale_messages += 1;
+                } else {
+                    CryptoWsClient::remember_ticker(&mut state.last_tickers, &message);
+                    CryptoWsClient::apply_tags(&state.subscription_tags, &mut message);
+                    return Some(message);
+                }
+            }
+        }
+
+        trace!("MessageReader::next_message: нет новых сообщений от всех бирж");
+        None
+    }
+}
+
 /// Основной унифицированный WebSocket клиент для всех криптовалютных бирж
+///
+/// Биржи хранятся в порядке добавления: в нём их обходят [`connect_all`](Self::connect_all),
+/// [`get_connected_exchanges`](Self::get_connected_exchanges) и
+/// [`next_message`](Self::next_message).
 pub struct CryptoWsClient {
-    clients: HashMap<ExchangeType, WsClientWrapper>,
-    message_sender: Option<async_mpsc::UnboundedSender<WsMessage>>,
-    message_receiver: Option<async_mpsc::UnboundedReceiver<WsMessage>>,
+    clients: IndexMap<ExchangeType, WsClientWrapper>,
+    /// Конфигурации добавленных бирж для пересоздания клиента при перезапуске
+    configs: IndexMap<ExchangeType, ExchangeConfig>,
     subscription_manager: SubscriptionManagerImpl,
-    connection_states: HashMap<ExchangeType, ConnectionState>,
+    connection_states: IndexMap<ExchangeType, ConnectionState>,
+    buffer_capacity: usize,
+    /// Состояние чтения сообщений, общее с [`MessageReader`]
+    reader: MessageReader,
+    standby_groups: HashMap<ExchangeType, StandbyGroup>,
+    subscription_limits: HashMap<ExchangeType, usize>,
+    max_concurrent_connects: usize,
+    /// Объединение частичных исполнений в `next_private_event`, если включено
+    fill_aggregator: Option<FillAggregator>,
+    /// Выполнять приватные подписки раньше публичных в `connect_with_subscriptions`
+    private_first: bool,
+    /// Ждать ответа биржи на публичные подписки
+    confirm_subscriptions: bool,
+    /// Открывать новое соединение, когда биржа отказывает в подписке по лимиту
+    auto_sharding: bool,
+    /// Дополнительные соединения (шарды) с подписками, не поместившимися в основное
+    shards: IndexMap<ExchangeType, Vec<WsClientWrapper>>,
+    /// Выдавать сообщения [`ChannelType::Heartbeat`] на каждый pong
+    heartbeats: bool,
+    /// Сохранять исходные бинарные кадры в [`WsMessage::raw_bytes`]
+    raw_frames: bool,
+    /// Число потоков декодирования бинарных кадров; 0 — декодирование в цикле чтения
+    parse_workers: usize,
+    /// Выдавать нераспознанные бинарные кадры вместо отбрасывания
+    undecodable_passthrough: bool,
+    /// Хуки переподключения бирж для всех их соединений, включая будущие
+    reconnect_hooks: HashMap<ExchangeType, ReconnectHook>,
+    /// Выдавать сообщения [`ChannelType::ConnectionStatus`] при смене состояния биржи
+    connection_status_messages: bool,
+    /// Последнее состояние каждой биржи, о котором сообщено в поток
+    reported_states: HashMap<ExchangeType, ConnectionState>,
+    /// Время ожидания ответа биржи на подписку
+    subscription_ack_timeout: Duration,
+    /// Сигнал остановки фоновых задач `run()` при удалении клиента
+    shutdown_tx: watch::Sender<bool>,
 }
 
 impl CryptoWsClient {
     /// Создание нового WebSocket клиента
     pub fn new() -> Self {
-        let (sender, receiver) = async_mpsc::unbounded_channel();
         Self {
-            clients: HashMap::new(),
-            message_sender: Some(sender),
-            message_receiver: Some(receiver),
+            clients: IndexMap::new(),
+            configs: IndexMap::new(),
             subscription_manager: SubscriptionManagerImpl::default(),
-            connection_states: HashMap::new(),
+            connection_states: IndexMap::new(),
+            buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+            reader: MessageReader::default(),
+            standby_groups: HashMap::new(),
+            subscription_limits: HashMap::new(),
+            max_concurrent_connects: DEFAULT_MAX_CONCURRENT_CONNECTS,
+            fill_aggregator: None,
+            private_first: false,
+            confirm_subscriptions: false,
+            auto_sharding: false,
+            shards: IndexMap::new(),
+            heartbeats: false,
+            raw_frames: false,
+            parse_workers: 0,
+            undecodable_passthrough: false,
+            reconnect_hooks: HashMap::new(),
+            connection_status_messages: false,
+            reported_states: HashMap::new(),
+            subscription_ack_timeout: DEFAULT_SUBSCRIPTION_ACK_TIMEOUT,
+            shutdown_tx: watch::channel(false).0,
+        }
+    }
+
+    /// Установить емкость буфера сообщений каждой биржи
+    ///
+    /// При переполнении самые старые сообщения отбрасываются, а их количество
+    /// доступно через [`dropped_messages`](Self::dropped_messages).
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        self.buffer_capacity = capacity;
+        for client in self.clients.values() {
+            client.set_buffer_capacity(capacity);
         }
+        for group in self.standby_groups.values() {
+            for (_, client) in &group.standby {
+                client.set_buffer_capacity(capacity);
+            }
+        }
+        self
+    }
+
+    /// Переопределить лимит подписок на соединение для биржи
+    ///
+    /// По умолчанию используется [`ExchangeType::max_ws_subscriptions`].
+    pub fn with_subscription_limit(mut self, exchange_type: ExchangeType, limit: usize) -> Self {
+        self.subscription_limits.insert(exchange_type, limit);
+        self
+    }
+
+    /// Ограничить число одновременных handshake в
+    /// [`connect_exchanges`](Self::connect_exchanges), чтобы не упираться в лимиты
+    /// подключений с одного IP. По умолчанию [`DEFAULT_MAX_CONCURRENT_CONNECTS`].
+    pub fn with_max_concurrent_connects(mut self, limit: usize) -> Self {
+        self.max_concurrent_connects = limit.max(1);
+        self
+    }
+
+    /// Объединять частичные исполнения одного ордера, пришедшие в течение `window`,
+    /// в одно событие [`PrivateEvent::Fill`] в [`next_private_event`](Self::next_private_event)
+    ///
+    /// Количество и комиссия суммируются, цена усредняется с весом по объёму.
+    /// Исполнение выдаётся после истечения окна от первого частичного исполнения.
+    pub fn with_fill_aggregation(mut self, window: Duration) -> Self {
+        self.fill_aggregator = Some(FillAggregator::new(window));
+        self
+    }
+
+    /// При первом подключении в [`connect_with_subscriptions`](Self::connect_with_subscriptions)
+    /// выполнять приватные подписки до публичных.
+    ///
+    /// Поток публичных данных не задерживает аутентификацию и ранние события аккаунта.
+    pub fn with_private_first(mut self, enabled: bool) -> Self {
+        self.private_first = enabled;
+        self
+    }
+
+    /// Ждать подтверждения биржи в публичных `subscribe_*`.
+    ///
+    /// Подписка считается выполненной только после ответа биржи; отказ биржи
+    /// возвращается ошибкой, а при отсутствии ответа за
+    /// [`set_subscription_ack_timeout`](Self::set_subscription_ack_timeout) возвращается
+    /// [`ExchangeError::SubscriptionTimeout`]. Неподтверждённая подписка не учитывается.
+    pub fn with_confirm_subscriptions(mut self, enabled: bool) -> Self {
+        self.confirm_subscriptions = enabled;
+        self
+    }
+
+    /// Автоматически открывать новые соединения (шарды), когда биржа отклоняет
+    /// подписку из-за лимита подписок на одно соединение.
+    ///
+    /// Отклонённая подписка повторяется на новом шарде, следующие подписки биржи
+    /// отправляются туда же, а сообщения шардов читаются вместе с основным
+    /// соединением. Отказ виден только в ответе биржи, поэтому включается и
+    /// [`with_confirm_subscriptions`](Self::with_confirm_subscriptions); локальная
+    /// проверка [`check_subscription_limit`](Self::check_subscription_limit) при этом
+    /// не ограничивает подписки. Поддерживается MEXC Spot.
+    pub fn with_auto_sharding(mut self, enabled: bool) -> Self {
+        self.auto_sharding = enabled;
+        if enabled {
+            self.confirm_subscriptions = true;
+        }
+        self
+    }
+
+    /// Выдавать в [`next_message`](Self::next_message) сообщение
+    /// [`ChannelType::Heartbeat`] на каждый pong транспорта.
+    ///
+    /// Подтверждает, что поток жив, даже когда по подпискам нет данных. По умолчанию
+    /// выключено, чтобы не засорять поток.
+    pub fn with_heartbeats(mut self, enabled: bool) -> Self {
+        self.heartbeats = enabled;
+        for client in self.clients.values() {
+            client.set_heartbeat_messages(enabled);
+        }
+        for group in self.standby_groups.values() {
+            for (_, client) in &group.standby {
+                client.set_heartbeat_messages(enabled);
+            }
+        }
+        self
+    }
+
+    /// Сохранять в [`WsMessage::raw_bytes`] исходные бинарные кадры (protobuf MEXC).
+    ///
+    /// Позволяет заново декодировать сообщения обновлённой схемой или разбирать
+    /// инциденты по исходным данным. По умолчанию выключено: каждое сообщение
+    /// бинарного потока хранит копию кадра.
+    pub fn with_raw_frames(mut self, enabled: bool) -> Self {
+        self.raw_frames = enabled;
+        for client in self.clients.values() {
+            client.set_raw_frames(enabled);
+        }
+        for group in self.standby_groups.values() {
+            for (_, client) in &group.standby {
+                client.set_raw_frames(enabled);
+            }
+        }
+        self
+    }
+
+    /// Декодировать бинарные кадры (protobuf MEXC) в пуле из `workers` потоков, а не
+    /// в цикле чтения сокета.
+    ///
+    /// Цикл чтения принимает новые кадры, пока предыдущие декодируются, порядок
+    /// сообщений сохраняется. 0 (по умолчанию) — декодирование в цикле чтения.
+    /// Применяется при следующем подключении.
+    pub fn with_parse_workers(mut self, workers: usize) -> Self {
+        self.parse_workers = workers;
+        for client in self.clients.values() {
+            client.set_parse_workers(workers);
+        }
+        for group in self.standby_groups.values() {
+            for (_, client) in &group.standby {
+                client.set_parse_workers(workers);
+            }
+        }
+        self
+    }
+
+    /// Выдавать бинарные кадры, которые транспорт не смог декодировать (например,
+    /// protobuf MEXC неизвестной схемы), сообщениями [`ChannelType::Unknown`] вместо
+    /// отбрасывания.
+    ///
+    /// Исходные байты кадра передаются в `data` в base64, чтобы их можно было
+    /// исследовать или декодировать позже. По умолчанию такие кадры отбрасываются.
+    pub fn with_undecodable_passthrough(mut self, enabled: bool) -> Self {
+        self.undecodable_passthrough = enabled;
+        for client in self.clients.values() {
+            client.set_undecodable_passthrough(enabled);
+        }
+        for group in self.standby_groups.values() {
+            for (_, client) in &group.standby {
+                client.set_undecodable_passthrough(enabled);
+            }
+        }
+        self
+    }
+
+    /// Выдавать в [`next_message`](Self::next_message) сообщение
+    /// [`ChannelType::ConnectionStatus`] при каждой смене состояния подключения биржи.
+    ///
+    /// Разрыв соединения, переподключение и ошибка приходят в том же потоке, что и
+    /// данные, поэтому потребитель узнаёт о пропуске данных без отдельного опроса
+    /// [`unhealthy_exchanges`](Self::unhealthy_exchanges). Состояние проверяется при каждом
+    /// вызове [`next_message`](Self::next_message); о начальном подключении биржи
+    /// сообщение не выдаётся. По умолчанию выключено.
+    pub fn with_connection_status_messages(mut self, enabled: bool) -> Self {
+        self.connection_status_messages = enabled;
+        self
     }
 
     /// Добавить WebSocket клиент для биржи
@@ -1564,27 +3462,82 @@ impl CryptoWsClient {
         exchange_type: ExchangeType,
         config: ExchangeConfig,
     ) -> Result<(), String> {
-        let client = WsClientFactory::create_client(exchange_type.clone(), config).await?;
+        let client = WsClientFactory::create_client(exchange_type.clone(), config.clone()).await?;
+        self.apply_client_settings(&exchange_type, &client);
         self.clients.insert(exchange_type.clone(), client);
+        self.configs.insert(exchange_type.clone(), config);
         self.connection_states.insert(exchange_type, ConnectionState::Disconnected);
         Ok(())
     }
 
+    /// Задать хук переподключения биржи для всех её соединений: основного,
+    /// резервных и шардов, в том числе созданных позже.
+    ///
+    /// Хук вызывается после успешного переподключения до восстановления подписок;
+    /// возвращаемые им команды (например, кадр аутентификации) отправляются первыми.
+    pub fn set_on_reconnect(
+        &mut self,
+        exchange_type: &ExchangeType,
+        hook: ReconnectHook,
+    ) -> Result<(), String> {
+        let client = self
+            .clients
+            .get(exchange_type)
+            .ok_or_else(|| format!("Клиент для биржи {:?} не найден", exchange_type))?;
+        client.set_on_reconnect(hook.clone());
+        if let Some(group) = self.standby_groups.get(exchange_type) {
+            for (_, standby) in &group.standby {
+                standby.set_on_reconnect(hook.clone());
+            }
+        }
+        for shard in self.shards.get(exchange_type).into_iter().flatten() {
+            shard.set_on_reconnect(hook.clone());
+        }
+        self.reconnect_hooks.insert(exchange_type.clone(), hook);
+        Ok(())
+    }
+
+    /// Применить настройки клиента и хук переподключения биржи к новому соединению
+    fn apply_client_settings(&self, exchange_type: &ExchangeType, client: &WsClientWrapper) {
+        client.set_buffer_capacity(self.buffer_capacity);
+        client.set_heartbeat_messages(self.heartbeats);
+        client.set_raw_frames(self.raw_frames);
+        client.set_parse_workers(self.parse_workers);
+        client.set_undecodable_passthrough(self.undecodable_passthrough);
+        if let Some(hook) = self.reconnect_hooks.get(exchange_type) {
+            client.set_on_reconnect(hook.clone());
+        }
+    }
+
     /// Удалить WebSocket клиент
     pub async fn remove_exchange(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
-        if let Some(mut client) = self.clients.remove(exchange_type) {
+        if let Some(mut client) = self.clients.shift_remove(exchange_type) {
             let _ = client.disconnect().await;
         }
-        self.connection_states.remove(exchange_type);
+        if let Some(group) = self.standby_groups.remove(exchange_type) {
+            for (_, mut client) in group.standby {
+                let _ = client.disconnect().await;
+            }
+        }
+        for mut shard in self.shards.shift_remove(exchange_type).unwrap_or_default() {
+            let _ = shard.disconnect().await;
+        }
+        self.connection_states.shift_remove(exchange_type);
+        self.configs.shift_remove(exchange_type);
+        self.reconnect_hooks.remove(exchange_type);
+        self.reader.state().paused_exchanges.remove(exchange_type);
+        self.subscription_manager.remove_exchange(exchange_type);
+        self.sync_reader();
         Ok(())
     }
 
     /// Подключиться к биржам
     pub async fn connect_all(&mut self) -> Result<(), String> {
+        let shutdown = self.shutdown_tx.subscribe();
         for (exchange_type, client) in &mut self.clients {
             self.connection_states.insert(exchange_type.clone(), ConnectionState::Connecting);
 
-            match client.connect().await {
+            match client.start_background_task_with_shutdown(shutdown.clone()).await {
                 Ok(_) => {
                     self.connection_states
                         .insert(exchange_type.clone(), ConnectionState::Connected);
@@ -1592,653 +3545,3937 @@ impl CryptoWsClient {
                 Err(e) => {
                     self.connection_states
                         .insert(exchange_type.clone(), ConnectionState::Error(e.clone()));
+                    self.sync_reader();
                     return Err(e);
                 }
             }
         }
+
+        // Резервные соединения не блокируют запуск: основное уже работает
+        for (exchange_type, group) in &mut self.standby_groups {
+            for (instance_id, client) in &mut group.standby {
+                if let Err(e) = client.start_background_task_with_shutdown(shutdown.clone()).await {
+                    warn!(
+                        "CryptoWsClient::connect_all: резервное соединение {} биржи {:?} не подключено: {}",
+                        instance_id, exchange_type, e
+                    );
+                }
+            }
+        }
+        self.sync_reader();
         Ok(())
     }
 
-    /// Подключиться к конкретной бирже
-    pub async fn connect_exchange(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            self.connection_states.insert(exchange_type.clone(), ConnectionState::Connecting);
-
-            match client.connect().await {
-                Ok(_) => {
-                    self.connection_states
-                        .insert(exchange_type.clone(), ConnectionState::Connected);
-                    Ok(())
+    /// Добавить и подключить несколько бирж параллельно.
+    ///
+    /// Одновременно выполняется не более `max_concurrent_connects` handshake
+    /// (см. [`with_max_concurrent_connects`](Self::with_max_concurrent_connects)).
+    /// Биржи, подключившиеся успешно, остаются подключенными; возвращается первая
+    /// ошибка, если она была. Биржи добавляются в порядке `exchanges`, независимо от
+    /// того, какой handshake завершился раньше.
+    pub async fn connect_exchanges(
+        &mut self,
+        exchanges: Vec<(ExchangeType, ExchangeConfig)>,
+    ) -> Result<(), String> {
+        let order: Vec<ExchangeType> =
+            exchanges.iter().map(|(exchange, _)| exchange.clone()).collect();
+        let mut created = connect_concurrently(
+            exchanges,
+            self.max_concurrent_connects,
+            |(exchange, config)| async move {
+                let result = WsClientFactory::create_client(exchange.clone(), config.clone())
+                    .await
+                    .map(|client| (client, config));
+                (exchange, result)
+            },
+        )
+        .await;
+        created.sort_by_key(|(exchange, _)| order.iter().position(|e| e == exchange));
+
+        let mut first_error = None;
+        for (exchange_type, result) in created {
+            let result = match result {
+                Ok((client, config)) => {
+                    self.apply_client_settings(&exchange_type, &client);
+                    self.clients.insert(exchange_type.clone(), client);
+                    self.configs.insert(exchange_type.clone(), config);
+                    self.connect_exchange(&exchange_type).await
                 }
                 Err(e) => {
                     self.connection_states
                         .insert(exchange_type.clone(), ConnectionState::Error(e.clone()));
                     Err(e)
                 }
+            };
+            if let Err(e) = result {
+                warn!(
+                    "CryptoWsClient::connect_exchanges: {:?} не подключена: {}",
+                    exchange_type, e
+                );
+                first_error.get_or_insert(e);
             }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Подключиться к конкретной бирже
+    pub async fn connect_exchange(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
+        let shutdown = self.shutdown_tx.subscribe();
+        if let Some(client) = self.clients.get_mut(exchange_type) {
+            self.connection_states.insert(exchange_type.clone(), ConnectionState::Connecting);
+
+            let result = client.start_background_task_with_shutdown(shutdown).await;
+            let state = match &result {
+                Ok(_) => ConnectionState::Connected,
+                Err(e) => ConnectionState::Error(e.clone()),
+            };
+            self.connection_states.insert(exchange_type.clone(), state);
+            self.sync_reader();
+            result.map(|_| ())
         } else {
             Err(format!("Клиент для биржи {:?} не найден", exchange_type))
         }
     }
 
-    /// Отключиться от всех бирж
-    pub async fn disconnect_all(&mut self) -> Result<(), String> {
-        for (exchange_type, client) in &mut self.clients {
-            let _ = client.disconnect().await;
-            self.connection_states.insert(exchange_type.clone(), ConnectionState::Disconnected);
+    /// Пометить ошибкой биржи, которые числятся подключёнными, но фоновая задача `run()`
+    /// которых уже завершилась (например, исчерпав попытки переподключения).
+    ///
+    /// Без этого такая биржа выглядит подключённой, хотя сообщения от неё больше не
+    /// приходят. Возвращает помеченные биржи; их перезапускает
+    /// [`spawn_supervisor`](Self::spawn_supervisor) при заданном
+    /// [`SupervisorPolicy::restart_stopped`].
+    pub fn mark_stopped_exchanges(&mut self) -> Vec<ExchangeType> {
+        let stopped: Vec<ExchangeType> = self
+            .connection_states
+            .iter()
+            .filter(|(exchange_type, state)| {
+                matches!(state, ConnectionState::Connected)
+                    && self.clients.get(*exchange_type).is_some_and(|client| {
+                        !matches!(client, WsClientWrapper::Placeholder) && !client.is_connected()
+                    })
+            })
+            .map(|(exchange_type, _)| exchange_type.clone())
+            .collect();
+
+        for exchange_type in &stopped {
+            warn!(
+                "CryptoWsClient: фоновая задача {:?} завершилась, биржа помечена отключённой",
+                exchange_type
+            );
+            self.connection_states.insert(
+                exchange_type.clone(),
+                ConnectionState::Error(RUN_TASK_STOPPED.to_string()),
+            );
         }
-        Ok(())
+        stopped
     }
 
-    /// Подписаться на orderbook
-    pub async fn subscribe_orderbook(
+    /// Пересоздать клиент биржи, фоновая задача которого завершилась, и подключить его.
+    ///
+    /// Завершившийся `run()` нельзя запустить повторно, поэтому клиент создаётся заново
+    /// по сохранённой конфигурации. Если конфигурация неизвестна, подключается
+    /// существующий клиент.
+    async fn restart_exchange(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
+        if let Some(config) = self.configs.get(exchange_type).cloned() {
+            let client = WsClientFactory::create_client(exchange_type.clone(), config).await?;
+            self.apply_client_settings(exchange_type, &client);
+            if let Some(mut stopped) = self.clients.insert(exchange_type.clone(), client) {
+                let _ = stopped.disconnect().await;
+            }
+        }
+        self.connect_exchange(exchange_type).await
+    }
+
+    /// Подключиться к бирже и выполнить начальные подписки.
+    ///
+    /// Подписки выполняются по порядку; с [`with_private_first`](Self::with_private_first)
+    /// приватные каналы подписываются (включая аутентификацию и получение listen key)
+    /// до отправки первой публичной подписки. Для kline `interval` задаётся в секундах.
+    pub async fn connect_with_subscriptions(
         &mut self,
         exchange_type: &ExchangeType,
-        symbol: &str,
+        subscriptions: &[SubscriptionConfig],
     ) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            client.subscribe_orderbook(symbol).await?;
-            self.subscription_manager.add_subscription("orderbook".to_string(), symbol.to_string());
-            Ok(())
-        } else {
-            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        self.connect_exchange(exchange_type).await?;
+
+        let mut ordered: Vec<&SubscriptionConfig> = subscriptions.iter().collect();
+        if self.private_first {
+            // Стабильная сортировка сохраняет исходный порядок внутри каждой группы
+            ordered.sort_by_key(|config| !config.channel.is_private());
+        }
+        for config in ordered {
+            self.subscribe_config(exchange_type, config).await?;
         }
+        Ok(())
     }
 
-    /// Подписаться на сделки
-    pub async fn subscribe_trades(
+    /// Выполнить подписку, описанную [`SubscriptionConfig`]
+    async fn subscribe_config(
         &mut self,
         exchange_type: &ExchangeType,
-        symbol: &str,
+        config: &SubscriptionConfig,
     ) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            client.subscribe_trades(symbol).await?;
-            self.subscription_manager.add_subscription("trades".to_string(), symbol.to_string());
-            Ok(())
-        } else {
-            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        let symbol = config.symbol.as_str();
+        match config.channel {
+            ChannelType::Orderbook => {
+                self.subscribe_orderbook(exchange_type, symbol, DepthMode::Incremental).await
+            }
+            ChannelType::Trades => self.subscribe_trades(exchange_type, symbol).await,
+            ChannelType::Ticker => self.subscribe_ticker(exchange_type, symbol).await,
+            ChannelType::IndexPrice => self.subscribe_index_price(exchange_type, symbol).await,
+            ChannelType::Kline => {
+                let interval = config
+                    .interval
+                    .as_deref()
+                    .and_then(|interval| interval.parse().ok())
+                    .ok_or_else(|| format!("Не задан интервал kline в секундах для {}", symbol))?;
+                self.subscribe_kline(exchange_type, symbol, interval).await
+            }
+            ChannelType::AccountBalance => {
+                self.subscribe_account_balance(exchange_type, None).await
+            }
+            ChannelType::PrivateDeals => self.subscribe_private_deals(exchange_type, None).await,
+            ChannelType::Positions => self.subscribe_positions(exchange_type, None).await,
+            _ => Err(format!("Подписка на канал {} не поддерживается", config.channel.as_str())),
         }
     }
 
-    /// Подписаться на тикеры
-    pub async fn subscribe_ticker(
+    /// Выполнить подписку с тегом, который получат все сообщения этой подписки в
+    /// [`WsMessage::tags`].
+    ///
+    /// Позволяет нескольким стратегиям подписаться на одну пару и различать свои
+    /// сообщения. Если подписка уже выполнена, повторный запрос на биржу не
+    /// отправляется, а только добавляется тег. Символ сравнивается без учёта
+    /// разделителей (`BTCUSDT` и `BTC_USDT` — одна пара).
+    pub async fn subscribe_tagged(
         &mut self,
         exchange_type: &ExchangeType,
-        symbol: &str,
+        config: &SubscriptionConfig,
+        tag: Option<String>,
     ) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            client.subscribe_ticker(symbol).await?;
-            self.subscription_manager.add_subscription("ticker".to_string(), symbol.to_string());
-            Ok(())
-        } else {
-            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        let channel = config.channel.as_str();
+        let already_subscribed = self
+            .subscription_manager
+            .subscriptions
+            .get(exchange_type)
+            .is_some_and(|subs| subs.contains(&(channel.to_string(), config.symbol.clone())));
+        if !already_subscribed {
+            self.subscribe_config(exchange_type, config).await?;
+        }
+
+        if let Some(tag) = tag {
+            let key = (
+                exchange_type.clone(),
+                channel.to_string(),
+                normalize_subscription_symbol(&config.symbol),
+            );
+            let mut state = self.reader.state();
+            let tags = state.subscription_tags.entry(key).or_default();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
         }
+        Ok(())
     }
 
-    /// Подписаться на баланс аккаунта
+    /// Отписаться от публичного канала (orderbook, trades или ticker).
     ///
-    /// # Параметры
-    /// * `exchange_type` - Тип биржи
-    /// * `listen_key` - Опциональный ключ для приватных каналов (например, для MEXC)
-    pub async fn subscribe_account_balance(
+    /// Отписка отправляется основному соединению, шардам и резервным соединениям;
+    /// подписка и её теги забываются.
+    pub async fn unsubscribe(
         &mut self,
         exchange_type: &ExchangeType,
-        _listen_key: Option<&str>,
+        config: &SubscriptionConfig,
     ) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            client.subscribe_account_balance(_listen_key).await?;
-            self.subscription_manager
-                .add_subscription("balance".to_string(), "ACCOUNT".to_string());
-            Ok(())
-        } else {
-            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        let channel = config.channel.as_str();
+        let symbol = config.symbol.as_str();
+        let client = self
+            .clients
+            .get_mut(exchange_type)
+            .ok_or_else(|| format!("Клиент для биржи {:?} не найден", exchange_type))?;
+        client.unsubscribe_channel(channel, symbol).await?;
+
+        // Подписка могла уйти на шард, поэтому отписка рассылается всем соединениям
+        let shards = self.shards.get_mut(exchange_type).into_iter().flatten();
+        let standby = self
+            .standby_groups
+            .get_mut(exchange_type)
+            .into_iter()
+            .flat_map(|group| group.standby.iter_mut().map(|(_, client)| client));
+        for client in shards.chain(standby) {
+            if let Err(e) = client.unsubscribe_channel(channel, symbol).await {
+                warn!("CryptoWsClient: отписка {} {} не выполнена: {}", channel, symbol, e);
+            }
         }
+
+        self.subscription_manager.remove_exchange_subscription(exchange_type, channel, symbol);
+        let key =
+            (exchange_type.clone(), channel.to_string(), normalize_subscription_symbol(symbol));
+        self.reader.state().subscription_tags.remove(&key);
+        Ok(())
     }
 
-    /// Подписаться на приватные сделки аккаунта
+    /// Подписаться, собрать до `count` сообщений этой подписки и отписаться.
     ///
-    /// # Параметры
-    /// * `exchange_type` - Тип биржи
-    /// * `listen_key` - Опциональный ключ для приватных каналов (например, для MEXC)
-    pub async fn subscribe_private_deals(
+    /// Удобно для разовой выборки потока, например следующих N сделок пары. Если за
+    /// `timeout` пришло меньше сообщений, возвращаются собранные. Сообщения других
+    /// подписок, прочитанные за это время, не теряются: их первыми отдаст
+    /// [`next_message`](Self::next_message). Если подписка уже была выполнена, она
+    /// сохраняется. Поддерживаются каналы orderbook, trades и ticker.
+    pub async fn collect(
         &mut self,
         exchange_type: &ExchangeType,
-        _listen_key: Option<&str>,
-    ) -> Result<(), String> {
-        if let Some(client) = self.clients.get_mut(exchange_type) {
-            client.subscribe_private_deals(_listen_key).await?;
-            self.subscription_manager
-                .add_subscription("private_deals".to_string(), "ACCOUNT".to_string());
-            Ok(())
-        } else {
-            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        config: SubscriptionConfig,
+        count: usize,
+        timeout: Duration,
+    ) -> Result<Vec<WsMessage>, String> {
+        if !matches!(
+            config.channel,
+            ChannelType::Orderbook | ChannelType::Trades | ChannelType::Ticker
+        ) {
+            return Err(format!(
+                "Сбор сообщений канала {} не поддерживается",
+                config.channel.as_str()
+            ));
+        }
+        let already_subscribed =
+            self.subscription_manager.subscriptions.get(exchange_type).is_some_and(|subs| {
+                subs.contains(&(config.channel.as_str().to_string(), config.symbol.clone()))
+            });
+        if !already_subscribed {
+            self.subscribe_config(exchange_type, &config).await?;
         }
-    }
 
-    /// Получить следующее сообщение из всех клиентов
-    pub async fn next_message(&mut self) -> Result<Option<WsMessage>, String> {
-        debug!("CryptoWsClient::next_message: запуск получения сообщений");
+        let symbol = normalize_subscription_symbol(&config.symbol);
+        let deadline = Instant::now() + timeout;
+        let mut collected = Vec::with_capacity(count);
+        let mut deferred = Vec::new();
+        let result = loop {
+            if collected.len() >= count || Instant::now() >= deadline {
+                break Ok(());
+            }
+            match self.next_message().await {
+                Ok(Some(message))
+                    if message.exchange == *exchange_type
+                        && message.channel == config.channel
+                        && normalize_subscription_symbol(&message.symbol) == symbol =>
+                {
+                    collected.push(message)
+                }
+                Ok(Some(message)) => deferred.push(message),
+                Ok(None) => tokio::time::sleep(Duration::from_millis(10)).await,
+                Err(e) => break Err(e),
+            }
+        };
+        self.reader.state().deferred.extend(deferred);
 
-        // Проверяем сообщения от всех активных клиентов
-        let connected_exchanges: Vec<_> = self
-            .connection_states
-            .iter()
-            .filter(|(_, state)| matches!(state, ConnectionState::Connected))
-            .map(|(exchange, _)| exchange.clone())
-            .collect();
+        if !already_subscribed {
+            self.unsubscribe(exchange_type, &config).await?;
+        }
+        result.map(|()| collected)
+    }
 
-        debug!(
-            "CryptoWsClient::next_message: найдено {} подключенных бирж: {:?}",
-            connected_exchanges.len(),
-            connected_exchanges
+    /// Проставить сообщению теги подходящих подписок
+    fn apply_tags(
+        subscription_tags: &HashMap<(ExchangeType, String, String), Vec<String>>,
+        message: &mut WsMessage,
+    ) {
+        if subscription_tags.is_empty() {
+            return;
+        }
+        let key = (
+            message.exchange.clone(),
+            message.channel.as_str().to_string(),
+            normalize_subscription_symbol(&message.symbol),
         );
+        if let Some(tags) = subscription_tags.get(&key) {
+            message.tags = tags.clone();
+        }
+    }
 
-        // Сначала проверяем неблокирующим способом наличие сообщений
-        for exchange_type in &connected_exchanges {
-            debug!(
-                "CryptoWsClient::next_message: проверяем сообщения от биржи {:?}",
-                exchange_type
-            );
+    /// Запустить фоновый супервизор, переподключающий выпавшие биржи.
+    ///
+    /// Каждые `policy.check_interval` супервизор проверяет
+    /// [`disconnected_exchanges`](Self::disconnected_exchanges) и для бирж в состоянии
+    /// [`ConnectionState::Error`] вызывает [`connect_exchange`](Self::connect_exchange)
+    /// с экспоненциальной задержкой между неудачными попытками. После подключения
+    /// публичные подписки восстанавливаются через
+    /// [`reconcile_subscriptions`](Self::reconcile_subscriptions). Биржи, отключённые
+    /// через [`disconnect_all`](Self::disconnect_all), не переподключаются.
+    ///
+    /// Биржи, фоновая задача которых завершилась сама, помечаются через
+    /// [`mark_stopped_exchanges`](Self::mark_stopped_exchanges) и, если задан
+    /// [`SupervisorPolicy::restart_stopped`], перезапускаются после этой паузы с
+    /// пересозданием клиента.
+    ///
+    /// Супервизор хранит слабую ссылку на клиент и завершается, когда клиент удалён.
+    pub async fn spawn_supervisor(
+        client: &Arc<AsyncMutex<Self>>,
+        policy: SupervisorPolicy,
+    ) -> JoinHandle<()> {
+        let mut shutdown = client.lock().await.shutdown_tx.subscribe();
+        let weak = Arc::downgrade(client);
+
+        tokio::spawn(async move {
+            // Число неудач подряд и момент следующей попытки по каждой бирже
+            let mut backoffs: HashMap<ExchangeType, (u32, Instant)> = HashMap::new();
+            loop {
+                tokio::select! {
+                    changed = shutdown.changed() => {
+                        if changed.is_err() || *shutdown.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(policy.check_interval) => {}
+                }
+                let Some(client) = weak.upgrade() else {
+                    break;
+                };
+                let mut client = client.lock().await;
 
-            if let Some(client) = self.clients.get_mut(exchange_type) {
-                match client.next_message().await {
-                    Ok(Some(message)) => {
-                        // Возвращаем сообщение напрямую
-                        return Ok(Some(message));
+                for exchange_type in client.mark_stopped_exchanges() {
+                    if let Some(cooldown) = policy.restart_stopped {
+                        backoffs.insert(exchange_type, (0, Instant::now() + cooldown));
                     }
-                    Ok(None) => {
-                        trace!(
-                            "CryptoWsClient::next_message: нет новых сообщений от биржи {:?}",
-                            exchange_type
-                        );
+                }
+
+                let failed: Vec<(ExchangeType, bool)> = client
+                    .disconnected_exchanges()
+                    .into_iter()
+                    .filter_map(|(exchange_type, state)| match state {
+                        ConnectionState::Error(reason) if reason == RUN_TASK_STOPPED => {
+                            policy.restart_stopped.map(|_| (exchange_type, true))
+                        }
+                        ConnectionState::Error(_) => Some((exchange_type, false)),
+                        _ => None,
+                    })
+                    .collect();
+                backoffs.retain(|exchange_type, _| {
+                    failed.iter().any(|(failed, _)| failed == exchange_type)
+                });
+
+                for (exchange_type, stopped) in failed {
+                    let now = Instant::now();
+                    let (failures, next_attempt) =
+                        backoffs.entry(exchange_type.clone()).or_insert((0, now));
+                    if now < *next_attempt {
+                        continue;
                     }
-                    Err(e) => {
-                        warn!(
-                            "CryptoWsClient::next_message: ошибка получения сообщения от биржи {:?}: {}",
-                            exchange_type, e
-                        );
+
+                    let result = if stopped {
+                        client.restart_exchange(&exchange_type).await
+                    } else {
+                        client.connect_exchange(&exchange_type).await
+                    };
+                    match result {
+                        Ok(()) => {
+                            backoffs.remove(&exchange_type);
+                            info!(
+                                "CryptoWsClient::supervisor: биржа {:?} переподключена",
+                                exchange_type
+                            );
+                            if let Err(e) =
+                                client.reconcile_subscriptions(&exchange_type, true).await
+                            {
+                                warn!(
+                                    "CryptoWsClient::supervisor: подписки {:?} не восстановлены: {}",
+                                    exchange_type, e
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            *failures += 1;
+                            let delay = policy.backoff(*failures);
+                            *next_attempt = now + delay;
+                            warn!(
+                                "CryptoWsClient::supervisor: переподключение {:?} не удалось ({}), повтор через {:?}",
+                                exchange_type, e, delay
+                            );
+                        }
                     }
                 }
-            } else {
-                warn!(
-                    "CryptoWsClient::next_message: клиент для биржи {:?} не найден",
-                    exchange_type
-                );
             }
-        }
-
-        // Если нет сообщений от клиентов, возвращаем None
-        trace!("CryptoWsClient::next_message: нет новых сообщений от всех бирж");
-        Ok(None)
+            debug!("CryptoWsClient::supervisor: остановлен");
+        })
     }
 
-    /// Получить следующее приватное сообщение (только PrivateDeals и AccountBalance)
-    pub async fn next_private_message(&mut self) -> Result<Option<WsMessage>, String> {
-        debug!("CryptoWsClient::next_private_message: запуск получения приватных сообщений");
-
+    /// Дождаться состояния [`ConnectionState::Connected`] биржи и выполнить подписку.
+    ///
+    /// Состояние проверяется каждые 10 мс; блокировка клиента между проверками
+    /// освобождается, чтобы подключение (например, из
+    /// [`spawn_supervisor`](Self::spawn_supervisor)) могло завершиться. Если биржа не
+    /// подключилась за `timeout`, подписка не выполняется и возвращается ошибка.
+    pub async fn subscribe_when_ready(
+        client: &Arc<AsyncMutex<Self>>,
+        exchange_type: &ExchangeType,
+        config: &SubscriptionConfig,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
         loop {
-            match self.next_message().await? {
-                Some(message) => {
-                    // Проверяем, является ли сообщение приватным
-                    if matches!(
-                        message.channel,
-                        ChannelType::PrivateDeals | ChannelType::AccountBalance
-                    ) {
-                        debug!(
-                            "CryptoWsClient::next_private_message: получено приватное сообщение: {:?} для символа {}",
-                            message.channel, message.symbol
-                        );
-                        return Ok(Some(message));
-                    } else {
-                        // Пропускаем публичные сообщения
-                        trace!(
-                            "CryptoWsClient::next_private_message: пропущено публичное сообщение: {:?}",
-                            message.channel
-                        );
-                        continue;
-                    }
-                }
-                None => {
-                    // Нет новых сообщений
-                    return Ok(None);
+            {
+                let mut client = client.lock().await;
+                if client.is_exchange_connected(exchange_type) {
+                    return client.subscribe_config(exchange_type, config).await;
                 }
             }
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Биржа {:?} не подключилась за {:?}, подписка на {} отменена",
+                    exchange_type,
+                    timeout,
+                    config.channel.as_str()
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
     }
 
-    /// Получить следующее публичное сообщение (исключая приватные)
-    pub async fn next_public_message(&mut self) -> Result<Option<WsMessage>, String> {
-        debug!("CryptoWsClient::next_public_message: запуск получения публичных сообщений");
+    /// Запустить задачу, пересылающую сообщения [`next_message`](Self::next_message)
+    /// в канал `tx`.
+    ///
+    /// Отправка ожидает свободного места в `tx`, поэтому медленный получатель
+    /// притормаживает чтение, а не теряет сообщения; блокировка клиента на время
+    /// отправки освобождается. Без новых сообщений задача опрашивает клиент каждые
+    /// 10 мс. Задача завершается, когда получатель `tx` закрыт или клиент удалён.
+    pub async fn forward_to(
+        client: &Arc<AsyncMutex<Self>>,
+        tx: async_mpsc::Sender<WsMessage>,
+    ) -> JoinHandle<()> {
+        let mut shutdown = client.lock().await.shutdown_tx.subscribe();
+        let weak = Arc::downgrade(client);
+
+        tokio::spawn(async move {
+            loop {
+                let Some(client) = weak.upgrade() else {
+                    break;
+                };
+                let received = client.lock().await.next_message().await;
+                drop(client);
 
-        loop {
-            match self.next_message().await? {
-                Some(message) => {
-                    // Проверяем, является ли сообщение публичным
-                    if !matches!(
-                        message.channel,
-                        ChannelType::PrivateDeals | ChannelType::AccountBalance
-                    ) {
-                        debug!(
-                            "CryptoWsClient::next_public_message: получено публичное сообщение: {:?} для символа {}",
-                            message.channel, message.symbol
-                        );
-                        return Ok(Some(message));
-                    } else {
-                        // Пропускаем приватные сообщения
-                        trace!(
-                            "CryptoWsClient::next_public_message: пропущено приватное сообщение: {:?}",
-                            message.channel
-                        );
+                match received {
+                    Ok(Some(message)) => {
+                        if tx.send(message).await.is_err() {
+                            break;
+                        }
                         continue;
                     }
+                    Ok(None) => {}
+                    Err(e) => warn!("CryptoWsClient::forward_to: {}", e),
                 }
-                None => {
-                    // Нет новых сообщений
-                    return Ok(None);
+                tokio::select! {
+                    changed = shutdown.changed() => {
+                        if changed.is_err() || *shutdown.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tx.closed() => break,
+                    _ = tokio::time::sleep(Duration::from_millis(10)) => {}
                 }
             }
-        }
+            debug!("CryptoWsClient::forward_to: остановлен");
+        })
     }
 
-    /// Проверить есть ли приватные сообщения определенного типа
-    pub fn has_private_channel_subscriptions(&self, channel_type: ChannelType) -> bool {
-        if !matches!(channel_type, ChannelType::PrivateDeals | ChannelType::AccountBalance) {
-            return false;
+    /// Отключиться от всех бирж
+    pub async fn disconnect_all(&mut self) -> Result<(), String> {
+        for (exchange_type, client) in &mut self.clients {
+            let _ = client.disconnect().await;
+            self.connection_states.insert(exchange_type.clone(), ConnectionState::Disconnected);
         }
-
-        let channel_str = channel_type.as_str();
-        self.subscription_manager
-            .get_subscriptions()
-            .iter()
-            .any(|(channel, _)| channel == channel_str)
+        for group in self.standby_groups.values_mut() {
+            for (_, client) in &mut group.standby {
+                let _ = client.disconnect().await;
+            }
+        }
+        for shard in self.shards.values_mut().flatten() {
+            let _ = shard.disconnect().await;
+        }
+        self.sync_reader();
+        Ok(())
     }
 
-    /// Получить список активных приватных подписок
-    pub fn get_private_subscriptions(&self) -> Vec<(String, String)> {
-        self.subscription_manager
-            .get_subscriptions()
-            .into_iter()
-            .filter(|(channel, _)| channel == "private_deals" || channel == "balance")
-            .collect()
+    /// Приостановить обработку сообщений биржи без разрыва соединения
+    ///
+    /// Соединение и подписки остаются активными, а поступающие сообщения
+    /// отбрасываются до вызова [`resume`](Self::resume).
+    pub fn pause(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
+        if !self.clients.contains_key(exchange_type) {
+            return Err(format!("Клиент для биржи {:?} не найден", exchange_type));
+        }
+        if self.reader.state().paused_exchanges.insert(exchange_type.clone()) {
+            info!("CryptoWsClient::pause: обработка сообщений биржи {:?} приостановлена", exchange_type);
+        }
+        Ok(())
     }
 
-    /// Получить состояние подключения для биржи
-    pub fn get_connection_state(&self, exchange_type: &ExchangeType) -> Option<&ConnectionState> {
+    /// Возобновить обработку сообщений биржи после [`pause`](Self::pause)
+    pub fn resume(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
+        if !self.clients.contains_key(exchange_type) {
+            return Err(format!("Клиент для биржи {:?} не найден", exchange_type));
+        }
+        if self.reader.state().paused_exchanges.remove(exchange_type) {
+            info!("CryptoWsClient::resume: обработка сообщений биржи {:?} возобновлена", exchange_type);
+        }
+        Ok(())
+    }
+
+    /// Проверить, приостановлена ли обработка сообщений биржи
+    pub fn is_paused(&self, exchange_type: &ExchangeType) -> bool {
+        self.reader.state().paused_exchanges.contains(exchange_type)
+    }
+
+    /// Зарегистрировать дополнительный признак служебного сообщения для биржи
+    ///
+    /// Предикат дополняет встроенные проверки: кадры, для которых он вернул `true`,
+    /// пропускаются так же, как ответы на подписку.
+    pub fn add_service_predicate<F>(&mut self, exchange_type: ExchangeType, predicate: F)
+    where
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        self.reader
+            .state()
+            .service_predicates
+            .entry(exchange_type)
+            .or_default()
+            .push(Arc::new(predicate));
+    }
+
+    /// Считать служебными кадры биржи, у которых поле `key` равно `value`
+    pub fn add_service_match(&mut self, exchange_type: ExchangeType, key: &str, value: Value) {
+        let key = key.to_string();
+        self.add_service_predicate(exchange_type, move |data| data.get(&key) == Some(&value));
+    }
+
+    /// Начать запись всех сырых кадров (с биржей и временем получения) в JSONL файл.
+    ///
+    /// Кадры дописываются в конец файла; запись воспроизводится через [`ReplayWsClient`].
+    ///
+    /// [`ReplayWsClient`]: crate::ReplayWsClient
+    pub fn start_recording(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        self.reader.state().recorder = Some(FrameRecorder::open(path)?);
+        Ok(())
+    }
+
+    /// Остановить запись кадров
+    pub fn stop_recording(&mut self) {
+        self.reader.state().recorder = None;
+    }
+
+    /// Удалить пользовательские признаки служебных сообщений биржи
+    pub fn clear_service_predicates(&mut self, exchange_type: &ExchangeType) {
+        self.reader.state().service_predicates.remove(exchange_type);
+    }
+
+    /// Добавить резервное соединение к бирже, уже добавленной через
+    /// [`add_exchange`](Self::add_exchange).
+    ///
+    /// Резервное соединение получает те же публичные подписки, что и основное.
+    /// [`next_message`](Self::next_message) объединяет потоки, отбрасывая дубликаты,
+    /// а при деградации основного соединения (остановка, переподключение, нет
+    /// heartbeat) прозрачно переключается на здоровое резервное.
+    pub async fn add_standby_connection(
+        &mut self,
+        exchange_type: ExchangeType,
+        instance_id: &str,
+        config: ExchangeConfig,
+    ) -> Result<(), String> {
+        if !self.clients.contains_key(&exchange_type) {
+            return Err(format!("Клиент для биржи {:?} не найден", exchange_type));
+        }
+        let client = WsClientFactory::create_client(exchange_type.clone(), config).await?;
+        self.attach_standby(exchange_type, instance_id, client).await
+    }
+
+    async fn attach_standby(
+        &mut self,
+        exchange_type: ExchangeType,
+        instance_id: &str,
+        mut client: WsClientWrapper,
+    ) -> Result<(), String> {
+        if !self.clients.contains_key(&exchange_type) {
+            return Err(format!("Клиент для биржи {:?} не найден", exchange_type));
+        }
+        self.apply_client_settings(&exchange_type, &client);
+        let group = self.standby_groups.entry(exchange_type.clone()).or_insert_with(|| {
+            StandbyGroup { primary_id: "primary".to_string(), ..Default::default() }
+        });
+        if group.primary_id == instance_id || group.standby.iter().any(|(id, _)| id == instance_id)
+        {
+            return Err(format!(
+                "Соединение {} для биржи {:?} уже существует",
+                instance_id, exchange_type
+            ));
+        }
+
+        if matches!(self.connection_states.get(&exchange_type), Some(ConnectionState::Connected)) {
+            client.start_background_task_with_shutdown(self.shutdown_tx.subscribe()).await?;
+            for (channel, symbol) in
+                self.subscription_manager.exchange_subscriptions(&exchange_type)
+            {
+                if let Err(e) = client.subscribe_channel(&channel, &symbol).await {
+                    warn!("CryptoWsClient::attach_standby: {} {}: {}", channel, symbol, e);
+                }
+            }
+        }
+
+        info!("Добавлено резервное соединение {} для биржи {:?}", instance_id, exchange_type);
+        group.standby.push((instance_id.to_string(), client));
+        self.sync_reader();
+        Ok(())
+    }
+
+    /// Повторить подписку на всех резервных соединениях биржи
+    async fn mirror_to_standby(
+        &mut self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+    ) {
+        if let Some(group) = self.standby_groups.get_mut(exchange_type) {
+            for (instance_id, client) in &mut group.standby {
+                if let Err(e) = client.subscribe_channel(channel, symbol).await {
+                    warn!(
+                        "CryptoWsClient: подписка {} {} на резервном соединении {} не выполнена: {}",
+                        channel, symbol, instance_id, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Переключиться на здоровое резервное соединение, если основное деградировало
+    fn failover_if_degraded(&mut self, exchange_type: &ExchangeType) {
+        let (Some(primary), Some(group)) =
+            (self.clients.get_mut(exchange_type), self.standby_groups.get_mut(exchange_type))
+        else {
+            return;
+        };
+        if !primary.is_degraded() {
+            return;
+        }
+        let Some((standby_id, standby)) =
+            group.standby.iter_mut().find(|(_, client)| !client.is_degraded())
+        else {
+            return;
+        };
+
+        std::mem::swap(primary, standby);
+        std::mem::swap(&mut group.primary_id, standby_id);
+        warn!(
+            "CryptoWsClient: основное соединение {:?} деградировало ({}), переключение на {}",
+            exchange_type, standby_id, group.primary_id
+        );
+    }
+
+    /// Фактический WebSocket адрес основного соединения биржи.
+    ///
+    /// Удобно для проверки, что переопределение адреса (testnet, региональный
+    /// endpoint или прокси) действительно применилось.
+    pub fn connection_url(&self, exchange_type: &ExchangeType) -> Option<&str> {
+        self.clients.get(exchange_type).and_then(|client| client.connection_url())
+    }
+
+    /// Идентификатор соединения, которое сейчас является основным для биржи.
+    ///
+    /// Для биржи без резервных соединений возвращает `"primary"`.
+    pub fn active_connection(&self, exchange_type: &ExchangeType) -> Option<&str> {
+        if !self.clients.contains_key(exchange_type) {
+            return None;
+        }
+        Some(
+            self.standby_groups
+                .get(exchange_type)
+                .map(|group| group.primary_id.as_str())
+                .unwrap_or("primary"),
+        )
+    }
+
+    /// Лимит подписок на соединение биржи с учётом переопределения
+    pub fn subscription_limit(&self, exchange_type: &ExchangeType) -> Option<usize> {
+        self.subscription_limits
+            .get(exchange_type)
+            .copied()
+            .or_else(|| exchange_type.max_ws_subscriptions())
+    }
+
+    /// Количество подписок, учтённых для биржи
+    pub fn subscription_count(&self, exchange_type: &ExchangeType) -> usize {
+        self.subscription_manager.subscriptions.get(exchange_type).map_or(0, HashSet::len)
+    }
+
+    /// Проверить, что подписка на `channel`/`symbol` не превысит лимит биржи.
+    ///
+    /// Повторная подписка на уже учтённую пару лимит не расходует.
+    pub fn check_subscription_limit(
+        &self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+    ) -> ExchangeResult<()> {
+        // С автошардингом лимит действует на соединение, и переполнение открывает шард
+        if self.auto_sharding {
+            return Ok(());
+        }
+        let Some(limit) = self.subscription_limit(exchange_type) else {
+            return Ok(());
+        };
+        let already_subscribed = self
+            .subscription_manager
+            .subscriptions
+            .get(exchange_type)
+            .is_some_and(|subs| subs.contains(&(channel.to_string(), symbol.to_string())));
+        if !already_subscribed && self.subscription_count(exchange_type) >= limit {
+            return Err(ExchangeError::SubscriptionLimitReached {
+                exchange: exchange_type.clone(),
+                limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Дождаться ответа биржи на отправленную подписку, если включено подтверждение.
+    ///
+    /// Подписки отправляются по одной, поэтому первый ответ в буфере относится к
+    /// последнему запросу. Кадры с данными остаются в буфере для `next_message`.
+    /// Ответ ожидается от соединения, принимающего новые подписки
+    /// ([`subscription_target`](Self::subscription_target)).
+    async fn confirm_subscription(
+        &self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+    ) -> Result<SubscriptionAck, String> {
+        if !self.confirm_subscriptions {
+            return Ok(SubscriptionAck::Confirmed);
+        }
+        let Some(message_channel) =
+            self.subscription_target(exchange_type).and_then(|client| client.message_channel())
+        else {
+            return Ok(SubscriptionAck::Confirmed);
+        };
+
+        let _awaiting_ack = AwaitingAck::new(&self.reader, exchange_type);
+        let deadline = Instant::now() + self.subscription_ack_timeout;
+        loop {
+            let ack = message_channel
+                .take_first(|raw| WsClientWrapper::subscription_ack(exchange_type, raw));
+            match ack {
+                Some(Ok(())) => {
+                    debug!(
+                        "CryptoWsClient::confirm_subscription: {:?} подтвердила подписку на {} {}",
+                        exchange_type, channel, symbol
+                    );
+                    return Ok(SubscriptionAck::Confirmed);
+                }
+                Some(Err(e))
+                    if self.auto_sharding
+                        && WsClientWrapper::is_subscription_limit_rejection(exchange_type, &e) =>
+                {
+                    return Ok(SubscriptionAck::ConnectionLimit);
+                }
+                Some(Err(e)) => {
+                    return Err(format!(
+                        "Биржа {:?} отклонила подписку на {} {}: {}",
+                        exchange_type, channel, symbol, e
+                    ));
+                }
+                None if Instant::now() >= deadline => {
+                    return Err(ExchangeError::SubscriptionTimeout {
+                        exchange: exchange_type.clone(),
+                        channel: channel.to_string(),
+                        symbol: symbol.to_string(),
+                    }
+                    .to_string());
+                }
+                None => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    }
+
+    /// Дождаться ответа на подписку и, если биржа отказала из-за лимита подписок на
+    /// соединение, открыть новый шард и повторить подписку на нём
+    async fn confirm_or_shard(
+        &mut self,
+        exchange_type: &ExchangeType,
+        channel: &str,
+        symbol: &str,
+        request: SubscriptionRequest<'_>,
+    ) -> Result<(), String> {
+        if self.confirm_subscription(exchange_type, channel, symbol).await?
+            == SubscriptionAck::Confirmed
+        {
+            return Ok(());
+        }
+        warn!(
+            "CryptoWsClient: {:?} отклонила подписку на {} {} по лимиту соединения, открываем новый шард",
+            exchange_type, channel, symbol
+        );
+        self.open_shard(exchange_type).await?;
+        if let Some(shard) = self.shards.get_mut(exchange_type).and_then(|shards| shards.last_mut())
+        {
+            shard.resubscribe(request, channel, symbol).await?;
+        }
+        match self.confirm_subscription(exchange_type, channel, symbol).await? {
+            SubscriptionAck::Confirmed => Ok(()),
+            SubscriptionAck::ConnectionLimit => Err(format!(
+                "Биржа {:?} отклонила подписку на {} {} по лимиту и на новом шарде",
+                exchange_type, channel, symbol
+            )),
+        }
+    }
+
+    /// Открыть новое соединение биржи для подписок, не поместившихся в текущие
+    async fn open_shard(&mut self, exchange_type: &ExchangeType) -> Result<(), String> {
+        let mut shard = match self.clients.get(exchange_type) {
+            #[cfg(test)]
+            Some(WsClientWrapper::Mock { .. }) => WsClientWrapper::new_mock(exchange_type.clone()),
+            Some(_) => {
+                WsClientFactory::create_client(exchange_type.clone(), ExchangeConfig::default())
+                    .await?
+            }
+            None => return Err(format!("Клиент для биржи {:?} не найден", exchange_type)),
+        };
+        self.apply_client_settings(exchange_type, &shard);
+        shard.start_background_task_with_shutdown(self.shutdown_tx.subscribe()).await?;
+
+        let shards = self.shards.entry(exchange_type.clone()).or_default();
+        shards.push(shard);
+        info!("Открыт шард {} для биржи {:?}", shards.len(), exchange_type);
+        self.sync_reader();
+        Ok(())
+    }
+
+    /// Соединение, принимающее новые подписки биржи: последний шард или основное
+    fn subscription_target(&self, exchange_type: &ExchangeType) -> Option<&WsClientWrapper> {
+        self.shards
+            .get(exchange_type)
+            .and_then(|shards| shards.last())
+            .or_else(|| self.clients.get(exchange_type))
+    }
+
+    fn subscription_target_mut(
+        &mut self,
+        exchange_type: &ExchangeType,
+    ) -> Option<&mut WsClientWrapper> {
+        match self.shards.get_mut(exchange_type).and_then(|shards| shards.last_mut()) {
+            Some(shard) => Some(shard),
+            None => self.clients.get_mut(exchange_type),
+        }
+    }
+
+    /// Количество дополнительных соединений, открытых автошардингом для биржи
+    pub fn shard_count(&self, exchange_type: &ExchangeType) -> usize {
+        self.shards.get(exchange_type).map_or(0, Vec::len)
+    }
+
+    /// Подписаться на orderbook
+    ///
+    /// `mode` выбирает поток: [`DepthMode::Incremental`] — изменения стакана в канале
+    /// [`ChannelType::Orderbook`], [`DepthMode::Snapshot`] — полные срезы лучших
+    /// уровней в канале [`ChannelType::OrderbookSnapshot`] (только MEXC Spot, глубина
+    /// 5, 10 или 20). Срезы не повторяются на резервных соединениях.
+    pub async fn subscribe_orderbook(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        mode: DepthMode,
+    ) -> Result<(), String> {
+        let channel = mode.channel().as_str();
+        self.check_subscription_limit(exchange_type, channel, symbol).map_err(|e| e.to_string())?;
+        let command = match mode {
+            DepthMode::Incremental => None,
+            DepthMode::Snapshot(levels) => {
+                Some(exchange_type.depth_snapshot_command(symbol, levels).ok_or_else(|| {
+                    ExchangeError::UnsupportedOperation(format!(
+                        "срезы стакана глубиной {} на {:?}",
+                        levels, exchange_type
+                    ))
+                    .to_string()
+                })?)
+            }
+        };
+        if let Some(client) = self.subscription_target_mut(exchange_type) {
+            let request = match &command {
+                None => {
+                    client.subscribe_orderbook(symbol).await?;
+                    SubscriptionRequest::Channel
+                }
+                Some(command) => {
+                    client.send_subscription(command).await?;
+                    SubscriptionRequest::Command(command)
+                }
+            };
+            self.confirm_or_shard(exchange_type, channel, symbol, request).await?;
+            self.subscription_manager.add_exchange_subscription(exchange_type, channel, symbol);
+            if mode == DepthMode::Incremental {
+                self.mirror_to_standby(exchange_type, channel, symbol).await;
+            }
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на изменения стакана и получить синхронизированный по снимку
+    /// [`OrderBookManager`].
+    ///
+    /// Изменения, пришедшие до снимка, накапливаются (сначала ожидается первое из
+    /// них, не дольше [`set_subscription_ack_timeout`](Self::set_subscription_ack_timeout)),
+    /// затем загружается `snapshot` — обычно
+    /// [`CryptoRestClient::fetch_orderbook`](crate::CryptoRestClient::fetch_orderbook).
+    /// Изменения, уже вошедшие в снимок, отбрасываются, остальные применяются вместе с
+    /// пришедшими во время загрузки. Стакан хранится под символом в формате
+    /// [`WsMessage::symbol`], поэтому дальнейшие изменения можно передавать в
+    /// [`OrderBookManager::apply`] как есть; если изменений ещё не было — под `symbol`.
+    /// Сообщения других каналов не теряются и возвращаются следующими вызовами
+    /// [`next_message`](Self::next_message).
+    ///
+    /// Изменения разбираются через [`WsMessage::as_orderbook_update`], поэтому пока
+    /// поддерживается только MEXC Spot. Если снимок старше первого накопленного
+    /// изменения, возвращается ошибка — снимок нужно запросить заново.
+    pub async fn subscribe_orderbook_synced(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        snapshot: impl Future<Output = Result<OrderBookUpdate, String>>,
+    ) -> Result<OrderBookManager, String> {
+        self.subscribe_orderbook(exchange_type, symbol, DepthMode::Incremental).await?;
+
+        let first_update_deadline = Instant::now() + self.subscription_ack_timeout;
+        let buffered =
+            self.take_orderbook_updates(exchange_type, symbol, first_update_deadline).await?;
+        let mut snapshot = snapshot.await?;
+        // Изменения, пришедшие во время загрузки снимка
+        let during_fetch =
+            self.take_orderbook_updates(exchange_type, symbol, Instant::now()).await?;
+
+        let book_symbol = buffered
+            .iter()
+            .chain(&during_fetch)
+            .next()
+            .map_or_else(|| symbol.to_string(), |update| update.symbol.clone());
+        snapshot.symbol = book_symbol.clone();
+        snapshot.is_snapshot = true;
+
+        let mut manager = OrderBookManager::new();
+        manager.request_snapshot(&book_symbol);
+        for update in buffered.iter().chain([&snapshot]).chain(&during_fetch) {
+            manager.apply(update);
+        }
+        if manager.needs_snapshot(&book_symbol) {
+            return Err(format!(
+                "Снимок стакана {} на {:?} не согласуется с обновлениями (версия {:?})",
+                book_symbol, exchange_type, snapshot.version
+            ));
+        }
+        info!(
+            "CryptoWsClient::subscribe_orderbook_synced: стакан {} на {:?} синхронизирован",
+            book_symbol, exchange_type
+        );
+        Ok(manager)
+    }
+
+    /// Забрать полученные изменения стакана `symbol`.
+    ///
+    /// До первого изменения сообщения ожидаются до `deadline`, после него читается
+    /// только уже полученное. Остальные сообщения откладываются для
+    /// [`next_message`](Self::next_message).
+    async fn take_orderbook_updates(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        deadline: Instant,
+    ) -> Result<Vec<OrderBookUpdate>, String> {
+        let symbol = normalize_subscription_symbol(symbol);
+        let mut updates = Vec::new();
+        let mut deferred = Vec::new();
+        let result = loop {
+            match self.next_message().await {
+                Ok(Some(message))
+                    if message.exchange == *exchange_type
+                        && message.channel == ChannelType::Orderbook
+                        && normalize_subscription_symbol(&message.symbol) == symbol =>
+                {
+                    match message.as_orderbook_update() {
+                        Some(update) => updates.push(update),
+                        None => deferred.push(message),
+                    }
+                }
+                Ok(Some(message)) => deferred.push(message),
+                Ok(None) if updates.is_empty() && Instant::now() < deadline => {
+                    tokio::time::sleep(Duration::from_millis(10)).await
+                }
+                Ok(None) => break Ok(updates),
+                Err(e) => break Err(e),
+            }
+        };
+        self.reader.state().deferred.extend(deferred);
+        result
+    }
+
+    /// Подписаться на сделки
+    pub async fn subscribe_trades(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "trades", symbol)
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.subscription_target_mut(exchange_type) {
+            client.subscribe_trades(symbol).await?;
+            self.confirm_or_shard(exchange_type, "trades", symbol, SubscriptionRequest::Channel)
+                .await?;
+            self.subscription_manager.add_exchange_subscription(exchange_type, "trades", symbol);
+            self.mirror_to_standby(exchange_type, "trades", symbol).await;
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на тикеры
+    pub async fn subscribe_ticker(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "ticker", symbol)
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.subscription_target_mut(exchange_type) {
+            client.subscribe_ticker(symbol).await?;
+            self.confirm_or_shard(exchange_type, "ticker", symbol, SubscriptionRequest::Channel)
+                .await?;
+            self.subscription_manager.add_exchange_subscription(exchange_type, "ticker", symbol);
+            self.mirror_to_standby(exchange_type, "ticker", symbol).await;
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на индексную цену бессрочного контракта
+    pub async fn subscribe_index_price(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "index_price", symbol)
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.subscription_target_mut(exchange_type) {
+            client.subscribe_index_price(symbol).await?;
+            self.confirm_or_shard(
+                exchange_type,
+                "index_price",
+                symbol,
+                SubscriptionRequest::Channel,
+            )
+            .await?;
+            self.subscription_manager.add_exchange_subscription(
+                exchange_type,
+                "index_price",
+                symbol,
+            );
+            self.mirror_to_standby(exchange_type, "index_price", symbol).await;
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на сделки по всем символам биржи одной подпиской
+    ///
+    /// Используется общий поток биржи (см. [`ExchangeType::all_trades_command`]);
+    /// символ каждой сделки берётся из самого сообщения. Подписка не учитывается
+    /// в лимите подписок и не повторяется на резервных соединениях.
+    pub async fn subscribe_all_trades(
+        &mut self,
+        exchange_type: &ExchangeType,
+    ) -> ExchangeResult<()> {
+        let command = exchange_type.all_trades_command().ok_or_else(|| {
+            ExchangeError::UnsupportedOperation(format!(
+                "сделки по всем символам на {:?}",
+                exchange_type
+            ))
+        })?;
+        let client = self.clients.get_mut(exchange_type).ok_or_else(|| {
+            ExchangeError::ConfigError(format!("Клиент для биржи {:?} не найден", exchange_type))
+        })?;
+        client.send_subscription(command).await.map_err(ExchangeError::WebSocketError)
+    }
+
+    /// Подписаться на все публичные каналы символа, которые поддерживает биржа
+    /// (см. [`ExchangeType::supported_channels`]).
+    ///
+    /// Неподдерживаемые каналы пропускаются без ошибки. Возвращает список каналов,
+    /// на которые выполнена подписка.
+    pub async fn subscribe_all_public(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+    ) -> Result<Vec<String>, String> {
+        if !self.clients.contains_key(exchange_type) {
+            return Err(format!("Клиент для биржи {:?} не найден", exchange_type));
+        }
+
+        let mut subscribed = Vec::new();
+        for channel in exchange_type.supported_channels() {
+            match *channel {
+                "trades" => self.subscribe_trades(exchange_type, symbol).await?,
+                "orderbook" => {
+                    self.subscribe_orderbook(exchange_type, symbol, DepthMode::Incremental).await?
+                }
+                "ticker" => self.subscribe_ticker(exchange_type, symbol).await?,
+                _ => continue,
+            }
+            subscribed.push(channel.to_string());
+        }
+
+        info!("Подписка на каналы {:?} для {} на {:?}", subscribed, symbol, exchange_type);
+        Ok(subscribed)
+    }
+
+    /// Подписаться на свечи
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи
+    /// * `symbol` - Торговая пара
+    /// * `interval` - Интервал свечи в секундах (например, 60 для 1 минуты)
+    pub async fn subscribe_kline(
+        &mut self,
+        exchange_type: &ExchangeType,
+        symbol: &str,
+        interval: usize,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "kline", symbol).map_err(|e| e.to_string())?;
+        if let Some(client) = self.subscription_target_mut(exchange_type) {
+            client.subscribe_kline(symbol, interval).await?;
+            let request = SubscriptionRequest::Kline(interval);
+            self.confirm_or_shard(exchange_type, "kline", symbol, request).await?;
+            self.subscription_manager.add_exchange_subscription(exchange_type, "kline", symbol);
+            // Интервал свечей не хранится в менеджере подписок, поэтому повторяем здесь
+            if let Some(group) = self.standby_groups.get_mut(exchange_type) {
+                for (instance_id, standby) in &mut group.standby {
+                    if let Err(e) = standby.subscribe_kline(symbol, interval).await {
+                        warn!(
+                            "CryptoWsClient::subscribe_kline: резервное соединение {}: {}",
+                            instance_id, e
+                        );
+                    }
+                }
+            }
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на баланс аккаунта
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи
+    /// * `listen_key` - Опциональный ключ для приватных каналов (например, для MEXC)
+    pub async fn subscribe_account_balance(
+        &mut self,
+        exchange_type: &ExchangeType,
+        _listen_key: Option<&str>,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "balance", "ACCOUNT")
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.clients.get_mut(exchange_type) {
+            client.subscribe_account_balance(_listen_key).await?;
+            self.subscription_manager.add_exchange_subscription(
+                exchange_type,
+                "balance",
+                "ACCOUNT",
+            );
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на приватные сделки аккаунта
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи
+    /// * `listen_key` - Опциональный ключ для приватных каналов (например, для MEXC)
+    pub async fn subscribe_private_deals(
+        &mut self,
+        exchange_type: &ExchangeType,
+        _listen_key: Option<&str>,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "private_deals", "ACCOUNT")
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.clients.get_mut(exchange_type) {
+            client.subscribe_private_deals(_listen_key).await?;
+            self.subscription_manager.add_exchange_subscription(
+                exchange_type,
+                "private_deals",
+                "ACCOUNT",
+            );
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Подписаться на изменения позиций фьючерсного аккаунта
+    ///
+    /// # Параметры
+    /// * `exchange_type` - Тип биржи
+    /// * `listen_key` - Опциональный ключ для приватных каналов
+    pub async fn subscribe_positions(
+        &mut self,
+        exchange_type: &ExchangeType,
+        listen_key: Option<&str>,
+    ) -> Result<(), String> {
+        self.check_subscription_limit(exchange_type, "positions", "ACCOUNT")
+            .map_err(|e| e.to_string())?;
+        if let Some(client) = self.clients.get_mut(exchange_type) {
+            client.subscribe_positions(listen_key).await?;
+            self.subscription_manager.add_exchange_subscription(
+                exchange_type,
+                "positions",
+                "ACCOUNT",
+            );
+            Ok(())
+        } else {
+            Err(format!("Клиент для биржи {:?} не найден", exchange_type))
+        }
+    }
+
+    /// Получить следующее сообщение из всех клиентов
+    ///
+    /// Перед чтением деградировавшие соединения заменяются резервными. Читать
+    /// сообщения параллельно с подписками можно через
+    /// [`message_reader`](Self::message_reader).
+    pub async fn next_message(&mut self) -> Result<Option<WsMessage>, String> {
+        per_message_debug!("CryptoWsClient::next_message: запуск получения сообщений");
+
+        let connected_exchanges: Vec<_> = self
+            .connection_states
+            .iter()
+            .filter(|(_, state)| matches!(state, ConnectionState::Connected))
+            .map(|(exchange, _)| exchange.clone())
+            .collect();
+        for exchange_type in &connected_exchanges {
+            if !self.is_paused(exchange_type) {
+                self.failover_if_degraded(exchange_type);
+            }
+        }
+        if self.connection_status_messages {
+            self.report_connection_states();
+        }
+
+        self.sync_reader();
+        Ok(self.reader.next_message())
+    }
+
+    /// Поставить в очередь чтения сообщения [`ChannelType::ConnectionStatus`] о
+    /// биржах, состояние которых изменилось с прошлой проверки
+    ///
+    /// Для подключенной биржи учитывается состояние транспорта: разрыв, который
+    /// клиент переподключает сам, виден только в нём.
+    fn report_connection_states(&mut self) {
+        let mut messages = Vec::new();
+        for (exchange_type, state) in &self.connection_states {
+            let state = match (state, self.clients.get(exchange_type)) {
+                (ConnectionState::Connected, Some(client)) => client
+                    .health_status()
+                    .map(|health| ConnectionState::from_transport(health.state))
+                    .unwrap_or(ConnectionState::Connected),
+                (state, _) => state.clone(),
+            };
+            let changed = match self.reported_states.get(exchange_type) {
+                Some(reported) => *reported != state,
+                None => state != ConnectionState::Connected,
+            };
+            if changed {
+                info!("Состояние подключения {:?}: {}", exchange_type, state.as_str());
+                messages.push(Self::connection_status_message(exchange_type, &state));
+            }
+            self.reported_states.insert(exchange_type.clone(), state);
+        }
+        self.reported_states
+            .retain(|exchange_type, _| self.connection_states.contains_key(exchange_type));
+        self.reader.state().deferred.extend(messages);
+    }
+
+    /// Сообщение [`ChannelType::ConnectionStatus`] с новым состоянием биржи
+    fn connection_status_message(
+        exchange_type: &ExchangeType,
+        state: &ConnectionState,
+    ) -> WsMessage {
+        let error = match state {
+            ConnectionState::Error(error) => Some(error.as_str()),
+            _ => None,
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        WsMessage {
+            exchange: exchange_type.clone(),
+            channel: ChannelType::ConnectionStatus,
+            symbol: String::new(),
+            data: serde_json::json!({
+                "connection_status": {"state": state.as_str(), "error": error}
+            }),
+            timestamp,
+            received_at_ns: monotonic_nanos(),
+            event_time: Some(timestamp),
+            tags: Vec::new(),
+            raw_bytes: None,
+        }
+    }
+
+    /// Читатель сообщений, которому достаточно общего доступа.
+    ///
+    /// Читатель можно передать в другую задачу: он читает те же соединения, что и
+    /// [`next_message`](Self::next_message), пока клиент подписывается, отписывается
+    /// и подключает биржи. Переключение на резервное соединение выполняет только
+    /// [`next_message`](Self::next_message), но сообщения резервных соединений
+    /// читатель получает и без него.
+    pub fn message_reader(&self) -> MessageReader {
+        self.sync_reader();
+        self.reader.clone()
+    }
+
+    /// Обновить набор подключенных соединений, из которых читает [`MessageReader`]
+    fn sync_reader(&self) {
+        let sources = self
+            .connection_states
+            .iter()
+            .filter(|(_, state)| matches!(state, ConnectionState::Connected))
+            .filter_map(|(exchange_type, _)| {
+                let mut sources = ExchangeSources {
+                    connections: vec![self.clients.get(exchange_type)?.message_source()?],
+                    standby: Vec::new(),
+                };
+                if let Some(shards) = self.shards.get(exchange_type) {
+                    sources
+                        .connections
+                        .extend(shards.iter().filter_map(|shard| shard.message_source()));
+                }
+                if let Some(group) = self.standby_groups.get(exchange_type) {
+                    sources.standby.extend(
+                        group.standby.iter().filter_map(|(_, client)| client.message_source()),
+                    );
+                }
+                Some((exchange_type.clone(), sources))
+            })
+            .collect();
+        self.reader.state().sources = sources;
+    }
+
+    /// Запомнить тикер из сообщения, заменив предыдущий по той же паре
+    fn remember_ticker(
+        last_tickers: &mut HashMap<(ExchangeType, String), Ticker>,
+        message: &WsMessage,
+    ) {
+        if let Some(ticker) = message.as_ticker() {
+            last_tickers.insert((message.exchange.clone(), message.symbol.clone()), ticker);
+        }
+    }
+
+    /// Последний тикер по символу, полученный через [`next_message`](Self::next_message).
+    ///
+    /// `symbol` указывается в нормализованном виде, как в [`WsMessage::symbol`]
+    /// (`BTC_USDT`). Хранится только последнее значение на пару.
+    pub fn last_ticker(&self, exchange_type: &ExchangeType, symbol: &str) -> Option<Ticker> {
+        self.reader.state().last_tickers.get(&(exchange_type.clone(), symbol.to_string())).cloned()
+    }
+
+    /// Лучшие цены покупки и продажи по символу среди всех подключённых бирж.
+    ///
+    /// Берутся из кэша последних тикеров (см. [`last_ticker`](Self::last_ticker)), поэтому
+    /// учитываются только биржи, тикеры которых передают лучшие цены. Символ сравнивается
+    /// без учёта формата биржи: `BTCUSDT`, `BTC_USDT` и `BTC-USDT` — одна пара. Возвращает
+    /// `None`, если ни одна биржа не передала ни одной лучшей цены.
+    pub fn consolidated_bbo(&self, symbol: &str) -> Option<ConsolidatedBbo> {
+        let symbol = normalize_subscription_symbol(symbol);
+        let mut bbo = ConsolidatedBbo { symbol, bid: None, ask: None };
+
+        for ((exchange_type, ticker_symbol), ticker) in &self.reader.state().last_tickers {
+            if normalize_subscription_symbol(ticker_symbol) != bbo.symbol
+                || !self.is_exchange_connected(exchange_type)
+            {
+                continue;
+            }
+            let quote =
+                |price: f64| BboQuote { exchange: exchange_type.clone(), price, time: ticker.time };
+            // При равных ценах предпочитаем более свежий тикер
+            if let Some(bid) = ticker.best_bid
+                && bbo.bid.as_ref().is_none_or(|best| {
+                    bid > best.price || (bid == best.price && ticker.time > best.time)
+                })
+            {
+                bbo.bid = Some(quote(bid));
+            }
+            if let Some(ask) = ticker.best_ask
+                && bbo.ask.as_ref().is_none_or(|best| {
+                    ask < best.price || (ask == best.price && ticker.time > best.time)
+                })
+            {
+                bbo.ask = Some(quote(ask));
+            }
+        }
+
+        (bbo.bid.is_some() || bbo.ask.is_some()).then_some(bbo)
+    }
+
+    /// Получить следующее приватное сообщение (каналы с [`ChannelType::is_private`])
+    pub async fn next_private_message(&mut self) -> Result<Option<WsMessage>, String> {
+        per_message_debug!(
+            "CryptoWsClient::next_private_message: запуск получения приватных сообщений"
+        );
+
+        loop {
+            match self.next_message().await? {
+                Some(message) => {
+                    // Проверяем, является ли сообщение приватным
+                    if message.channel.is_private() {
+                        per_message_debug!(
+                            "CryptoWsClient::next_private_message: получено приватное сообщение: {:?} для символа {}",
+                            message.channel,
+                            message.symbol
+                        );
+                        return Ok(Some(message));
+                    } else {
+                        // Пропускаем публичные сообщения
+                        trace!(
+                            "CryptoWsClient::next_private_message: пропущено публичное сообщение: {:?}",
+                            message.channel
+                        );
+                        continue;
+                    }
+                }
+                None => {
+                    // Нет новых сообщений
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Получить следующее приватное событие в типизированном виде [`PrivateEvent`]
+    ///
+    /// Если включена [`with_fill_aggregation`](Self::with_fill_aggregation), исполнения
+    /// накапливаются до истечения окна, а остальные события возвращаются сразу.
+    pub async fn next_private_event(&mut self) -> Result<Option<PrivateEvent>, String> {
+        loop {
+            if let Some(fill) = self
+                .fill_aggregator
+                .as_mut()
+                .and_then(|aggregator| aggregator.pop_expired(Instant::now()))
+            {
+                return Ok(Some(PrivateEvent::Fill(fill)));
+            }
+
+            let Some(message) = self.next_private_message().await? else {
+                return Ok(None);
+            };
+            match (message.as_private_event(), self.fill_aggregator.as_mut()) {
+                (Some(PrivateEvent::Fill(fill)), Some(aggregator)) => {
+                    aggregator.push(fill, Instant::now());
+                }
+                (Some(event), _) => return Ok(Some(event)),
+                (None, _) => trace!(
+                    "CryptoWsClient::next_private_event: неизвестный формат приватного сообщения {:?}",
+                    message.channel
+                ),
+            }
+        }
+    }
+
+    /// Получить следующее публичное сообщение (исключая приватные)
+    pub async fn next_public_message(&mut self) -> Result<Option<WsMessage>, String> {
+        debug!("CryptoWsClient::next_public_message: запуск получения публичных сообщений");
+
+        loop {
+            match self.next_message().await? {
+                Some(message) => {
+                    // Проверяем, является ли сообщение публичным
+                    if !message.channel.is_private() {
+                        debug!(
+                            "CryptoWsClient::next_public_message: получено публичное сообщение: {:?} для символа {}",
+                            message.channel, message.symbol
+                        );
+                        return Ok(Some(message));
+                    } else {
+                        // Пропускаем приватные сообщения
+                        trace!(
+                            "CryptoWsClient::next_public_message: пропущено приватное сообщение: {:?}",
+                            message.channel
+                        );
+                        continue;
+                    }
+                }
+                None => {
+                    // Нет новых сообщений
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    /// Проверить есть ли приватные сообщения определенного типа
+    pub fn has_private_channel_subscriptions(&self, channel_type: ChannelType) -> bool {
+        if !channel_type.is_private() {
+            return false;
+        }
+
+        let channel_str = channel_type.as_str();
+        self.subscription_manager
+            .get_subscriptions()
+            .iter()
+            .any(|(channel, _)| channel == channel_str)
+    }
+
+    /// Получить список активных приватных подписок
+    pub fn get_private_subscriptions(&self) -> Vec<(String, String)> {
+        self.subscription_manager
+            .get_subscriptions()
+            .into_iter()
+            .filter(|(channel, _)| ChannelType::from_name(channel).is_some_and(|c| c.is_private()))
+            .collect()
+    }
+
+    /// Получить состояние подключения для биржи
+    pub fn get_connection_state(&self, exchange_type: &ExchangeType) -> Option<&ConnectionState> {
         self.connection_states.get(exchange_type)
     }
 
-    /// Получить список подключённых бирж
-    pub fn get_connected_exchanges(&self) -> Vec<ExchangeType> {
-        self.connection_states
-            .iter()
-            .filter(|(_, state)| matches!(state, ConnectionState::Connected))
-            .map(|(exchange, _)| exchange.clone())
-            .collect()
+    /// Получить список подключённых бирж
+    pub fn get_connected_exchanges(&self) -> Vec<ExchangeType> {
+        self.connection_states
+            .iter()
+            .filter(|(_, state)| matches!(state, ConnectionState::Connected))
+            .map(|(exchange, _)| exchange.clone())
+            .collect()
+    }
+
+    /// Получить добавленные, но не подключённые биржи с их текущим состоянием
+    ///
+    /// Биржи упорядочены по имени, чтобы супервизор переподключения обходил их стабильно.
+    pub fn disconnected_exchanges(&self) -> Vec<(ExchangeType, ConnectionState)> {
+        let mut exchanges: Vec<_> = self
+            .connection_states
+            .iter()
+            .filter(|(_, state)| !matches!(state, ConnectionState::Connected))
+            .map(|(exchange, state)| (exchange.clone(), state.clone()))
+            .collect();
+        exchanges.sort_by_key(|(exchange, _)| exchange.as_str());
+        exchanges
+    }
+
+    /// Проверить, подключена ли конкретная биржа
+    pub fn is_exchange_connected(&self, exchange_type: &ExchangeType) -> bool {
+        matches!(self.connection_states.get(exchange_type), Some(ConnectionState::Connected))
+    }
+
+    /// Отбрасывать в [`next_message`](Self::next_message) сообщения, полученные позже
+    /// `max_age` после события на бирже (`timestamp - event_time`).
+    ///
+    /// Сообщения без времени события не отбрасываются.
+    pub fn set_max_message_age(&mut self, max_age: Duration) {
+        self.reader.state().max_message_age = Some(max_age);
+    }
+
+    /// Установить время ожидания ответа биржи на подписку (по умолчанию 5 секунд).
+    ///
+    /// Используется, если включено [`with_confirm_subscriptions`](Self::with_confirm_subscriptions).
+    pub fn set_subscription_ack_timeout(&mut self, timeout: Duration) {
+        self.subscription_ack_timeout = timeout;
+    }
+
+    /// Количество сообщений, отброшенных как устаревшие
+    pub fn dropped_stale_messages(&self) -> u64 {
+        self.reader.state().stale_messages
+    }
+
+    /// Проверить, что сообщение старше допустимого возраста
+    fn is_stale(message: &WsMessage, max_age: Option<Duration>) -> bool {
+        match (max_age, message.event_time) {
+            (Some(max_age), Some(event_time)) => {
+                message.timestamp.saturating_sub(event_time) > max_age.as_millis() as u64
+            }
+            _ => false,
+        }
+    }
+
+    /// Количество сообщений биржи, отброшенных из-за переполнения буфера
+    pub fn dropped_messages(&self, exchange_type: &ExchangeType) -> u64 {
+        self.clients.get(exchange_type).map(|client| client.dropped_messages()).unwrap_or(0)
+    }
+
+    /// Суммарное количество сообщений, отброшенных из-за переполнения буферов
+    pub fn total_dropped_messages(&self) -> u64 {
+        self.clients.values().map(|client| client.dropped_messages()).sum()
+    }
+
+    /// Проверить, что все настроенные биржи подключены и получают heartbeat
+    ///
+    /// Подходит для readiness/liveness проб.
+    pub fn all_healthy(&self) -> bool {
+        self.unhealthy_exchanges().is_empty()
+    }
+
+    /// Получить список бирж с проблемами подключения и описанием проблемы
+    pub fn unhealthy_exchanges(&self) -> Vec<(ExchangeType, String)> {
+        let now = chrono::Utc::now().timestamp();
+        let mut problems = Vec::new();
+
+        for (exchange_type, client) in &self.clients {
+            match self.connection_states.get(exchange_type) {
+                Some(ConnectionState::Connected) => {}
+                Some(state) => {
+                    problems.push((
+                        exchange_type.clone(),
+                        format!("Состояние подключения: {:?}", state),
+                    ));
+                    continue;
+                }
+                None => {
+                    problems.push((
+                        exchange_type.clone(),
+                        "Состояние подключения неизвестно".to_string(),
+                    ));
+                    continue;
+                }
+            }
+
+            // Клиенты без метрик транспорта считаются здоровыми по состоянию подключения
+            let Some(health) = client.health_status() else {
+                continue;
+            };
+
+            if health.state != TransportState::Connected {
+                let reason = match health.last_error {
+                    Some(err) => format!("Транспорт в состоянии {:?}: {}", health.state, err),
+                    None => format!("Транспорт в состоянии {:?}", health.state),
+                };
+                problems.push((exchange_type.clone(), reason));
+            } else if now - health.last_ping > HEARTBEAT_TIMEOUT_SECS {
+                problems.push((
+                    exchange_type.clone(),
+                    format!("Нет heartbeat {} секунд", now - health.last_ping),
+                ));
+            }
+        }
+
+        problems
+    }
+
+    /// Получить все активные подписки
+    pub fn get_subscriptions(&self) -> Vec<(String, String)> {
+        self.subscription_manager.get_subscriptions()
+    }
+
+    /// Сравнить подписки менеджера с подписками транспортного уровня биржи
+    ///
+    /// После переподключения транспорт восстанавливает только сохранённые им команды,
+    /// поэтому представления могут разойтись. При `repair == true` подписки, которых нет
+    /// на транспорте, отправляются повторно; приватные каналы требуют listen_key и
+    /// не восстанавливаются.
+    pub async fn reconcile_subscriptions(
+        &mut self,
+        exchange_type: &ExchangeType,
+        repair: bool,
+    ) -> Result<SubscriptionDiff, String> {
+        let client = self
+            .clients
+            .get_mut(exchange_type)
+            .ok_or_else(|| format!("Клиент для биржи {:?} не найден", exchange_type))?;
+
+        let transport: HashSet<(String, String)> = client
+            .transport_subscriptions()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|command| WsClientWrapper::parse_transport_subscription(command))
+            .collect();
+        let managed = self.subscription_manager.exchange_subscriptions(exchange_type);
+
+        let mut diff = SubscriptionDiff::default();
+        let mut managed_keys = HashSet::new();
+        for (channel, symbol) in managed {
+            let key = (channel.clone(), normalize_subscription_symbol(&symbol));
+            if !transport.contains(&key) {
+                diff.missing_in_transport.push((channel, symbol));
+            }
+            managed_keys.insert(key);
+        }
+        diff.missing_in_manager = transport.difference(&managed_keys).cloned().collect();
+        diff.missing_in_manager.sort();
+
+        if !diff.is_consistent() {
+            warn!(
+                "Подписки {:?} расходятся: нет на транспорте {:?}, нет в менеджере {:?}",
+                exchange_type, diff.missing_in_transport, diff.missing_in_manager
+            );
+        }
+
+        if repair {
+            for (channel, symbol) in &diff.missing_in_transport {
+                let result = match channel.as_str() {
+                    "orderbook" => client.subscribe_orderbook(symbol).await,
+                    "trades" => client.subscribe_trades(symbol).await,
+                    "ticker" => client.subscribe_ticker(symbol).await,
+                    "index_price" => client.subscribe_index_price(symbol).await,
+                    _ => {
+                        warn!(
+                            "Подписка {} {} не может быть восстановлена автоматически",
+                            channel, symbol
+                        );
+                        continue;
+                    }
+                };
+                match result {
+                    Ok(()) => diff.repaired.push((channel.clone(), symbol.clone())),
+                    Err(e) => {
+                        warn!("Не удалось восстановить подписку {} {}: {}", channel, symbol, e)
+                    }
+                }
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Получить количество настроенных WebSocket клиентов
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+}
+
+impl Default for CryptoWsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CryptoWsClient {
+    /// Останавливает фоновые задачи `run()` и закрывает соединения, если клиент удалён
+    /// без [`disconnect_all`](Self::disconnect_all).
+    ///
+    /// `Drop` не может быть асинхронным, поэтому закрытие сокетов выполняется отдельной
+    /// задачей в текущем tokio runtime по принципу best-effort.
+    fn drop(&mut self) {
+        let _ = self.shutdown_tx.send(true);
+
+        let mut clients: Vec<WsClientWrapper> = self.clients.drain(..).map(|(_, c)| c).collect();
+        for (_, group) in self.standby_groups.drain() {
+            clients.extend(group.standby.into_iter().map(|(_, client)| client));
+        }
+        clients.extend(self.shards.drain(..).flat_map(|(_, shards)| shards));
+        if clients.is_empty() {
+            return;
+        }
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    for mut client in clients {
+                        let _ = client.disconnect().await;
+                    }
+                    debug!("CryptoWsClient::drop: соединения закрыты");
+                });
+            }
+            Err(_) => warn!(
+                "CryptoWsClient::drop: нет tokio runtime, соединения закроются вместе с процессом"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_private_deals_channel() {
+        // Тестируем парсинг сообщения приватных сделок
+        let raw_data = json!({
+            "c": "spot@public.deals.v3.api",
+            "d": {
+                "price": "",
+                "quantity": "CLOREUSDT",
+                "symbol": "spot@private.deals.v3.api.pb",
+                "takerOrderSide": 0,
+                "time": 0
+            },
+            "t": 0
+        });
+
+        let result =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+
+        match result {
+            Ok((channel_type, symbol)) => {
+                assert_eq!(channel_type, ChannelType::PrivateDeals);
+                assert_eq!(symbol, "CLOREUSDT");
+                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+            }
+            Err(e) => {
+                panic!("❌ Тест не прошел: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_public_deals_channel() {
+        // Тестируем парсинг обычных публичных сделок
+        let raw_data = json!({
+            "c": "spot@public.deals.v3.api@BTCUSDT",
+            "d": {
+                "symbol": "BTCUSDT",
+                "price": "50000.00",
+                "quantity": "0.1",
+                "time": 1640995200000_i64,
+                "takerOrderSide": 1
+            },
+            "t": 1640995200000_i64
+        });
+
+        let result =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+
+        match result {
+            Ok((channel_type, symbol)) => {
+                assert_eq!(channel_type, ChannelType::Trades);
+                assert_eq!(symbol, "BTC_USDT");
+                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+            }
+            Err(e) => {
+                panic!("❌ Тест не прошел: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_user_data_stream_private_deals() {
+        // Тестируем парсинг User Data Stream приватных сделок
+        let raw_data = json!({
+            "channel": "spot@private.deals.v3.api.pb",
+            "privateDeals": {
+                "price": "1.2345",
+                "quantity": "10.0",
+                "amount": "12.345",
+                "tradeType": 1,
+                "isMaker": false,
+                "isSelfTrade": false,
+                "tradeId": "987654321",
+                "clientOrderId": "my_order_123",
+                "orderId": "order_789",
+                "feeAmount": "0.0123",
+                "feeCurrency": "USDT",
+                "time": 1749495346968_i64
+            },
+            "sendTime": 1749495346968_i64
+        });
+
+        let result =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+
+        match result {
+            Ok((channel_type, symbol)) => {
+                assert_eq!(channel_type, ChannelType::PrivateDeals);
+                // Symbol будет "UNKNOWN" так как privateDeals не содержит symbol
+                assert_eq!(symbol, "UNKNOWN");
+                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+            }
+            Err(e) => {
+                panic!("❌ Тест не прошел: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_private_message_detection_and_parsing() {
+        // Тестируем обнаружение и парсинг различных форматов приватных сообщений
+
+        // 1. User Data Stream формат с privateDeals
+        let user_data_stream_message = json!({
+            "channel": "spot@private.deals.v3.api.pb",
+            "symbol": "MXUSDT",
+            "sendTime": 1736417034332_i64,
+            "privateDeals": {
+                "price": "3.6962",
+                "quantity": "1",
+                "amount": "3.6962",
+                "tradeType": 2,
+                "tradeId": "505979017439002624X1",
+                "orderId": "C02__505979017439002624115",
+                "feeAmount": "0.0003998377369698171",
+                "feeCurrency": "MX",
+                "time": 1736417034280_i64
+            }
+        });
+
+        // Проверяем что это приватное сообщение
+        assert!(WsClientWrapper::is_private_message(
+            &ExchangeType::MexcSpot,
+            &user_data_stream_message
+        ));
+
+        // Парсим приватное сообщение
+        let result = WsClientWrapper::parse_private_message(
+            ExchangeType::MexcSpot,
+            &user_data_stream_message,
+            &user_data_stream_message.to_string(),
+        );
+
+        assert!(result.is_ok());
+        let ws_message = result.unwrap();
+        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
+        assert_eq!(ws_message.symbol, "MXUSDT");
+        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+
+        // 2. Старый смешанный формат где приватные сделки попадают в публичный канал
+        let mixed_format_message = json!({
+            "c": "spot@public.deals.v3.api",
+            "d": {
+                "price": "",
+                "quantity": "CLOREUSDT",
+                "symbol": "spot@private.deals.v3.api.pb",
+                "takerOrderSide": 0,
+                "time": 0
+            },
+            "t": 0
+        });
+
+        // Проверяем что это приватное сообщение
+        assert!(WsClientWrapper::is_private_message(
+            &ExchangeType::MexcSpot,
+            &mixed_format_message
+        ));
+
+        // Парсим приватное сообщение
+        let result = WsClientWrapper::parse_private_message(
+            ExchangeType::MexcSpot,
+            &mixed_format_message,
+            &mixed_format_message.to_string(),
+        );
+
+        assert!(result.is_ok());
+        let ws_message = result.unwrap();
+        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
+        assert_eq!(ws_message.symbol, "CLOREUSDT");
+        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+
+        // 3. Обычное публичное сообщение НЕ должно быть приватным
+        let public_message = json!({
+            "c": "spot@public.deals.v3.api@BTCUSDT",
+            "d": {
+                "symbol": "BTCUSDT",
+                "price": "50000.00",
+                "quantity": "0.1",
+                "time": 1640995200000_i64,
+                "takerOrderSide": 1
+            },
+            "t": 1640995200000_i64
+        });
+
+        // Проверяем что это НЕ приватное сообщение
+        assert!(!WsClientWrapper::is_private_message(&ExchangeType::MexcSpot, &public_message));
+
+        println!("✅ Тест обнаружения и парсинга приватных сообщений прошел успешно");
+    }
+
+    #[test]
+    fn test_okx_private_orders_and_account() {
+        let order_frame = r#"{
+            "arg": {
+                "channel": "orders", "instType": "SPOT", "instId": "BTC-USDT",
+                "uid": "614488474791936"
+            },
+            "data": [{
+                "instType": "SPOT", "instId": "BTC-USDT", "ordId": "680800019749904384",
+                "clOrdId": "", "px": "60000", "sz": "0.001", "ordType": "limit", "side": "buy",
+                "state": "live", "accFillSz": "0",
+                "cTime": "1708587373361", "uTime": "1708587373362"
+            }]
+        }"#;
+        let data: Value = serde_json::from_str(order_frame).unwrap();
+        assert!(WsClientWrapper::is_private_message(&ExchangeType::OkxSpot, &data));
+        let order =
+            WsClientWrapper::parse_private_message(ExchangeType::OkxSpot, &data, order_frame)
+                .unwrap();
+        assert_eq!(order.channel, ChannelType::Orders);
+        assert_eq!(order.symbol, "BTC-USDT");
+        assert_eq!(order.data["data"][0]["ordId"], "680800019749904384");
+
+        let account_frame = r#"{
+            "arg": {"channel": "account", "uid": "614488474791936"},
+            "data": [{
+                "uTime": "1708587373362", "totalEq": "101.23",
+                "details": [{"ccy": "USDT", "availBal": "100.5", "cashBal": "100.5", "eq": "100.5"}]
+            }]
+        }"#;
+        let data: Value = serde_json::from_str(account_frame).unwrap();
+        assert!(WsClientWrapper::is_private_message(&ExchangeType::OkxSpot, &data));
+        let account =
+            WsClientWrapper::parse_private_message(ExchangeType::OkxSpot, &data, account_frame)
+                .unwrap();
+        assert_eq!(account.channel, ChannelType::AccountBalance);
+        assert_eq!(account.symbol, "ACCOUNT");
+
+        let positions = json!({
+            "arg": {"channel": "positions", "instType": "SWAP", "uid": "614488474791936"},
+            "data": [{"instId": "BTC-USDT-SWAP", "pos": "1", "posSide": "long"}]
+        });
+        let position = WsClientWrapper::parse_private_message(
+            ExchangeType::OkxSpot,
+            &positions,
+            &positions.to_string(),
+        )
+        .unwrap();
+        assert_eq!(position.channel, ChannelType::Positions);
+        assert_eq!(position.symbol, "BTC-USDT-SWAP");
+
+        // Подтверждение подписки и публичные каналы приватными не считаются
+        let ack = json!({"event": "subscribe", "arg": {"channel": "orders", "instType": "SPOT"}});
+        assert!(!WsClientWrapper::is_private_message(&ExchangeType::OkxSpot, &ack));
+        let trades = json!({"arg": {"channel": "trades", "instId": "BTC-USDT"}, "data": []});
+        assert!(!WsClientWrapper::is_private_message(&ExchangeType::OkxSpot, &trades));
+    }
+
+    #[test]
+    fn test_user_data_stream_private_message_parsing() {
+        // Тестируем точный формат сообщения User Data Stream как у пользователя
+        let user_data_stream_message = r#"{
+            "channel": "spot@private.deals.v3.api.pb",
+            "symbol": "MXUSDT",
+            "sendTime": 1736417034332,
+            "privateDeals": {
+                "price": "3.6962",
+                "quantity": "1",
+                "amount": "3.6962",
+                "tradeType": 2,
+                "tradeId": "505979017439002624X1",
+                "orderId": "C02__505979017439002624115",
+                "feeAmount": "0.0003998377369698171",
+                "feeCurrency": "MX",
+                "time": 1736417034280
+            }
+        }"#;
+
+        // Парсим JSON
+        let data: Value = serde_json::from_str(user_data_stream_message).unwrap();
+
+        // Проверяем что это распознается как приватное сообщение
+        assert!(WsClientWrapper::is_private_message(&ExchangeType::MexcSpot, &data));
+
+        // Парсим приватное сообщение
+        let result = WsClientWrapper::parse_private_message(
+            ExchangeType::MexcSpot,
+            &data,
+            user_data_stream_message,
+        );
+
+        assert!(result.is_ok());
+        let ws_message = result.unwrap();
+        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
+        assert_eq!(ws_message.symbol, "MXUSDT");
+        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+
+        // Проверяем что данные сохранены правильно
+        assert_eq!(
+            ws_message.data.get("channel").unwrap().as_str().unwrap(),
+            "spot@private.deals.v3.api.pb"
+        );
+        assert_eq!(ws_message.data.get("symbol").unwrap().as_str().unwrap(), "MXUSDT");
+
+        let private_deals = ws_message.data.get("privateDeals").unwrap();
+        assert_eq!(private_deals.get("price").unwrap().as_str().unwrap(), "3.6962");
+        assert_eq!(private_deals.get("quantity").unwrap().as_str().unwrap(), "1");
+        assert_eq!(private_deals.get("tradeType").unwrap().as_i64().unwrap(), 2);
+        assert_eq!(private_deals.get("feeCurrency").unwrap().as_str().unwrap(), "MX");
+
+        println!("✅ Тест парсинга User Data Stream приватных сообщений прошел успешно");
+    }
+
+    #[test]
+    fn test_parse_message_static_with_user_data_stream() {
+        // Тестируем полный pipeline parse_message_static с User Data Stream сообщением
+        let user_data_stream_message = r#"{
+            "channel": "spot@private.deals.v3.api.pb",
+            "symbol": "MXUSDT",
+            "sendTime": 1736417034332,
+            "privateDeals": {
+                "price": "3.6962",
+                "quantity": "1",
+                "amount": "3.6962",
+                "tradeType": 2,
+                "tradeId": "505979017439002624X1",
+                "orderId": "C02__505979017439002624115",
+                "feeAmount": "0.0003998377369698171",
+                "feeCurrency": "MX",
+                "time": 1736417034280
+            }
+        }"#;
+
+        let result =
+            WsClientWrapper::parse_message_static(ExchangeType::MexcSpot, user_data_stream_message)
+                .into_message();
+
+        assert!(
+            result.is_some(),
+            "parse_message_static должен успешно обработать User Data Stream сообщение"
+        );
+
+        let ws_message = result.unwrap();
+        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
+        assert_eq!(ws_message.symbol, "MXUSDT");
+        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+
+        println!("✅ Тест parse_message_static с User Data Stream прошел успешно");
+        println!(
+            "Результат: channel={:?}, symbol={}, exchange={:?}",
+            ws_message.channel, ws_message.symbol, ws_message.exchange
+        );
+    }
+
+    #[test]
+    fn test_user_data_stream_not_service_message() {
+        // Тестируем что User Data Stream сообщения НЕ классифицируются как служебные
+        let user_data_stream_message = json!({
+            "channel": "spot@private.deals.v3.api.pb",
+            "privateDeals": {
+                "amount": "1.19818703",
+                "clientOrderId": "1897e831-fe41-4118-b5cf-fa2e3ec5",
+                "feeAmount": "0.000599093515",
+                "feeCurrency": "USDT",
+                "isMaker": false,
+                "isSelfTrade": false,
+                "orderId": "C02__560846921632235520099",
+                "price": "0.017561",
+                "quantity": "68.23",
+                "time": 1749498562026_i64,
+                "tradeId": "560846921632235520X1",
+                "tradeType": 1
+            },
+            "sendTime": 1749498562045_i64,
+            "symbol": "CLOREUSDT"
+        });
+
+        // Проверяем что это НЕ служебное сообщение
+        assert!(!WsClientWrapper::is_service_message(
+            &ExchangeType::MexcSpot,
+            &user_data_stream_message
+        ), "User Data Stream сообщения с privateDeals НЕ должны быть служебными");
+
+        // Проверяем что это приватное сообщение
+        assert!(WsClientWrapper::is_private_message(
+            &ExchangeType::MexcSpot,
+            &user_data_stream_message
+        ), "User Data Stream сообщения с privateDeals должны быть приватными");
+
+        // Тестируем полный pipeline парсинга
+        let result = WsClientWrapper::parse_message_static(
+            ExchangeType::MexcSpot,
+            &user_data_stream_message.to_string(),
+        )
+        .into_message();
+
+        assert!(result.is_some(), "User Data Stream сообщение должно успешно парситься");
+
+        let ws_message = result.unwrap();
+        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
+        assert_eq!(ws_message.symbol, "CLOREUSDT");
+        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+
+        println!("✅ Тест классификации User Data Stream сообщений прошел успешно");
+    }
+
+    #[test]
+    fn test_received_at_ns_distinct_and_increasing() {
+        // Два сообщения, пришедшие подряд, должны получить разные возрастающие метки
+        let channel = MessageChannel::new();
+        let sink = channel.frame_sink();
+        assert!(sink(monotonic_nanos(), "first".to_string()));
+        assert!(sink(monotonic_nanos(), "second".to_string()));
+
+        let received: Vec<_> = std::iter::from_fn(|| channel.try_recv()).collect();
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].1, "first");
+        assert_eq!(received[1].1, "second");
+        assert!(received[1].0 > received[0].0, "метки времени должны строго возрастать");
+
+        println!("✅ Тест монотонных меток времени прошел: {} < {}", received[0].0, received[1].0);
+    }
+
+    /// Подать сырое сообщение в тестовый клиент
+    fn feed_mock(client: &CryptoWsClient, exchange_type: &ExchangeType, raw: &str) {
+        if let Some(WsClientWrapper::Mock { message_channel, .. }) =
+            client.clients.get(exchange_type)
+        {
+            message_channel.push(raw);
+        }
+    }
+
+    #[test]
+    fn test_ws_client_is_send_sync() {
+        // Проверка на этапе компиляции: клиенты можно передавать между потоками
+        // и разделять через Arc без unsafe impl
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MessageChannel>();
+        assert_send_sync::<WsClientWrapper>();
+        assert_send_sync::<CryptoWsClient>();
+        assert_send_sync::<MessageReader>();
+    }
+
+    fn mock_ws_client(exchanges: &[ExchangeType]) -> CryptoWsClient {
+        let mut client = CryptoWsClient::new();
+        for exchange_type in exchanges {
+            client
+                .clients
+                .insert(exchange_type.clone(), WsClientWrapper::new_mock(exchange_type.clone()));
+            client.connection_states.insert(exchange_type.clone(), ConnectionState::Disconnected);
+        }
+        client
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_subscriptions_private_first() {
+        let exchange = ExchangeType::MexcSpot;
+        let subscriptions = [
+            SubscriptionConfig {
+                channel: ChannelType::Trades,
+                symbol: "BTCUSDT".to_string(),
+                interval: None,
+            },
+            SubscriptionConfig {
+                channel: ChannelType::PrivateDeals,
+                symbol: "ACCOUNT".to_string(),
+                interval: None,
+            },
+            SubscriptionConfig {
+                channel: ChannelType::Orderbook,
+                symbol: "BTCUSDT".to_string(),
+                interval: None,
+            },
+            SubscriptionConfig {
+                channel: ChannelType::AccountBalance,
+                symbol: "ACCOUNT".to_string(),
+                interval: None,
+            },
+        ];
+        let subscribe_calls = |client: &CryptoWsClient| match client.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, .. }) => subscribe_calls.clone(),
+            _ => unreachable!(),
+        };
+
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_with_subscriptions(&exchange, &subscriptions).await.unwrap();
+        assert_eq!(
+            subscribe_calls(&client),
+            vec!["trades:BTCUSDT", "private_deals", "orderbook:BTCUSDT", "balance"]
+        );
+
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]).with_private_first(true);
+        client.connect_with_subscriptions(&exchange, &subscriptions).await.unwrap();
+        assert!(client.is_exchange_connected(&exchange));
+        assert_eq!(
+            subscribe_calls(&client),
+            vec!["private_deals", "balance", "trades:BTCUSDT", "orderbook:BTCUSDT"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_positions() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.subscribe_positions(&ExchangeType::MexcSwap, None).await.unwrap();
+        assert!(client.has_private_channel_subscriptions(ChannelType::Positions));
+        assert_eq!(
+            client.get_private_subscriptions(),
+            vec![("positions".to_string(), "ACCOUNT".to_string())]
+        );
+
+        let frame = r#"{"channel":"push.personal.position","data":{"positionId":1394650,
+            "symbol":"BTC_USDT","positionType":1,"state":1,"holdVol":2,"holdAvgPrice":64210.5,
+            "leverage":20},"ts":1715770402000}"#;
+        let message = WsClientWrapper::parse_message_static(ExchangeType::MexcSwap, frame)
+            .into_message()
+            .unwrap();
+        assert_eq!(message.channel, ChannelType::Positions);
+        assert!(message.channel.is_private());
+    }
+
+    #[tokio::test]
+    async fn test_position_updates_take_private_path() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+        let frame = r#"{"channel":"push.personal.position","data":{"positionId":1394650,
+            "symbol":"BTC_USDT","positionType":1,"state":1,"holdVol":2,"holdAvgPrice":64210.5,
+            "leverage":20},"ts":1715770402000}"#;
+
+        feed_mock(&client, &ExchangeType::MexcSwap, frame);
+        assert!(client.next_public_message().await.unwrap().is_none());
+
+        feed_mock(&client, &ExchangeType::MexcSwap, frame);
+        let message = client.next_private_message().await.unwrap().expect("position update");
+        assert_eq!(message.channel, ChannelType::Positions);
+
+        client.subscription_manager.add_exchange_subscription(
+            &ExchangeType::MexcSwap,
+            "orders",
+            "ACCOUNT",
+        );
+        assert!(client.has_private_channel_subscriptions(ChannelType::Orders));
+        assert_eq!(
+            client.get_private_subscriptions(),
+            vec![("orders".to_string(), "ACCOUNT".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_all_public() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot, ExchangeType::MexcSwap]);
+
+        let spot = client.subscribe_all_public(&ExchangeType::MexcSpot, "BTCUSDT").await.unwrap();
+        assert_eq!(spot, vec!["trades", "orderbook", "ticker"]);
+        assert_eq!(
+            client.subscription_manager.exchange_subscriptions(&ExchangeType::MexcSpot),
+            vec![
+                ("orderbook".to_string(), "BTCUSDT".to_string()),
+                ("ticker".to_string(), "BTCUSDT".to_string()),
+                ("trades".to_string(), "BTCUSDT".to_string()),
+            ]
+        );
+
+        let swap = client.subscribe_all_public(&ExchangeType::MexcSwap, "BTC_USDT").await.unwrap();
+        assert_eq!(swap, vec!["trades", "orderbook", "ticker"]);
+        assert!(
+            client
+                .subscription_manager
+                .exchange_subscriptions(&ExchangeType::MexcSwap)
+                .contains(&("ticker".to_string(), "BTC_USDT".to_string()))
+        );
+
+        assert!(client.subscribe_all_public(&ExchangeType::BingxSpot, "BTC-USDT").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume_keeps_connection() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+
+        let trade = r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0},"ts":1}"#;
+
+        client.pause(&ExchangeType::MexcSwap).unwrap();
+        assert!(client.is_paused(&ExchangeType::MexcSwap));
+
+        feed_mock(&client, &ExchangeType::MexcSwap, trade);
+        assert!(client.next_message().await.unwrap().is_none());
+        assert!(client.is_exchange_connected(&ExchangeType::MexcSwap));
+        assert!(client.clients.get(&ExchangeType::MexcSwap).unwrap().is_connected());
+
+        client.resume(&ExchangeType::MexcSwap).unwrap();
+        // Сообщение, пришедшее во время паузы, отброшено
+        assert!(client.next_message().await.unwrap().is_none());
+
+        feed_mock(&client, &ExchangeType::MexcSwap, trade);
+        let message = client.next_message().await.unwrap().expect("сообщение после resume");
+        assert_eq!(message.channel, ChannelType::Trades);
+        assert_eq!(message.symbol, "BTC_USDT");
+
+        assert!(client.pause(&ExchangeType::BingxSpot).is_err());
+
+        println!("✅ Тест pause/resume прошел успешно");
+    }
+
+    #[tokio::test]
+    async fn test_connect_concurrently_respects_limit() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let mut results = connect_concurrently((0..5).collect(), 2, |i: usize| {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .await;
+
+        results.sort();
+        assert_eq!(results, vec![0, 1, 2, 3, 4]);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_last_ticker_cached_from_next_message() {
+        let swap = ExchangeType::MexcSwap;
+        let mut client = mock_ws_client(std::slice::from_ref(&swap));
+        client.connect_all().await.unwrap();
+        assert!(client.last_ticker(&swap, "BTC_USDT").is_none());
+
+        let ticker = |price: f64| {
+            format!(
+                r#"{{"channel":"push.ticker","symbol":"BTC_USDT","data":{{"symbol":"BTC_USDT",
+                    "lastPrice":{price},"bid1":{bid},"ask1":{ask},"timestamp":1716000000000}},
+                    "ts":1716000000000}}"#,
+                bid = price - 0.5,
+                ask = price + 0.5
+            )
+        };
+
+        feed_mock(&client, &swap, &ticker(50000.0));
+        client.next_message().await.unwrap().expect("ticker");
+        feed_mock(&client, &swap, &ticker(50100.0));
+        client.next_message().await.unwrap().expect("ticker");
+
+        // Хранится только последний тикер по паре
+        let last = client.last_ticker(&swap, "BTC_USDT").expect("тикер в кэше");
+        assert_eq!(last.last_price, 50100.0);
+        assert_eq!(last.best_bid, Some(50099.5));
+        assert_eq!(last.best_ask, Some(50100.5));
+        assert!(client.last_ticker(&swap, "ETH_USDT").is_none());
+        assert!(client.last_ticker(&ExchangeType::MexcSpot, "BTC_USDT").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_consolidated_bbo_across_exchanges() {
+        let swap = ExchangeType::MexcSwap;
+        let bingx = ExchangeType::BingxSwap;
+        let mut client = mock_ws_client(&[swap.clone(), bingx.clone()]);
+        client.connect_all().await.unwrap();
+        assert!(client.consolidated_bbo("BTC_USDT").is_none());
+
+        let ticker = |symbol: &str, bid: f64, ask: f64, time: u64| Ticker {
+            symbol: symbol.to_string(),
+            last_price: bid,
+            price_change_rate: 0.0,
+            high: ask,
+            low: bid,
+            volume: 0.0,
+            quote_volume: 0.0,
+            best_bid: Some(bid),
+            best_ask: Some(ask),
+            time,
+        };
+        {
+            let mut state = client.reader.state();
+            state.last_tickers.insert(
+                (swap.clone(), "BTC_USDT".to_string()),
+                ticker("BTC_USDT", 50000.0, 50001.0, 1716000000000),
+            );
+            state.last_tickers.insert(
+                (bingx.clone(), "BTC-USDT".to_string()),
+                ticker("BTC-USDT", 50000.5, 50002.0, 1716000000100),
+            );
+            state.last_tickers.insert(
+                (bingx.clone(), "ETH-USDT".to_string()),
+                ticker("ETH-USDT", 3000.0, 3000.5, 1716000000100),
+            );
+        }
+
+        // Покупка лучше на BingX, продажа — на MEXC
+        let bbo = client.consolidated_bbo("BTCUSDT").expect("сводные цены");
+        assert_eq!(bbo.symbol, "BTCUSDT");
+        assert_eq!(
+            bbo.bid,
+            Some(BboQuote { exchange: bingx.clone(), price: 50000.5, time: 1716000000100 })
+        );
+        assert_eq!(
+            bbo.ask,
+            Some(BboQuote { exchange: swap.clone(), price: 50001.0, time: 1716000000000 })
+        );
+        assert_eq!(bbo.spread(), Some(0.5));
+
+        // Отключённая биржа не участвует
+        client.connection_states.insert(bingx, ConnectionState::Disconnected);
+        let bbo = client.consolidated_bbo("BTC_USDT").unwrap();
+        assert_eq!(bbo.bid.unwrap().exchange, swap);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_all_trades() {
+        let mut client = mock_ws_client(&[ExchangeType::BitmexSwap, ExchangeType::MexcSpot]);
+
+        client.subscribe_all_trades(&ExchangeType::BitmexSwap).await.unwrap();
+        assert_eq!(
+            client.clients[&ExchangeType::BitmexSwap].transport_subscriptions().unwrap(),
+            vec![r#"{"op":"subscribe","args":["trade"]}"#.to_string()]
+        );
+
+        let error = client.subscribe_all_trades(&ExchangeType::MexcSpot).await.unwrap_err();
+        assert!(matches!(error, ExchangeError::UnsupportedOperation(_)), "{}", error);
+        assert!(
+            client.clients[&ExchangeType::MexcSpot].transport_subscriptions().unwrap().is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_next_private_event_aggregates_fills() {
+        let spot = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(std::slice::from_ref(&spot))
+            .with_fill_aggregation(Duration::from_millis(100));
+        client.connect_all().await.unwrap();
+
+        let deal = |trade_id: u32, price: &str, quantity: &str| {
+            format!(
+                r#"{{"channel":"spot@private.deals.v3.api.pb","symbol":"BTCUSDT",
+                    "privateDeals":{{"price":"{price}","quantity":"{quantity}","tradeType":1,
+                    "isMaker":false,"tradeId":"T{trade_id}","orderId":"C02__1",
+                    "feeAmount":"0.1","feeCurrency":"USDT","time":{time}}}}}"#,
+                time = 1736417034000u64 + trade_id as u64
+            )
+        };
+        feed_mock(&client, &spot, &deal(1, "60000", "0.1"));
+        feed_mock(&client, &spot, &deal(2, "60100", "0.3"));
+        feed_mock(&client, &spot, &deal(3, "60200", "0.1"));
+
+        // Исполнения ждут окончания окна
+        assert_eq!(client.next_private_event().await.unwrap(), None);
+        tokio::time::sleep(Duration::from_millis(120)).await;
+
+        let Some(PrivateEvent::Fill(fill)) = client.next_private_event().await.unwrap() else {
+            panic!("ожидалось объединённое исполнение");
+        };
+        assert_eq!(fill.order_id, "C02__1");
+        assert!((fill.quantity - 0.5).abs() < 1e-9);
+        // (60000 * 0.1 + 60100 * 0.3 + 60200 * 0.1) / 0.5
+        assert!((fill.price - 60100.0).abs() < 1e-6);
+        assert!((fill.fee - 0.3).abs() < 1e-9);
+        assert_eq!(fill.trade_id, "T3");
+        assert_eq!(client.next_private_event().await.unwrap(), None);
     }
 
-    /// Проверить, подключена ли конкретная биржа
-    pub fn is_exchange_connected(&self, exchange_type: &ExchangeType) -> bool {
-        matches!(self.connection_states.get(exchange_type), Some(ConnectionState::Connected))
+    #[tokio::test]
+    async fn test_subscription_limit_rejects_extra_subscription() {
+        let swap = ExchangeType::MexcSwap;
+        let mut client =
+            mock_ws_client(std::slice::from_ref(&swap)).with_subscription_limit(swap.clone(), 2);
+        assert_eq!(client.subscription_limit(&ExchangeType::MexcSpot), Some(30));
+
+        client.subscribe_trades(&swap, "BTC_USDT").await.unwrap();
+        client.subscribe_ticker(&swap, "BTC_USDT").await.unwrap();
+        assert_eq!(client.subscription_count(&swap), 2);
+
+        let err = client
+            .subscribe_orderbook(&swap, "BTC_USDT", DepthMode::Incremental)
+            .await
+            .unwrap_err();
+        assert!(err.contains("лимит подписок"), "{}", err);
+        assert!(matches!(
+            client.check_subscription_limit(&swap, "orderbook", "BTC_USDT"),
+            Err(ExchangeError::SubscriptionLimitReached { limit: 2, .. })
+        ));
+        assert_eq!(client.subscription_count(&swap), 2);
+
+        // Повторная подписка на учтённую пару лимит не расходует
+        client.subscribe_trades(&swap, "BTC_USDT").await.unwrap();
     }
 
-    /// Получить все активные подписки
-    pub fn get_subscriptions(&self) -> Vec<(String, String)> {
-        self.subscription_manager.get_subscriptions()
+    #[tokio::test]
+    async fn test_standby_failover_without_gap() {
+        let exchange = ExchangeType::MexcSwap;
+        let mut client = mock_ws_client(std::slice::from_ref(&exchange));
+        client.connect_all().await.unwrap();
+        client.subscribe_trades(&exchange, "BTC_USDT").await.unwrap();
+        client
+            .attach_standby(exchange.clone(), "backup", WsClientWrapper::new_mock(exchange.clone()))
+            .await
+            .unwrap();
+        assert!(
+            client
+                .attach_standby(
+                    exchange.clone(),
+                    "backup",
+                    WsClientWrapper::new_mock(exchange.clone())
+                )
+                .await
+                .is_err()
+        );
+        assert_eq!(client.active_connection(&exchange), Some("primary"));
+
+        let feed_standby = |client: &CryptoWsClient, raw: &str| {
+            if let Some(WsClientWrapper::Mock { message_channel, .. }) =
+                client.standby_groups.get(&exchange).map(|group| &group.standby[0].1)
+            {
+                message_channel.push(raw);
+            }
+        };
+
+        let m1 = r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0,"t":1},"ts":1}"#;
+        let m2 = r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50001.0,"t":2},"ts":2}"#;
+
+        // Оба соединения получили одно и то же сообщение — потребитель видит его один раз
+        feed_mock(&client, &exchange, m1);
+        feed_standby(&client, m1);
+        let first = client.next_message().await.unwrap().expect("первое сообщение");
+        assert_eq!(first.data["data"]["p"], 50000.0);
+        assert!(client.next_message().await.unwrap().is_none());
+
+        // Основное соединение упало и пропустило m2, резервное его получило
+        client.clients.get_mut(&exchange).unwrap().disconnect().await.unwrap();
+        feed_standby(&client, m2);
+        let second = client.next_message().await.unwrap().expect("сообщение после failover");
+        assert_eq!(second.data["data"]["p"], 50001.0);
+        assert_eq!(client.active_connection(&exchange), Some("backup"));
+        assert!(client.next_message().await.unwrap().is_none());
+
+        println!("✅ Тест переключения на резервное соединение прошел успешно");
     }
 
-    /// Получить количество настроенных WebSocket клиентов
-    pub fn client_count(&self) -> usize {
-        self.clients.len()
+    fn mock_health(state: TransportState, last_ping: i64) -> HealthStatus {
+        HealthStatus {
+            state,
+            total_connections: 1,
+            successful_connections: 1,
+            failed_connections: 0,
+            reconnection_attempts: 0,
+            ping_failures: 0,
+            last_ping,
+            uptime: std::time::Duration::from_secs(60),
+            last_error: None,
+            avg_handshake_ms: 0.0,
+            last_handshake_ms: 0,
+            messages_received: 0,
+            bytes_received: 0,
+            messages_per_sec: 0.0,
+            parse_failures: 0,
+        }
     }
-}
 
-impl Default for CryptoWsClient {
-    fn default() -> Self {
-        Self::new()
+    #[tokio::test]
+    async fn test_unhealthy_exchanges() {
+        let mut client = mock_ws_client(&[
+            ExchangeType::MexcSpot,
+            ExchangeType::MexcSwap,
+            ExchangeType::BingxSpot,
+            ExchangeType::BingxSwap,
+        ]);
+        client.connect_all().await.unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        for (exchange_type, health) in [
+            (ExchangeType::MexcSpot, mock_health(TransportState::Connected, now)),
+            (ExchangeType::MexcSwap, mock_health(TransportState::Connected, now - 600)),
+            (
+                ExchangeType::BingxSpot,
+                mock_health(TransportState::Failed("Reconnection failed".to_string()), now),
+            ),
+        ] {
+            if let Some(WsClientWrapper::Mock { health: slot, .. }) =
+                client.clients.get_mut(&exchange_type)
+            {
+                *slot = Some(health);
+            }
+        }
+        client
+            .connection_states
+            .insert(ExchangeType::BingxSwap, ConnectionState::Error("timeout".to_string()));
+
+        assert!(!client.all_healthy());
+
+        let mut unhealthy: Vec<ExchangeType> =
+            client.unhealthy_exchanges().into_iter().map(|(exchange, _)| exchange).collect();
+        unhealthy.sort_by_key(|exchange| exchange.as_str());
+        assert_eq!(
+            unhealthy,
+            vec![ExchangeType::BingxSpot, ExchangeType::BingxSwap, ExchangeType::MexcSwap]
+        );
+
+        client.clients.retain(|exchange, _| *exchange == ExchangeType::MexcSpot);
+        assert!(client.all_healthy());
+
+        println!("✅ Тест агрегатора здоровья соединений прошел успешно");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+    #[tokio::test]
+    async fn test_pong_heartbeat_message() {
+        let mut client = mock_ws_client(&[ExchangeType::BingxSwap]).with_heartbeats(true);
+        client.connect_all().await.unwrap();
+
+        // Кадр, который транспорт отправляет на pong при включенных heartbeat
+        feed_mock(
+            &client,
+            &ExchangeType::BingxSwap,
+            r#"{"heartbeat":{"exchange":"bingx","latency_ms":42},"ts":1716000000123}"#,
+        );
+        let message = client.next_message().await.unwrap().expect("heartbeat");
+        assert_eq!(message.channel, ChannelType::Heartbeat);
+        assert_eq!(message.exchange, ExchangeType::BingxSwap);
+        assert_eq!(message.data["heartbeat"]["latency_ms"], 42);
+        assert_eq!(message.event_time, Some(1716000000123));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_emits_connection_status_message() {
+        let mut client =
+            mock_ws_client(&[ExchangeType::BingxSwap]).with_connection_status_messages(true);
+        client.connect_all().await.unwrap();
+        let set_transport_state = |client: &mut CryptoWsClient, state: TransportState| {
+            let now = chrono::Utc::now().timestamp();
+            if let Some(WsClientWrapper::Mock { health, .. }) =
+                client.clients.get_mut(&ExchangeType::BingxSwap)
+            {
+                *health = Some(mock_health(state, now));
+            }
+        };
+
+        // О начальном подключении сообщение не выдаётся
+        set_transport_state(&mut client, TransportState::Connected);
+        assert!(client.next_message().await.unwrap().is_none());
+
+        // Транспорт потерял соединение и переподключается
+        set_transport_state(&mut client, TransportState::Reconnecting);
+        let message = client.next_message().await.unwrap().expect("connection status");
+        assert_eq!(message.channel, ChannelType::ConnectionStatus);
+        assert_eq!(message.exchange, ExchangeType::BingxSwap);
+        assert_eq!(message.as_connection_state(), Some(ConnectionState::Reconnecting));
+        assert!(client.next_message().await.unwrap().is_none());
+
+        set_transport_state(&mut client, TransportState::Failed("timeout".to_string()));
+        let message = client.next_message().await.unwrap().expect("connection status");
+        assert_eq!(
+            message.as_connection_state(),
+            Some(ConnectionState::Error("timeout".to_string()))
+        );
+
+        set_transport_state(&mut client, TransportState::Connected);
+        let message = client.next_message().await.unwrap().expect("connection status");
+        assert_eq!(message.as_connection_state(), Some(ConnectionState::Connected));
+    }
 
     #[test]
-    fn test_extract_private_deals_channel() {
-        // Тестируем парсинг сообщения приватных сделок
-        let raw_data = json!({
-            "c": "spot@public.deals.v3.api",
-            "d": {
-                "price": "",
-                "quantity": "CLOREUSDT",
-                "symbol": "spot@private.deals.v3.api.pb",
-                "takerOrderSide": 0,
-                "time": 0
-            },
-            "t": 0
-        });
+    fn test_protobuf_message_carries_raw_bytes() {
+        // Кадр, который транспорт с включенным `set_raw_frames` передаёт для
+        // protobuf сделок: исходные байты в base64 рядом с декодированным JSON
+        let frame = r#"{"c":"spot@public.aggre.deals.v3.api.pb@100ms@BTCUSDT","d":{"deals":[{"S":1,"p":"50000.00","t":1736417034100,"v":"0.1"}],"e":"spot@public.aggre.deals.v3.api"},"s":"BTCUSDT","t":1736417034332,"raw_frame":"CgRzcG90EgM="}"#;
+        let message =
+            WsClientWrapper::parse_message_with_predicates(ExchangeType::MexcSpot, frame, &[])
+                .into_message()
+                .unwrap();
+        assert_eq!(message.channel, ChannelType::Trades);
+        assert_eq!(message.raw_bytes, Some(vec![0x0a, 0x04, b's', b'p', b'o', b't', 0x12, 0x03]));
+        assert!(message.data.get("raw_frame").is_none());
+        assert_eq!(message.as_trades().unwrap().len(), 1);
+
+        // Без поля raw_frame байты не сохраняются
+        let frame = frame.replace(r#","raw_frame":"CgRzcG90EgM=""#, "");
+        let message =
+            WsClientWrapper::parse_message_with_predicates(ExchangeType::MexcSpot, &frame, &[])
+                .into_message()
+                .unwrap();
+        assert_eq!(message.raw_bytes, None);
+    }
 
-        let result =
-            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+    #[tokio::test]
+    async fn test_undecodable_frame_surfaced_as_unknown() {
+        let mut client =
+            mock_ws_client(&[ExchangeType::MexcSpot]).with_undecodable_passthrough(true);
+        client.connect_all().await.unwrap();
 
-        match result {
-            Ok((channel_type, symbol)) => {
-                assert_eq!(channel_type, ChannelType::PrivateDeals);
-                assert_eq!(symbol, "CLOREUSDT");
-                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+        // Кадр, который транспорт передаёт вместо нераспознанного protobuf
+        feed_mock(
+            &client,
+            &ExchangeType::MexcSpot,
+            r#"{"undecodable_frame":{"exchange":"mexc","error":"Protocol Buffers decoding failed: buffer underflow","raw_frame":"CiD//v0="},"ts":1716000000123}"#,
+        );
+        let message = client.next_message().await.unwrap().expect("нераспознанный кадр");
+        assert_eq!(message.channel, ChannelType::Unknown);
+        assert_eq!(message.exchange, ExchangeType::MexcSpot);
+        assert_eq!(message.data["undecodable_frame"]["raw_frame"], "CiD//v0=");
+        assert_eq!(message.event_time, Some(1716000000123));
+    }
+
+    /// Логгер, запоминающий сообщения текущего потока, пока включён захват
+    struct CaptureLogger;
+
+    thread_local! {
+        static CAPTURED_LOGS: std::cell::RefCell<Option<Vec<String>>> =
+            const { std::cell::RefCell::new(None) };
+    }
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS.with(|logs| {
+                if let Some(logs) = logs.borrow_mut().as_mut() {
+                    logs.push(record.args().to_string());
+                }
+            });
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[tokio::test]
+    async fn test_quiet_verbosity_suppresses_per_message_logs() {
+        static LOGGER: CaptureLogger = CaptureLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+        let frame = r#"{"channel":"push.ticker","data":{"symbol":"BTC_USDT","lastPrice":64000.5},"symbol":"BTC_USDT","ts":1716000000123}"#;
+
+        async fn read_logged(client: &mut CryptoWsClient, frame: &str) -> Vec<String> {
+            feed_mock(client, &ExchangeType::MexcSwap, frame);
+            CAPTURED_LOGS.with(|logs| *logs.borrow_mut() = Some(Vec::new()));
+            assert!(client.next_message().await.unwrap().is_some());
+            CAPTURED_LOGS.with(|logs| logs.borrow_mut().take().unwrap())
+        }
+
+        crypto_ws_client::set_verbosity(crypto_ws_client::Verbosity::Quiet);
+        let quiet = read_logged(&mut client, frame).await;
+        crypto_ws_client::set_verbosity(crypto_ws_client::Verbosity::Normal);
+        let normal = read_logged(&mut client, frame).await;
+
+        let raw_frame_logged =
+            |logs: &[String]| logs.iter().any(|line| line.contains("получено сырое сообщение"));
+        assert!(!raw_frame_logged(&quiet), "{quiet:?}");
+        assert!(raw_frame_logged(&normal), "{normal:?}");
+    }
+
+    #[test]
+    fn test_mexc_execution_report_classified_as_orders() {
+        use crate::order::OrderStatus;
+
+        let message = WsClientWrapper::parse_message_static(
+            ExchangeType::MexcSpot,
+            r#"{"c":"spot@private.executionReport.v3.api","d":{"s":"BTCUSDT","i":"C02__413479413","S":"BUY",
+                "X":"PARTIALLY_FILLED","p":"60000","q":"0.002","z":"0.001","L":"59999.5"},
+                "s":"BTCUSDT","t":1716000000123}"#,
+        )
+        .into_message()
+        .unwrap();
+
+        assert_eq!(message.channel, ChannelType::Orders);
+        assert_eq!(message.symbol, "BTC_USDT");
+        let update = message.as_order_update().unwrap();
+        assert_eq!(update.order_id, "C02__413479413");
+        assert_eq!(update.side, "BUY");
+        assert_eq!(update.status, OrderStatus::PartiallyFilled);
+        assert_eq!(update.filled_quantity, 0.001);
+        assert_eq!(update.last_price, Some(59999.5));
+        assert_eq!(message.as_private_event(), Some(PrivateEvent::OrderUpdate(update)));
+    }
+
+    #[tokio::test]
+    async fn test_parse_failures_counted_per_exchange() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap, ExchangeType::BingxSwap]);
+        client.connect_all().await.unwrap();
+        let now = chrono::Utc::now().timestamp();
+        for health in client.clients.values_mut() {
+            if let WsClientWrapper::Mock { health, .. } = health {
+                *health = Some(mock_health(TransportState::Connected, now));
             }
-            Err(e) => {
-                panic!("❌ Тест не прошел: {}", e);
+        }
+        let parse_failures = |client: &CryptoWsClient, exchange: &ExchangeType| {
+            client.clients[exchange].health_status().unwrap().parse_failures
+        };
+
+        feed_mock(&client, &ExchangeType::MexcSwap, r#"{"id":1,"code":0,"msg":"success"}"#);
+        assert!(client.next_message().await.unwrap().is_none());
+        assert_eq!(parse_failures(&client, &ExchangeType::MexcSwap), 0);
+
+        feed_mock(&client, &ExchangeType::MexcSwap, "not a json frame");
+        assert!(client.next_message().await.unwrap().is_none());
+        assert_eq!(parse_failures(&client, &ExchangeType::MexcSwap), 1);
+        assert_eq!(parse_failures(&client, &ExchangeType::BingxSwap), 0);
+    }
+
+    #[tokio::test]
+    async fn test_supervisor_reconnects_failed_exchange() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_all().await.unwrap();
+        client.subscribe_trades(&exchange, "BTCUSDT").await.unwrap();
+        let client = Arc::new(AsyncMutex::new(client));
+
+        let policy = SupervisorPolicy {
+            check_interval: Duration::from_millis(10),
+            ..SupervisorPolicy::default()
+        };
+        let supervisor = CryptoWsClient::spawn_supervisor(&client, policy).await;
+
+        // Соединение выпало: транспорт потерял подписки
+        {
+            let mut client = client.lock().await;
+            client
+                .connection_states
+                .insert(exchange.clone(), ConnectionState::Error("connection reset".to_string()));
+            if let Some(WsClientWrapper::Mock { subscribe_calls, .. }) =
+                client.clients.get_mut(&exchange)
+            {
+                subscribe_calls.clear();
+            }
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !client.lock().await.is_exchange_connected(&exchange) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("супервизор не переподключил биржу");
+
+        match client.lock().await.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, .. }) => {
+                assert_eq!(subscribe_calls, &vec!["trades:BTCUSDT".to_string()]);
             }
+            _ => unreachable!(),
         }
+
+        // Удаление клиента останавливает супервизор
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(1), supervisor).await.unwrap().unwrap();
     }
 
-    #[test]
-    fn test_extract_public_deals_channel() {
-        // Тестируем парсинг обычных публичных сделок
-        let raw_data = json!({
-            "c": "spot@public.deals.v3.api@BTCUSDT",
-            "d": {
-                "symbol": "BTCUSDT",
-                "price": "50000.00",
-                "quantity": "0.1",
-                "time": 1640995200000_i64,
-                "takerOrderSide": 1
-            },
-            "t": 1640995200000_i64
-        });
+    #[tokio::test]
+    async fn test_run_task_exit_marks_client_stopped() {
+        let is_running = Arc::new(Mutex::new(true));
+        // run() вернулся сам, без сигнала завершения
+        spawn_run_task("Mock", async {}, is_running.clone(), watch::channel(false).1);
 
-        let result =
-            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while *is_running.lock().unwrap() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("is_running не сброшен после завершения run()");
+    }
 
-        match result {
-            Ok((channel_type, symbol)) => {
-                assert_eq!(channel_type, ChannelType::Trades);
-                assert_eq!(symbol, "BTC_USDT");
-                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+    #[tokio::test]
+    async fn test_supervisor_restarts_stopped_exchange() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(std::slice::from_ref(&exchange));
+        client.connect_all().await.unwrap();
+        let client = Arc::new(AsyncMutex::new(client));
+
+        // Фоновая задача завершилась, а состояние всё ещё Connected
+        let stop = || async {
+            let client = client.lock().await;
+            if let Some(WsClientWrapper::Mock { is_running, .. }) = client.clients.get(&exchange) {
+                *is_running.lock().unwrap() = false;
             }
-            Err(e) => {
-                panic!("❌ Тест не прошел: {}", e);
+        };
+        stop().await;
+        assert!(client.lock().await.is_exchange_connected(&exchange));
+        assert_eq!(client.lock().await.mark_stopped_exchanges(), vec![exchange.clone()]);
+        assert_eq!(
+            client.lock().await.disconnected_exchanges(),
+            vec![(exchange.clone(), ConnectionState::Error(RUN_TASK_STOPPED.to_string()))]
+        );
+
+        let policy = SupervisorPolicy {
+            check_interval: Duration::from_millis(10),
+            restart_stopped: Some(Duration::from_millis(30)),
+            ..SupervisorPolicy::default()
+        };
+        client.lock().await.connection_states.insert(exchange.clone(), ConnectionState::Connected);
+        stop().await;
+        let started = Instant::now();
+        let supervisor = CryptoWsClient::spawn_supervisor(&client, policy).await;
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                {
+                    let client = client.lock().await;
+                    let running = client.clients.get(&exchange).is_some_and(|c| c.is_connected());
+                    if running && client.is_exchange_connected(&exchange) {
+                        break;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("супервизор не перезапустил биржу");
+        assert!(started.elapsed() >= Duration::from_millis(30), "перезапуск до паузы");
+
+        drop(client);
+        tokio::time::timeout(Duration::from_secs(1), supervisor).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_when_ready_waits_for_connected() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_all().await.unwrap();
+        client.connection_states.insert(exchange.clone(), ConnectionState::Connecting);
+        let client = Arc::new(AsyncMutex::new(client));
+
+        let config = SubscriptionConfig {
+            channel: ChannelType::Trades,
+            symbol: "BTCUSDT".to_string(),
+            interval: None,
+        };
+
+        // Не подключилась за отведённое время: подписки нет
+        let err = CryptoWsClient::subscribe_when_ready(
+            &client,
+            &exchange,
+            &config,
+            Duration::from_millis(30),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("не подключилась"));
+
+        let connector = {
+            let client = Arc::clone(&client);
+            let exchange = exchange.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                client.lock().await.connection_states.insert(exchange, ConnectionState::Connected);
+            })
+        };
+        CryptoWsClient::subscribe_when_ready(&client, &exchange, &config, Duration::from_secs(1))
+            .await
+            .unwrap();
+        connector.await.unwrap();
+
+        match client.lock().await.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, .. }) => {
+                assert_eq!(subscribe_calls, &vec!["trades:BTCUSDT".to_string()]);
             }
+            _ => unreachable!(),
         }
     }
 
-    #[test]
-    fn test_extract_user_data_stream_private_deals() {
-        // Тестируем парсинг User Data Stream приватных сделок
-        let raw_data = json!({
-            "channel": "spot@private.deals.v3.api.pb",
-            "privateDeals": {
-                "price": "1.2345",
-                "quantity": "10.0",
-                "amount": "12.345",
-                "tradeType": 1,
-                "isMaker": false,
-                "isSelfTrade": false,
-                "tradeId": "987654321",
-                "clientOrderId": "my_order_123",
-                "orderId": "order_789",
-                "feeAmount": "0.0123",
-                "feeCurrency": "USDT",
-                "time": 1749495346968_i64
-            },
-            "sendTime": 1749495346968_i64
+    #[tokio::test]
+    async fn test_forward_to_delivers_messages_to_channel() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_all().await.unwrap();
+        for price in ["50000", "50001"] {
+            feed_mock(
+                &client,
+                &exchange,
+                &format!(
+                    r#"{{"c":"spot@public.deals.v3.api@BTCUSDT","d":{{"deals":[{{"S":1,"p":"{}","t":1,"v":"1"}}],"e":"spot@public.deals.v3.api"}},"s":"BTCUSDT","t":1}}"#,
+                    price
+                ),
+            );
+        }
+        let client = Arc::new(AsyncMutex::new(client));
+
+        // Канал на одно сообщение: второе ждёт, пока получатель заберёт первое
+        let (tx, mut rx) = async_mpsc::channel(1);
+        let forwarder = CryptoWsClient::forward_to(&client, tx).await;
+
+        for price in [50000.0, 50001.0] {
+            let message =
+                tokio::time::timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+            assert_eq!(message.symbol, "BTC_USDT");
+            assert_eq!(message.as_trades().unwrap()[0].price, price);
+        }
+
+        // Закрытие получателя останавливает пересылку
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(1), forwarder).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_while_reading_messages() {
+        let exchange = ExchangeType::MexcSwap;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+
+        let reader = client.message_reader();
+        let consumer = tokio::spawn(async move {
+            loop {
+                if let Some(message) = reader.next_message() {
+                    return message;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
         });
 
-        let result =
-            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data);
+        // Клиент подписывается, пока читатель в другой задаче ждёт сообщений
+        client.subscribe_trades(&exchange, "BTC_USDT").await.unwrap();
+        client.pause(&exchange).unwrap();
+        client.resume(&exchange).unwrap();
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0},"ts":1}"#,
+        );
 
-        match result {
-            Ok((channel_type, symbol)) => {
-                assert_eq!(channel_type, ChannelType::PrivateDeals);
-                // Symbol будет "UNKNOWN" так как privateDeals не содержит symbol
-                assert_eq!(symbol, "UNKNOWN");
-                println!("✅ Тест прошел: channel_type = {:?}, symbol = {}", channel_type, symbol);
+        let message =
+            tokio::time::timeout(Duration::from_secs(1), consumer).await.unwrap().unwrap();
+        assert_eq!(message.channel, ChannelType::Trades);
+        assert_eq!(message.symbol, "BTC_USDT");
+        assert_eq!(client.subscription_count(&exchange), 1);
+        assert!(client.next_message().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_orderbook_depth_modes() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.subscribe_orderbook(&exchange, "BTC_USDT", DepthMode::Incremental).await.unwrap();
+        client.subscribe_orderbook(&exchange, "ETH_USDT", DepthMode::Snapshot(20)).await.unwrap();
+
+        match client.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, subscriptions, .. }) => {
+                // Инкрементальный режим идёт через increase.depth транспорта
+                assert_eq!(subscribe_calls, &vec!["orderbook:BTC_USDT".to_string()]);
+                assert_eq!(
+                    subscriptions,
+                    &vec![
+                        r#"{"method":"SUBSCRIPTION","params":["spot@public.limit.depth.v3.api@ETHUSDT@20"]}"#
+                            .to_string()
+                    ]
+                );
             }
-            Err(e) => {
-                panic!("❌ Тест не прошел: {}", e);
+            _ => unreachable!(),
+        }
+        let subscriptions = client.get_subscriptions();
+        assert!(subscriptions.contains(&("orderbook".to_string(), "BTC_USDT".to_string())));
+        assert!(
+            subscriptions.contains(&("orderbook_snapshot".to_string(), "ETH_USDT".to_string()))
+        );
+
+        // MEXC публикует срезы только глубиной 5, 10 и 20
+        assert!(
+            client
+                .subscribe_orderbook(&exchange, "ETH_USDT", DepthMode::Snapshot(50))
+                .await
+                .is_err()
+        );
+
+        for (mode, raw) in [
+            (
+                DepthMode::Incremental,
+                r#"{"c":"spot@public.increase.depth.v3.api@BTCUSDT","d":{
+                    "asks":[{"p":"50001.00","v":"0"}],"bids":[],"r":"101"},"t":2}"#,
+            ),
+            (
+                DepthMode::Snapshot(20),
+                r#"{"c":"spot@public.limit.depth.v3.api@ETHUSDT@20","d":{
+                    "asks":[{"p":"3001.00","v":"0.5"}],"bids":[],"r":"100"},"s":"ETHUSDT","t":1}"#,
+            ),
+        ] {
+            let message =
+                WsClientWrapper::parse_message_with_predicates(exchange.clone(), raw, &[])
+                    .into_message()
+                    .unwrap();
+            assert_eq!(message.channel, mode.channel());
+            assert_eq!(
+                message.as_orderbook_update().unwrap().is_snapshot,
+                matches!(mode, DepthMode::Snapshot(_))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_limit_rejection_opens_shard() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]).with_auto_sharding(true);
+        client.set_subscription_ack_timeout(Duration::from_millis(500));
+        client.connect_all().await.unwrap();
+
+        // Основное соединение заполнено: биржа отклоняет подписку по лимиту
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"id":0,"code":0,"msg":"Not Subscribed successfully! [spot@public.deals.v3.api@ETHUSDT].  Reason： Subscription limit exceeded"}"#,
+        );
+        // Новый шард подтверждает повторную подписку, как только появляется в читателе
+        let reader = client.message_reader();
+        let shard_ack = async {
+            loop {
+                if let Some(shard) =
+                    reader.state().sources.get(&exchange).and_then(|s| s.connections.get(1))
+                {
+                    let ack = r#"{"id":0,"code":0,"msg":"spot@public.deals.v3.api@ETHUSDT"}"#;
+                    shard.buffer.push((0, ack.to_string()));
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        };
+        let (result, _) = tokio::join!(client.subscribe_trades(&exchange, "ETHUSDT"), shard_ack);
+        result.unwrap();
+
+        assert_eq!(client.shard_count(&exchange), 1);
+        assert_eq!(client.subscription_count(&exchange), 1);
+        let subscribe_calls = |client: &WsClientWrapper| match client {
+            WsClientWrapper::Mock { subscribe_calls, .. } => subscribe_calls.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(subscribe_calls(&client.clients[&exchange]), ["trades:ETHUSDT"]);
+        assert_eq!(subscribe_calls(&client.shards[&exchange][0]), ["trades:ETHUSDT"]);
+
+        // Сообщения шарда читаются вместе с основным соединением
+        if let WsClientWrapper::Mock { message_channel, .. } = &client.shards[&exchange][0] {
+            message_channel.push(r#"{"c":"spot@public.deals.v3.api@ETHUSDT","d":{"deals":[{"S":1,"p":"3000","t":1,"v":"1"}],"e":"spot@public.deals.v3.api"},"s":"ETHUSDT","t":1}"#);
+        }
+        let message = client.next_message().await.unwrap().expect("сделка с шарда");
+        assert_eq!(message.channel, ChannelType::Trades);
+        assert_eq!(message.symbol, "ETH_USDT");
+    }
+
+    #[tokio::test]
+    async fn test_open_shard_applies_reconnect_hook() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        let hook: ReconnectHook = Arc::new(|| vec!["auth".to_string()]);
+        client.set_on_reconnect(&exchange, hook).unwrap();
+
+        client.open_shard(&exchange).await.unwrap();
+
+        let hook_commands = |client: &WsClientWrapper| match client {
+            WsClientWrapper::Mock { on_reconnect, .. } => {
+                on_reconnect.lock().unwrap().as_ref().map(|hook| hook())
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(hook_commands(&client.clients[&exchange]), Some(vec!["auth".to_string()]));
+        assert_eq!(hook_commands(&client.shards[&exchange][0]), Some(vec!["auth".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_orderbook_synced_applies_diffs_after_snapshot() {
+        let exchange = ExchangeType::MexcSpot;
+        let diff = |version: u64, bid: &str| {
+            format!(
+                r#"{{"c":"spot@public.increase.depth.v3.api@BTCUSDT","d":{{"asks":[],
+                    "bids":[{{"p":"{}","v":"1"}}],"r":"{}","e":"spot@public.increase.depth.v3.api"}},
+                    "s":"BTCUSDT","t":{}}}"#,
+                bid, version, version
+            )
+        };
+        let snapshot = |version: u64| {
+            OrderBookUpdate::from_rest_json(
+                &ExchangeType::MexcSpot,
+                "BTCUSDT",
+                &format!(
+                    r#"{{"lastUpdateId":{},"bids":[["50000.00","2"]],"asks":[["50001.00","1"]],
+                        "timestamp":{}}}"#,
+                    version, version
+                ),
+            )
+        };
+        let mock_sink = |client: &CryptoWsClient| match &client.clients[&exchange] {
+            WsClientWrapper::Mock { message_channel, .. } => message_channel.frame_sink(),
+            _ => unreachable!(),
+        };
+
+        let mut client = mock_ws_client(std::slice::from_ref(&exchange));
+        client.connect_all().await.unwrap();
+        client.set_subscription_ack_timeout(Duration::from_millis(200));
+        // Изменения до снимка и сообщение другого канала
+        for (version, bid) in [(102, "49990.00"), (103, "49991.00"), (104, "49992.00")] {
+            feed_mock(&client, &exchange, &diff(version, bid));
+        }
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","d":{"deals":[
+                {"S":1,"p":"50000.50","t":1716000000456,"v":"0.5"}],
+                "e":"spot@public.deals.v3.api"},"s":"BTCUSDT","t":1716000000460}"#,
+        );
+
+        // Изменение 105 приходит, пока загружается снимок версии 103
+        let sink = mock_sink(&client);
+        let during_fetch = diff(105, "49993.00");
+        let manager = client
+            .subscribe_orderbook_synced(&exchange, "BTCUSDT", async move {
+                sink(monotonic_nanos(), during_fetch);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                snapshot(103)
+            })
+            .await
+            .unwrap();
+
+        // 102 и 103 уже вошли в снимок, 104 применено из буфера, 105 — после снимка
+        let book = manager.book("BTC_USDT").unwrap();
+        assert_eq!(book.version, Some(105));
+        assert_eq!(book.bids, vec![(50000.0, 2.0), (49993.0, 1.0), (49992.0, 1.0)]);
+        assert_eq!(book.asks, vec![(50001.0, 1.0)]);
+        let deferred = client.next_message().await.unwrap().unwrap();
+        assert_eq!(deferred.channel, ChannelType::Trades);
+
+        // Снимок старше первого накопленного изменения не согласуется с ним
+        let mut client = mock_ws_client(std::slice::from_ref(&exchange));
+        client.connect_all().await.unwrap();
+        feed_mock(&client, &exchange, &diff(102, "49990.00"));
+        let err = client
+            .subscribe_orderbook_synced(&exchange, "BTCUSDT", async { snapshot(100) })
+            .await
+            .unwrap_err();
+        assert!(err.contains("не согласуется"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_subscription_ack_timeout() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]).with_confirm_subscriptions(true);
+        client.set_subscription_ack_timeout(Duration::from_millis(50));
+        client.connect_all().await.unwrap();
+
+        // Биржа молча проигнорировала запрос
+        let err = client.subscribe_trades(&exchange, "BTCUSDT").await.unwrap_err();
+        assert_eq!(
+            err,
+            ExchangeError::SubscriptionTimeout {
+                exchange: exchange.clone(),
+                channel: "trades".to_string(),
+                symbol: "BTCUSDT".to_string(),
+            }
+            .to_string()
+        );
+        assert_eq!(client.subscription_count(&exchange), 0);
+
+        // Отказ биржи возвращается сразу
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"id":0,"code":0,"msg":"Not Subscribed successfully! [spot@public.deals.v3.api@ETHUSDT].  Reason： Blocked! "}"#,
+        );
+        let err = client.subscribe_trades(&exchange, "ETHUSDT").await.unwrap_err();
+        assert!(err.contains("отклонила"), "{}", err);
+
+        // Подтверждение извлекается из буфера, данные остаются для next_message
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","d":{"deals":[{"S":1,"p":"50000","t":1,"v":"1"}],"e":"spot@public.deals.v3.api"},"s":"BTCUSDT","t":1}"#,
+        );
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"id":0,"code":0,"msg":"spot@public.deals.v3.api@BTCUSDT"}"#,
+        );
+        client.subscribe_trades(&exchange, "BTCUSDT").await.unwrap();
+        assert_eq!(client.subscription_count(&exchange), 1);
+        let message = client.next_message().await.unwrap().expect("сделка после подтверждения");
+        assert_eq!(message.channel, ChannelType::Trades);
+    }
+
+    #[tokio::test]
+    async fn test_tagged_subscription_messages_carry_tags() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_all().await.unwrap();
+
+        let trades = |symbol: &str| SubscriptionConfig {
+            channel: ChannelType::Trades,
+            symbol: symbol.to_string(),
+            interval: None,
+        };
+        client
+            .subscribe_tagged(&exchange, &trades("BTCUSDT"), Some("arbitrage".to_string()))
+            .await
+            .unwrap();
+        client
+            .subscribe_tagged(&exchange, &trades("BTCUSDT"), Some("market_making".to_string()))
+            .await
+            .unwrap();
+        client.subscribe_tagged(&exchange, &trades("ETHUSDT"), None).await.unwrap();
+
+        // Вторая стратегия не отправляет повторную подписку на биржу
+        match client.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, .. }) => {
+                assert_eq!(
+                    subscribe_calls,
+                    &vec!["trades:BTCUSDT".to_string(), "trades:ETHUSDT".to_string()]
+                );
             }
+            _ => unreachable!(),
         }
+
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"c":"spot@public.deals.v3.api@BTCUSDT","d":{"deals":[{"S":1,"p":"50000","t":1,"v":"1"}],"e":"spot@public.deals.v3.api"},"s":"BTCUSDT","t":1}"#,
+        );
+        let message = client.next_message().await.unwrap().expect("сделка BTCUSDT");
+        assert_eq!(message.tags, vec!["arbitrage".to_string(), "market_making".to_string()]);
+
+        feed_mock(
+            &client,
+            &exchange,
+            r#"{"c":"spot@public.deals.v3.api@ETHUSDT","d":{"deals":[{"S":2,"p":"3000","t":2,"v":"1"}],"e":"spot@public.deals.v3.api"},"s":"ETHUSDT","t":2}"#,
+        );
+        let message = client.next_message().await.unwrap().expect("сделка ETHUSDT");
+        assert!(message.tags.is_empty());
     }
 
-    #[test]
-    fn test_private_message_detection_and_parsing() {
-        // Тестируем обнаружение и парсинг различных форматов приватных сообщений
+    #[tokio::test]
+    async fn test_collect_returns_count_messages_then_unsubscribes() {
+        let exchange = ExchangeType::MexcSpot;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        client.connect_all().await.unwrap();
 
-        // 1. User Data Stream формат с privateDeals
-        let user_data_stream_message = json!({
-            "channel": "spot@private.deals.v3.api.pb",
-            "symbol": "MXUSDT",
-            "sendTime": 1736417034332_i64,
-            "privateDeals": {
-                "price": "3.6962",
-                "quantity": "1",
-                "amount": "3.6962",
-                "tradeType": 2,
-                "tradeId": "505979017439002624X1",
-                "orderId": "C02__505979017439002624115",
-                "feeAmount": "0.0003998377369698171",
-                "feeCurrency": "MX",
-                "time": 1736417034280_i64
-            }
-        });
+        let deal = |symbol: &str, t: u64| {
+            format!(
+                r#"{{"c":"spot@public.deals.v3.api@{symbol}","d":{{"deals":[{{"S":1,"p":"50000","t":{t},"v":"1"}}],"e":"spot@public.deals.v3.api"}},"s":"{symbol}","t":{t}}}"#
+            )
+        };
+        feed_mock(&client, &exchange, &deal("ETHUSDT", 1));
+        for t in 2..5 {
+            feed_mock(&client, &exchange, &deal("BTCUSDT", t));
+        }
 
-        // Проверяем что это приватное сообщение
-        assert!(WsClientWrapper::is_private_message(
-            &ExchangeType::MexcSpot,
-            &user_data_stream_message
-        ));
+        let config = SubscriptionConfig {
+            channel: ChannelType::Trades,
+            symbol: "BTCUSDT".to_string(),
+            interval: None,
+        };
+        let messages = client.collect(&exchange, config, 2, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.channel == ChannelType::Trades));
+        assert_eq!(messages[0].data["t"], 2);
+        assert_eq!(messages[1].data["t"], 3);
+
+        match client.clients.get(&exchange) {
+            Some(WsClientWrapper::Mock { subscribe_calls, unsubscribe_calls, .. }) => {
+                assert_eq!(subscribe_calls, &vec!["trades:BTCUSDT".to_string()]);
+                assert_eq!(unsubscribe_calls, &vec!["trades:BTCUSDT".to_string()]);
+            }
+            _ => unreachable!(),
+        }
+        assert!(client.get_subscriptions().is_empty());
 
-        // Парсим приватное сообщение
-        let result = WsClientWrapper::parse_private_message(
-            ExchangeType::MexcSpot,
-            &user_data_stream_message,
-            &user_data_stream_message.to_string(),
-        );
+        // Сделка другой пары, прочитанная во время сбора, не потеряна
+        let message = client.next_message().await.unwrap().expect("сделка ETHUSDT");
+        assert_eq!(message.data["t"], 1);
+        let message = client.next_message().await.unwrap().expect("третья сделка BTCUSDT");
+        assert_eq!(message.data["t"], 4);
+    }
 
-        assert!(result.is_ok());
-        let ws_message = result.unwrap();
-        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
-        assert_eq!(ws_message.symbol, "MXUSDT");
-        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+    #[tokio::test]
+    async fn test_max_message_age_drops_stale_messages() {
+        let exchange = ExchangeType::MexcSwap;
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+        client.set_max_message_age(Duration::from_secs(1));
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let frame = |price: f64, ts: i64| {
+            format!(
+                r#"{{"channel":"push.deal","symbol":"BTC_USDT","data":{{"p":{}}},"ts":{}}}"#,
+                price, ts
+            )
+        };
+        feed_mock(&client, &exchange, &frame(50000.0, now - 5_000));
+        feed_mock(&client, &exchange, &frame(50001.0, now));
 
-        // 2. Старый смешанный формат где приватные сделки попадают в публичный канал
-        let mixed_format_message = json!({
-            "c": "spot@public.deals.v3.api",
-            "d": {
-                "price": "",
-                "quantity": "CLOREUSDT",
-                "symbol": "spot@private.deals.v3.api.pb",
-                "takerOrderSide": 0,
-                "time": 0
-            },
-            "t": 0
-        });
+        let message = client.next_message().await.unwrap().unwrap();
+        assert_eq!(message.data["data"]["p"], 50001.0);
+        assert_eq!(message.event_time, Some(now as u64));
+        assert_eq!(client.dropped_stale_messages(), 1);
+    }
 
-        // Проверяем что это приватное сообщение
-        assert!(WsClientWrapper::is_private_message(
-            &ExchangeType::MexcSpot,
-            &mixed_format_message
-        ));
+    #[tokio::test]
+    async fn test_exchange_iteration_follows_insertion_order() {
+        let order = vec![
+            ExchangeType::BingxSwap,
+            ExchangeType::MexcSpot,
+            ExchangeType::BingxSpot,
+            ExchangeType::MexcSwap,
+        ];
+        // Каждый клиент получает свой seed хеширования, поэтому порядок проверяется
+        // на нескольких экземплярах
+        for _ in 0..8 {
+            let mut client = mock_ws_client(&order);
+            client.connect_all().await.unwrap();
+
+            assert_eq!(client.get_connected_exchanges(), order);
+            client.sync_reader();
+            let read_order: Vec<_> = client.reader.state().sources.keys().cloned().collect();
+            assert_eq!(read_order, order);
+
+            // Повторно добавленная биржа переходит в конец
+            client.remove_exchange(&ExchangeType::MexcSpot).await.unwrap();
+            client
+                .clients
+                .insert(ExchangeType::MexcSpot, WsClientWrapper::new_mock(ExchangeType::MexcSpot));
+            client.connect_exchange(&ExchangeType::MexcSpot).await.unwrap();
+            assert_eq!(
+                client.get_connected_exchanges(),
+                [
+                    ExchangeType::BingxSwap,
+                    ExchangeType::BingxSpot,
+                    ExchangeType::MexcSwap,
+                    ExchangeType::MexcSpot,
+                ]
+            );
+        }
+    }
 
-        // Парсим приватное сообщение
-        let result = WsClientWrapper::parse_private_message(
+    #[tokio::test]
+    async fn test_disconnected_exchanges() {
+        let mut client = mock_ws_client(&[
             ExchangeType::MexcSpot,
-            &mixed_format_message,
-            &mixed_format_message.to_string(),
+            ExchangeType::MexcSwap,
+            ExchangeType::BingxSpot,
+        ]);
+        client.connect_all().await.unwrap();
+        assert!(client.disconnected_exchanges().is_empty());
+
+        client
+            .connection_states
+            .insert(ExchangeType::MexcSwap, ConnectionState::Error("timeout".to_string()));
+        client.connection_states.insert(ExchangeType::BingxSpot, ConnectionState::Reconnecting);
+
+        assert_eq!(
+            client.disconnected_exchanges(),
+            vec![
+                (ExchangeType::BingxSpot, ConnectionState::Reconnecting),
+                (ExchangeType::MexcSwap, ConnectionState::Error("timeout".to_string())),
+            ]
         );
+        assert_eq!(client.get_connected_exchanges(), vec![ExchangeType::MexcSpot]);
+    }
 
-        assert!(result.is_ok());
-        let ws_message = result.unwrap();
-        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
-        assert_eq!(ws_message.symbol, "CLOREUSDT");
-        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+    #[test]
+    fn test_extract_bingx_envelope_variants() {
+        let extract = |exchange_type: ExchangeType, data: Value| {
+            WsClientWrapper::extract_channel_and_symbol(&exchange_type, &data)
+        };
 
-        // 3. Обычное публичное сообщение НЕ должно быть приватным
-        let public_message = json!({
-            "c": "spot@public.deals.v3.api@BTCUSDT",
+        // Конверт с кодом ответа и объектом в data
+        let (channel_type, symbol) = extract(
+            ExchangeType::BingxSwap,
+            json!({
+                "code": 0,
+                "dataType": "BTC-USDT@depth",
+                "data": {"asks": [["50001.0", "0.5"]], "bids": [["50000.0", "1.2"]]},
+                "ts": 1716000000123_i64
+            }),
+        )
+        .unwrap();
+        assert_eq!(channel_type, ChannelType::Orderbook);
+        assert_eq!(symbol, "BTC_USDT");
+
+        // Конверт без кода с массивом в data
+        let (channel_type, symbol) = extract(
+            ExchangeType::BingxSpot,
+            json!({
+                "dataType": "ETH-USDT@trade",
+                "data": [{"p": "3000.5", "q": "0.1", "T": 1716000000123_i64, "m": true}]
+            }),
+        )
+        .unwrap();
+        assert_eq!(channel_type, ChannelType::Trades);
+        assert_eq!(symbol, "ETH_USDT");
+
+        // dataType внутри data
+        let (channel_type, symbol) = extract(
+            ExchangeType::BingxSpot,
+            json!({
+                "code": 0,
+                "dataType": "",
+                "data": {"dataType": "BTC-USDT@ticker", "data": {"c": "50000.5"}}
+            }),
+        )
+        .unwrap();
+        assert_eq!(channel_type, ChannelType::Ticker);
+        assert_eq!(symbol, "BTC_USDT");
+
+        // Ненулевой код — ошибка биржи, а не данные
+        let err = extract(
+            ExchangeType::BingxSwap,
+            json!({"code": 100400, "msg": "invalid dataType", "dataType": "BTC-USDT@depth"}),
+        )
+        .unwrap_err();
+        assert!(err.contains("100400"), "{}", err);
+    }
+
+    #[test]
+    fn test_extract_limit_depth_channel() {
+        // Частичный стакан MEXC: символ идет перед количеством уровней
+        let raw_data = json!({
+            "c": "spot@public.limit.depth.v3.api@BTCUSDT@20",
             "d": {
-                "symbol": "BTCUSDT",
-                "price": "50000.00",
-                "quantity": "0.1",
-                "time": 1640995200000_i64,
-                "takerOrderSide": 1
+                "asks": [{"p": "50001.00", "v": "0.5"}],
+                "bids": [{"p": "50000.00", "v": "1.2"}],
+                "e": "spot@public.limit.depth.v3.api",
+                "r": "3407459756"
             },
-            "t": 1640995200000_i64
+            "s": "BTCUSDT",
+            "t": 1661932660144_i64
         });
 
-        // Проверяем что это НЕ приватное сообщение
-        assert!(!WsClientWrapper::is_private_message(&ExchangeType::MexcSpot, &public_message));
-
-        println!("✅ Тест обнаружения и парсинга приватных сообщений прошел успешно");
+        let (channel_type, symbol) =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &raw_data)
+                .unwrap();
+        assert_eq!(channel_type, ChannelType::OrderbookSnapshot);
+        assert_eq!(symbol, "BTC_USDT");
+
+        // Инкрементальный стакан по-прежнему классифицируется как Orderbook
+        let increase = json!({
+            "c": "spot@public.increase.depth.v3.api@ETHUSDT",
+            "d": {"asks": [], "bids": [], "r": "1"},
+            "t": 1661932660144_i64
+        });
+        let (channel_type, symbol) =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSpot, &increase)
+                .unwrap();
+        assert_eq!(channel_type, ChannelType::Orderbook);
+        assert_eq!(symbol, "ETH_USDT");
+
+        let kline_symbol = WsClientWrapper::extract_mexc_symbol_from_channel(
+            "spot@public.kline.v3.api@BTCUSDT@Min15",
+        )
+        .unwrap();
+        assert_eq!(kline_symbol, "BTC_USDT");
+
+        println!("✅ Тест парсинга limit.depth прошел успешно");
     }
 
-    #[test]
-    fn test_user_data_stream_private_message_parsing() {
-        // Тестируем точный формат сообщения User Data Stream как у пользователя
-        let user_data_stream_message = r#"{
-            "channel": "spot@private.deals.v3.api.pb",
-            "symbol": "MXUSDT",
-            "sendTime": 1736417034332,
-            "privateDeals": {
-                "price": "3.6962",
-                "quantity": "1",
-                "amount": "3.6962",
-                "tradeType": 2,
-                "tradeId": "505979017439002624X1",
-                "orderId": "C02__505979017439002624115",
-                "feeAmount": "0.0003998377369698171",
-                "feeCurrency": "MX",
-                "time": 1736417034280
+    #[tokio::test]
+    async fn test_buffer_overflow_drops_oldest() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]).with_buffer_capacity(3);
+        client.connect_all().await.unwrap();
+
+        if let Some(WsClientWrapper::Mock { message_channel, .. }) =
+            client.clients.get(&ExchangeType::MexcSwap)
+        {
+            for i in 0..5 {
+                let raw = format!(
+                    r#"{{"channel":"push.deal","symbol":"BTC_USDT","data":{{"id":{}}},"ts":1}}"#,
+                    i
+                );
+                message_channel.push(raw);
             }
-        }"#;
+        }
 
-        // Парсим JSON
-        let data: Value = serde_json::from_str(user_data_stream_message).unwrap();
+        assert_eq!(client.dropped_messages(&ExchangeType::MexcSwap), 2);
+        assert_eq!(client.total_dropped_messages(), 2);
 
-        // Проверяем что это распознается как приватное сообщение
-        assert!(WsClientWrapper::is_private_message(&ExchangeType::MexcSpot, &data));
+        // В буфере остались только три самых свежих сообщения
+        let mut ids = Vec::new();
+        while let Some(message) = client.next_message().await.unwrap() {
+            ids.push(message.data["data"]["id"].as_i64().unwrap());
+        }
+        assert_eq!(ids, vec![2, 3, 4]);
 
-        // Парсим приватное сообщение
-        let result = WsClientWrapper::parse_private_message(
-            ExchangeType::MexcSpot,
-            &data,
-            user_data_stream_message,
-        );
+        println!("✅ Тест переполнения буфера прошел успешно");
+    }
 
-        assert!(result.is_ok());
-        let ws_message = result.unwrap();
-        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
-        assert_eq!(ws_message.symbol, "MXUSDT");
-        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+    #[tokio::test]
+    async fn test_reconcile_subscriptions_reports_divergence() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSpot]);
+        let exchange = ExchangeType::MexcSpot;
+        client.subscribe_trades(&exchange, "BTCUSDT").await.unwrap();
+        client.subscribe_orderbook(&exchange, "ETH_USDT", DepthMode::Incremental).await.unwrap();
+
+        // Транспорт восстановил только сделки и хранит подписку, о которой менеджер не знает
+        if let Some(WsClientWrapper::Mock { subscriptions, .. }) = client.clients.get_mut(&exchange)
+        {
+            subscriptions.push(
+                r#"{"method":"SUBSCRIPTION","params":["spot@public.deals.v3.api@BTCUSDT"]}"#
+                    .to_string(),
+            );
+            subscriptions.push(
+                r#"{"method":"SUBSCRIPTION","params":["spot@public.kline.v3.api@LTCUSDT@Min1"]}"#
+                    .to_string(),
+            );
+        }
 
-        // Проверяем что данные сохранены правильно
+        let diff = client.reconcile_subscriptions(&exchange, false).await.unwrap();
+        assert!(!diff.is_consistent());
         assert_eq!(
-            ws_message.data.get("channel").unwrap().as_str().unwrap(),
-            "spot@private.deals.v3.api.pb"
+            diff.missing_in_transport,
+            vec![("orderbook".to_string(), "ETH_USDT".to_string())]
         );
-        assert_eq!(ws_message.data.get("symbol").unwrap().as_str().unwrap(), "MXUSDT");
+        assert_eq!(diff.missing_in_manager, vec![("kline".to_string(), "LTCUSDT".to_string())]);
+        assert!(diff.repaired.is_empty());
 
-        let private_deals = ws_message.data.get("privateDeals").unwrap();
-        assert_eq!(private_deals.get("price").unwrap().as_str().unwrap(), "3.6962");
-        assert_eq!(private_deals.get("quantity").unwrap().as_str().unwrap(), "1");
-        assert_eq!(private_deals.get("tradeType").unwrap().as_i64().unwrap(), 2);
-        assert_eq!(private_deals.get("feeCurrency").unwrap().as_str().unwrap(), "MX");
+        let diff = client.reconcile_subscriptions(&exchange, true).await.unwrap();
+        assert_eq!(diff.repaired, vec![("orderbook".to_string(), "ETH_USDT".to_string())]);
 
-        println!("✅ Тест парсинга User Data Stream приватных сообщений прошел успешно");
+        assert!(client.reconcile_subscriptions(&ExchangeType::BingxSpot, false).await.is_err());
     }
 
     #[test]
-    fn test_parse_message_static_with_user_data_stream() {
-        // Тестируем полный pipeline parse_message_static с User Data Stream сообщением
-        let user_data_stream_message = r#"{
-            "channel": "spot@private.deals.v3.api.pb",
-            "symbol": "MXUSDT",
-            "sendTime": 1736417034332,
-            "privateDeals": {
-                "price": "3.6962",
-                "quantity": "1",
-                "amount": "3.6962",
-                "tradeType": 2,
-                "tradeId": "505979017439002624X1",
-                "orderId": "C02__505979017439002624115",
-                "feeAmount": "0.0003998377369698171",
-                "feeCurrency": "MX",
-                "time": 1736417034280
+    fn test_parse_transport_subscription_formats() {
+        let parse = WsClientWrapper::parse_transport_subscription;
+        assert_eq!(
+            parse(r#"{"method":"sub.deal","param":{"symbol":"BTC_USDT"}}"#),
+            Some(("trades".to_string(), "BTCUSDT".to_string()))
+        );
+        assert_eq!(
+            parse(r#"{"id":"1700000000000","dataType":"BTC-USDT@depth"}"#),
+            Some(("orderbook".to_string(), "BTCUSDT".to_string()))
+        );
+        assert_eq!(
+            parse(r#"{"method":"SUBSCRIPTION","params":["spot@private.account.v3.api.pb"]}"#),
+            Some(("balance".to_string(), "ACCOUNT".to_string()))
+        );
+        assert_eq!(
+            parse(
+                r#"{"method":"SUBSCRIPTION","params":["spot@public.miniTicker.v3.api@BTCUSDT@UTC+0"]}"#
+            ),
+            Some(("ticker".to_string(), "BTCUSDT".to_string()))
+        );
+        assert_eq!(parse(r#"{"method":"PING"}"#), None);
+    }
+
+    #[tokio::test]
+    async fn test_mexc_swap_kline() {
+        let raw_data = json!({
+            "channel": "push.kline",
+            "data": {
+                "a": 233.74026934364474,
+                "c": 6885,
+                "h": 6910.5,
+                "interval": "Min60",
+                "l": 6885,
+                "o": 6894.5,
+                "q": 1611754,
+                "symbol": "BTC_USDT",
+                "t": 1587448800
+            },
+            "symbol": "BTC_USDT",
+            "ts": 1587442022003_i64
+        });
+        let (channel_type, symbol) =
+            WsClientWrapper::extract_channel_and_symbol(&ExchangeType::MexcSwap, &raw_data)
+                .unwrap();
+        assert_eq!(channel_type, ChannelType::Kline);
+        assert_eq!(symbol, "BTC_USDT");
+
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.subscribe_kline(&ExchangeType::MexcSwap, "BTC_USDT", 3600).await.unwrap();
+        assert_eq!(client.get_subscriptions(), vec![("kline".to_string(), "BTC_USDT".to_string())]);
+        assert!(client.subscribe_kline(&ExchangeType::BingxSwap, "BTC-USDT", 60).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_frames() {
+        let path =
+            std::env::temp_dir().join(format!("crypto_client_replay_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+        client.start_recording(&path).unwrap();
+
+        let frames = [
+            r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0,"v":2},"ts":1}"#,
+            r#"{"channel":"pong","data":1587453241453}"#,
+            r#"{"channel":"push.ticker","symbol":"ETH_USDT","data":{"lastPrice":3000.5},"ts":2}"#,
+        ];
+        let mut live = Vec::new();
+        for frame in frames {
+            feed_mock(&client, &ExchangeType::MexcSwap, frame);
+            if let Some(message) = client.next_message().await.unwrap() {
+                live.push(message);
             }
-        }"#;
+        }
+        client.stop_recording();
+        assert_eq!(live.len(), 2);
 
-        let result =
-            WsClientWrapper::parse_message_static(ExchangeType::MexcSpot, user_data_stream_message);
+        let mut replay = crate::ReplayWsClient::open(&path).unwrap().with_speed(0.0);
+        let mut replayed = Vec::new();
+        while let Some(message) = replay.next_message().await.unwrap() {
+            replayed.push(message);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed.len(), live.len());
+        for (replayed, live) in replayed.iter().zip(&live) {
+            assert_eq!(replayed.exchange, live.exchange);
+            assert_eq!(replayed.channel, live.channel);
+            assert_eq!(replayed.symbol, live.symbol);
+            assert_eq!(replayed.data, live.data);
+        }
+    }
 
-        assert!(
-            result.is_ok(),
-            "parse_message_static должен успешно обработать User Data Stream сообщение"
-        );
+    #[test]
+    fn test_parse_outcome_distinguishes_service_frames() {
+        let ack = r#"{"id":1,"code":0,"msg":"success"}"#;
+        assert!(matches!(
+            WsClientWrapper::parse_message_static(ExchangeType::MexcSwap, ack),
+            ParseOutcome::Service
+        ));
 
-        let ws_message = result.unwrap();
-        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
-        assert_eq!(ws_message.symbol, "MXUSDT");
-        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+        let malformed = r#"{"channel":"push.deal","symbol":"BTC_USDT""#;
+        assert!(matches!(
+            WsClientWrapper::parse_message_static(ExchangeType::MexcSwap, malformed),
+            ParseOutcome::Error(e) if e.contains("JSON")
+        ));
 
-        println!("✅ Тест parse_message_static с User Data Stream прошел успешно");
-        println!(
-            "Результат: channel={:?}, symbol={}, exchange={:?}",
-            ws_message.channel, ws_message.symbol, ws_message.exchange
-        );
+        let trade = r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0},"ts":1}"#;
+        let message = WsClientWrapper::parse_message_static(ExchangeType::MexcSwap, trade)
+            .into_message()
+            .unwrap();
+        assert_eq!(message.channel, ChannelType::Trades);
     }
 
-    #[test]
-    fn test_user_data_stream_not_service_message() {
-        // Тестируем что User Data Stream сообщения НЕ классифицируются как служебные
-        let user_data_stream_message = json!({
-            "channel": "spot@private.deals.v3.api.pb",
-            "privateDeals": {
-                "amount": "1.19818703",
-                "clientOrderId": "1897e831-fe41-4118-b5cf-fa2e3ec5",
-                "feeAmount": "0.000599093515",
-                "feeCurrency": "USDT",
-                "isMaker": false,
-                "isSelfTrade": false,
-                "orderId": "C02__560846921632235520099",
-                "price": "0.017561",
-                "quantity": "68.23",
-                "time": 1749498562026_i64,
-                "tradeId": "560846921632235520X1",
-                "tradeType": 1
-            },
-            "sendTime": 1749498562045_i64,
-            "symbol": "CLOREUSDT"
+    #[tokio::test]
+    async fn test_custom_service_predicate_suppresses_frame() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
+        let ack = r#"{"channel":"rs.sub.kline","data":"success","ts":1587442022003}"#;
+
+        // Без предиката новый формат подтверждения не распознается как служебный
+        assert!(matches!(
+            WsClientWrapper::parse_message_static(ExchangeType::MexcSwap, ack),
+            ParseOutcome::Error(_)
+        ));
+
+        client.add_service_match(ExchangeType::MexcSwap, "data", json!("success"));
+        client.add_service_predicate(ExchangeType::MexcSwap, |data| {
+            data.get("channel").and_then(|c| c.as_str()).is_some_and(|c| c.starts_with("rs."))
         });
+        let predicates = client.reader.state().service_predicates[&ExchangeType::MexcSwap].clone();
+        assert!(matches!(
+            WsClientWrapper::parse_message_with_predicates(
+                ExchangeType::MexcSwap,
+                ack,
+                &predicates
+            ),
+            ParseOutcome::Service
+        ));
 
-        // Проверяем что это НЕ служебное сообщение
-        assert!(!WsClientWrapper::is_service_message(
-            &ExchangeType::MexcSpot,
-            &user_data_stream_message
-        ), "User Data Stream сообщения с privateDeals НЕ должны быть служебными");
+        // Обычные данные предикатами не затрагиваются
+        let trade = r#"{"channel":"push.deal","symbol":"BTC_USDT","data":{"p":50000.0},"ts":1}"#;
+        feed_mock(&client, &ExchangeType::MexcSwap, ack);
+        feed_mock(&client, &ExchangeType::MexcSwap, trade);
+        assert!(client.next_message().await.unwrap().is_none());
+        let message = client.next_message().await.unwrap().expect("сделка после служебного кадра");
+        assert_eq!(message.channel, ChannelType::Trades);
 
-        // Проверяем что это приватное сообщение
-        assert!(WsClientWrapper::is_private_message(
-            &ExchangeType::MexcSpot,
-            &user_data_stream_message
-        ), "User Data Stream сообщения с privateDeals должны быть приватными");
+        client.clear_service_predicates(&ExchangeType::MexcSwap);
+        assert!(!client.reader.state().service_predicates.contains_key(&ExchangeType::MexcSwap));
+    }
 
-        // Тестируем полный pipeline парсинга
-        let result = WsClientWrapper::parse_message_static(
-            ExchangeType::MexcSpot,
-            &user_data_stream_message.to_string(),
-        );
+    #[tokio::test]
+    async fn test_drop_stops_background_tasks() {
+        let mut client = mock_ws_client(&[ExchangeType::MexcSwap]);
+        client.connect_all().await.unwrap();
 
-        assert!(
-            result.is_ok(),
-            "User Data Stream сообщение должно успешно парситься"
-        );
+        // Фоновая задача и обёртка держат по ссылке на флаг запуска
+        let is_running = match client.clients.get(&ExchangeType::MexcSwap) {
+            Some(WsClientWrapper::Mock { is_running, .. }) => Arc::clone(is_running),
+            _ => unreachable!(),
+        };
+        assert!(*is_running.lock().unwrap());
+        assert_eq!(Arc::strong_count(&is_running), 3);
 
-        let ws_message = result.unwrap();
-        assert_eq!(ws_message.channel, ChannelType::PrivateDeals);
-        assert_eq!(ws_message.symbol, "CLOREUSDT");
-        assert_eq!(ws_message.exchange, ExchangeType::MexcSpot);
+        drop(client);
 
-        println!("✅ Тест классификации User Data Stream сообщений прошел успешно");
+        for _ in 0..100 {
+            if Arc::strong_count(&is_running) == 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(Arc::strong_count(&is_running), 1, "фоновая задача не остановлена");
+        assert!(!*is_running.lock().unwrap());
     }
 }
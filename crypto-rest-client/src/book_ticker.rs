@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Лучшие цены спроса и предложения по символу
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookTicker {
+    pub symbol: String,
+    pub bid_price: f64,
+    pub bid_qty: f64,
+    pub ask_price: f64,
+    pub ask_qty: f64,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Свеча (OHLCV) в едином для всех бирж формате
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    /// Время открытия свечи в миллисекундах Unix
+    pub open_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Объём в базовой валюте (для MEXC Swap — в контрактах)
+    pub volume: f64,
+}
@@ -6,6 +6,49 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct Error(pub String);
 
+/// Category of a REST error, detected from its message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The request did not reach the exchange or the connection failed
+    Network,
+    /// Missing API keys, invalid signature or HTTP 401/403
+    Auth,
+    /// The response could not be parsed
+    Parse,
+    /// The exchange rejected the request
+    Api,
+}
+
+const NETWORK_MARKERS: &[&str] =
+    &["request error", "error sending request", "timed out", "timeout", "connection refused"];
+const PARSE_MARKERS: &[&str] = &["json parse error", "error decoding response body"];
+const AUTH_MARKERS: &[&str] = &[
+    "(401",
+    "(403",
+    "401 unauthorized",
+    "403 forbidden",
+    "signature",
+    "api key",
+    "api ключ",
+    "apikey",
+];
+
+impl Error {
+    pub fn kind(&self) -> ErrorKind {
+        let message = self.0.to_lowercase();
+        let contains_any = |markers: &[&str]| markers.iter().any(|m| message.contains(m));
+        if contains_any(NETWORK_MARKERS) {
+            ErrorKind::Network
+        } else if contains_any(PARSE_MARKERS) {
+            ErrorKind::Parse
+        } else if contains_any(AUTH_MARKERS) {
+            ErrorKind::Auth
+        } else {
+            ErrorKind::Api
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -22,7 +65,7 @@ impl From<reqwest::Error> for Error {
 
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
-        Error(err.to_string())
+        Error(format!("JSON parse error: {}", err))
     }
 }
 
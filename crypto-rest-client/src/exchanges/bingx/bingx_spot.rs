@@ -64,6 +64,21 @@ impl BingxSpotRestClient {
         Ok(response)
     }
 
+    /// Создать ордер.
+    ///
+    /// Использует BingX Spot API v1 эндпоинт `/openApi/spot/v1/trade/order`. Параметры
+    /// подписываются HMAC-SHA256 вместе с `timestamp`, API ключ передаётся в заголовке
+    /// `X-BX-APIKEY`.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTC-USDT"
+    /// * `side` - Сторона ордера: "BUY" или "SELL"
+    /// * `quantity` - Количество базового актива
+    /// * `price` - Цена; обязательна для `LIMIT`
+    /// * `order_type` - Тип ордера: "LIMIT" или "MARKET"
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON ответ с информацией о созданном ордере
     pub async fn create_order(
         &self,
         symbol: &str,
@@ -71,25 +86,40 @@ impl BingxSpotRestClient {
         quantity: f64,
         price: Option<f64>,
         order_type: &str,
+    ) -> Result<String> {
+        self.create_order_with_client_id(symbol, side, quantity, price, order_type, None).await
+    }
+
+    /// Создать ордер с клиентским ID (`newClientOrderId`).
+    ///
+    /// То же, что [`create_order`](Self::create_order); по `client_order_id` ордер можно
+    /// найти через [`get_order_status`](Self::get_order_status), если ответ потерян.
+    pub async fn create_order_with_client_id(
+        &self,
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: Option<f64>,
+        order_type: &str,
+        client_order_id: Option<&str>,
     ) -> Result<String> {
         if self.api_key.is_none() || self.api_secret.is_none() {
             return Err(crate::error::Error("API key and secret are required".to_string()));
         }
 
         let endpoint = format!("{}/openApi/spot/v1/trade/order", BASE_URL);
-        let mut params = BTreeMap::new();
-
-        params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("side".to_string(), side.to_string());
-        params.insert("type".to_string(), order_type.to_string());
-        params.insert("quantity".to_string(), quantity.to_string());
-
-        if let Some(p) = price {
-            params.insert("price".to_string(), p.to_string());
+        let mut params = Self::create_order_params(
+            symbol,
+            side,
+            quantity,
+            price,
+            order_type,
+            Self::get_timestamp(),
+        )?;
+        if let Some(client_order_id) = client_order_id {
+            params.insert("newClientOrderId".to_string(), client_order_id.to_string());
         }
 
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
-
         let response = http_post_async(
             &endpoint,
             &mut params,
@@ -145,6 +175,36 @@ impl BingxSpotRestClient {
         Ok(response)
     }
 
+    /// Получить открытые ордера.
+    ///
+    /// Использует BingX Spot API v1 эндпоинт `/openApi/spot/v1/trade/openOrders`.
+    /// Требует API ключ и секретный ключ для аутентификации.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTC-USDT"; `None` — ордера по всем парам
+    pub async fn fetch_open_orders(&self, symbol: Option<&str>) -> Result<String> {
+        if self.api_key.is_none() || self.api_secret.is_none() {
+            return Err(crate::error::Error("API key and secret are required".to_string()));
+        }
+
+        let endpoint = format!("{}/openApi/spot/v1/trade/openOrders", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        if let Some(symbol) = symbol {
+            params.insert("symbol".to_string(), symbol.to_string());
+        }
+        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+
+        http_get_async(
+            &endpoint,
+            &mut params,
+            self.api_key.as_deref(),
+            self.api_secret.as_deref(),
+            self.proxy.as_deref(),
+        )
+        .await
+    }
+
     /// Отменить существующий ордер.
     ///
     /// Использует BingX Spot API v1 эндпоинт `/openApi/spot/v1/trade/cancel` для отмены ордера.
@@ -173,11 +233,7 @@ impl BingxSpotRestClient {
         }
 
         let endpoint = format!("{}/openApi/spot/v1/trade/cancel", BASE_URL);
-        let mut params = BTreeMap::new();
-
-        params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("orderId".to_string(), order_id.to_string());
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+        let mut params = Self::cancel_order_params(symbol, order_id, Self::get_timestamp())?;
 
         let response = http_post_async(
             &endpoint,
@@ -191,6 +247,140 @@ impl BingxSpotRestClient {
         Ok(response)
     }
 
+    /// Атомарно отменить ордер и выставить новый лимитный (cancel-replace).
+    ///
+    /// Новый ордер может быть на другую сторону и встаёт в конец очереди: BingX Spot не
+    /// умеет изменять ордер на месте. Между отменой и созданием нет окна без котировки;
+    /// если отмена не удалась, новый ордер не создаётся (`STOP_ON_FAILURE`).
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON ответ с `orderCancelResponse` и `orderOpenResponse`
+    pub async fn cancel_replace_order(
+        &self,
+        symbol: &str,
+        cancel_order_id: &str,
+        new_side: &str,
+        new_quantity: f64,
+        new_price: f64,
+    ) -> Result<String> {
+        if self.api_key.is_none() || self.api_secret.is_none() {
+            return Err(crate::error::Error("API key and secret are required".to_string()));
+        }
+
+        let endpoint = format!("{}/openApi/spot/v1/trade/order/cancelReplace", BASE_URL);
+        let mut params = Self::cancel_replace_params(
+            symbol,
+            cancel_order_id,
+            new_side,
+            new_quantity,
+            new_price,
+            Self::get_timestamp(),
+        )?;
+
+        http_post_async(
+            &endpoint,
+            &mut params,
+            self.api_key.as_deref(),
+            self.api_secret.as_deref(),
+            self.proxy.as_deref(),
+        )
+        .await
+    }
+
+    fn create_order_params(
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: Option<f64>,
+        order_type: &str,
+        timestamp: u64,
+    ) -> Result<BTreeMap<String, String>> {
+        if symbol.is_empty() {
+            return Err(crate::error::Error("symbol is required".to_string()));
+        }
+        let side = side.to_uppercase();
+        if side != "BUY" && side != "SELL" {
+            return Err(crate::error::Error(format!("Invalid order side: {}", side)));
+        }
+        let order_type = order_type.to_uppercase();
+        if order_type != "LIMIT" && order_type != "MARKET" {
+            return Err(crate::error::Error(format!("Invalid order type: {}", order_type)));
+        }
+        if quantity <= 0.0 {
+            return Err(crate::error::Error("Quantity must be positive".to_string()));
+        }
+        match price {
+            Some(price) if price <= 0.0 => {
+                return Err(crate::error::Error("Price must be positive".to_string()));
+            }
+            None if order_type == "LIMIT" => {
+                return Err(crate::error::Error("Price is required for LIMIT order".to_string()));
+            }
+            _ => {}
+        }
+
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.replace('/', "-"));
+        params.insert("side".to_string(), side);
+        params.insert("type".to_string(), order_type);
+        params.insert("quantity".to_string(), quantity.to_string());
+        if let Some(price) = price {
+            params.insert("price".to_string(), price.to_string());
+        }
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        Ok(params)
+    }
+
+    fn cancel_order_params(
+        symbol: &str,
+        order_id: &str,
+        timestamp: u64,
+    ) -> Result<BTreeMap<String, String>> {
+        if symbol.is_empty() {
+            return Err(crate::error::Error("symbol is required".to_string()));
+        }
+        if order_id.is_empty() {
+            return Err(crate::error::Error("order_id is required".to_string()));
+        }
+
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.replace('/', "-"));
+        params.insert("orderId".to_string(), order_id.to_string());
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        Ok(params)
+    }
+
+    fn cancel_replace_params(
+        symbol: &str,
+        order_id: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+        timestamp: u64,
+    ) -> Result<BTreeMap<String, String>> {
+        if order_id.is_empty() {
+            return Err(crate::error::Error("order_id is required".to_string()));
+        }
+        let side = side.to_uppercase();
+        if side != "BUY" && side != "SELL" {
+            return Err(crate::error::Error(format!("Invalid order side: {}", side)));
+        }
+        if !(quantity > 0.0 && price > 0.0) {
+            return Err(crate::error::Error("Quantity and price must be positive".to_string()));
+        }
+
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.replace('/', "-"));
+        params.insert("cancelReplaceMode".to_string(), "STOP_ON_FAILURE".to_string());
+        params.insert("cancelOrderId".to_string(), order_id.to_string());
+        params.insert("side".to_string(), side);
+        params.insert("type".to_string(), "LIMIT".to_string());
+        params.insert("quantity".to_string(), quantity.to_string());
+        params.insert("price".to_string(), price.to_string());
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        Ok(params)
+    }
+
     /// Get a Level2 snapshot of orderbook.
     ///
     /// For example: <https://open-api.bingx.com/openApi/spot/v1/market/depth?symbol=BTC-USDT&limit=100>
@@ -204,6 +394,20 @@ impl BingxSpotRestClient {
         http_get_async(&url, &mut params, None, None, None).await
     }
 
+    /// Get candlesticks.
+    ///
+    /// For example: <https://open-api.bingx.com/openApi/spot/v2/market/kline?symbol=BTC-USDT&interval=1m&limit=100>
+    pub async fn fetch_klines(symbol: &str, interval: &str, limit: u32) -> Result<String> {
+        let symbol = symbol.replace('/', "-");
+        let url = format!("{}/openApi/spot/v2/market/kline", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol);
+        params.insert("interval".to_string(), interval.to_string());
+        params.insert("limit".to_string(), limit.to_string());
+
+        http_get_async(&url, &mut params, None, None, None).await
+    }
+
     /// Get all available trading pairs
     pub async fn fetch_all_symbols() -> Result<Vec<Value>> {
         let url = format!("{}/openApi/spot/v1/common/symbols", BASE_URL);
@@ -215,3 +419,105 @@ impl BingxSpotRestClient {
         if let Some(data) = json["data"].as_array() { Ok(data.clone()) } else { Ok(Vec::new()) }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bingx_cancel_replace_signed_params() {
+        let params = BingxSpotRestClient::cancel_replace_params(
+            "BTC/USDT",
+            "1802040102451200000",
+            "buy",
+            0.002,
+            61000.0,
+            1700000000000,
+        )
+        .unwrap();
+
+        let payload =
+            params.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        assert_eq!(
+            payload,
+            "cancelOrderId=1802040102451200000&cancelReplaceMode=STOP_ON_FAILURE&price=61000\
+             &quantity=0.002&side=BUY&symbol=BTC-USDT&timestamp=1700000000000&type=LIMIT"
+        );
+        assert_eq!(
+            BingxSpotRestClient::sign_request("test_secret", &payload),
+            "8721e5d7c54e40c99543aa37152e9b77cdd66f094625fa8e98230faa56454775"
+        );
+
+        let params = |order_id, side, quantity| {
+            BingxSpotRestClient::cancel_replace_params("BTC-USDT", order_id, side, quantity, 1.0, 0)
+        };
+        assert!(params("", "BUY", 1.0).is_err());
+        assert!(params("1", "HOLD", 1.0).is_err());
+        assert!(params("1", "SELL", 0.0).is_err());
+    }
+
+    #[test]
+    fn test_bingx_create_and_cancel_order_signed_params() {
+        let params = BingxSpotRestClient::create_order_params(
+            "BTC/USDT",
+            "sell",
+            0.001,
+            Some(65000.5),
+            "limit",
+            1700000000000,
+        )
+        .unwrap();
+        let payload =
+            params.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        assert_eq!(
+            payload,
+            "price=65000.5&quantity=0.001&side=SELL&symbol=BTC-USDT&timestamp=1700000000000\
+             &type=LIMIT"
+        );
+        assert_eq!(
+            BingxSpotRestClient::sign_request("test_secret", &payload),
+            "7a3d0e41a3ddda3d23726b434ad476f33e1bd1457b27592540a6cdcf77fbda35"
+        );
+
+        let params = BingxSpotRestClient::cancel_order_params(
+            "BTC-USDT",
+            "1802040102451200000",
+            1700000000000,
+        )
+        .unwrap();
+        let payload =
+            params.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        assert_eq!(payload, "orderId=1802040102451200000&symbol=BTC-USDT&timestamp=1700000000000");
+
+        let create = |symbol, side, quantity, price, order_type| {
+            BingxSpotRestClient::create_order_params(symbol, side, quantity, price, order_type, 0)
+        };
+        let invalid = [
+            create("", "BUY", 1.0, Some(1.0), "LIMIT"),
+            create("BTC-USDT", "HOLD", 1.0, Some(1.0), "LIMIT"),
+            create("BTC-USDT", "BUY", 0.0, Some(1.0), "LIMIT"),
+            create("BTC-USDT", "BUY", 1.0, Some(-1.0), "LIMIT"),
+            create("BTC-USDT", "BUY", 1.0, None, "LIMIT"),
+            create("BTC-USDT", "BUY", 1.0, Some(1.0), "OCO"),
+            BingxSpotRestClient::cancel_order_params("BTC-USDT", "", 0),
+        ];
+        assert!(invalid.iter().all(|params| params.is_err()));
+        assert!(!create("BTC-USDT", "BUY", 0.5, None, "MARKET").unwrap().contains_key("price"));
+    }
+
+    #[tokio::test]
+    async fn test_bingx_create_and_cancel_order_require_keys() {
+        let client = BingxSpotRestClient::new(Some("key".to_string()), None, None);
+        let result = client.create_order("BTC-USDT", "BUY", 1.0, Some(1.0), "LIMIT").await;
+        assert!(result.unwrap_err().to_string().contains("API key and secret are required"));
+        let result = client.cancel_order("BTC-USDT", "1").await;
+        assert!(result.unwrap_err().to_string().contains("API key and secret are required"));
+    }
+
+    #[tokio::test]
+    async fn test_bingx_cancel_replace_requires_keys() {
+        let client = BingxSpotRestClient::new(None, None, None);
+        let result = client.cancel_replace_order("BTC-USDT", "1", "SELL", 1.0, 1.0).await;
+        assert!(result.unwrap_err().to_string().contains("API key and secret are required"));
+    }
+}
@@ -127,6 +127,33 @@ impl BingxSwapRestClient {
         Ok(response)
     }
 
+    /// Get open orders, optionally only for one symbol.
+    ///
+    /// Endpoint: `GET /openApi/swap/v2/trade/openOrders`, requires API keys.
+    pub async fn fetch_open_orders(&self, symbol: Option<&str>) -> Result<String> {
+        if self.api_key.is_none() || self.api_secret.is_none() {
+            return Err(crate::error::Error("API key and secret are required".to_string()));
+        }
+
+        let endpoint = format!("{}/openApi/swap/v2/trade/openOrders", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        if let Some(s) = symbol {
+            params.insert("symbol".to_string(), s.to_string());
+        }
+
+        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+
+        http_get_async(
+            &endpoint,
+            &mut params,
+            self.api_key.as_deref(),
+            self.api_secret.as_deref(),
+            self.proxy.as_deref(),
+        )
+        .await
+    }
+
     /// Get a Level2 snapshot of orderbook.
     ///
     /// For example: <https://open-api.bingx.com/openApi/swap/v2/quote/depth?symbol=BTC-USDT&limit=100>
@@ -140,6 +167,20 @@ impl BingxSwapRestClient {
         http_get_async(&url, &mut params, None, None, None).await
     }
 
+    /// Get candlesticks.
+    ///
+    /// For example: <https://open-api.bingx.com/openApi/swap/v3/quote/klines?symbol=BTC-USDT&interval=1m&limit=100>
+    pub async fn fetch_klines(symbol: &str, interval: &str, limit: u32) -> Result<String> {
+        let symbol = symbol.replace('/', "-");
+        let url = format!("{}/openApi/swap/v3/quote/klines", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol);
+        params.insert("interval".to_string(), interval.to_string());
+        params.insert("limit".to_string(), limit.to_string());
+
+        http_get_async(&url, &mut params, None, None, None).await
+    }
+
     /// Get open interest data for a specific symbol
     ///
     /// For example: <https://open-api.bingx.com/openApi/swap/v2/quote/openInterest?symbol=BTC-USDT>
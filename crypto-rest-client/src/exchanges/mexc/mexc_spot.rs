@@ -1,13 +1,124 @@
 use super::super::utils::http_get_async;
-use crate::error::Result;
-use hmac::{Hmac, Mac};
+use crate::book_ticker::BookTicker;
+use crate::candle::Candle;
+use crate::error::{ErrorKind, Result};
+use crate::request_options::RequestOptions;
+use crate::signer::{HmacSha256Signer, Signer};
+use crate::trade::{Trade, TradeSide};
+use futures_util::{Stream, StreamExt, stream};
 use reqwest;
 use serde_json::Value;
-use sha2::Sha256;
-use std::collections::BTreeMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const BASE_URL: &str = "https://api.mexc.com";
+/// Таймаут приватных запросов по умолчанию
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Сколько запросов отмены отправляется одновременно в `cancel_orders`
+const MAX_CONCURRENT_CANCELS: usize = 5;
+/// Код ошибки MEXC: timestamp запроса вне окна recvWindow
+const TIMESTAMP_OUTSIDE_RECV_WINDOW: i64 = 700003;
+/// Максимум свечей в одном ответе `/api/v3/klines`
+const MAX_KLINES_PER_REQUEST: usize = 1000;
+/// Пауза между страницами `klines_range`, чтобы не упираться в лимит запросов
+const KLINES_PAGE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Режим защиты от самоисполнения (STP) ордера MEXC Spot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StpMode {
+    /// Без защиты, параметр не отправляется
+    None,
+    /// Отменить встречный ордер мейкера
+    CancelMaker,
+    /// Отменить новый ордер тейкера
+    CancelTaker,
+    /// Отменить оба ордера
+    CancelBoth,
+}
+
+impl StpMode {
+    /// Значение `stpMode` MEXC API; для [`StpMode::None`] параметр не передаётся
+    fn as_str(self) -> Option<&'static str> {
+        match self {
+            StpMode::None => None,
+            StpMode::CancelMaker => Some("cancel_maker"),
+            StpMode::CancelTaker => Some("cancel_taker"),
+            StpMode::CancelBoth => Some("cancel_both"),
+        }
+    }
+}
+
+/// Срок действия лимитного ордера MEXC Spot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeInForce {
+    /// Действует до отмены
+    #[default]
+    Gtc,
+    /// Исполнить сразу доступный объём, остаток отменить
+    Ioc,
+    /// Исполнить целиком сразу или отменить
+    Fok,
+}
+
+impl TimeInForce {
+    /// Значение `timeInForce` MEXC API
+    fn as_str(self) -> &'static str {
+        match self {
+            TimeInForce::Gtc => "GTC",
+            TimeInForce::Ioc => "IOC",
+            TimeInForce::Fok => "FOK",
+        }
+    }
+
+    /// Тип ордера MEXC, которым задаётся срок действия: IOC и FOK в MEXC Spot —
+    /// отдельные типы, а не параметр лимитного ордера
+    fn order_type(self) -> &'static str {
+        match self {
+            TimeInForce::Gtc => "LIMIT",
+            TimeInForce::Ioc => "IMMEDIATE_OR_CANCEL",
+            TimeInForce::Fok => "FILL_OR_KILL",
+        }
+    }
+}
+
+impl std::str::FromStr for TimeInForce {
+    type Err = crate::error::Error;
+
+    /// Разобрать срок действия из строки без учёта регистра (`gtc`, `ioc`, `fok`)
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "GTC" => Ok(TimeInForce::Gtc),
+            "IOC" => Ok(TimeInForce::Ioc),
+            "FOK" => Ok(TimeInForce::Fok),
+            _ => Err(crate::error::Error(format!(
+                "Недопустимый срок действия ордера '{}': ожидается GTC, IOC или FOK",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::str::FromStr for StpMode {
+    type Err = crate::error::Error;
+
+    /// Разобрать режим из значения MEXC API (`cancel_maker`, `cancel_taker`,
+    /// `cancel_both`) или пустой строки
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "none" => Ok(StpMode::None),
+            "cancel_maker" => Ok(StpMode::CancelMaker),
+            "cancel_taker" => Ok(StpMode::CancelTaker),
+            "cancel_both" => Ok(StpMode::CancelBoth),
+            _ => Err(crate::error::Error(format!(
+                "Недопустимый режим STP '{}': ожидается cancel_maker, cancel_taker или cancel_both",
+                s
+            ))),
+        }
+    }
+}
 
 /// MEXC Spot market.
 ///
@@ -19,6 +130,10 @@ pub struct MexcSpotRestClient {
     _access_key: Option<String>,
     _secret_key: Option<String>,
     _proxy: Option<String>,
+    timeout: Duration,
+    signer: Option<Arc<dyn Signer>>,
+    /// Поправка локальных часов относительно сервера MEXC в миллисекундах
+    time_offset_ms: AtomicI64,
 }
 
 impl MexcSpotRestClient {
@@ -27,36 +142,194 @@ impl MexcSpotRestClient {
         secret_key: Option<String>,
         proxy: Option<String>,
     ) -> Self {
-        MexcSpotRestClient { _access_key: access_key, _secret_key: secret_key, _proxy: proxy }
+        let signer = secret_key
+            .as_ref()
+            .map(|secret| Arc::new(HmacSha256Signer::new(secret.as_str())) as Arc<dyn Signer>);
+        MexcSpotRestClient {
+            _access_key: access_key,
+            _secret_key: secret_key,
+            _proxy: proxy,
+            timeout: REQUEST_TIMEOUT,
+            signer,
+            time_offset_ms: AtomicI64::new(0),
+        }
+    }
+
+    /// Подписывать запросы собственной реализацией [`Signer`] вместо HMAC секретным ключом.
+    ///
+    /// Секретный ключ, переданный в [`Self::new`], после этого не используется.
+    pub fn with_signer(mut self, signer: Box<dyn Signer>) -> Self {
+        self._secret_key = None;
+        self.signer = Some(Arc::from(signer));
+        self
+    }
+
+    /// Клиент для разовых запросов с другим прокси или таймаутом.
+    ///
+    /// Ключи, подпись и поправка часов берутся из исходного клиента, незаданные поля
+    /// `options` — из его конфигурации. Сам исходный клиент не меняется.
+    ///
+    /// ```ignore
+    /// let order = client
+    ///     .with_options(RequestOptions::new().with_proxy("http://fast-proxy:3128"))
+    ///     .create_order("BTCUSDT", "BUY", 0.001, 50000.0, None, TimeInForce::Gtc, false)
+    ///     .await?;
+    /// ```
+    pub fn with_options(&self, options: RequestOptions) -> Self {
+        MexcSpotRestClient {
+            _access_key: self._access_key.clone(),
+            _secret_key: self._secret_key.clone(),
+            _proxy: options.proxy.or_else(|| self._proxy.clone()),
+            timeout: options.timeout.unwrap_or(self.timeout),
+            signer: self.signer.clone(),
+            time_offset_ms: AtomicI64::new(self.time_offset_ms.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Построитель HTTP клиента с прокси и таймаутом этого клиента
+    fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut client_builder = reqwest::Client::builder().timeout(self.timeout);
+
+        if let Some(proxy_url) = &self._proxy {
+            client_builder = client_builder.proxy(
+                reqwest::Proxy::all(proxy_url)
+                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
+            );
+        }
+
+        Ok(client_builder)
     }
 
     fn get_timestamp() -> u64 {
         SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
     }
 
-    /// Создать подпись для MEXC API
-    fn generate_signature(params: &BTreeMap<String, String>, secret: &str) -> Result<String> {
-        let mut params_str = String::new();
+    /// Timestamp для подписи с учётом поправки, полученной при синхронизации с сервером
+    fn timestamp(&self) -> u64 {
+        let offset = self.time_offset_ms.load(Ordering::Relaxed);
+        (Self::get_timestamp() as i64 + offset) as u64
+    }
+
+    /// Ошибка означает, что timestamp запроса не попал в recvWindow из-за расхождения
+    /// часов: биржа ответила HTTP 400 с кодом 700003. Текст ошибки целиком не
+    /// проверяется: в нём может быть URL запроса с ценой, суммой и подписью.
+    fn is_clock_skew_error(error: &crate::error::Error) -> bool {
+        Self::bad_request_code(error) == Some(TIMESTAMP_OUTSIDE_RECV_WINDOW)
+    }
+
+    /// Код MEXC из тела ответа HTTP 400 (`MEXC API error ... (400 Bad Request): {...}`).
+    /// Сетевые ошибки и таймауты кода не имеют.
+    fn bad_request_code(error: &crate::error::Error) -> Option<i64> {
+        let (_, body) =
+            error.0.strip_prefix("MEXC API error")?.split_once("(400 Bad Request): ")?;
+        serde_json::from_str::<Value>(body).ok()?["code"].as_i64()
+    }
+
+    /// Отправить подписанный запрос. При ошибке HTTP тело ответа сохраняется в тексте
+    /// ошибки, чтобы по коду MEXC можно было распознать расхождение часов.
+    async fn send_signed(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        params: &BTreeMap<String, String>,
+    ) -> Result<String> {
+        let api_key = self._access_key.as_deref().unwrap_or_default();
+        let full_url = format!("{}?{}", endpoint, Self::params_string(params));
+
+        let client = self
+            .client_builder()?
+            .build()
+            .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
+
+        let response = client
+            .request(method, &full_url)
+            .header("X-MEXC-APIKEY", api_key)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(|e| crate::error::Error(format!("Request error: {}", e)))?;
+
+        let status = response.status();
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| crate::error::Error(format!("Response text error: {}", e)))?;
 
-        for (key, value) in params {
-            params_str.push_str(&format!("{}={}&", key, value));
+        if !status.is_success() {
+            return Err(crate::error::Error(format!(
+                "MEXC API error ({}): {}",
+                status, response_text
+            )));
         }
 
-        // Удаляем последний &
-        if !params_str.is_empty() {
-            params_str.pop();
+        Ok(response_text)
+    }
+
+    /// Выполнить подписанный запрос; при ошибке расхождения часов один раз
+    /// синхронизироваться с сервером через [`Self::fetch_server_time`] и повторить.
+    async fn with_time_resync<F, Fut>(&self, request: F) -> Result<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        self.retry_after_resync(request, Self::fetch_server_time).await
+    }
+
+    async fn retry_after_resync<F, Fut, S, SFut>(
+        &self,
+        request: F,
+        server_time: S,
+    ) -> Result<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String>>,
+        S: FnOnce() -> SFut,
+        SFut: Future<Output = Result<u64>>,
+    {
+        match request().await {
+            Err(e) if Self::is_clock_skew_error(&e) => {
+                let local_before = Self::get_timestamp() as i64;
+                let server = server_time().await? as i64;
+                let local_after = Self::get_timestamp() as i64;
+                // Сервер отвечал где-то между отправкой и получением, берём середину
+                let offset = server - (local_before + local_after) / 2;
+                log::warn!(
+                    "MEXC: timestamp вне recvWindow, поправка часов {} мс, повторяем запрос",
+                    offset
+                );
+                self.time_offset_ms.store(offset, Ordering::Relaxed);
+                request().await
+            }
+            result => result,
         }
+    }
+
+    /// Строка параметров `key=value&...` в порядке сортировки ключей
+    fn params_string(params: &BTreeMap<String, String>) -> String {
+        params.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join("&")
+    }
+
+    /// Создать подпись для MEXC API
+    #[cfg(test)]
+    fn generate_signature(params: &BTreeMap<String, String>, secret: &str) -> Result<String> {
+        // MEXC подпись создается по формуле: HMAC-SHA256(secretKey, params_str) в lowercase hex
+        HmacSha256Signer::new(secret).sign(&Self::params_string(params))
+    }
 
-        // MEXC подпись создается по формуле: HMAC-SHA256(secretKey, params_str)
-        type HmacSha256 = Hmac<Sha256>;
-        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-            .map_err(|_| crate::error::Error("Failed to create HMAC".to_string()))?;
-        mac.update(params_str.as_bytes());
-        let result = mac.finalize();
-        // MEXC требует lowercase подпись
-        let signature = hex::encode(result.into_bytes()).to_lowercase();
+    /// Подписать параметры запроса подписчиком клиента
+    fn sign_params(&self, params: &BTreeMap<String, String>) -> Result<String> {
+        match &self.signer {
+            Some(signer) => signer.sign(&Self::params_string(params)),
+            None => Err(crate::error::Error("API secret or signer is required".to_string())),
+        }
+    }
 
-        Ok(signature)
+    /// Добавить timestamp (если его ещё нет) и подпись к параметрам запроса
+    fn sign_request(&self, params: &mut BTreeMap<String, String>) -> Result<()> {
+        params.entry("timestamp".to_string()).or_insert_with(|| self.timestamp().to_string());
+        let signature = self.sign_params(params)?;
+        params.insert("signature".to_string(), signature);
+        Ok(())
     }
 
     /// Создать лимитный ордер.
@@ -69,33 +342,174 @@ impl MexcSpotRestClient {
     /// * `side` - Сторона ордера: "BUY" или "SELL"
     /// * `quantity` - Количество для покупки/продажи (должно соответствовать минимальным требованиям биржи)
     /// * `price` - Цена лимитного ордера (должна соответствовать точности биржи)
+    /// * `stp` - Режим защиты от самоисполнения (`stpMode`), `None` - не передаётся
+    /// * `time_in_force` - Срок действия: GTC — обычный `LIMIT`, IOC и FOK отправляются
+    ///   типами `IMMEDIATE_OR_CANCEL` и `FILL_OR_KILL`
+    /// * `post_only` - Только мейкер: ордер отправляется типом `LIMIT_MAKER` и отклоняется
+    ///   биржей, если исполнился бы сразу
     ///
     /// # Возвращает
     /// * `Result<String>` - JSON ответ с информацией о созданном ордере
     ///
     /// # Ошибки
     /// * `Error` - Если отсутствуют API ключи
+    /// * `Error` - Если `post_only` задан вместе с IOC или FOK: такие ордера исполняются
+    ///   как тейкер
     /// * `Error` - Если параметры ордера не соответствуют требованиям биржи
     /// * `Error` - Если недостаточно баланса для ордера
     ///
     /// # Пример
     /// ```
     /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
-    /// let order = client.create_order("BTCUSDT", "BUY", 0.001, 50000.0).await?;
+    /// let order = client
+    ///     .create_order(
+    ///         "BTCUSDT",
+    ///         "BUY",
+    ///         0.001,
+    ///         50000.0,
+    ///         Some(StpMode::CancelMaker),
+    ///         TimeInForce::Gtc,
+    ///         true,
+    ///     )
+    ///     .await?;
     /// ```
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_order(
         &self,
         symbol: &str,
         side: &str,
         quantity: f64,
         price: f64,
+        stp: Option<StpMode>,
+        time_in_force: TimeInForce,
+        post_only: bool,
+    ) -> Result<String> {
+        self.create_order_with_client_id(
+            symbol,
+            side,
+            quantity,
+            price,
+            stp,
+            time_in_force,
+            post_only,
+            None,
+        )
+        .await
+    }
+
+    /// Создать лимитный ордер с клиентским ID (`newClientOrderId`).
+    ///
+    /// То же, что [`create_order`](Self::create_order); по `client_order_id` ордер можно
+    /// найти через [`query_order`](Self::query_order), если ответ на создание потерян.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_order_with_client_id(
+        &self,
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+        stp: Option<StpMode>,
+        time_in_force: TimeInForce,
+        post_only: bool,
+        client_order_id: Option<&str>,
+    ) -> Result<String> {
+        self.with_time_resync(|| {
+            self.create_order_once(
+                symbol,
+                side,
+                quantity,
+                price,
+                stp,
+                time_in_force,
+                post_only,
+                client_order_id,
+            )
+        })
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_order_once(
+        &self,
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+        stp: Option<StpMode>,
+        time_in_force: TimeInForce,
+        post_only: bool,
+        client_order_id: Option<&str>,
     ) -> Result<String> {
-        if self._access_key.is_none() || self._secret_key.is_none() {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
                 "API ключ и секретный ключ обязательны для создания ордера".to_string(),
             ));
         }
 
+        let api_key = self._access_key.as_ref().unwrap();
+
+        let url = format!("{}/api/v3/order", BASE_URL);
+        let mut params =
+            Self::order_params(symbol, side, quantity, price, stp, time_in_force, post_only)?;
+        if let Some(client_order_id) = client_order_id {
+            params.insert("newClientOrderId".to_string(), client_order_id.to_string());
+        }
+        params.insert("timestamp".to_string(), self.timestamp().to_string());
+
+        // Генерируем подпись
+        let signature = self.sign_params(&params)?;
+        params.insert("signature".to_string(), signature);
+
+        // Создаем query string из параметров
+        let mut query_params = Vec::new();
+        for (key, value) in params.iter() {
+            query_params.push(format!("{}={}", key, value));
+        }
+        let query_string = query_params.join("&");
+        let full_url = format!("{}?{}", url, query_string);
+
+        // Создаем HTTP клиент
+        let client = self
+            .client_builder()?
+            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36")
+            .build()
+            .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
+
+        // Отправляем POST запрос с параметрами в query string
+        let response = client
+            .post(&full_url)
+            .header("X-MEXC-APIKEY", api_key)
+            .header("Content-Type", "application/json")
+            .send()
+            .await
+            .map_err(|e| crate::error::Error(format!("Request error: {}", e)))?;
+
+        let status = response.status();
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| crate::error::Error(format!("Response text error: {}", e)))?;
+
+        if !status.is_success() {
+            return Err(crate::error::Error(format!(
+                "MEXC API error ({}): {}",
+                status, response_text
+            )));
+        }
+
+        Ok(response_text)
+    }
+
+    /// Параметры лимитного ордера (без timestamp и подписи)
+    fn order_params(
+        symbol: &str,
+        side: &str,
+        quantity: f64,
+        price: f64,
+        stp: Option<StpMode>,
+        time_in_force: TimeInForce,
+        post_only: bool,
+    ) -> Result<BTreeMap<String, String>> {
         // Валидация параметров
         if symbol.is_empty() {
             return Err(crate::error::Error(
@@ -117,6 +531,13 @@ impl MexcSpotRestClient {
             return Err(crate::error::Error("Цена должна быть больше 0".to_string()));
         }
 
+        if post_only && time_in_force != TimeInForce::Gtc {
+            return Err(crate::error::Error(format!(
+                "Ордер post-only не может иметь срок действия {}: он исполняется как тейкер",
+                time_in_force.as_str()
+            )));
+        }
+
         // Дополнительная валидация для MEXC API
         if quantity < 0.000001 {
             return Err(crate::error::Error("Количество слишком мало для MEXC API".to_string()));
@@ -133,10 +554,6 @@ impl MexcSpotRestClient {
             );
         }
 
-        let api_key = self._access_key.as_ref().unwrap();
-        let secret_key = self._secret_key.as_ref().unwrap();
-
-        let url = format!("{}/api/v3/order", BASE_URL);
         let mut params = BTreeMap::new();
 
         // Форматируем числовые значения правильно для MEXC API
@@ -154,63 +571,19 @@ impl MexcSpotRestClient {
 
         params.insert("symbol".to_string(), symbol.to_string());
         params.insert("side".to_string(), side.to_uppercase());
-        params.insert("type".to_string(), "LIMIT".to_string());
         params.insert("quantity".to_string(), formatted_quantity);
         params.insert("price".to_string(), formatted_price);
-        params.insert("timeInForce".to_string(), "GTC".to_string());
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
-
-        // Генерируем подпись
-        let signature = Self::generate_signature(&params, secret_key)?;
-        params.insert("signature".to_string(), signature);
-
-        // Создаем query string из параметров
-        let mut query_params = Vec::new();
-        for (key, value) in params.iter() {
-            query_params.push(format!("{}={}", key, value));
-        }
-        let query_string = query_params.join("&");
-        let full_url = format!("{}?{}", url, query_string);
-
-        // Создаем HTTP клиент
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36");
-
-        if let Some(proxy_url) = &self._proxy {
-            client_builder = client_builder.proxy(
-                reqwest::Proxy::all(proxy_url)
-                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
-            );
+        if post_only {
+            // LIMIT_MAKER сам по себе означает «только мейкер», timeInForce не передаётся
+            params.insert("type".to_string(), "LIMIT_MAKER".to_string());
+        } else {
+            params.insert("type".to_string(), time_in_force.order_type().to_string());
+            params.insert("timeInForce".to_string(), time_in_force.as_str().to_string());
         }
-
-        let client = client_builder
-            .build()
-            .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
-
-        // Отправляем POST запрос с параметрами в query string
-        let response = client
-            .post(&full_url)
-            .header("X-MEXC-APIKEY", api_key)
-            .header("Content-Type", "application/json")
-            .send()
-            .await
-            .map_err(|e| crate::error::Error(format!("Request error: {}", e)))?;
-
-        let status = response.status();
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| crate::error::Error(format!("Response text error: {}", e)))?;
-
-        if !status.is_success() {
-            return Err(crate::error::Error(format!(
-                "MEXC API error ({}): {}",
-                status, response_text
-            )));
+        if let Some(mode) = stp.and_then(StpMode::as_str) {
+            params.insert("stpMode".to_string(), mode.to_string());
         }
-
-        Ok(response_text)
+        Ok(params)
     }
 
     /// Получить баланс аккаунта для конкретного актива или все балансы.
@@ -235,8 +608,12 @@ impl MexcSpotRestClient {
     /// let all_balances = client.get_account_balance("").await?;
     /// ```
     pub async fn get_account_balance(&self, asset: &str) -> Result<String> {
+        self.with_time_resync(|| self.get_account_balance_once(asset)).await
+    }
+
+    async fn get_account_balance_once(&self, asset: &str) -> Result<String> {
         // Проверяем наличие API ключа и секрета
-        if self._access_key.is_none() || self._secret_key.is_none() {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
                 "API ключ и секретный ключ обязательны для получения баланса".to_string(),
             ));
@@ -245,16 +622,10 @@ impl MexcSpotRestClient {
         let endpoint = format!("{}/api/v3/account", BASE_URL);
         let mut params = BTreeMap::new();
 
-        // timestamp будет добавлен автоматически в http_get_async
+        // Подписываем здесь: подпись может выдавать внешний Signer, а не секретный ключ
+        self.sign_request(&mut params)?;
 
-        let response = http_get_async(
-            &endpoint,
-            &mut params,
-            self._access_key.as_deref(),
-            self._secret_key.as_deref(),
-            self._proxy.as_deref(),
-        )
-        .await?;
+        let response = self.send_signed(reqwest::Method::GET, &endpoint, &params).await?;
 
         // Если asset пустой, возвращаем полный JSON ответ
         if asset.is_empty() {
@@ -281,59 +652,184 @@ impl MexcSpotRestClient {
         Ok("0".to_string())
     }
 
-    /// Отменить существующий ордер.
+    /// Проверить API ключи лёгким подписанным запросом `/api/v3/account`.
     ///
-    /// Использует MEXC API v3 эндпоинт `/api/v3/order` с DELETE методом для отмены ордера.
-    /// Требует API ключ и секретный ключ для аутентификации.
+    /// Возвращает `Ok(true)`, если биржа приняла ключи, и `Ok(false)`, если ключи не заданы
+    /// или отклонены (неверный ключ, подпись, HTTP 401/403). Сетевые сбои и прочие ошибки
+    /// возвращаются как `Err`: по ним нельзя судить о ключах.
+    ///
+    /// # Пример
+    /// ```ignore
+    /// if !client.validate_credentials().await? {
+    ///     panic!("MEXC отклонила API ключи");
+    /// }
+    /// ```
+    pub async fn validate_credentials(&self) -> Result<bool> {
+        match self.get_account_balance("").await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::Auth => {
+                log::warn!("MEXC: API ключи не прошли проверку: {}", e);
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Получить ордер по клиентскому ID.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/order` (GET) с `origClientOrderId`.
+    /// Позволяет узнать, принят ли ордер, если ответ на его создание потерян.
     ///
     /// # Параметры
     /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
-    /// * `order_id` - Идентификатор ордера для отмены
+    /// * `client_order_id` - Клиентский ID, переданный при создании ордера
     ///
     /// # Возвращает
-    /// * `Result<String>` - JSON ответ с информацией об отмененном ордере
-    ///
-    /// # Ошибки
-    /// * `Error` - Если отсутствуют API ключи
-    /// * `Error` - Если ордер не найден или уже отменен
-    /// * `Error` - Если ордер нельзя отменить (например, уже исполнен)
-    ///
-    /// # Пример
-    /// ```
-    /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
-    /// let result = client.cancel_order("BTCUSDT", "12345678").await?;
-    /// ```
-    pub async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<String> {
-        if self._access_key.is_none() || self._secret_key.is_none() {
+    /// * `Result<String>` - JSON ордера; если ордера нет, ошибка API с кодом -2013
+    pub async fn query_order(&self, symbol: &str, client_order_id: &str) -> Result<String> {
+        self.with_time_resync(|| self.query_order_once(symbol, client_order_id)).await
+    }
+
+    async fn query_order_once(&self, symbol: &str, client_order_id: &str) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
-                "API ключ и секретный ключ обязательны для отмены ордера".to_string(),
+                "API ключ и секретный ключ обязательны для получения ордера".to_string(),
             ));
         }
 
-        // Валидация параметров
-        if symbol.is_empty() {
+        if symbol.is_empty() || client_order_id.is_empty() {
             return Err(crate::error::Error(
-                "Символ торговой пары не может быть пустым".to_string(),
+                "Символ торговой пары и ID ордера не могут быть пустыми".to_string(),
             ));
         }
 
-        if order_id.is_empty() {
-            return Err(crate::error::Error("ID ордера не может быть пустым".to_string()));
-        }
-
         let api_key = self._access_key.as_ref().unwrap();
-        let secret_key = self._secret_key.as_ref().unwrap();
 
-        let url = format!("{}/api/v3/order", BASE_URL);
         let mut params = BTreeMap::new();
-
         params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("orderId".to_string(), order_id.to_string());
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
-
-        // Генерируем подпись
-        let signature = Self::generate_signature(&params, secret_key)?;
-        params.insert("signature".to_string(), signature);
+        params.insert("origClientOrderId".to_string(), client_order_id.to_string());
+        self.sign_request(&mut params)?;
+        let full_url = format!("{}/api/v3/order?{}", BASE_URL, Self::params_string(&params));
+
+        let client = self
+            .client_builder()?
+            .build()
+            .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
+
+        // Тело ошибки сохраняется в тексте: по коду -2013 видно, что ордера нет
+        let response = client
+            .get(&full_url)
+            .header("X-MEXC-APIKEY", api_key)
+            .send()
+            .await
+            .map_err(|e| crate::error::Error(format!("Request error: {}", e)))?;
+
+        let status = response.status();
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| crate::error::Error(format!("Response text error: {}", e)))?;
+
+        if !status.is_success() {
+            return Err(crate::error::Error(format!(
+                "MEXC API error ({}): {}",
+                status, response_text
+            )));
+        }
+
+        Ok(response_text)
+    }
+
+    /// Получить открытые ордера по торговой паре.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/openOrders`; MEXC требует указать пару.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON массив открытых ордеров
+    pub async fn fetch_open_orders(&self, symbol: &str) -> Result<String> {
+        self.with_time_resync(|| self.fetch_open_orders_once(symbol)).await
+    }
+
+    async fn fetch_open_orders_once(&self, symbol: &str) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
+            return Err(crate::error::Error(
+                "API ключ и секретный ключ обязательны для получения открытых ордеров".to_string(),
+            ));
+        }
+
+        if symbol.is_empty() {
+            return Err(crate::error::Error(
+                "Символ торговой пары не может быть пустым".to_string(),
+            ));
+        }
+
+        let endpoint = format!("{}/api/v3/openOrders", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        self.sign_request(&mut params)?;
+
+        self.send_signed(reqwest::Method::GET, &endpoint, &params).await
+    }
+
+    /// Отменить существующий ордер.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/order` с DELETE методом для отмены ордера.
+    /// Требует API ключ и секретный ключ для аутентификации.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// * `order_id` - Идентификатор ордера для отмены
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON ответ с информацией об отмененном ордере
+    ///
+    /// # Ошибки
+    /// * `Error` - Если отсутствуют API ключи
+    /// * `Error` - Если ордер не найден или уже отменен
+    /// * `Error` - Если ордер нельзя отменить (например, уже исполнен)
+    ///
+    /// # Пример
+    /// ```
+    /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
+    /// let result = client.cancel_order("BTCUSDT", "12345678").await?;
+    /// ```
+    pub async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<String> {
+        self.with_time_resync(|| self.cancel_order_once(symbol, order_id)).await
+    }
+
+    async fn cancel_order_once(&self, symbol: &str, order_id: &str) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
+            return Err(crate::error::Error(
+                "API ключ и секретный ключ обязательны для отмены ордера".to_string(),
+            ));
+        }
+
+        // Валидация параметров
+        if symbol.is_empty() {
+            return Err(crate::error::Error(
+                "Символ торговой пары не может быть пустым".to_string(),
+            ));
+        }
+
+        if order_id.is_empty() {
+            return Err(crate::error::Error("ID ордера не может быть пустым".to_string()));
+        }
+
+        let api_key = self._access_key.as_ref().unwrap();
+
+        let url = format!("{}/api/v3/order", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), order_id.to_string());
+        params.insert("timestamp".to_string(), self.timestamp().to_string());
+
+        // Генерируем подпись
+        let signature = self.sign_params(&params)?;
+        params.insert("signature".to_string(), signature);
 
         // Создаем query string из параметров
         let mut query_params = Vec::new();
@@ -344,18 +840,9 @@ impl MexcSpotRestClient {
         let full_url = format!("{}?{}", url, query_string);
 
         // Создаем HTTP клиент
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36");
-
-        if let Some(proxy_url) = &self._proxy {
-            client_builder = client_builder.proxy(
-                reqwest::Proxy::all(proxy_url)
-                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
-            );
-        }
-
-        let client = client_builder
+        let client = self
+            .client_builder()?
+            .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36")
             .build()
             .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
 
@@ -384,6 +871,62 @@ impl MexcSpotRestClient {
         Ok(response_text)
     }
 
+    /// Отменить набор ордеров по их ID.
+    ///
+    /// MEXC не предоставляет пакетной отмены по списку ID, поэтому ордера отменяются
+    /// отдельными запросами `/api/v3/order`, не более [`MAX_CONCURRENT_CANCELS`]
+    /// одновременно. Ошибка отмены одного ордера не прерывает отмену остальных.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// * `order_ids` - ID ордеров для отмены
+    ///
+    /// # Возвращает
+    /// * `Result<Vec<Result<String>>>` - Результат отмены каждого ордера в порядке `order_ids`
+    ///
+    /// # Ошибки
+    /// * `Error` - Если отсутствуют API ключи
+    /// * `Error` - Если символ торговой пары пустой
+    ///
+    /// # Пример
+    /// ```
+    /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
+    /// let ids = vec!["12345678".to_string(), "12345679".to_string()];
+    /// let results = client.cancel_orders("BTCUSDT", &ids).await?;
+    /// ```
+    pub async fn cancel_orders(
+        &self,
+        symbol: &str,
+        order_ids: &[String],
+    ) -> Result<Vec<Result<String>>> {
+        if self._access_key.is_none() || self.signer.is_none() {
+            return Err(crate::error::Error(
+                "API ключ и секретный ключ обязательны для отмены ордера".to_string(),
+            ));
+        }
+
+        if symbol.is_empty() {
+            return Err(crate::error::Error(
+                "Символ торговой пары не может быть пустым".to_string(),
+            ));
+        }
+
+        Ok(Self::cancel_each(order_ids, |order_id| self.cancel_order(symbol, order_id)).await)
+    }
+
+    /// Выполнить `cancel` для каждого ID с ограничением параллельности, сохраняя порядок
+    async fn cancel_each<'a, F, Fut>(order_ids: &'a [String], cancel: F) -> Vec<Result<String>>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        stream::iter(order_ids)
+            .map(|order_id| cancel(order_id))
+            .buffered(MAX_CONCURRENT_CANCELS)
+            .collect()
+            .await
+    }
+
     /// Получить listen_key для WebSocket приватных данных.
     ///
     /// Использует MEXC API v3 эндпоинт `/api/v3/userDataStream` для создания listen_key,
@@ -409,23 +952,26 @@ impl MexcSpotRestClient {
     /// - Старые listen_key автоматически становятся недействительными
     /// - Для создания listen_key требуется подпись с timestamp
     pub async fn get_listen_key(&self) -> Result<String> {
-        if self._access_key.is_none() || self._secret_key.is_none() {
+        self.with_time_resync(|| self.get_listen_key_once()).await
+    }
+
+    async fn get_listen_key_once(&self) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
                 "API ключ и секретный ключ обязательны для получения listen_key".to_string(),
             ));
         }
 
         let api_key = self._access_key.as_ref().unwrap();
-        let secret_key = self._secret_key.as_ref().unwrap();
 
         let url = format!("{}/api/v3/userDataStream", BASE_URL);
 
         // Создаем параметры с timestamp для подписи
         let mut params = BTreeMap::new();
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+        params.insert("timestamp".to_string(), self.timestamp().to_string());
 
         // Генерируем подпись
-        let signature = Self::generate_signature(&params, secret_key)?;
+        let signature = self.sign_params(&params)?;
         params.insert("signature".to_string(), signature);
 
         // Создаем query string из параметров
@@ -437,18 +983,9 @@ impl MexcSpotRestClient {
         let full_url = format!("{}?{}", url, query_string);
 
         // Создаем HTTP клиент
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("office_bots/1.0");
-
-        if let Some(proxy_url) = &self._proxy {
-            client_builder = client_builder.proxy(
-                reqwest::Proxy::all(proxy_url)
-                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
-            );
-        }
-
-        let client = client_builder
+        let client = self
+            .client_builder()?
+            .user_agent("office_bots/1.0")
             .build()
             .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
 
@@ -513,7 +1050,11 @@ impl MexcSpotRestClient {
     /// - После продления, listen_key действует следующие 60 минут
     /// - Для продления listen_key требуется подпись с listenKey и timestamp
     pub async fn keep_alive_listen_key(&self, listen_key: &str) -> Result<String> {
-        if self._access_key.is_none() || self._secret_key.is_none() {
+        self.with_time_resync(|| self.keep_alive_listen_key_once(listen_key)).await
+    }
+
+    async fn keep_alive_listen_key_once(&self, listen_key: &str) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
                 "API ключ и секретный ключ обязательны для продления listen_key".to_string(),
             ));
@@ -524,17 +1065,16 @@ impl MexcSpotRestClient {
         }
 
         let api_key = self._access_key.as_ref().unwrap();
-        let secret_key = self._secret_key.as_ref().unwrap();
 
         let url = format!("{}/api/v3/userDataStream", BASE_URL);
 
         // Создаем параметры с listenKey и timestamp для подписи
         let mut params = BTreeMap::new();
         params.insert("listenKey".to_string(), listen_key.to_string());
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+        params.insert("timestamp".to_string(), self.timestamp().to_string());
 
         // Генерируем подпись
-        let signature = Self::generate_signature(&params, secret_key)?;
+        let signature = self.sign_params(&params)?;
         params.insert("signature".to_string(), signature);
 
         // Создаем query string из параметров
@@ -546,18 +1086,9 @@ impl MexcSpotRestClient {
         let full_url = format!("{}?{}", url, query_string);
 
         // Создаем HTTP клиент
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("office_bots/1.0");
-
-        if let Some(proxy_url) = &self._proxy {
-            client_builder = client_builder.proxy(
-                reqwest::Proxy::all(proxy_url)
-                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
-            );
-        }
-
-        let client = client_builder
+        let client = self
+            .client_builder()?
+            .user_agent("office_bots/1.0")
             .build()
             .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
 
@@ -615,7 +1146,11 @@ impl MexcSpotRestClient {
     /// - Используйте для явного завершения сессии
     /// - Для удаления listen_key требуется подпись с listenKey и timestamp
     pub async fn close_listen_key(&self, listen_key: &str) -> Result<String> {
-        if self._access_key.is_none() || self._secret_key.is_none() {
+        self.with_time_resync(|| self.close_listen_key_once(listen_key)).await
+    }
+
+    async fn close_listen_key_once(&self, listen_key: &str) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
             return Err(crate::error::Error(
                 "API ключ и секретный ключ обязательны для удаления listen_key".to_string(),
             ));
@@ -626,17 +1161,16 @@ impl MexcSpotRestClient {
         }
 
         let api_key = self._access_key.as_ref().unwrap();
-        let secret_key = self._secret_key.as_ref().unwrap();
 
         let url = format!("{}/api/v3/userDataStream", BASE_URL);
 
         // Создаем параметры с listenKey и timestamp для подписи
         let mut params = BTreeMap::new();
         params.insert("listenKey".to_string(), listen_key.to_string());
-        params.insert("timestamp".to_string(), Self::get_timestamp().to_string());
+        params.insert("timestamp".to_string(), self.timestamp().to_string());
 
         // Генерируем подпись
-        let signature = Self::generate_signature(&params, secret_key)?;
+        let signature = self.sign_params(&params)?;
         params.insert("signature".to_string(), signature);
 
         // Создаем query string из параметров
@@ -648,18 +1182,9 @@ impl MexcSpotRestClient {
         let full_url = format!("{}?{}", url, query_string);
 
         // Создаем HTTP клиент
-        let mut client_builder = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .user_agent("office_bots/1.0");
-
-        if let Some(proxy_url) = &self._proxy {
-            client_builder = client_builder.proxy(
-                reqwest::Proxy::all(proxy_url)
-                    .map_err(|e| crate::error::Error(format!("Proxy error: {}", e)))?,
-            );
-        }
-
-        let client = client_builder
+        let client = self
+            .client_builder()?
+            .user_agent("office_bots/1.0")
             .build()
             .map_err(|e| crate::error::Error(format!("Client build error: {}", e)))?;
 
@@ -688,82 +1213,599 @@ impl MexcSpotRestClient {
         Ok(response_text)
     }
 
-    /// Получить последние сделки.
+    /// Получить адрес для депозита монеты.
     ///
-    /// Использует MEXC API v3 эндпоинт `/api/v3/trades` для получения недавних сделок.
-    /// По умолчанию возвращает 500 сделок, максимум 1000.
+    /// Использует MEXC API v3 эндпоинт `/api/v3/capital/deposit/address`.
+    /// Требует API ключ и секретный ключ для аутентификации.
     ///
     /// # Параметры
-    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// * `coin` - Монета, например "USDT"
+    /// * `network` - Сеть для монет, доступных в нескольких сетях (например, "TRC20").
+    ///   Если `None`, биржа вернет адреса во всех сетях.
     ///
     /// # Возвращает
-    /// * `Result<String>` - JSON строка с данными сделок
+    /// * `Result<String>` - JSON ответ со списком адресов (address, memo, network)
+    ///
+    /// # Ошибки
+    /// * `Error` - Если отсутствуют API ключи
+    /// * `Error` - Если монета не указана
     ///
     /// # Пример
     /// ```
-    /// let trades = MexcSpotRestClient::fetch_trades("BTCUSDT").await?;
+    /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
+    /// let addresses = client.fetch_deposit_address("USDT", Some("TRC20")).await?;
     /// ```
-    pub async fn fetch_trades(symbol: &str) -> Result<String> {
-        let endpoint = format!("{}/api/v3/trades", BASE_URL);
-        let mut params = BTreeMap::new();
-        params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("limit".to_string(), "1000".to_string());
+    pub async fn fetch_deposit_address(&self, coin: &str, network: Option<&str>) -> Result<String> {
+        self.with_time_resync(|| self.fetch_deposit_address_once(coin, network)).await
+    }
 
-        http_get_async(&endpoint, &mut params, None, None, None).await
+    async fn fetch_deposit_address_once(
+        &self,
+        coin: &str,
+        network: Option<&str>,
+    ) -> Result<String> {
+        if self._access_key.is_none() || self.signer.is_none() {
+            return Err(crate::error::Error(
+                "API ключ и секретный ключ обязательны для получения адреса депозита".to_string(),
+            ));
+        }
+
+        let mut params = Self::deposit_address_params(coin, network)?;
+        let endpoint = format!("{}/api/v3/capital/deposit/address", BASE_URL);
+
+        self.sign_request(&mut params)?;
+        self.send_signed(reqwest::Method::GET, &endpoint, &params).await
     }
 
-    /// Получить снимок книги ордеров L2.
+    /// Параметры запроса адреса депозита (без timestamp и подписи)
+    fn deposit_address_params(
+        coin: &str,
+        network: Option<&str>,
+    ) -> Result<BTreeMap<String, String>> {
+        if coin.trim().is_empty() {
+            return Err(crate::error::Error("Монета не может быть пустой".to_string()));
+        }
+
+        let mut params = BTreeMap::new();
+        params.insert("coin".to_string(), coin.trim().to_uppercase());
+        if let Some(network) = network.filter(|n| !n.trim().is_empty()) {
+            params.insert("network".to_string(), network.trim().to_string());
+        }
+        Ok(params)
+    }
+
+    /// Вывести средства на внешний адрес.
     ///
-    /// Использует MEXC API v3 эндпоинт `/api/v3/depth` для получения данных OrderBook.
-    /// Лимит по умолчанию - 100, максимум 5000.
+    /// Использует MEXC API v3 эндпоинт `/api/v3/capital/withdraw`.
+    /// Требует API ключ и секретный ключ для аутентификации.
     ///
-    /// # Параметры  
-    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// Метод перемещает реальные средства, поэтому запрос отправляется только при
+    /// `confirm == true`. Все проверки выполняются до обращения к бирже.
+    ///
+    /// # Параметры
+    /// * `coin` - Монета, например "USDT"
+    /// * `address` - Адрес получателя
+    /// * `amount` - Сумма вывода (должна быть больше 0)
+    /// * `network` - Сеть вывода (например, "TRC20"), `None` - сеть по умолчанию для монеты
+    /// * `memo` - Memo/тег адреса для монет, которые его требуют
+    /// * `confirm` - Явное подтверждение вывода, должно быть `true`
     ///
     /// # Возвращает
-    /// * `Result<String>` - JSON строка с данными книги ордеров
+    /// * `Result<String>` - JSON ответ с идентификатором вывода (`id`)
+    ///
+    /// # Ошибки
+    /// * `Error` - Если вывод не подтвержден
+    /// * `Error` - Если отсутствуют API ключи
+    /// * `Error` - Если монета или адрес не указаны, либо сумма не больше 0
     ///
     /// # Пример
     /// ```
-    /// let snapshot = MexcSpotRestClient::fetch_l2_snapshot("BTCUSDT").await?;
+    /// let client = MexcSpotRestClient::new(Some(api_key), Some(secret_key), None);
+    /// let withdrawal =
+    ///     client.withdraw("USDT", "TXyz...", 10.0, Some("TRC20"), None, true).await?;
     /// ```
-    pub async fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
-        let endpoint = format!("{}/api/v3/depth", BASE_URL);
-        let mut params = BTreeMap::new();
-        params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("limit".to_string(), "5000".to_string());
-
-        http_get_async(&endpoint, &mut params, None, None, None).await
+    pub async fn withdraw(
+        &self,
+        coin: &str,
+        address: &str,
+        amount: f64,
+        network: Option<&str>,
+        memo: Option<&str>,
+        confirm: bool,
+    ) -> Result<String> {
+        self.with_time_resync(|| self.withdraw_once(coin, address, amount, network, memo, confirm))
+            .await
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    async fn withdraw_once(
+        &self,
+        coin: &str,
+        address: &str,
+        amount: f64,
+        network: Option<&str>,
+        memo: Option<&str>,
+        confirm: bool,
+    ) -> Result<String> {
+        if !confirm {
+            return Err(crate::error::Error(
+                "Вывод средств не подтвержден: передайте confirm = true".to_string(),
+            ));
+        }
 
-    #[tokio::test]
-    async fn test_mexc_account_balance_without_api_keys() {
-        let client = MexcSpotRestClient::new(None, None, None);
+        if self._access_key.is_none() || self.signer.is_none() {
+            return Err(crate::error::Error(
+                "API ключ и секретный ключ обязательны для вывода средств".to_string(),
+            ));
+        }
 
-        // Тест получения баланса конкретной монеты
-        let result = client.get_account_balance("USDT").await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("API ключ и секретный ключ обязательны"));
+        let mut params = Self::withdraw_params(coin, address, amount, network, memo)?;
+        let endpoint = format!("{}/api/v3/capital/withdraw", BASE_URL);
 
-        // Тест получения всех балансов
-        let result_all = client.get_account_balance("").await;
-        assert!(result_all.is_err());
-        assert!(
-            result_all.unwrap_err().to_string().contains("API ключ и секретный ключ обязательны")
-        );
+        self.sign_request(&mut params)?;
+        self.send_signed(reqwest::Method::POST, &endpoint, &params).await
     }
 
-    #[tokio::test]
-    async fn test_mexc_listen_key_methods_without_api_keys() {
-        let client = MexcSpotRestClient::new(None, None, None);
+    /// Параметры запроса на вывод (без timestamp и подписи)
+    fn withdraw_params(
+        coin: &str,
+        address: &str,
+        amount: f64,
+        network: Option<&str>,
+        memo: Option<&str>,
+    ) -> Result<BTreeMap<String, String>> {
+        if coin.trim().is_empty() {
+            return Err(crate::error::Error("Монета не может быть пустой".to_string()));
+        }
 
-        // Тест получения listen_key без API ключей
-        let result = client.get_listen_key().await;
+        if address.trim().is_empty() {
+            return Err(crate::error::Error("Адрес вывода не может быть пустым".to_string()));
+        }
+
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(crate::error::Error("Сумма вывода должна быть больше 0".to_string()));
+        }
+
+        let mut params = BTreeMap::new();
+        params.insert("coin".to_string(), coin.trim().to_uppercase());
+        params.insert("address".to_string(), address.trim().to_string());
+        params.insert("amount".to_string(), amount.to_string());
+        if let Some(network) = network.filter(|n| !n.trim().is_empty()) {
+            params.insert("netWork".to_string(), network.trim().to_string());
+        }
+        if let Some(memo) = memo.filter(|m| !m.trim().is_empty()) {
+            params.insert("memo".to_string(), memo.trim().to_string());
+        }
+        Ok(params)
+    }
+
+    /// Получить последние сделки.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/trades` для получения недавних сделок.
+    /// По умолчанию возвращает 500 сделок, максимум 1000.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON строка с данными сделок
+    ///
+    /// # Пример
+    /// ```
+    /// let trades = MexcSpotRestClient::fetch_trades("BTCUSDT").await?;
+    /// ```
+    pub async fn fetch_trades(symbol: &str) -> Result<String> {
+        let endpoint = format!("{}/api/v3/trades", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("limit".to_string(), "1000".to_string());
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Получить последние сделки в виде [`Trade`].
+    ///
+    /// То же, что [`Self::fetch_trades`], но с заданным количеством сделок и уже
+    /// разобранным ответом. Сторона сделки — сторона тейкера: `isBuyerMaker = true`
+    /// означает продажу.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// * `limit` - Количество сделок, от 1 до 1000
+    pub async fn fetch_trades_typed(symbol: &str, limit: u32) -> Result<Vec<Trade>> {
+        let endpoint = format!("{}/api/v3/trades", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("limit".to_string(), limit.clamp(1, 1000).to_string());
+
+        let response = http_get_async(&endpoint, &mut params, None, None, None).await?;
+        Self::parse_trades(symbol, &response)
+    }
+
+    fn parse_trades(symbol: &str, json: &str) -> Result<Vec<Trade>> {
+        let value: Value = serde_json::from_str(json)?;
+        let invalid = || crate::error::Error(format!("Invalid trades response: {}", json));
+        let number = |value: &Value| value.as_str().and_then(|s| s.parse::<f64>().ok());
+
+        value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|trade| {
+                let side = match trade["isBuyerMaker"].as_bool() {
+                    Some(true) => TradeSide::Sell,
+                    Some(false) => TradeSide::Buy,
+                    None => return Err(invalid()),
+                };
+                Ok(Trade {
+                    symbol: symbol.to_string(),
+                    trade_id: match &trade["id"] {
+                        Value::String(id) => Some(id.clone()),
+                        Value::Number(id) => Some(id.to_string()),
+                        _ => None,
+                    },
+                    price: number(&trade["price"]).ok_or_else(invalid)?,
+                    quantity: number(&trade["qty"]).ok_or_else(invalid)?,
+                    side,
+                    time: trade["time"].as_u64().ok_or_else(invalid)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Получить снимок книги ордеров L2.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/depth` для получения данных OrderBook.
+    /// Лимит по умолчанию - 100, максимум 5000.
+    ///
+    /// # Параметры  
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON строка с данными книги ордеров
+    ///
+    /// # Пример
+    /// ```
+    /// let snapshot = MexcSpotRestClient::fetch_l2_snapshot("BTCUSDT").await?;
+    /// ```
+    pub async fn fetch_l2_snapshot(symbol: &str) -> Result<String> {
+        let endpoint = format!("{}/api/v3/depth", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("limit".to_string(), "5000".to_string());
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Получить свечи торговой пары.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/klines`. Не требует API ключей.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    /// * `interval` - Интервал в формате MEXC: "1m", "5m", "15m", "30m", "60m", "4h", "1d", "1W", "1M"
+    /// * `limit` - Количество свечей, максимум 1000
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON массив свечей `[openTime, open, high, low, close, volume, closeTime, quoteVolume]`
+    ///
+    /// # Пример
+    /// ```
+    /// let klines = MexcSpotRestClient::fetch_klines("BTCUSDT", "1m", 100).await?;
+    /// ```
+    pub async fn fetch_klines(symbol: &str, interval: &str, limit: u32) -> Result<String> {
+        let endpoint = format!("{}/api/v3/klines", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("interval".to_string(), interval.to_string());
+        params.insert("limit".to_string(), limit.to_string());
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Получить свечи за период `[start, end]` (миллисекунды Unix) потоком.
+    ///
+    /// Период запрашивается страницами по 1000 свечей: каждая следующая страница
+    /// начинается сразу после последней полученной свечи, поэтому свечи на границах
+    /// страниц не повторяются. Страницы запрашиваются последовательно с паузой
+    /// 100 мс. Свечи отдаются по возрастанию времени открытия; после ошибки поток
+    /// завершается.
+    ///
+    /// # Пример
+    /// ```no_run
+    /// use crypto_rest_client::MexcSpotRestClient;
+    /// use futures_util::StreamExt;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // Сутки минутных свечей: 1440 свечей, две страницы
+    ///     let (start, end) = (1_700_000_000_000, 1_700_086_399_999);
+    ///     let candles: Vec<_> =
+    ///         MexcSpotRestClient::klines_range("BTCUSDT", "1m", start, end).collect().await;
+    /// }
+    /// ```
+    pub fn klines_range(
+        symbol: &str,
+        interval: &str,
+        start: u64,
+        end: u64,
+    ) -> impl Stream<Item = Result<Candle>> {
+        let step = Self::kline_interval_ms(interval);
+        let (symbol, interval) = (symbol.to_string(), interval.to_string());
+        Self::paginate_klines(
+            move |from, to| {
+                let endpoint = format!("{}/api/v3/klines", BASE_URL);
+                let mut params = BTreeMap::new();
+                params.insert("symbol".to_string(), symbol.clone());
+                params.insert("interval".to_string(), interval.clone());
+                params.insert("startTime".to_string(), from.to_string());
+                params.insert("endTime".to_string(), to.to_string());
+                params.insert("limit".to_string(), MAX_KLINES_PER_REQUEST.to_string());
+                async move {
+                    let response = http_get_async(&endpoint, &mut params, None, None, None).await?;
+                    Self::parse_klines(&response)
+                }
+            },
+            start,
+            end,
+            MAX_KLINES_PER_REQUEST,
+            step,
+        )
+    }
+
+    /// Длительность интервала свечей MEXC в миллисекундах.
+    ///
+    /// Месяц считается за 28 дней, а неизвестный интервал — за минуту: при
+    /// пагинации курсор лучше сдвинуть меньше интервала, чем пропустить свечи.
+    fn kline_interval_ms(interval: &str) -> u64 {
+        const MINUTE: u64 = 60_000;
+        match interval {
+            "5m" => 5 * MINUTE,
+            "15m" => 15 * MINUTE,
+            "30m" => 30 * MINUTE,
+            "60m" => 60 * MINUTE,
+            "4h" => 240 * MINUTE,
+            "1d" => 1440 * MINUTE,
+            "1W" => 7 * 1440 * MINUTE,
+            "1M" => 28 * 1440 * MINUTE,
+            _ => MINUTE,
+        }
+    }
+
+    /// Обойти период страницами `fetch(from, end)` не больше `page_limit` свечей.
+    /// Неполная или пустая страница означает, что период исчерпан. Если полная
+    /// страница целиком вне периода (например, одни повторы граничной свечи),
+    /// курсор сдвигается на `step` — длительность интервала.
+    fn paginate_klines<F, Fut>(
+        fetch: F,
+        start: u64,
+        end: u64,
+        page_limit: usize,
+        step: u64,
+    ) -> impl Stream<Item = Result<Candle>>
+    where
+        F: Fn(u64, u64) -> Fut,
+        Fut: Future<Output = Result<Vec<Candle>>>,
+    {
+        // Состояние: начало следующей страницы, свечи текущей страницы, период исчерпан
+        stream::unfold(
+            (start, VecDeque::new(), false, fetch),
+            move |(mut cursor, mut pending, mut exhausted, fetch)| async move {
+                loop {
+                    if let Some(candle) = pending.pop_front() {
+                        return Some((Ok(candle), (cursor, pending, exhausted, fetch)));
+                    }
+                    if exhausted || cursor > end {
+                        return None;
+                    }
+                    if cursor > start {
+                        tokio::time::sleep(KLINES_PAGE_INTERVAL).await;
+                    }
+
+                    let mut page = match fetch(cursor, end).await {
+                        Ok(page) => page,
+                        Err(e) => return Some((Err(e), (cursor, pending, true, fetch))),
+                    };
+                    exhausted = page.len() < page_limit;
+                    page.sort_by_key(|candle| candle.open_time);
+                    pending = page
+                        .into_iter()
+                        .filter(|candle| (cursor..=end).contains(&candle.open_time))
+                        .collect();
+                    match pending.back() {
+                        Some(last) => cursor = last.open_time + 1,
+                        // Курсор стоит через 1 мс после последней свечи, следующая
+                        // свеча открывается через `step`
+                        None => cursor += step - 1,
+                    }
+                }
+            },
+        )
+    }
+
+    /// Разобрать ответ `/api/v3/klines`: `[openTime, open, high, low, close, volume, ...]`
+    fn parse_klines(json: &str) -> Result<Vec<Candle>> {
+        let value: Value = serde_json::from_str(json)?;
+        let invalid = || crate::error::Error(format!("Invalid klines response: {}", json));
+        let number = |value: &Value| match value {
+            Value::String(s) => s.parse::<f64>().ok(),
+            value => value.as_f64(),
+        };
+
+        value
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|row| {
+                Ok(Candle {
+                    open_time: row[0].as_u64().ok_or_else(invalid)?,
+                    open: number(&row[1]).ok_or_else(invalid)?,
+                    high: number(&row[2]).ok_or_else(invalid)?,
+                    low: number(&row[3]).ok_or_else(invalid)?,
+                    close: number(&row[4]).ok_or_else(invalid)?,
+                    volume: number(&row[5]).ok_or_else(invalid)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Получить среднюю цену торговой пары.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/avgPrice`. Дешевле полного снимка
+    /// книги ордеров, когда нужна только опорная цена. Не требует API ключей.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" (без подчеркивания)
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON строка вида `{"mins":5,"price":"9.35751834"}`
+    ///
+    /// # Пример
+    /// ```
+    /// let avg = MexcSpotRestClient::fetch_avg_price("BTCUSDT").await?;
+    /// ```
+    pub async fn fetch_avg_price(symbol: &str) -> Result<String> {
+        let endpoint = format!("{}/api/v3/avgPrice", BASE_URL);
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Получить среднюю цену торговой пары в виде числа.
+    ///
+    /// То же, что [`Self::fetch_avg_price`], но возвращает уже разобранную цену.
+    pub async fn fetch_avg_price_f64(symbol: &str) -> Result<f64> {
+        Self::parse_avg_price(&Self::fetch_avg_price(symbol).await?)
+    }
+
+    fn parse_avg_price(json: &str) -> Result<f64> {
+        let value: Value = serde_json::from_str(json)?;
+        value["price"]
+            .as_str()
+            .and_then(|price| price.parse::<f64>().ok())
+            .ok_or_else(|| crate::error::Error(format!("Invalid avgPrice response: {}", json)))
+    }
+
+    /// Получить лучшие цены спроса и предложения.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/ticker/bookTicker`. Легче, чем
+    /// `/api/v3/ticker/24hr`, поэтому подходит для снимка по многим символам сразу.
+    ///
+    /// # Параметры
+    /// * `symbol` - Торговая пара в формате "BTCUSDT" или `None` для всех пар
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON объект для одной пары или массив для всех пар
+    pub async fn fetch_book_ticker(symbol: Option<&str>) -> Result<String> {
+        let endpoint = format!("{}/api/v3/ticker/bookTicker", BASE_URL);
+        let mut params = BTreeMap::new();
+        if let Some(symbol) = symbol {
+            params.insert("symbol".to_string(), symbol.to_string());
+        }
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Получить лучшие цены спроса и предложения в виде [`BookTicker`].
+    ///
+    /// То же, что [`Self::fetch_book_ticker`], но с уже разобранным ответом; для
+    /// одной пары возвращается вектор из одного элемента.
+    pub async fn fetch_book_ticker_typed(symbol: Option<&str>) -> Result<Vec<BookTicker>> {
+        Self::parse_book_ticker(&Self::fetch_book_ticker(symbol).await?)
+    }
+
+    fn parse_book_ticker(json: &str) -> Result<Vec<BookTicker>> {
+        let value: Value = serde_json::from_str(json)?;
+        let invalid = || crate::error::Error(format!("Invalid bookTicker response: {}", json));
+        let number = |value: &Value| value.as_str().and_then(|s| s.parse::<f64>().ok());
+
+        let tickers = match &value {
+            Value::Array(tickers) => tickers.iter().collect(),
+            Value::Object(_) => vec![&value],
+            _ => return Err(invalid()),
+        };
+        tickers
+            .into_iter()
+            .map(|ticker| {
+                Ok(BookTicker {
+                    symbol: ticker["symbol"].as_str().ok_or_else(invalid)?.to_string(),
+                    bid_price: number(&ticker["bidPrice"]).ok_or_else(invalid)?,
+                    bid_qty: number(&ticker["bidQty"]).ok_or_else(invalid)?,
+                    ask_price: number(&ticker["askPrice"]).ok_or_else(invalid)?,
+                    ask_qty: number(&ticker["askQty"]).ok_or_else(invalid)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Получить время сервера MEXC.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/time`. Не требует API ключей.
+    ///
+    /// # Возвращает
+    /// * `Result<u64>` - Время сервера в миллисекундах Unix
+    ///
+    /// # Пример
+    /// ```
+    /// let server_time = MexcSpotRestClient::fetch_server_time().await?;
+    /// ```
+    pub async fn fetch_server_time() -> Result<u64> {
+        let endpoint = format!("{}/api/v3/time", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        let response = http_get_async(&endpoint, &mut params, None, None, None).await?;
+        let value: Value = serde_json::from_str(&response)?;
+        value["serverTime"].as_u64().ok_or_else(|| {
+            crate::error::Error(format!("Invalid server time response: {}", response))
+        })
+    }
+
+    /// Получить информацию о торговых парах спотового рынка.
+    ///
+    /// Использует MEXC API v3 эндпоинт `/api/v3/exchangeInfo`.
+    ///
+    /// # Возвращает
+    /// * `Result<String>` - JSON строка со списком `symbols` и правилами торговли
+    ///
+    /// # Пример
+    /// ```
+    /// let info = MexcSpotRestClient::fetch_exchange_info().await?;
+    /// ```
+    pub async fn fetch_exchange_info() -> Result<String> {
+        let endpoint = format!("{}/api/v3/exchangeInfo", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mexc_account_balance_without_api_keys() {
+        let client = MexcSpotRestClient::new(None, None, None);
+
+        // Тест получения баланса конкретной монеты
+        let result = client.get_account_balance("USDT").await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("API ключ и секретный ключ обязательны"));
+
+        // Тест получения всех балансов
+        let result_all = client.get_account_balance("").await;
+        assert!(result_all.is_err());
+        assert!(
+            result_all.unwrap_err().to_string().contains("API ключ и секретный ключ обязательны")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mexc_listen_key_methods_without_api_keys() {
+        let client = MexcSpotRestClient::new(None, None, None);
+
+        // Тест получения listen_key без API ключей
+        let result = client.get_listen_key().await;
         assert!(result.is_err());
         assert!(
             result
@@ -825,6 +1867,50 @@ mod tests {
         assert_eq!(client._proxy, Some("http://proxy:8080".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_mexc_per_call_request_options() {
+        let client = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("test_secret".to_string()),
+            Some("http://proxy:8080".to_string()),
+        );
+
+        // Некорректный прокси отвергается построителем HTTP клиента ещё до отправки
+        let scoped = client.with_options(
+            RequestOptions::new().with_proxy("not a proxy").with_timeout(Duration::from_secs(2)),
+        );
+        let error = scoped.cancel_order("BTCUSDT", "12345678").await.unwrap_err();
+        assert!(error.0.contains("Proxy error"), "{}", error);
+        assert_eq!(scoped.timeout, Duration::from_secs(2));
+        assert_eq!(scoped._access_key, client._access_key);
+
+        // Исходный клиент сохраняет свой прокси и таймаут
+        assert_eq!(client._proxy, Some("http://proxy:8080".to_string()));
+        assert_eq!(client.timeout, REQUEST_TIMEOUT);
+        assert!(client.client_builder().is_ok());
+
+        // Незаданные поля берутся из конфигурации клиента
+        let defaults = client.with_options(RequestOptions::default());
+        assert_eq!(defaults._proxy, client._proxy);
+        assert_eq!(defaults.timeout, client.timeout);
+    }
+
+    #[tokio::test]
+    async fn test_mexc_validate_credentials_auth_vs_network() {
+        // Без ключей запрос не отправляется: это отказ в аутентификации, а не сбой
+        let anonymous = MexcSpotRestClient::new(None, None, None);
+        assert!(!anonymous.validate_credentials().await.unwrap());
+
+        // Недоступный прокси — сетевая ошибка, о ключах по ней судить нельзя
+        let unreachable = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("test_secret".to_string()),
+            Some("http://127.0.0.1:9".to_string()),
+        );
+        let error = unreachable.validate_credentials().await.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Network, "{}", error);
+    }
+
     #[test]
     fn test_mexc_timestamp_generation() {
         let timestamp = MexcSpotRestClient::get_timestamp();
@@ -882,4 +1968,471 @@ mod tests {
         assert!(signature.chars().all(|c| c.is_ascii_hexdigit()));
         assert!(signature.chars().all(|c| c.is_lowercase() || c.is_ascii_digit()));
     }
+
+    #[tokio::test]
+    async fn test_mexc_deposit_address_without_api_keys() {
+        let client = MexcSpotRestClient::new(None, None, None);
+
+        let result = client.fetch_deposit_address("USDT", None).await;
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("API ключ и секретный ключ обязательны для получения адреса депозита")
+        );
+
+        let client = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("test_secret".to_string()),
+            None,
+        );
+        let result = client.fetch_deposit_address("  ", None).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Монета не может быть пустой"));
+    }
+
+    #[test]
+    fn test_mexc_deposit_address_params() {
+        let params = MexcSpotRestClient::deposit_address_params("usdt", Some("TRC20")).unwrap();
+        assert_eq!(params.get("coin"), Some(&"USDT".to_string()));
+        assert_eq!(params.get("network"), Some(&"TRC20".to_string()));
+
+        let params = MexcSpotRestClient::deposit_address_params("BTC", None).unwrap();
+        assert_eq!(params.len(), 1);
+        assert!(!params.contains_key("network"));
+
+        // Подпись строится по отсортированным параметрам вместе с timestamp
+        let mut params = MexcSpotRestClient::deposit_address_params("USDT", Some("TRC20")).unwrap();
+        params.insert("timestamp".to_string(), "1644489390087".to_string());
+        let signature =
+            MexcSpotRestClient::generate_signature(&params, "45d0b3c26f2644f19bfb98b07741b2f5")
+                .unwrap();
+        assert_eq!(signature.len(), 64);
+        assert!(signature.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[tokio::test]
+    async fn test_mexc_withdraw_guards() {
+        let client = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("test_secret".to_string()),
+            None,
+        );
+
+        // Без подтверждения запрос не отправляется даже при валидных параметрах
+        let result = client.withdraw("USDT", "TXyz", 10.0, Some("TRC20"), None, false).await;
+        assert!(result.unwrap_err().to_string().contains("Вывод средств не подтвержден"));
+
+        let result = client.withdraw("USDT", "TXyz", 0.0, None, None, true).await;
+        assert!(result.unwrap_err().to_string().contains("Сумма вывода должна быть больше 0"));
+
+        let result = client.withdraw("USDT", "TXyz", -1.0, None, None, true).await;
+        assert!(result.unwrap_err().to_string().contains("Сумма вывода должна быть больше 0"));
+
+        let result = client.withdraw("USDT", "TXyz", f64::NAN, None, None, true).await;
+        assert!(result.unwrap_err().to_string().contains("Сумма вывода должна быть больше 0"));
+
+        let result = client.withdraw("USDT", " ", 10.0, None, None, true).await;
+        assert!(result.unwrap_err().to_string().contains("Адрес вывода не может быть пустым"));
+
+        let result = client.withdraw("", "TXyz", 10.0, None, None, true).await;
+        assert!(result.unwrap_err().to_string().contains("Монета не может быть пустой"));
+
+        let client = MexcSpotRestClient::new(None, None, None);
+        let result = client.withdraw("USDT", "TXyz", 10.0, None, None, true).await;
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("API ключ и секретный ключ обязательны для вывода средств")
+        );
+    }
+
+    #[test]
+    fn test_mexc_withdraw_params() {
+        let params =
+            MexcSpotRestClient::withdraw_params("usdt", "TXyz", 12.5, Some("TRC20"), Some("42"))
+                .unwrap();
+        assert_eq!(params.get("coin"), Some(&"USDT".to_string()));
+        assert_eq!(params.get("address"), Some(&"TXyz".to_string()));
+        assert_eq!(params.get("amount"), Some(&"12.5".to_string()));
+        assert_eq!(params.get("netWork"), Some(&"TRC20".to_string()));
+        assert_eq!(params.get("memo"), Some(&"42".to_string()));
+
+        let params =
+            MexcSpotRestClient::withdraw_params("BTC", "bc1q", 0.01, None, Some("")).unwrap();
+        assert_eq!(params.len(), 3);
+    }
+
+    #[test]
+    fn test_mexc_order_params_with_stp() {
+        let client = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("45d0b3c26f2644f19bfb98b07741b2f5".to_string()),
+            None,
+        );
+        let mut params = MexcSpotRestClient::order_params(
+            "BTCUSDT",
+            "buy",
+            100.0,
+            50000.0,
+            Some(StpMode::CancelMaker),
+            TimeInForce::Gtc,
+            false,
+        )
+        .unwrap();
+        params.insert("timestamp".to_string(), "1644489390087".to_string());
+        client.sign_request(&mut params).unwrap();
+
+        // stpMode входит в подписываемую строку
+        let signature = params.remove("signature").unwrap();
+        let query = MexcSpotRestClient::params_string(&params);
+        assert!(query.contains("stpMode=cancel_maker"));
+        assert_eq!(
+            signature,
+            MexcSpotRestClient::generate_signature(&params, "45d0b3c26f2644f19bfb98b07741b2f5")
+                .unwrap()
+        );
+
+        for stp in [None, Some(StpMode::None)] {
+            let params = MexcSpotRestClient::order_params(
+                "BTCUSDT",
+                "SELL",
+                100.0,
+                50000.0,
+                stp,
+                TimeInForce::Gtc,
+                false,
+            )
+            .unwrap();
+            assert!(!params.contains_key("stpMode"));
+        }
+
+        assert_eq!("cancel_both".parse::<StpMode>().unwrap(), StpMode::CancelBoth);
+        assert_eq!("".parse::<StpMode>().unwrap(), StpMode::None);
+        assert!("cancel_all".parse::<StpMode>().is_err());
+    }
+
+    #[test]
+    fn test_mexc_order_params_time_in_force() {
+        let order = |time_in_force, post_only| {
+            MexcSpotRestClient::order_params(
+                "BTCUSDT",
+                "BUY",
+                100.0,
+                50000.0,
+                None,
+                time_in_force,
+                post_only,
+            )
+        };
+
+        for (time_in_force, order_type, tif) in [
+            (TimeInForce::Gtc, "LIMIT", "GTC"),
+            (TimeInForce::Ioc, "IMMEDIATE_OR_CANCEL", "IOC"),
+            (TimeInForce::Fok, "FILL_OR_KILL", "FOK"),
+        ] {
+            let params = order(time_in_force, false).unwrap();
+            assert_eq!(params["type"], order_type);
+            assert_eq!(params["timeInForce"], tif);
+        }
+
+        // post-only — отдельный тип ордера без timeInForce
+        let params = order(TimeInForce::Gtc, true).unwrap();
+        assert_eq!(params["type"], "LIMIT_MAKER");
+        assert!(!params.contains_key("timeInForce"));
+
+        // post-only несовместим с немедленным исполнением
+        for time_in_force in [TimeInForce::Ioc, TimeInForce::Fok] {
+            let error = order(time_in_force, true).unwrap_err();
+            assert!(error.0.contains("post-only"), "{}", error);
+        }
+
+        assert_eq!("ioc".parse::<TimeInForce>().unwrap(), TimeInForce::Ioc);
+        assert_eq!(TimeInForce::default(), TimeInForce::Gtc);
+        assert!("GTX".parse::<TimeInForce>().is_err());
+    }
+
+    #[test]
+    fn test_mexc_parse_avg_price() {
+        let price =
+            MexcSpotRestClient::parse_avg_price(r#"{"mins":5,"price":"9.35751834"}"#).unwrap();
+        assert_eq!(price, 9.35751834);
+
+        assert!(
+            MexcSpotRestClient::parse_avg_price(r#"{"code":-1121,"msg":"Invalid symbol."}"#)
+                .is_err()
+        );
+        assert!(MexcSpotRestClient::parse_avg_price("not json").is_err());
+    }
+
+    #[test]
+    fn test_mexc_parse_trades() {
+        let trades = MexcSpotRestClient::parse_trades(
+            "BTCUSDT",
+            r#"[{"id":null,"price":"64125.37","qty":"0.0123","quoteQty":"788.74","time":1716000000123,
+                 "isBuyerMaker":true,"isBestMatch":true,"tradeType":"ASK"},
+                {"id":null,"price":"64125.38","qty":"0.5","quoteQty":"32062.69","time":1716000000456,
+                 "isBuyerMaker":false,"isBestMatch":true,"tradeType":"BID"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            trades,
+            vec![
+                Trade {
+                    symbol: "BTCUSDT".to_string(),
+                    trade_id: None,
+                    price: 64125.37,
+                    quantity: 0.0123,
+                    side: TradeSide::Sell,
+                    time: 1716000000123,
+                },
+                Trade {
+                    symbol: "BTCUSDT".to_string(),
+                    trade_id: None,
+                    price: 64125.38,
+                    quantity: 0.5,
+                    side: TradeSide::Buy,
+                    time: 1716000000456,
+                },
+            ]
+        );
+
+        let error = r#"{"code":-1121,"msg":"Invalid symbol."}"#;
+        assert!(MexcSpotRestClient::parse_trades("BTCUSDT", error).is_err());
+        assert!(MexcSpotRestClient::parse_trades("BTCUSDT", r#"[{"price":"1"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_mexc_parse_book_ticker() {
+        let tickers = MexcSpotRestClient::parse_book_ticker(
+            r#"[{"symbol":"BTCUSDT","bidPrice":"64125.37","bidQty":"1.2","askPrice":"64125.38","askQty":"0.5"},
+                {"symbol":"ETHUSDT","bidPrice":"3050.1","bidQty":"12","askPrice":"3050.2","askQty":"7.25"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            tickers,
+            vec![
+                BookTicker {
+                    symbol: "BTCUSDT".to_string(),
+                    bid_price: 64125.37,
+                    bid_qty: 1.2,
+                    ask_price: 64125.38,
+                    ask_qty: 0.5,
+                },
+                BookTicker {
+                    symbol: "ETHUSDT".to_string(),
+                    bid_price: 3050.1,
+                    bid_qty: 12.0,
+                    ask_price: 3050.2,
+                    ask_qty: 7.25,
+                },
+            ]
+        );
+
+        // Запрос по одной паре возвращает объект, а не массив
+        let single =
+            r#"{"symbol":"BTCUSDT","bidPrice":"1","bidQty":"2","askPrice":"3","askQty":"4"}"#;
+        assert_eq!(MexcSpotRestClient::parse_book_ticker(single).unwrap().len(), 1);
+        let error = r#"{"code":-1121,"msg":"Invalid symbol."}"#;
+        assert!(MexcSpotRestClient::parse_book_ticker(error).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mexc_klines_range_paginates_contiguously() {
+        const MINUTE: u64 = 60_000;
+        let start = 1_700_000_000_000;
+        let series: Vec<Candle> = (0..10)
+            .map(|i| Candle {
+                open_time: start + i * MINUTE,
+                open: i as f64,
+                high: i as f64 + 1.0,
+                low: i as f64 - 1.0,
+                close: i as f64 + 0.5,
+                volume: 1.0,
+            })
+            .collect();
+        let requests = std::sync::Mutex::new(Vec::new());
+
+        // Страница до 4 свечей; как и биржа, отдаёт ещё и свечу на границе предыдущей
+        let candles: Vec<Candle> = MexcSpotRestClient::paginate_klines(
+            |from, to| {
+                requests.lock().unwrap().push(from);
+                let page: Vec<Candle> = series
+                    .iter()
+                    .filter(|candle| candle.open_time + MINUTE > from && candle.open_time <= to)
+                    .take(4)
+                    .cloned()
+                    .collect();
+                async move { Ok(page) }
+            },
+            start,
+            start + 9 * MINUTE,
+            4,
+            MINUTE,
+        )
+        .map(|candle| candle.unwrap())
+        .collect()
+        .await;
+
+        assert_eq!(candles, series);
+        assert_eq!(requests.lock().unwrap()[0], start);
+        assert!(requests.lock().unwrap().len() >= 3);
+
+        // Полная страница из одних повторов граничных свечей не завершает поток
+        let candles: Vec<Candle> = MexcSpotRestClient::paginate_klines(
+            |from, to| {
+                let page: Vec<Candle> = series
+                    .iter()
+                    .filter(|candle| candle.open_time + 2 * MINUTE > from && candle.open_time <= to)
+                    .take(2)
+                    .cloned()
+                    .collect();
+                async move { Ok(page) }
+            },
+            start,
+            start + 9 * MINUTE,
+            2,
+            MINUTE,
+        )
+        .map(|candle| candle.unwrap())
+        .collect()
+        .await;
+        assert_eq!(candles, series);
+
+        let klines = MexcSpotRestClient::parse_klines(
+            r#"[[1700000000000,"37000.5","37010","36990.25","37005","12.5",1700000059999,"462531.2"]]"#,
+        )
+        .unwrap();
+        assert_eq!(klines[0].open_time, 1700000000000);
+        assert_eq!(klines[0].volume, 12.5);
+        assert!(MexcSpotRestClient::parse_klines(r#"{"code":-1121}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mexc_cancel_orders_per_order_results() {
+        let client = MexcSpotRestClient::new(None, None, None);
+        let ids = vec!["1".to_string()];
+        assert!(client.cancel_orders("BTCUSDT", &ids).await.is_err());
+
+        let ids: Vec<String> = ["101", "102", "103", "104", "105", "106", "107"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        let results = MexcSpotRestClient::cancel_each(&ids, |order_id| async move {
+            if order_id.parse::<u64>().unwrap() % 2 == 0 {
+                Err(crate::error::Error(format!(
+                    r#"MEXC API error (400 Bad Request): {{"code":-2011,"msg":"Unknown order {}"}}"#,
+                    order_id
+                )))
+            } else {
+                Ok(format!(r#"{{"symbol":"BTCUSDT","orderId":"{}","status":"CANCELED"}}"#, order_id))
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), ids.len());
+        for (order_id, result) in ids.iter().zip(&results) {
+            match result {
+                Ok(body) => assert!(body.contains(order_id.as_str())),
+                Err(e) => assert!(e.0.contains(order_id.as_str()) && e.0.contains("-2011")),
+            }
+        }
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 4);
+    }
+
+    #[test]
+    fn test_mexc_custom_signer() {
+        struct PrefixSigner;
+
+        impl Signer for PrefixSigner {
+            fn sign(&self, payload: &str) -> Result<String> {
+                Ok(format!("hsm:{}", payload))
+            }
+        }
+
+        let client = MexcSpotRestClient::new(Some("test_key".to_string()), None, None)
+            .with_signer(Box::new(PrefixSigner));
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), "BTCUSDT".to_string());
+        params.insert("timestamp".to_string(), "1644489390087".to_string());
+        client.sign_request(&mut params).unwrap();
+        assert_eq!(params["signature"], "hsm:symbol=BTCUSDT&timestamp=1644489390087");
+
+        // По умолчанию используется HMAC-SHA256 секретным ключом
+        let secret = "45d0b3c26f2644f19bfb98b07741b2f5";
+        let client =
+            MexcSpotRestClient::new(Some("test_key".to_string()), Some(secret.to_string()), None);
+        params.remove("signature");
+        assert_eq!(
+            client.sign_params(&params).unwrap(),
+            MexcSpotRestClient::generate_signature(&params, secret).unwrap()
+        );
+
+        let keyless = MexcSpotRestClient::new(Some("test_key".to_string()), None, None);
+        assert!(keyless.sign_params(&params).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mexc_clock_skew_resync_and_retry() {
+        use std::sync::atomic::AtomicUsize;
+
+        let client = MexcSpotRestClient::new(
+            Some("test_key".to_string()),
+            Some("test_secret".to_string()),
+            None,
+        );
+        let server_now = MexcSpotRestClient::get_timestamp() + 60_000;
+        let attempts = AtomicUsize::new(0);
+
+        let result = client
+            .retry_after_resync(
+                || async {
+                    if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(crate::error::Error(
+                            r#"MEXC API error (400 Bad Request): {"code":700003,"msg":"Timestamp for this request is outside of the recvWindow."}"#
+                                .to_string(),
+                        ))
+                    } else {
+                        Ok(client.timestamp().to_string())
+                    }
+                },
+                || async { Ok(server_now) },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        // Повторный запрос подписан уже по часам сервера
+        let retried_at: u64 = result.parse().unwrap();
+        assert!(retried_at >= server_now && retried_at < server_now + 1_000, "{}", retried_at);
+
+        // Прочие ошибки не повторяются и не трогают поправку. В URL сетевой ошибки
+        // может встретиться 700003 (цена, сумма, подпись), но это не ответ биржи:
+        // ордер с неизвестным результатом нельзя отправлять повторно.
+        for error in [
+            "Insufficient balance",
+            "Request error: error sending request for url (https://api.mexc.com/api/v3/order\
+             ?price=0.700003&quantity=1&side=BUY&signature=ab700003cd): operation timed out",
+            r#"MEXC API error (400 Bad Request): {"code":700004,"msg":"price 0.700003"}"#,
+            r#"MEXC API error (503 Service Unavailable): {"code":700003}"#,
+        ] {
+            let attempts = AtomicUsize::new(0);
+            let result = client
+                .retry_after_resync(
+                    || async {
+                        attempts.fetch_add(1, Ordering::SeqCst);
+                        Err(crate::error::Error(error.to_string()))
+                    },
+                    || async { Ok(0) },
+                )
+                .await;
+            assert!(result.is_err());
+            assert_eq!(attempts.load(Ordering::SeqCst), 1, "{}", error);
+        }
+        assert!(client.timestamp() >= server_now);
+    }
 }
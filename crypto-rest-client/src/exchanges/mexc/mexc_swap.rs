@@ -1,21 +1,112 @@
 use super::super::utils::http_get;
-use crate::{error::Result, exchanges::utils::http_get_async};
+use crate::{
+    error::Result,
+    exchanges::utils::{
+        http_get_async, http_get_with_headers_async, http_post_json_with_headers_async,
+    },
+    FundingRate,
+};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
 use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const BASE_URL: &str = "https://contract.mexc.com";
 
+/// Upper bound of contract leverage accepted by MEXC.
+const MAX_LEVERAGE: u32 = 200;
+
+/// Page size used when paginating funding rate history.
+const FUNDING_HISTORY_PAGE_SIZE: u32 = 100;
+
+/// Margin mode of a contract position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenType {
+    Isolated,
+    Cross,
+}
+
+impl OpenType {
+    /// `openType` value of the MEXC contract API.
+    fn as_code(self) -> u8 {
+        match self {
+            OpenType::Isolated => 1,
+            OpenType::Cross => 2,
+        }
+    }
+}
+
+/// Side of a contract position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionType {
+    Long,
+    Short,
+}
+
+impl PositionType {
+    /// `positionType` value of the MEXC contract API.
+    fn as_code(self) -> u8 {
+        match self {
+            PositionType::Long => 1,
+            PositionType::Short => 2,
+        }
+    }
+}
+
 /// MEXC Swap market.
 ///
 /// * REST API doc: <https://mxcdevelop.github.io/APIDoc/>
 /// * Trading at: <https://contract.mexc.com/exchange>
 pub struct MexcSwapRestClient {
-    _api_key: Option<String>,
-    _api_secret: Option<String>,
+    api_key: Option<String>,
+    api_secret: Option<String>,
 }
 
 impl MexcSwapRestClient {
     pub fn new(api_key: Option<String>, api_secret: Option<String>) -> Self {
-        MexcSwapRestClient { _api_key: api_key, _api_secret: api_secret }
+        MexcSwapRestClient { api_key, api_secret }
+    }
+
+    fn get_timestamp() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+    }
+
+    /// Contract API signature: hex(HMAC-SHA256(secret, apiKey + Request-Time + payload)),
+    /// where `payload` is the sorted query string for GET and the JSON body for POST.
+    fn contract_signature(
+        api_key: &str,
+        api_secret: &str,
+        request_time: u64,
+        payload: &str,
+    ) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(format!("{api_key}{request_time}{payload}").as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn query_string(params: &BTreeMap<String, String>) -> String {
+        params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&")
+    }
+
+    /// Headers of a signed private contract request.
+    fn signed_headers(
+        &self,
+        payload: &str,
+        request_time: u64,
+    ) -> Result<Vec<(&'static str, String)>> {
+        let (Some(api_key), Some(api_secret)) =
+            (self.api_key.as_deref(), self.api_secret.as_deref())
+        else {
+            return Err(crate::error::Error("API key and secret are required".to_string()));
+        };
+
+        Ok(vec![
+            ("ApiKey", api_key.to_string()),
+            ("Request-Time", request_time.to_string()),
+            ("Signature", Self::contract_signature(api_key, api_secret, request_time, payload)),
+        ])
     }
 
     /// Get most recent trades.
@@ -41,6 +132,212 @@ impl MexcSwapRestClient {
         http_get_async(&endpoint, &mut params, None, None, None).await
     }
 
+    /// Get candlesticks of a contract.
+    ///
+    /// `interval` is one of `Min1`, `Min5`, `Min15`, `Min30`, `Min60`, `Hour4`, `Hour8`,
+    /// `Day1`, `Week1`, `Month1`; `start` and `end` are Unix timestamps in seconds.
+    /// At most 2000 candles are returned per request.
+    ///
+    /// For example: <https://contract.mexc.com/api/v1/contract/kline/BTC_USDT?interval=Min1>
+    pub async fn fetch_klines(
+        symbol: &str,
+        interval: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+    ) -> Result<String> {
+        let endpoint = format!("{}/api/v1/contract/kline/{}", BASE_URL, symbol);
+        let mut params = BTreeMap::new();
+        params.insert("interval".to_string(), interval.to_string());
+        if let Some(start) = start {
+            params.insert("start".to_string(), start.to_string());
+        }
+        if let Some(end) = end {
+            params.insert("end".to_string(), end.to_string());
+        }
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Get details of all contracts.
+    ///
+    /// For example: <https://contract.mexc.com/api/v1/contract/detail>
+    pub async fn fetch_contract_details() -> Result<String> {
+        let endpoint = format!("{}/api/v1/contract/detail", BASE_URL);
+        let mut params = BTreeMap::new();
+
+        http_get_async(&endpoint, &mut params, None, None, None).await
+    }
+
+    /// Get funding rate history of a contract, oldest first.
+    ///
+    /// The endpoint has no time filter and returns pages newest first, so pages are
+    /// fetched until `start` is passed and entries outside `start..=end` (Unix
+    /// milliseconds) are dropped. `limit` keeps only the latest entries. Both `time` and
+    /// `next_settle_time` of an entry are its settlement time.
+    ///
+    /// For example: <https://contract.mexc.com/api/v1/contract/funding_rate/history?symbol=BTC_USDT&page_num=1&page_size=100>
+    pub async fn fetch_funding_rate_history(
+        symbol: &str,
+        start: Option<u64>,
+        end: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<FundingRate>> {
+        let endpoint = format!("{}/api/v1/contract/funding_rate/history", BASE_URL);
+        let limit = limit.map(|limit| limit as usize);
+        let mut history = Vec::new();
+
+        for page_num in 1.. {
+            let mut params = BTreeMap::new();
+            params.insert("symbol".to_string(), symbol.to_string());
+            params.insert("page_num".to_string(), page_num.to_string());
+            params.insert("page_size".to_string(), FUNDING_HISTORY_PAGE_SIZE.to_string());
+            let response = http_get_async(&endpoint, &mut params, None, None, None).await?;
+            let (page, total_pages) = Self::parse_funding_rate_history(&response)?;
+
+            let passed_start = match (page.last(), start) {
+                (Some(oldest), Some(start)) => oldest.time < start,
+                (oldest, _) => oldest.is_none(),
+            };
+            history.extend(page.into_iter().filter(|rate| {
+                start.is_none_or(|start| rate.time >= start)
+                    && end.is_none_or(|end| rate.time <= end)
+            }));
+            if passed_start
+                || page_num >= total_pages
+                || limit.is_some_and(|limit| history.len() >= limit)
+            {
+                break;
+            }
+        }
+
+        history.truncate(limit.unwrap_or(usize::MAX));
+        history.reverse();
+        Ok(history)
+    }
+
+    /// Parse one page of funding rate history, returning it with the total page count.
+    fn parse_funding_rate_history(json: &str) -> Result<(Vec<FundingRate>, u64)> {
+        let value: Value = serde_json::from_str(json)?;
+        let invalid =
+            || crate::error::Error(format!("Invalid funding rate history response: {}", json));
+        let number = |value: &Value| match value {
+            Value::String(s) => s.parse::<f64>().ok(),
+            value => value.as_f64(),
+        };
+
+        let data = &value["data"];
+        let history = data["resultList"]
+            .as_array()
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|entry| {
+                let settle_time = entry["settleTime"].as_u64().ok_or_else(invalid)?;
+                Ok(FundingRate {
+                    symbol: entry["symbol"].as_str().ok_or_else(invalid)?.to_string(),
+                    rate: number(&entry["fundingRate"]).ok_or_else(invalid)?,
+                    next_settle_time: settle_time,
+                    time: settle_time,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok((history, data["totalPage"].as_u64().unwrap_or(1)))
+    }
+
+    /// Get open positions, optionally only for one contract.
+    ///
+    /// Endpoint: `GET /api/v1/private/position/open_positions`, requires API keys.
+    /// Returns the raw JSON response.
+    pub async fn fetch_positions(&self, symbol: Option<&str>) -> Result<String> {
+        let endpoint = format!("{}/api/v1/private/position/open_positions", BASE_URL);
+        let mut params = BTreeMap::new();
+        if let Some(symbol) = symbol {
+            params.insert("symbol".to_string(), symbol.to_string());
+        }
+        let headers = self.signed_headers(&Self::query_string(&params), Self::get_timestamp())?;
+
+        http_get_with_headers_async(&endpoint, &params, &headers, None).await
+    }
+
+    /// Get asset balances of the contract account.
+    ///
+    /// Endpoint: `GET /api/v1/private/account/assets`, requires API keys.
+    /// Returns the raw JSON response.
+    pub async fn fetch_assets(&self) -> Result<String> {
+        let endpoint = format!("{}/api/v1/private/account/assets", BASE_URL);
+        let params = BTreeMap::new();
+        let headers = self.signed_headers(&Self::query_string(&params), Self::get_timestamp())?;
+
+        http_get_with_headers_async(&endpoint, &params, &headers, None).await
+    }
+
+    /// Get open orders, optionally only for one contract.
+    ///
+    /// Endpoint: `GET /api/v1/private/order/list/open_orders/{symbol}`, requires API keys.
+    /// Returns the raw JSON response.
+    pub async fn fetch_open_orders(&self, symbol: Option<&str>) -> Result<String> {
+        let endpoint = format!(
+            "{}/api/v1/private/order/list/open_orders/{}",
+            BASE_URL,
+            symbol.unwrap_or_default()
+        );
+        let mut params = BTreeMap::new();
+        params.insert("page_size".to_string(), "100".to_string());
+        let headers = self.signed_headers(&Self::query_string(&params), Self::get_timestamp())?;
+
+        http_get_with_headers_async(&endpoint, &params, &headers, None).await
+    }
+
+    /// Change leverage of one side of a contract.
+    ///
+    /// Endpoint: `POST /api/v1/private/position/change_leverage`, requires API keys.
+    /// Without an open position MEXC needs `symbol`, `openType` and `positionType`, so
+    /// leverage can be set before opening; `position_id` targets an existing position.
+    /// Leverage must be within `1..=200`. Returns the confirmation JSON.
+    pub async fn set_leverage(
+        &self,
+        symbol: &str,
+        leverage: u32,
+        open_type: OpenType,
+        position_type: PositionType,
+        position_id: Option<u64>,
+    ) -> Result<String> {
+        let endpoint = format!("{}/api/v1/private/position/change_leverage", BASE_URL);
+        let body =
+            Self::set_leverage_body(symbol, leverage, open_type, position_type, position_id)?;
+        let headers = self.signed_headers(&body, Self::get_timestamp())?;
+
+        http_post_json_with_headers_async(&endpoint, &body, &headers, None).await
+    }
+
+    fn set_leverage_body(
+        symbol: &str,
+        leverage: u32,
+        open_type: OpenType,
+        position_type: PositionType,
+        position_id: Option<u64>,
+    ) -> Result<String> {
+        if symbol.is_empty() {
+            return Err(crate::error::Error("symbol is required".to_string()));
+        }
+        if !(1..=MAX_LEVERAGE).contains(&leverage) {
+            return Err(crate::error::Error(format!(
+                "Leverage must be between 1 and {}, got {}",
+                MAX_LEVERAGE, leverage
+            )));
+        }
+
+        let mut body = json!({
+            "symbol": symbol,
+            "leverage": leverage,
+            "openType": open_type.as_code(),
+            "positionType": position_type.as_code(),
+        });
+        if let Some(position_id) = position_id {
+            body["positionId"] = json!(position_id);
+        }
+        Ok(body.to_string())
+    }
+
     /// Получить listen_key для WebSocket приватных данных (Swap API).
     ///
     /// # Примечания
@@ -53,3 +350,109 @@ impl MexcSwapRestClient {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mexc_swap_contract_signature() {
+        let client = MexcSwapRestClient::new(
+            Some("mx0vglTestKey".to_string()),
+            Some("test_secret".to_string()),
+        );
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), "BTC_USDT".to_string());
+
+        let query = MexcSwapRestClient::query_string(&params);
+        let headers = client.signed_headers(&query, 1700000000000).unwrap();
+        assert_eq!(headers[0], ("ApiKey", "mx0vglTestKey".to_string()));
+        assert_eq!(headers[1], ("Request-Time", "1700000000000".to_string()));
+        assert_eq!(headers[2].0, "Signature");
+        assert_eq!(
+            headers[2].1,
+            "18ace7489a7938c64dbbfee70c2deca784eb2e4baa2319c02c1e86897eb1bdf4"
+        );
+    }
+
+    #[test]
+    fn test_mexc_swap_set_leverage_params() {
+        let leverage_body = |symbol, leverage, open_type, position_type, position_id| {
+            MexcSwapRestClient::set_leverage_body(
+                symbol,
+                leverage,
+                open_type,
+                position_type,
+                position_id,
+            )
+        };
+
+        // Without a position symbol, openType and positionType are required
+        let body =
+            leverage_body("BTC_USDT", 20, OpenType::Isolated, PositionType::Long, None).unwrap();
+        assert_eq!(body, r#"{"leverage":20,"openType":1,"positionType":1,"symbol":"BTC_USDT"}"#);
+        assert_eq!(
+            MexcSwapRestClient::contract_signature(
+                "mx0vglTestKey",
+                "test_secret",
+                1700000000000,
+                &body
+            ),
+            "57b29e37cb18df73aa0393db90578ba9ab1d2f2e84a59d99dda5c912abf413b1"
+        );
+
+        // An open position is addressed by positionId
+        let body =
+            leverage_body("ETH_USDT", 200, OpenType::Cross, PositionType::Short, Some(123456789))
+                .unwrap();
+        assert_eq!(
+            body,
+            r#"{"leverage":200,"openType":2,"positionId":123456789,"positionType":2,"symbol":"ETH_USDT"}"#
+        );
+
+        assert!(leverage_body("BTC_USDT", 0, OpenType::Cross, PositionType::Long, None).is_err());
+        assert!(leverage_body("BTC_USDT", 201, OpenType::Cross, PositionType::Long, None).is_err());
+        assert!(leverage_body("", 10, OpenType::Cross, PositionType::Long, None).is_err());
+    }
+
+    #[test]
+    fn test_mexc_swap_parse_funding_rate_history() {
+        let json = r#"{"success":true,"code":0,"data":{"pageSize":2,"totalCount":3,"totalPage":2,"currentPage":1,"resultList":[
+            {"symbol":"BTC_USDT","fundingRate":0.000266,"settleTime":1716019200000},
+            {"symbol":"BTC_USDT","fundingRate":-0.0001,"settleTime":1715990400000}]}}"#;
+
+        let (history, total_pages) = MexcSwapRestClient::parse_funding_rate_history(json).unwrap();
+        assert_eq!(total_pages, 2);
+        assert_eq!(
+            history,
+            vec![
+                FundingRate {
+                    symbol: "BTC_USDT".to_string(),
+                    rate: 0.000266,
+                    next_settle_time: 1716019200000,
+                    time: 1716019200000,
+                },
+                FundingRate {
+                    symbol: "BTC_USDT".to_string(),
+                    rate: -0.0001,
+                    next_settle_time: 1715990400000,
+                    time: 1715990400000,
+                },
+            ]
+        );
+
+        let error = r#"{"success":false,"code":1001,"message":"contract not exist"}"#;
+        assert!(MexcSwapRestClient::parse_funding_rate_history(error).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mexc_swap_private_requests_require_keys() {
+        let client = MexcSwapRestClient::new(None, None);
+        let result = client.fetch_positions(Some("BTC_USDT")).await;
+        assert!(result.unwrap_err().to_string().contains("API key and secret are required"));
+
+        let result =
+            client.set_leverage("BTC_USDT", 10, OpenType::Cross, PositionType::Long, None).await;
+        assert!(result.unwrap_err().to_string().contains("API key and secret are required"));
+    }
+}
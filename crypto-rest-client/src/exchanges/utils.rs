@@ -1,4 +1,5 @@
 use crate::error::{Error, Result};
+use crate::request_options::RequestOptions;
 use hmac::{Hmac, Mac};
 use reqwest::{blocking::Response, header};
 use sha2::Sha256;
@@ -110,6 +111,18 @@ pub(super) async fn http_get_async(
     api_key: Option<&str>,
     api_secret: Option<&str>,
     proxy: Option<&str>,
+) -> Result<String> {
+    let options = RequestOptions { proxy: proxy.map(str::to_string), timeout: None };
+    http_get_async_with_options(url, params, api_key, api_secret, &options).await
+}
+
+/// GET запрос с прокси и таймаутом из [`RequestOptions`]
+pub(super) async fn http_get_async_with_options(
+    url: &str,
+    params: &mut BTreeMap<String, String>,
+    api_key: Option<&str>,
+    api_secret: Option<&str>,
+    options: &RequestOptions,
 ) -> Result<String> {
     // Обрабатываем аутентификацию если API ключи предоставлены
     if api_key.is_some() && api_secret.is_some() {
@@ -154,11 +167,11 @@ pub(super) async fn http_get_async(
 
     let mut client_builder = reqwest::Client::builder()
         .default_headers(headers)
-        .timeout(Duration::from_secs(REQUEST_TIMEOUT))
+        .timeout(options.timeout.unwrap_or(Duration::from_secs(REQUEST_TIMEOUT)))
         .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36")
         .gzip(true);
 
-    if let Some(proxy_url) = proxy {
+    if let Some(proxy_url) = &options.proxy {
         client_builder =
             client_builder.proxy(reqwest::Proxy::all(proxy_url).map_err(|e| Error::from(e))?);
     }
@@ -184,6 +197,18 @@ pub(super) async fn http_post_async(
     api_key: Option<&str>,
     api_secret: Option<&str>,
     proxy: Option<&str>,
+) -> Result<String> {
+    let options = RequestOptions { proxy: proxy.map(str::to_string), timeout: None };
+    http_post_async_with_options(url, params, api_key, api_secret, &options).await
+}
+
+/// POST запрос с прокси и таймаутом из [`RequestOptions`]
+pub(super) async fn http_post_async_with_options(
+    url: &str,
+    params: &mut BTreeMap<String, String>,
+    api_key: Option<&str>,
+    api_secret: Option<&str>,
+    options: &RequestOptions,
 ) -> Result<String> {
     // Шаг 1: Добавляем timestamp, если используется авторизация и его еще нет
     if api_key.is_some() && api_secret.is_some() {
@@ -248,7 +273,11 @@ pub(super) async fn http_post_async(
         .default_headers(headers)
         .user_agent("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/87.0.4280.88 Safari/537.36");
 
-    if let Some(proxy_url) = proxy {
+    if let Some(timeout) = options.timeout {
+        client_builder = client_builder.timeout(timeout);
+    }
+
+    if let Some(proxy_url) = &options.proxy {
         client_builder =
             client_builder.proxy(reqwest::Proxy::all(proxy_url).map_err(|e| Error::from(e))?);
     }
@@ -280,6 +309,80 @@ pub(super) async fn http_post_async(
     }
 }
 
+/// GET запрос с подписью в заголовках (схема MEXC Contract: `ApiKey`, `Request-Time`,
+/// `Signature`). Параметры уже должны быть подписаны вызывающим.
+pub(super) async fn http_get_with_headers_async(
+    url: &str,
+    params: &BTreeMap<String, String>,
+    extra_headers: &[(&'static str, String)],
+    proxy: Option<&str>,
+) -> Result<String> {
+    let mut full_url = url.to_string();
+    if !params.is_empty() {
+        let query = params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&");
+        full_url.push('?');
+        full_url.push_str(&query);
+    }
+
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, header::HeaderValue::from_static("application/json"));
+    for (name, value) in extra_headers {
+        headers.insert(*name, header::HeaderValue::from_str(value)?);
+    }
+
+    let mut client_builder = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT))
+        .gzip(true);
+
+    if let Some(proxy_url) = proxy {
+        client_builder = client_builder.proxy(reqwest::Proxy::all(proxy_url)?);
+    }
+
+    let response = client_builder.build()?.get(full_url.as_str()).send().await?;
+
+    match response.error_for_status() {
+        Ok(resp) => Ok(resp.text().await?),
+        Err(error) => Err(Error(format!(
+            "API Error: {} для URL ({}) - Проверьте API ключи и параметры запроса.",
+            error, full_url
+        ))),
+    }
+}
+
+/// POST запрос с JSON телом и подписью в заголовках (схема MEXC Contract).
+pub(super) async fn http_post_json_with_headers_async(
+    url: &str,
+    body: &str,
+    extra_headers: &[(&'static str, String)],
+    proxy: Option<&str>,
+) -> Result<String> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, header::HeaderValue::from_static("application/json"));
+    for (name, value) in extra_headers {
+        headers.insert(*name, header::HeaderValue::from_str(value)?);
+    }
+
+    let mut client_builder = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT))
+        .gzip(true);
+
+    if let Some(proxy_url) = proxy {
+        client_builder = client_builder.proxy(reqwest::Proxy::all(proxy_url)?);
+    }
+
+    let response = client_builder.build()?.post(url).body(body.to_string()).send().await?;
+
+    match response.error_for_status() {
+        Ok(resp) => Ok(resp.text().await?),
+        Err(error) => Err(Error(format!(
+            "API Error: {} для URL ({}) - Проверьте API ключи и параметры запроса.",
+            error, url
+        ))),
+    }
+}
+
 pub(super) async fn http_request_async(
     url: &str,
     method: &str,
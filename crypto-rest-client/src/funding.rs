@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Ставка финансирования бессрочного контракта
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingRate {
+    pub symbol: String,
+    /// Ставка за период в долях (0.0001 = 0.01%)
+    pub rate: f64,
+    /// Время следующего расчёта в миллисекундах Unix
+    pub next_settle_time: u64,
+    /// Время события в миллисекундах Unix
+    pub time: u64,
+}
@@ -1,7 +1,15 @@
+mod book_ticker;
+mod candle;
 mod error;
 mod exchanges;
+mod funding;
+mod request_options;
+mod signer;
+mod trade;
 
-pub use error::Error;
+pub use book_ticker::BookTicker;
+pub use candle::Candle;
+pub use error::{Error, ErrorKind};
 pub use exchanges::{
     binance::{
         binance_inverse::BinanceInverseRestClient, binance_linear::BinanceLinearRestClient,
@@ -27,11 +35,18 @@ pub use exchanges::{
     },
     kraken::{kraken_futures::KrakenFuturesRestClient, kraken_spot::KrakenSpotRestClient},
     kucoin::*,
-    mexc::{mexc_spot::MexcSpotRestClient, mexc_swap::MexcSwapRestClient},
+    mexc::{
+        mexc_spot::{MexcSpotRestClient, StpMode, TimeInForce},
+        mexc_swap::{MexcSwapRestClient, OpenType, PositionType},
+    },
     okx::OkxRestClient,
     zb::*,
     zbg::*,
 };
+pub use funding::FundingRate;
+pub use request_options::RequestOptions;
+pub use signer::{HmacSha256Signer, Signer};
+pub use trade::{Trade, TradeSide};
 
 use crypto_market_type::MarketType;
 use error::Result;
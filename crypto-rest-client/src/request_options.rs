@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+/// Параметры отдельного запроса, перекрывающие настройки клиента.
+///
+/// Незаданные поля берутся из конфигурации клиента. Пригодится, например, чтобы
+/// отправить один рыночный ордер через более быстрый прокси.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Прокси для запроса вместо прокси клиента
+    pub proxy: Option<String>,
+    /// Таймаут запроса вместо таймаута клиента
+    pub timeout: Option<Duration>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
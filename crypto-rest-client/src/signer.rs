@@ -0,0 +1,32 @@
+use crate::error::{Error, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Подпись приватных REST запросов.
+///
+/// Позволяет хранить секретный ключ вне клиента (например, в HSM) или менять ключ
+/// на лету: реализация сама решает, каким ключом подписать очередной запрос.
+pub trait Signer: Send + Sync {
+    /// Подписать строку запроса и вернуть подпись в том виде, в котором её ждёт биржа
+    fn sign(&self, payload: &str) -> Result<String>;
+}
+
+/// Подпись HMAC-SHA256 секретным ключом в lowercase hex
+pub struct HmacSha256Signer {
+    secret: String,
+}
+
+impl HmacSha256Signer {
+    pub fn new(secret: impl Into<String>) -> Self {
+        HmacSha256Signer { secret: secret.into() }
+    }
+}
+
+impl Signer for HmacSha256Signer {
+    fn sign(&self, payload: &str) -> Result<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .map_err(|_| Error("Failed to create HMAC".to_string()))?;
+        mac.update(payload.as_bytes());
+        Ok(hex::encode(mac.finalize().into_bytes()))
+    }
+}
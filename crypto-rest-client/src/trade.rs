@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Сторона инициатора (тейкера) сделки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Публичная сделка в едином формате для REST истории и WebSocket потока
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub symbol: String,
+    /// Идентификатор сделки, если биржа его передаёт
+    pub trade_id: Option<String>,
+    pub price: f64,
+    pub quantity: f64,
+    pub side: TradeSide,
+    /// Время сделки в миллисекундах Unix
+    pub time: u64,
+}
@@ -249,6 +249,10 @@ impl<const URL: char> WSClient for BinanceWSClient<URL> {
     async fn close(&self) {
         self.client.close().await;
     }
+
+    async fn close_with(&self, code: u16, reason: &str) {
+        self.client.close_with(code, reason).await;
+    }
 }
 
 struct BinanceMessageHandler {}
@@ -335,6 +339,21 @@ impl MessageHandler for BinanceMessageHandler {
         // Для общих потоков данных используется стандартный интервал в 180 секунд.
         Some((Message::Pong(Vec::new()), 180))
     }
+
+    fn on_connect_actions(&self) -> Vec<Message> {
+        // Инициализирующий Pong сразу после подключения помогает установить
+        // корректное соединение, особенно для user_data каналов
+        vec![Message::Pong(Vec::new())]
+    }
+
+    fn should_pong_on_ping(&self) -> bool {
+        // Сервер присылает ping каждые 3 минуты и ждет pong в течение 10 минут
+        true
+    }
+
+    fn health_check_interval(&self) -> Option<u64> {
+        Some(30)
+    }
 }
 
 impl CommandTranslator for BinanceCommandTranslator {
@@ -374,7 +393,8 @@ impl CommandTranslator for BinanceCommandTranslator {
 
 #[cfg(test)]
 mod tests {
-    use crate::common::command_translator::CommandTranslator;
+    use crate::common::{command_translator::CommandTranslator, message_handler::MessageHandler};
+    use tokio_tungstenite::tungstenite::Message;
 
     #[test]
     fn test_one_topic() {
@@ -406,4 +426,14 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_connection_hooks() {
+        let handler = super::BinanceMessageHandler {};
+
+        assert_eq!(vec![Message::Pong(Vec::new())], handler.on_connect_actions());
+        assert!(handler.should_pong_on_ping());
+        assert_eq!(Some(30), handler.health_check_interval());
+        assert_eq!(Some((Message::Pong(Vec::new()), 180)), handler.get_ping_msg_and_interval());
+    }
 }
@@ -123,6 +123,18 @@ impl MessageHandler for BinanceOptionMessageHandler {
         // frames are allowed.
         Some((Message::Text(r#"{"event":"ping"}"#.to_string()), 120))
     }
+
+    fn on_connect_actions(&self) -> Vec<Message> {
+        vec![Message::Pong(Vec::new())]
+    }
+
+    fn should_pong_on_ping(&self) -> bool {
+        true
+    }
+
+    fn health_check_interval(&self) -> Option<u64> {
+        Some(30)
+    }
 }
 
 impl CommandTranslator for BinanceOptionCommandTranslator {
@@ -2,13 +2,12 @@ use async_trait::async_trait;
 use log::*;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::mpsc::Sender;
 use tokio_tungstenite::tungstenite::Message;
 
-use crate::WSClient;
+use crate::{TlsConfig, WSClient};
 use crate::common::command_translator::CommandTranslator;
 use crate::common::message_handler::{MessageHandler, MiscMessage};
-use crate::common::ws_client_internal::WSClientInternal;
+use crate::common::ws_client_internal::{FrameOutput, WSClientInternal};
 
 const EXCHANGE_NAME: &str = "bingx";
 
@@ -25,7 +24,7 @@ pub struct BingxMessageHandler {}
 pub struct BingxCommandTranslator {}
 
 impl BingxSpotWSClient {
-    pub async fn new(tx: Sender<String>, _proxy: Option<String>) -> BingxSpotWSClient {
+    pub async fn new(tx: impl Into<FrameOutput>, _proxy: Option<String>) -> BingxSpotWSClient {
         BingxSpotWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
@@ -37,6 +36,21 @@ impl BingxSpotWSClient {
             .await,
         }
     }
+
+    /// Creates a client with custom TLS settings.
+    pub async fn new_with_tls(tx: impl Into<FrameOutput>, tls: TlsConfig) -> BingxSpotWSClient {
+        BingxSpotWSClient {
+            client: WSClientInternal::connect_with_tls(
+                EXCHANGE_NAME,
+                SPOT_WEBSOCKET_URL,
+                BingxMessageHandler {},
+                None,
+                Some(tls),
+                tx,
+            )
+            .await,
+        }
+    }
 }
 
 #[async_trait]
@@ -132,6 +146,54 @@ impl WSClient for BingxSpotWSClient {
     async fn close(&self) {
         self.client.close().await;
     }
+
+    async fn close_with(&self, code: u16, reason: &str) {
+        self.client.close_with(code, reason).await;
+    }
+
+    fn health_status(&self) -> Option<crate::HealthStatus> {
+        Some(self.client.get_health_status())
+    }
+
+    fn active_subscriptions(&self) -> Vec<String> {
+        self.client.active_subscriptions()
+    }
+
+    fn connection_url(&self) -> Option<&str> {
+        Some(self.client.url())
+    }
+
+    fn set_parse_workers(&self, workers: usize) {
+        self.client.set_parse_workers(workers);
+    }
+
+    fn set_max_frame_size(&self, bytes: usize) {
+        self.client.set_max_frame_size(bytes);
+    }
+
+    fn set_auto_reconnect(&self, enabled: bool) {
+        self.client.set_auto_reconnect(enabled);
+    }
+
+    fn set_heartbeat_messages(&self, enabled: bool) {
+        self.client.set_heartbeat_messages(enabled);
+    }
+
+    fn set_raw_frames(&self, enabled: bool) {
+        self.client.set_raw_frames(enabled);
+    }
+
+    fn set_undecodable_passthrough(&self, enabled: bool) {
+        self.client.set_undecodable_passthrough(enabled);
+    }
+
+    fn record_parse_failure(&self) {
+        self.client.record_parse_failure();
+    }
+
+    fn set_on_reconnect(&self, hook: crate::ReconnectHook) {
+        self.client.set_on_reconnect(hook);
+    }
 }
 
 impl BingxCommandTranslator {
@@ -155,9 +155,9 @@ macro_rules! impl_new_constructor {
             ///
             /// # Arguments
             ///
-            /// * `tx` - The sending part of a channel
+            /// * `tx` - The sending part of a channel, or a [`FrameSink`]($crate::FrameSink)
             /// * `url` - Optional server url, usually you don't need specify it
-            pub async fn new(tx: std::sync::mpsc::Sender<String>, url: Option<&str>) -> Self {
+            pub async fn new(tx: impl Into<$crate::FrameOutput>, url: Option<&str>) -> Self {
                 let real_url = match url {
                     Some(endpoint) => endpoint,
                     None => $default_url,
@@ -168,6 +168,27 @@ macro_rules! impl_new_constructor {
                     translator: $translator,
                 }
             }
+
+            /// Creates a websocket client with custom TLS settings, e.g. extra root
+            /// certificates for a TLS-inspecting proxy.
+            pub async fn new_with_tls(
+                tx: impl Into<$crate::FrameOutput>,
+                url: Option<&str>,
+                tls: $crate::TlsConfig,
+            ) -> Self {
+                $struct_name {
+                    client: WSClientInternal::connect_with_tls(
+                        $exchange,
+                        url.unwrap_or($default_url),
+                        $handler,
+                        None,
+                        Some(tls),
+                        tx,
+                    )
+                    .await,
+                    translator: $translator,
+                }
+            }
         }
     };
 }
@@ -231,6 +252,54 @@ macro_rules! impl_ws_client_trait {
             async fn close(&self) {
                 self.client.close().await;
             }
+
+            async fn close_with(&self, code: u16, reason: &str) {
+                self.client.close_with(code, reason).await;
+            }
+
+            fn health_status(&self) -> Option<$crate::HealthStatus> {
+                Some(self.client.get_health_status())
+            }
+
+            fn active_subscriptions(&self) -> Vec<String> {
+                self.client.active_subscriptions()
+            }
+
+            fn connection_url(&self) -> Option<&str> {
+                Some(self.client.url())
+            }
+
+            fn set_parse_workers(&self, workers: usize) {
+                self.client.set_parse_workers(workers);
+            }
+
+            fn set_max_frame_size(&self, bytes: usize) {
+                self.client.set_max_frame_size(bytes);
+            }
+
+            fn set_auto_reconnect(&self, enabled: bool) {
+                self.client.set_auto_reconnect(enabled);
+            }
+
+            fn set_heartbeat_messages(&self, enabled: bool) {
+                self.client.set_heartbeat_messages(enabled);
+            }
+
+            fn set_raw_frames(&self, enabled: bool) {
+                self.client.set_raw_frames(enabled);
+            }
+
+            fn set_undecodable_passthrough(&self, enabled: bool) {
+                self.client.set_undecodable_passthrough(enabled);
+            }
+
+            fn record_parse_failure(&self) {
+                self.client.record_parse_failure();
+            }
+
+            fn set_on_reconnect(&self, hook: $crate::ReconnectHook) {
+                self.client.set_on_reconnect(hook);
+            }
         }
     };
 }
@@ -184,6 +184,10 @@ impl<const URL: char> WSClient for HuobiWSClient<URL> {
     async fn close(&self) {
         self.client.close().await;
     }
+
+    async fn close_with(&self, code: u16, reason: &str) {
+        self.client.close_with(code, reason).await;
+    }
 }
 
 struct HuobiMessageHandler {}
@@ -2,13 +2,15 @@ use async_trait::async_trait;
 use log::*;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 use tokio_tungstenite::tungstenite::Message;
 
 use crate::common::command_translator::CommandTranslator;
+use crate::{per_message_debug, per_message_info};
 use crate::common::message_handler::{MessageHandler, MiscMessage};
-use crate::common::ws_client_internal::WSClientInternal;
-use crate::WSClient;
+use crate::common::ws_client_internal::{FrameOutput, WSClientInternal};
+use crate::{TlsConfig, WSClient};
 
 const EXCHANGE_NAME: &str = "mexc";
 
@@ -18,6 +20,9 @@ pub(super) const SPOT_WEBSOCKET_URL: &str = "wss://wbs.mexc.com/ws";
 // URL для User Data Stream
 pub(super) const USER_DATA_STREAM_BASE_URL: &str = "wss://wbs-api.mexc.com/ws";
 
+/// Время жизни listenKey MEXC без продления
+pub const LISTEN_KEY_TTL: Duration = Duration::from_secs(60 * 60);
+
 pub struct MexcSpotWSClient {
     client: WSClientInternal<MexcMessageHandler>,
 }
@@ -28,6 +33,32 @@ pub struct MexcSpotWSClient {
 /// и получает приватные данные аккаунта автоматически
 pub struct MexcUserDataStreamWSClient {
     client: WSClientInternal<MexcUserDataStreamMessageHandler>,
+    listen_key_status: Mutex<ListenKeyStatus>,
+}
+
+/// Жизненный цикл listenKey User Data Stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListenKeyStatus {
+    /// Когда клиент начал использовать ключ
+    pub created_at: SystemTime,
+    /// Последнее продление ключа; до первого продления совпадает с `created_at`
+    pub last_refreshed: SystemTime,
+    /// Когда ключ истечёт, если его не продлить
+    pub expires_at: SystemTime,
+}
+
+impl ListenKeyStatus {
+    fn new(created_at: SystemTime) -> Self {
+        ListenKeyStatus {
+            created_at,
+            last_refreshed: created_at,
+            expires_at: created_at + LISTEN_KEY_TTL,
+        }
+    }
+
+    fn refreshed(self, at: SystemTime) -> Self {
+        ListenKeyStatus { last_refreshed: at, expires_at: at + LISTEN_KEY_TTL, ..self }
+    }
 }
 
 impl MexcUserDataStreamWSClient {
@@ -38,7 +69,7 @@ impl MexcUserDataStreamWSClient {
     /// * `listen_key` - Ключ, полученный через REST API POST /api/v3/userDataStream
     /// * `tx` - Канал для отправки полученных сообщений
     /// * `proxy` - Опциональный прокси
-    pub async fn new(listen_key: &str, tx: Sender<String>, _proxy: Option<String>) -> MexcUserDataStreamWSClient {
+    pub async fn new(listen_key: &str, tx: impl Into<FrameOutput>, _proxy: Option<String>) -> MexcUserDataStreamWSClient {
         let url = format!("{}?listenKey={}", USER_DATA_STREAM_BASE_URL, listen_key);
         
         info!("Подключение к MEXC User Data Stream: {}", url);
@@ -52,14 +83,20 @@ impl MexcUserDataStreamWSClient {
             warn!("Listen key кажется слишком коротким ({}), проверьте правильность", listen_key.len());
         }
         
+        Self::connect(&url, tx).await
+    }
+
+    /// Подключиться к User Data Stream по готовому адресу с listenKey
+    pub(crate) async fn connect(url: &str, tx: impl Into<FrameOutput>) -> MexcUserDataStreamWSClient {
         MexcUserDataStreamWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
-                &url,
+                url,
                 MexcUserDataStreamMessageHandler {},
                 None,
                 tx,
             ).await,
+            listen_key_status: Mutex::new(ListenKeyStatus::new(SystemTime::now())),
         }
     }
 
@@ -150,6 +187,72 @@ impl MexcUserDataStreamWSClient {
     pub async fn close(&self) {
         self.client.close().await;
     }
+
+    /// Текущее состояние соединения User Data Stream
+    pub fn health_status(&self) -> crate::HealthStatus {
+        self.client.get_health_status()
+    }
+
+    /// Учесть кадр User Data Stream, который не удалось разобрать
+    pub fn record_parse_failure(&self) {
+        self.client.record_parse_failure();
+    }
+
+    /// Кадры heartbeat на каждый pong (см. [`WSClient::set_heartbeat_messages`])
+    pub fn set_heartbeat_messages(&self, enabled: bool) {
+        self.client.set_heartbeat_messages(enabled);
+    }
+
+    /// Исходные байты бинарных кадров (см. [`WSClient::set_raw_frames`])
+    pub fn set_raw_frames(&self, enabled: bool) {
+        self.client.set_raw_frames(enabled);
+    }
+
+    /// Пул декодирования бинарных кадров (см. [`WSClient::set_parse_workers`])
+    pub fn set_parse_workers(&self, workers: usize) {
+        self.client.set_parse_workers(workers);
+    }
+
+    /// Передача нераспознанных бинарных кадров (см.
+    /// [`WSClient::set_undecodable_passthrough`])
+    pub fn set_undecodable_passthrough(&self, enabled: bool) {
+        self.client.set_undecodable_passthrough(enabled);
+    }
+
+    /// Текущий жизненный цикл listenKey: когда получен, продлён и истечёт.
+    ///
+    /// Если `expires_at` приближается, а `last_refreshed` не меняется, значит
+    /// keep-alive перестал продлевать ключ.
+    pub fn listen_key_status(&self) -> ListenKeyStatus {
+        *self.listen_key_status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Отметить успешное продление listenKey (`PUT /api/v3/userDataStream`).
+    ///
+    /// Вызывается задачей keep-alive после успешного ответа биржи (см.
+    /// `WsClientWrapper::spawn_listen_key_keepalive` в crypto-client); срок действия
+    /// ключа отсчитывается заново от момента вызова.
+    pub fn record_listen_key_refresh(&self) {
+        let mut status =
+            self.listen_key_status.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *status = status.refreshed(SystemTime::now());
+    }
+
+    /// Команды подписки User Data Stream, сохранённые для переподключения
+    pub fn active_subscriptions(&self) -> Vec<String> {
+        self.client.active_subscriptions()
+    }
+
+    /// Адрес User Data Stream вместе с listenKey
+    pub fn connection_url(&self) -> &str {
+        self.client.url()
+    }
+
+    /// Хук переподключения, например для проверки актуальности listenKey
+    /// (см. [`WSClient::set_on_reconnect`])
+    pub fn set_on_reconnect(&self, hook: crate::ReconnectHook) {
+        self.client.set_on_reconnect(hook);
+    }
 }
 
 #[derive(Clone)]
@@ -162,7 +265,7 @@ pub struct MexcUserDataStreamMessageHandler {}
 pub struct MexcCommandTranslator {}
 
 impl MexcSpotWSClient {
-    pub async fn new(tx: Sender<String>, _proxy: Option<String>) -> MexcSpotWSClient {
+    pub async fn new(tx: impl Into<FrameOutput>, _proxy: Option<String>) -> MexcSpotWSClient {
         MexcSpotWSClient {
             client: WSClientInternal::connect(
                 EXCHANGE_NAME,
@@ -173,6 +276,20 @@ impl MexcSpotWSClient {
             ).await,
         }
     }
+
+    /// Создать клиент с собственными настройками TLS
+    pub async fn new_with_tls(tx: impl Into<FrameOutput>, tls: TlsConfig) -> MexcSpotWSClient {
+        MexcSpotWSClient {
+            client: WSClientInternal::connect_with_tls(
+                EXCHANGE_NAME,
+                SPOT_WEBSOCKET_URL,
+                MexcMessageHandler {},
+                None,
+                Some(tls),
+                tx,
+            ).await,
+        }
+    }
 }
 
 #[async_trait]
@@ -207,8 +324,13 @@ impl WSClient for MexcSpotWSClient {
         panic!("MEXC does not have level3 orderbook");
     }
 
-    async fn subscribe_ticker(&self, _symbols: &[String]) {
-        panic!("MEXC spot does not have ticker channel");
+    async fn subscribe_ticker(&self, symbols: &[String]) {
+        // Отдельного ticker канала у MEXC Spot нет, используем miniTicker
+        let commands = symbols.iter()
+            .map(|symbol| MexcCommandTranslator::v3_subscription_command("ticker", symbol))
+            .collect::<Vec<_>>();
+
+        self.client.send(&commands).await;
     }
 
     async fn subscribe_candlestick(&self, symbol_interval_list: &[(String, usize)]) {
@@ -297,6 +419,54 @@ impl WSClient for MexcSpotWSClient {
     async fn close(&self) {
         self.client.close().await;
     }
+
+    async fn close_with(&self, code: u16, reason: &str) {
+        self.client.close_with(code, reason).await;
+    }
+
+    fn health_status(&self) -> Option<crate::HealthStatus> {
+        Some(self.client.get_health_status())
+    }
+
+    fn active_subscriptions(&self) -> Vec<String> {
+        self.client.active_subscriptions()
+    }
+
+    fn connection_url(&self) -> Option<&str> {
+        Some(self.client.url())
+    }
+
+    fn set_parse_workers(&self, workers: usize) {
+        self.client.set_parse_workers(workers);
+    }
+
+    fn set_max_frame_size(&self, bytes: usize) {
+        self.client.set_max_frame_size(bytes);
+    }
+
+    fn set_auto_reconnect(&self, enabled: bool) {
+        self.client.set_auto_reconnect(enabled);
+    }
+
+    fn set_heartbeat_messages(&self, enabled: bool) {
+        self.client.set_heartbeat_messages(enabled);
+    }
+
+    fn set_raw_frames(&self, enabled: bool) {
+        self.client.set_raw_frames(enabled);
+    }
+
+    fn set_undecodable_passthrough(&self, enabled: bool) {
+        self.client.set_undecodable_passthrough(enabled);
+    }
+
+    fn record_parse_failure(&self) {
+        self.client.record_parse_failure();
+    }
+
+    fn set_on_reconnect(&self, hook: crate::ReconnectHook) {
+        self.client.set_on_reconnect(hook);
+    }
 }
 
 impl MexcCommandTranslator {
@@ -318,6 +488,10 @@ impl MexcCommandTranslator {
                 r#"{{"method":"SUBSCRIPTION","params":["spot@public.kline.v3.api@{}@Min1"]}}"#,
                 mexc_symbol
             ),
+            "ticker" | "miniTicker" => format!(
+                r#"{{"method":"SUBSCRIPTION","params":["spot@public.miniTicker.v3.api@{}@UTC+0"]}}"#,
+                mexc_symbol
+            ),
             _ => {
                 warn!("Неизвестный канал: {}", channel);
                 format!(
@@ -346,6 +520,10 @@ impl MexcCommandTranslator {
                 r#"{{"method":"UNSUBSCRIPTION","params":["spot@public.kline.v3.api@{}@Min1"]}}"#,
                 mexc_symbol
             ),
+            "ticker" | "miniTicker" => format!(
+                r#"{{"method":"UNSUBSCRIPTION","params":["spot@public.miniTicker.v3.api@{}@UTC+0"]}}"#,
+                mexc_symbol
+            ),
             _ => {
                 warn!("Неизвестный канал: {}", channel);
                 format!(
@@ -494,34 +672,37 @@ impl MessageHandler for MexcMessageHandler {
 
 impl MessageHandler for MexcUserDataStreamMessageHandler {
     fn handle_message(&mut self, msg: &str) -> MiscMessage {
-        debug!("User Data Stream получено сообщение: {}", msg);
+        per_message_debug!("User Data Stream получено сообщение: {}", msg);
         
         if msg == "PONG" {
-            debug!("Получен простой PONG ответ");
+            per_message_debug!("Получен простой PONG ответ");
             return MiscMessage::Pong;
         }
         
         // Проверяем на ping-сообщения
         if msg.trim() == r#"{"msg":"PING"}"# {
-            debug!("Получен PING, отправляем PONG");
+            per_message_debug!("Получен PING, отправляем PONG");
             return MiscMessage::WebSocket(Message::Text(r#"{"method":"PONG"}"#.to_string()));
         }
         
         // Обрабатываем JSON сообщения
         if let Ok(obj) = serde_json::from_str::<HashMap<String, Value>>(msg) {
-            debug!("Разобрано JSON сообщение с полями: {:?}", obj.keys().collect::<Vec<_>>());
+            per_message_debug!(
+                "Разобрано JSON сообщение с полями: {:?}",
+                obj.keys().collect::<Vec<_>>()
+            );
             
             // Проверяем PONG ответы
             if let Some(method) = obj.get("method") {
                 if method.as_str() == Some("PONG") {
-                    debug!("Получен JSON PONG с методом");
+                    per_message_debug!("Получен JSON PONG с методом");
                     return MiscMessage::Pong;
                 }
             }
             
             if let Some(msg_val) = obj.get("msg") {
                 if msg_val.as_str() == Some("PONG") {
-                    debug!("Получен JSON PONG с полем msg");
+                    per_message_debug!("Получен JSON PONG с полем msg");
                     return MiscMessage::Pong;
                 }
             }
@@ -545,16 +726,16 @@ impl MessageHandler for MexcUserDataStreamMessageHandler {
             // Проверяем данные аккаунта по полю "channel"
             if let Some(channel) = obj.get("channel").and_then(|v| v.as_str()) {
                 if channel == "spot@private.account.v3.api.pb" {
-                    info!("Получены данные баланса аккаунта через User Data Stream");
-                    debug!("Данные баланса: {}", msg);
+                    per_message_info!("Получены данные баланса аккаунта через User Data Stream");
+                    per_message_debug!("Данные баланса: {}", msg);
                     return MiscMessage::Normal;
                 }
                 if channel == "spot@private.deals.v3.api.pb" {
-                    info!("Получены данные приватных сделок через User Data Stream");
-                    debug!("Данные приватных сделок: {}", msg);
+                    per_message_info!("Получены данные приватных сделок через User Data Stream");
+                    per_message_debug!("Данные приватных сделок: {}", msg);
                     return MiscMessage::Normal;
                 }
-                debug!("Получен неизвестный канал: {}", channel);
+                per_message_debug!("Получен неизвестный канал: {}", channel);
             }
             
             // Проверяем на ошибки
@@ -564,7 +745,7 @@ impl MessageHandler for MexcUserDataStreamMessageHandler {
             }
             
             // Все остальные сообщения считаем нормальными данными
-            debug!("Обработка сообщения как обычных данных");
+            per_message_debug!("Обработка сообщения как обычных данных");
             return MiscMessage::Normal;
         } else {
             warn!("Не удалось разобрать сообщение как JSON: {}", msg);
@@ -604,6 +785,12 @@ mod tests {
             kline_cmd,
             r#"{"method":"SUBSCRIPTION","params":["spot@public.kline.v3.api@LTCUSDT@Min1"]}"#
         );
+
+        let ticker_cmd = MexcCommandTranslator::v3_subscription_command("ticker", "BTC_USDT");
+        assert_eq!(
+            ticker_cmd,
+            r#"{"method":"SUBSCRIPTION","params":["spot@public.miniTicker.v3.api@BTCUSDT@UTC+0"]}"#
+        );
     }
 
     #[test]
@@ -616,6 +803,30 @@ mod tests {
         assert_eq!(actual_url, "ws://wbs-api.mexc.com/ws?listenKey=test_listen_key_123");
     }
 
+    #[tokio::test]
+    async fn test_listen_key_status_after_refresh() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/ws?listenKey=test_listen_key", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = MexcUserDataStreamWSClient::connect(&url, tx).await;
+        let status = client.listen_key_status();
+        assert_eq!(status.last_refreshed, status.created_at);
+        assert_eq!(status.expires_at, status.created_at + LISTEN_KEY_TTL);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        client.record_listen_key_refresh();
+        let refreshed = client.listen_key_status();
+        assert_eq!(refreshed.created_at, status.created_at);
+        assert!(refreshed.last_refreshed > status.last_refreshed);
+        assert_eq!(refreshed.expires_at, refreshed.last_refreshed + LISTEN_KEY_TTL);
+    }
+
     #[test]
     fn test_candlestick_commands() {
         let translator = MexcCommandTranslator {};
@@ -188,4 +188,49 @@ mod tests {
             commands[0]
         );
     }
+
+    #[test]
+    fn test_candlestick_intervals() {
+        let translator = super::MexcCommandTranslator {};
+        let commands = translator.translate_to_candlestick_commands(
+            true,
+            &[("BTC_USDT".to_string(), 3600), ("ETH_USDT".to_string(), 86400)],
+        );
+
+        assert_eq!(2, commands.len());
+        assert_eq!(
+            r#"{"method":"sub.kline","param":{"symbol":"BTC_USDT","interval":"Min60"}}"#,
+            commands[0]
+        );
+        assert_eq!(
+            r#"{"method":"sub.kline","param":{"symbol":"ETH_USDT","interval":"Day1"}}"#,
+            commands[1]
+        );
+
+        let commands =
+            translator.translate_to_candlestick_commands(false, &[("BTC_USDT".to_string(), 300)]);
+        assert_eq!(
+            r#"{"method":"unsub.kline","param":{"symbol":"BTC_USDT","interval":"Min5"}}"#,
+            commands[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_url_reports_override() {
+        use crate::WSClient;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = super::MexcSwapWSClient::new(tx, Some(&url)).await;
+
+        assert_eq!(client.connection_url(), Some(url.as_str()));
+        assert_ne!(client.connection_url(), Some(super::SWAP_WEBSOCKET_URL));
+    }
 }
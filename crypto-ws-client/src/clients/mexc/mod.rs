@@ -4,6 +4,8 @@ pub mod protobuf;
 
 pub const EXCHANGE_NAME: &str = "mexc";
 
-pub use mexc_spot::{MexcSpotWSClient, MexcUserDataStreamWSClient};
+pub use mexc_spot::{
+    ListenKeyStatus, MexcSpotWSClient, MexcUserDataStreamWSClient, LISTEN_KEY_TTL,
+};
 pub use mexc_swap::MexcSwapWSClient;
 pub use protobuf::decode_mexc_protobuf;
@@ -31,6 +31,13 @@ pub mod mexc_proto {
         pub taker_order_side: i32,
     }
 
+    /// Пакет сделок агрегированного канала `spot@public.aggre.deals.v3.api.pb`
+    #[derive(Clone, PartialEq, Message)]
+    pub struct PublicDealsBatch {
+        #[prost(message, repeated, tag = "1")]
+        pub deals: Vec<Deal>,
+    }
+
     #[derive(Clone, PartialEq, Message)]
     pub struct DepthData {
         #[prost(string, tag = "1")]
@@ -41,6 +48,12 @@ pub mod mexc_proto {
         pub bids: Vec<PriceLevel>,
         #[prost(int64, tag = "4")]
         pub version: i64,
+        /// Первая версия, вошедшая в обновление (агрегированная глубина)
+        #[prost(int64, optional, tag = "5")]
+        pub from_version: Option<i64>,
+        /// Последняя версия, вошедшая в обновление (агрегированная глубина)
+        #[prost(int64, optional, tag = "6")]
+        pub to_version: Option<i64>,
     }
 
     #[derive(Clone, PartialEq, Message)]
@@ -153,6 +166,8 @@ pub mod mexc_proto {
             PrivateAccount(AccountData),
             #[prost(message, tag = "308")]
             PublicSpotKline(KlineData),
+            #[prost(message, tag = "314")]
+            PublicDealsBatch(PublicDealsBatch),
         }
     }
 }
@@ -231,6 +246,37 @@ fn wrapper_to_json(wrapper: &PushDataV3ApiWrapper) -> Result<String, Box<dyn std
                 "t": wrapper.send_time.unwrap_or(deal.time)
             })
         }
+        Some(push_data_v3_api_wrapper::Body::PublicDealsBatch(batch)) => {
+            // Формат JSON канала deals: одна сделка кадра превращается в один элемент d.deals
+            let deals = batch
+                .deals
+                .iter()
+                .map(|deal| {
+                    json!({
+                        "p": deal.price,
+                        "v": deal.quantity,
+                        "S": deal.taker_order_side,
+                        "t": deal.time
+                    })
+                })
+                .collect::<Vec<_>>();
+            let symbol = wrapper
+                .symbol
+                .clone()
+                .or_else(|| batch.deals.first().map(|deal| deal.symbol.clone()))
+                .unwrap_or_default();
+            let last_time = batch.deals.iter().map(|deal| deal.time).max().unwrap_or_default();
+
+            json!({
+                "c": wrapper.channel,
+                "d": {
+                    "deals": deals,
+                    "e": "spot@public.aggre.deals.v3.api"
+                },
+                "s": symbol,
+                "t": wrapper.send_time.unwrap_or(last_time)
+            })
+        }
         Some(push_data_v3_api_wrapper::Body::PrivateAccount(account)) => {
             let balances = account
                 .balances
@@ -273,7 +319,9 @@ fn wrapper_to_json(wrapper: &PushDataV3ApiWrapper) -> Result<String, Box<dyn std
                     "symbol": depth.symbol,
                     "asks": asks,
                     "bids": bids,
-                    "version": depth.version
+                    "version": depth.version,
+                    "fromVersion": depth.from_version,
+                    "toVersion": depth.to_version
                 },
                 "t": wrapper.send_time.unwrap_or_else(|| {
                     std::time::SystemTime::now()
@@ -489,7 +537,9 @@ fn protobuf_to_json(ws_message: &WsMessage) -> Result<String, Box<dyn std::error
                 "symbol": depth_data.symbol,
                 "asks": asks,
                 "bids": bids,
-                "version": depth_data.version
+                "version": depth_data.version,
+                "fromVersion": depth_data.from_version,
+                "toVersion": depth_data.to_version
             })
         } else {
             return Err("Failed to decode depth data from protobuf".into());
@@ -601,7 +651,9 @@ fn depth_to_json(depth: &DepthData) -> Result<String, Box<dyn std::error::Error>
             "symbol": depth.symbol,
             "asks": asks,
             "bids": bids,
-            "version": depth.version
+            "version": depth.version,
+            "fromVersion": depth.from_version,
+            "toVersion": depth.to_version
         },
         "t": std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1091,5 +1143,86 @@ mod tests {
         println!("✅ Официальная схема MEXC PushDataV3ApiWrapper работает корректно!");
         println!("JSON результат: {}", json_result);
     }
-}
 
+    #[test]
+    fn test_depth_version_range_round_trip() {
+        use push_data_v3_api_wrapper::Body;
+
+        let depth = DepthData {
+            symbol: "BTCUSDT".to_string(),
+            asks: vec![PriceLevel { price: "50001.00".to_string(), quantity: "0.5".to_string() }],
+            bids: vec![PriceLevel { price: "50000.00".to_string(), quantity: "0".to_string() }],
+            version: 105,
+            from_version: Some(103),
+            to_version: Some(105),
+        };
+        let mut buf = Vec::new();
+        depth.encode(&mut buf).unwrap();
+        assert_eq!(DepthData::decode(&buf[..]).unwrap(), depth);
+
+        let wrapper = PushDataV3ApiWrapper {
+            channel: "spot@public.aggre.depth.v3.api.pb@100ms@BTCUSDT".to_string(),
+            body: Some(Body::PublicIncreaseDepths(depth.clone())),
+            symbol: Some("BTCUSDT".to_string()),
+            symbol_id: None,
+            create_time: None,
+            send_time: Some(1736417034332),
+        };
+        let mut buf = Vec::new();
+        wrapper.encode(&mut buf).unwrap();
+
+        let parsed: Value = serde_json::from_str(&decode_mexc_protobuf(&buf).unwrap()).unwrap();
+        assert_eq!(parsed["d"]["fromVersion"], 103);
+        assert_eq!(parsed["d"]["toVersion"], 105);
+        assert_eq!(parsed["d"]["asks"][0][0], "50001.00");
+
+        // Обновление без диапазона версий
+        let parsed: Value = serde_json::from_str(
+            &depth_to_json(&DepthData { from_version: None, to_version: None, ..depth }).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(parsed["d"]["version"], 105);
+        assert!(parsed["d"]["fromVersion"].is_null());
+    }
+
+    #[test]
+    fn test_public_deals_batch_round_trip() {
+        use push_data_v3_api_wrapper::Body;
+
+        let deal = |price: &str, quantity: &str, time: i64, side: i32| Deal {
+            symbol: "BTCUSDT".to_string(),
+            price: price.to_string(),
+            quantity: quantity.to_string(),
+            time,
+            taker_order_side: side,
+        };
+        let batch = PublicDealsBatch {
+            deals: vec![
+                deal("50000.00", "0.1", 1736417034100, 1),
+                deal("50000.50", "0.2", 1736417034200, 2),
+                deal("50001.00", "0.3", 1736417034300, 1),
+            ],
+        };
+        let wrapper = PushDataV3ApiWrapper {
+            channel: "spot@public.aggre.deals.v3.api.pb@100ms@BTCUSDT".to_string(),
+            body: Some(Body::PublicDealsBatch(batch.clone())),
+            symbol: Some("BTCUSDT".to_string()),
+            symbol_id: None,
+            create_time: None,
+            send_time: Some(1736417034332),
+        };
+        let mut buf = Vec::new();
+        wrapper.encode(&mut buf).unwrap();
+        assert_eq!(PushDataV3ApiWrapper::decode(&buf[..]).unwrap(), wrapper);
+
+        let parsed: Value = serde_json::from_str(&decode_mexc_protobuf(&buf).unwrap()).unwrap();
+        let deals = parsed["d"]["deals"].as_array().unwrap();
+        assert_eq!(deals.len(), 3);
+        assert_eq!(deals[0]["p"], "50000.00");
+        assert_eq!(deals[1]["v"], "0.2");
+        assert_eq!(deals[1]["S"], 2);
+        assert_eq!(deals[2]["t"], 1736417034300_i64);
+        assert_eq!(parsed["s"], "BTCUSDT");
+        assert_eq!(parsed["t"], 1736417034332_i64);
+    }
+}
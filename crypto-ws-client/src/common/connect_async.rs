@@ -14,6 +14,8 @@ use tokio_tungstenite::{
     tungstenite::{Error, Message},
 };
 
+use super::tls::TlsConfig;
+
 /// Wraps a websocket client inside an event loop, returns a message_rx to
 /// receive messages and a command_tx to send commands to the websocket server.
 ///
@@ -21,10 +23,14 @@ use tokio_tungstenite::{
 /// command_tx.
 ///
 /// `limit`, max number of uplink messsages, for example, 100 per 10 seconds
+///
+/// `tls`, custom TLS settings; `None` uses the default secure connector
 pub async fn connect_async(
     url: &str,
     uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
+    tls: Option<&TlsConfig>,
 ) -> Result<(Receiver<Message>, Sender<Message>), Error> {
+    let connector = tls.map(TlsConfig::connector).transpose()?;
     if let Ok(proxy_env) = env::var("https_proxy").or_else(|_| env::var("http_proxy")) {
         let proxy_url = Url::parse(&proxy_env).unwrap();
         let proxy_scheme = proxy_url.scheme().to_lowercase();
@@ -49,12 +55,19 @@ pub async fn connect_async(
         )
         .await
         .unwrap();
-        let (ws_stream, _) = tokio_tungstenite::client_async_tls(connect_url, proxy_stream).await?;
+        let (ws_stream, _) = tokio_tungstenite::client_async_tls_with_config(
+            connect_url,
+            proxy_stream,
+            None,
+            connector,
+        )
+        .await?;
         // replaced
         // let ret = tokio_tungstenite::connect_async(url).await;
         connect_async_internal(ws_stream, uplink_limit).await
     } else {
-        let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
+        let (ws_stream, _) =
+            tokio_tungstenite::connect_async_tls_with_config(url, None, connector).await?;
 
         connect_async_internal(ws_stream, uplink_limit).await
     }
@@ -84,7 +97,7 @@ async fn connect_async_internal<S: AsyncRead + AsyncWrite + Unpin + Send + 'stat
                   Some(command) => {
                     match command {
                       Message::Close(resp) => {
-                        match resp {
+                        match &resp {
                             Some(frame) => {
                                 warn!(
                                     "Received a CloseFrame: code: {}, reason: {}",
@@ -93,6 +106,10 @@ async fn connect_async_internal<S: AsyncRead + AsyncWrite + Unpin + Send + 'stat
                             }
                             None => warn!("Received an empty close message"),
                         }
+                        // Отправляем кадр закрытия, чтобы биржа получила код и причину
+                        if let Err(err) = write.send(Message::Close(resp)).await {
+                          debug!("Failed to send close frame, error: {}", err);
+                        }
                         break; // close the connection and break the loop
                       }
                       _ => {
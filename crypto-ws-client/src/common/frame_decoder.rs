@@ -0,0 +1,348 @@
+use std::io::{Error, ErrorKind, Read, Result};
+
+use base64::Engine;
+use flate2::read::{DeflateDecoder, GzDecoder};
+use log::*;
+use serde_json::Value;
+
+use crate::{per_message_debug, per_message_info};
+
+/// Поле декодированного кадра с исходными байтами в base64 (см. [`take_raw_frame`])
+const RAW_FRAME_KEY: &str = "raw_frame";
+
+/// Декодирует бинарный WebSocket фрейм биржи в текст (как правило JSON).
+///
+/// Применяет тот же конвейер распаковки, что и основной цикл клиента: gzip для
+/// Huobi/Binance/Bitget/Bitz, deflate для OKX, а для MEXC — автоопределение
+/// формата (Protocol Buffers, gzip, zlib, несжатый JSON, raw deflate).
+/// Удобно для офлайн-разбора сохраненных фреймов.
+///
+/// Для бирж без бинарного протокола возвращает ошибку с `ErrorKind::Unsupported`.
+pub fn decode_frame(exchange: &str, binary: &[u8]) -> Result<String> {
+    let mut txt = String::new();
+    match exchange {
+        crate::clients::huobi::EXCHANGE_NAME
+        | crate::clients::binance::EXCHANGE_NAME
+        | "bitget"
+        | "bitz" => {
+            let mut decoder = GzDecoder::new(binary);
+            decoder.read_to_string(&mut txt)?;
+            Ok(txt)
+        }
+        crate::clients::okx::EXCHANGE_NAME => {
+            let mut decoder = DeflateDecoder::new(binary);
+            decoder.read_to_string(&mut txt)?;
+            Ok(txt)
+        }
+        crate::clients::mexc::EXCHANGE_NAME => decode_mexc_frame(binary),
+        _ => Err(Error::new(
+            ErrorKind::Unsupported,
+            format!("Unknown binary format from {}", exchange),
+        )),
+    }
+}
+
+// MEXC может использовать разные форматы, попробуем несколько вариантов
+fn decode_mexc_frame(binary: &[u8]) -> Result<String> {
+    if binary.is_empty() {
+        error!("MEXC received empty binary data");
+        return Err(Error::new(ErrorKind::InvalidData, "Empty binary data from MEXC"));
+    }
+
+    // Попробуем определить формат данных по первым байтам
+    per_message_debug!(
+        "MEXC binary data - первые 10 байт: {:?}",
+        &binary[..std::cmp::min(10, binary.len())]
+    );
+
+    // Проверяем типичные заголовки сжатия СНАЧАЛА
+    let is_gzip = binary.len() >= 2 && binary[0] == 0x1f && binary[1] == 0x8b;
+    let is_deflate_zlib = binary.len() >= 2
+        && binary[0] == 0x78
+        && (binary[1] == 0x01 || binary[1] == 0x9c || binary[1] == 0xda);
+
+    // Улучшенное определение Protocol Buffers
+    // Protobuf часто начинается с varint field number + wire type
+    // Первые байты [10, 30] = field 1, wire type 2 (length-delimited), length 30
+    let is_likely_protobuf = binary.len() >= 4
+        && !is_gzip
+        && !is_deflate_zlib
+        && (
+            // Типичные protobuf паттерны
+            (binary[0] == 0x08 && binary[1] < 0x80) || // field 1, varint
+            (binary[0] == 0x0a && binary[1] < 0x80) || // field 1, length-delimited
+            (binary[0] == 0x10 && binary[1] < 0x80) || // field 2, varint
+            (binary[0] == 0x12 && binary[1] < 0x80) || // field 2, length-delimited
+            // Специально для данного случая: [10, 30, "spot@private..."]
+            (binary[0] == 0x0a && binary.len() > 10 && binary[2..].starts_with(b"spot@"))
+        );
+
+    per_message_debug!(
+        "MEXC binary analysis: is_gzip={}, is_deflate_zlib={}, is_likely_protobuf={}",
+        is_gzip,
+        is_deflate_zlib,
+        is_likely_protobuf
+    );
+
+    if is_likely_protobuf {
+        // Определенно Protocol Buffers данные
+        per_message_info!("🔍 MEXC: Обнаружены Protocol Buffers данные (длина: {})", binary.len());
+
+        match crate::clients::mexc::decode_mexc_protobuf(binary) {
+            Ok(json_string) => {
+                per_message_info!("✅ Успешно декодированы protobuf данные в JSON");
+                per_message_debug!("Декодированный JSON: {}", json_string);
+                Ok(json_string)
+            }
+            Err(decode_err) => {
+                // Если декодирование не удалось, показываем диагностику
+                warn!("❌ Не удалось декодировать protobuf данные: {}", decode_err);
+
+                // Попробуем извлечь информацию о канале из protobuf для диагностики
+                if binary.len() > 10 && binary[0] == 0x0a {
+                    let channel_length = binary[1] as usize;
+                    if binary.len() > 2 + channel_length {
+                        if let Ok(channel_name) =
+                            String::from_utf8(binary[2..2 + channel_length].to_vec())
+                        {
+                            warn!("📡 Канал протобуф: '{}'", channel_name);
+                            if channel_name.contains(".pb") {
+                                warn!("💡 Совет: возможно используется другая protobuf схема");
+                                warn!(
+                                    "   Рекомендация: используйте JSON канал '{}'",
+                                    channel_name.replace(".pb", "")
+                                );
+                            }
+                        }
+                    }
+                }
+
+                warn!("📖 См. README_mexc_websocket_troubleshooting.md для подробностей");
+
+                Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Protocol Buffers decoding failed: {}", decode_err),
+                ))
+            }
+        }
+    } else if is_gzip {
+        // Данные сжаты gzip
+        per_message_debug!("Trying GZIP decompression for MEXC");
+        let mut decompressed = Vec::new();
+        GzDecoder::new(binary).read_to_end(&mut decompressed)?;
+        decode_mexc_decompressed(decompressed)
+    } else if is_deflate_zlib {
+        // Данные сжаты deflate/zlib
+        per_message_debug!("Trying DEFLATE decompression for MEXC");
+        let mut decompressed = Vec::new();
+        DeflateDecoder::new(binary).read_to_end(&mut decompressed)?;
+        decode_mexc_decompressed(decompressed)
+    } else {
+        // Возможно это несжатые JSON данные
+        per_message_debug!("Trying raw UTF-8 parsing for MEXC");
+        match String::from_utf8(binary.to_vec()) {
+            Ok(utf8_string) => {
+                if utf8_string.trim().starts_with('{') || utf8_string.trim().starts_with('[') {
+                    // Это JSON данные
+                    Ok(utf8_string)
+                } else {
+                    // Не JSON и не protobuf - неизвестный формат
+                    warn!("MEXC: Неизвестный формат данных (длина: {})", binary.len());
+                    warn!("Первые 20 байт: {:?}", &binary[..std::cmp::min(20, binary.len())]);
+
+                    Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Unknown data format - not JSON, not protobuf, not compressed",
+                    ))
+                }
+            }
+            Err(utf8_error) => {
+                // Не UTF-8, последняя попытка - raw deflate
+                per_message_debug!("Trying raw DEFLATE decompression for MEXC");
+                let mut decompressed = Vec::new();
+                match DeflateDecoder::new(binary).read_to_end(&mut decompressed) {
+                    Ok(_) => {
+                        if is_json(&decompressed) {
+                            per_message_debug!("Successfully decompressed with raw DEFLATE");
+                            return Ok(String::from_utf8_lossy(&decompressed).into_owned());
+                        }
+                        // Сжатый protobuf
+                        crate::clients::mexc::decode_mexc_protobuf(&decompressed).map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidData,
+                                "Raw DEFLATE produced non-JSON content",
+                            )
+                        })
+                    }
+                    Err(_) => {
+                        // Все методы не сработали - возможно это протобуф, который мы не распознали
+                        warn!("MEXC: Все методы декомпрессии не сработали");
+                        warn!("Возможно это протобуф данные или неподдерживаемый формат");
+                        warn!("Данные: длина={}, UTF-8 ошибка: {}", binary.len(), utf8_error);
+
+                        Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!("All decompression methods failed: {}", utf8_error),
+                        ))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Начинаются ли данные с JSON объекта или массива
+fn is_json(bytes: &[u8]) -> bool {
+    matches!(bytes.trim_ascii_start().first(), Some(b'{' | b'['))
+}
+
+/// Разбор распакованного gzip/deflate кадра MEXC.
+///
+/// MEXC иногда сжимает protobuf: если распакованные данные не JSON, они
+/// декодируются как protobuf, а при неудаче возвращаются как текст.
+fn decode_mexc_decompressed(decompressed: Vec<u8>) -> Result<String> {
+    if is_json(&decompressed) {
+        return String::from_utf8(decompressed).map_err(|e| Error::new(ErrorKind::InvalidData, e));
+    }
+    match crate::clients::mexc::decode_mexc_protobuf(&decompressed) {
+        Ok(json_string) => {
+            per_message_debug!(
+                "MEXC: декодирован сжатый protobuf кадр (длина: {})",
+                decompressed.len()
+            );
+            Ok(json_string)
+        }
+        Err(decode_err) => String::from_utf8(decompressed).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Decompressed data is neither JSON nor protobuf: {}", decode_err),
+            )
+        }),
+    }
+}
+
+/// Добавить к декодированному JSON объекту исходный бинарный кадр в поле `raw_frame`.
+///
+/// Кадры, которые декодировались не в JSON объект, возвращаются без изменений.
+pub(crate) fn attach_raw_frame(txt: String, binary: &[u8]) -> String {
+    match serde_json::from_str::<Value>(&txt) {
+        Ok(Value::Object(mut object)) => {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(binary);
+            object.insert(RAW_FRAME_KEY.to_string(), Value::String(encoded));
+            Value::Object(object).to_string()
+        }
+        _ => txt,
+    }
+}
+
+/// Кадр `{"undecodable_frame":{"exchange":..,"error":..,"raw_frame":..},"ts":..}` для
+/// бинарного кадра, который не удалось декодировать; `raw_frame` — исходные байты в base64.
+pub(crate) fn undecodable_frame(exchange: &str, binary: &[u8], error: &Error) -> String {
+    serde_json::json!({
+        "undecodable_frame": {
+            "exchange": exchange,
+            "error": error.to_string(),
+            RAW_FRAME_KEY: base64::engine::general_purpose::STANDARD.encode(binary),
+        },
+        "ts": chrono::Utc::now().timestamp_millis(),
+    })
+    .to_string()
+}
+
+/// Извлечь из разобранного кадра исходные байты, добавленные при включенном
+/// `WSClient::set_raw_frames`, и удалить служебное поле.
+pub fn take_raw_frame(data: &mut Value) -> Option<Vec<u8>> {
+    let encoded = data.as_object_mut()?.remove(RAW_FRAME_KEY)?;
+    base64::engine::general_purpose::STANDARD.decode(encoded.as_str()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_frame;
+    use crate::mexc::protobuf::mexc_proto::{
+        Deal, PrivateDealsV3Api, PushDataV3ApiWrapper, push_data_v3_api_wrapper::Body,
+    };
+    use prost::Message;
+    use serde_json::Value;
+    use std::io::{ErrorKind, Write};
+
+    #[test]
+    fn test_decode_mexc_protobuf_frame() {
+        let wrapper = PushDataV3ApiWrapper {
+            channel: "spot@private.deals.v3.api.pb".to_string(),
+            body: Some(Body::PrivateDeals(PrivateDealsV3Api {
+                price: "3.6962".to_string(),
+                quantity: "1".to_string(),
+                amount: "3.6962".to_string(),
+                trade_type: 2,
+                is_maker: false,
+                is_self_trade: false,
+                trade_id: "505979017439002624X1".to_string(),
+                client_order_id: "".to_string(),
+                order_id: "C02__505979017439002624115".to_string(),
+                fee_amount: "0.0003998377369698171".to_string(),
+                fee_currency: "MX".to_string(),
+                time: 1736417034280,
+            })),
+            symbol: Some("MXUSDT".to_string()),
+            symbol_id: None,
+            create_time: Some(1736417034332),
+            send_time: Some(1736417034332),
+        };
+        let mut frame = Vec::new();
+        wrapper.encode(&mut frame).unwrap();
+
+        let json = decode_frame("mexc", &frame).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["channel"], "spot@private.deals.v3.api.pb");
+        assert_eq!(parsed["symbol"], "MXUSDT");
+        assert_eq!(parsed["privateDeals"]["price"], "3.6962");
+        assert_eq!(parsed["privateDeals"]["orderId"], "C02__505979017439002624115");
+    }
+
+    #[test]
+    fn test_decode_gzip_and_unknown_frames() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(br#"{"ping":1}"#).unwrap();
+        let frame = encoder.finish().unwrap();
+
+        assert_eq!(decode_frame("huobi", &frame).unwrap(), r#"{"ping":1}"#);
+        assert_eq!(decode_frame("mexc", br#"{"ping":1}"#).unwrap(), r#"{"ping":1}"#);
+        assert_eq!(decode_frame("kraken", &frame).unwrap_err().kind(), ErrorKind::Unsupported);
+        assert!(decode_frame("mexc", &[]).is_err());
+    }
+
+    #[test]
+    fn test_decode_mexc_gzip_wrapped_protobuf_frame() {
+        let wrapper = PushDataV3ApiWrapper {
+            channel: "spot@public.deals.v3.api.pb@BTCUSDT".to_string(),
+            body: Some(Body::PublicDeals(Deal {
+                symbol: "BTCUSDT".to_string(),
+                price: "64125.37".to_string(),
+                quantity: "0.0123".to_string(),
+                time: 1736417034280,
+                taker_order_side: 2,
+            })),
+            symbol: Some("BTCUSDT".to_string()),
+            symbol_id: None,
+            create_time: Some(1736417034332),
+            send_time: Some(1736417034332),
+        };
+        let mut protobuf = Vec::new();
+        wrapper.encode(&mut protobuf).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&protobuf).unwrap();
+        let frame = encoder.finish().unwrap();
+
+        let json = decode_frame("mexc", &frame).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["c"], "spot@public.deals.v3.api.pb@BTCUSDT");
+        assert_eq!(parsed["d"]["price"], "64125.37");
+        assert_eq!(parsed["d"]["takerOrderSide"], 2);
+
+        // Сжатый JSON по-прежнему возвращается как есть
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(br#"{"ping":1}"#).unwrap();
+        assert_eq!(decode_frame("mexc", &encoder.finish().unwrap()).unwrap(), r#"{"ping":1}"#);
+    }
+}
@@ -19,4 +19,18 @@ pub(crate) trait MessageHandler {
     /// None means the client doesn't need to send ping, instead the server will
     /// send ping and the client just needs to reply a pong
     fn get_ping_msg_and_interval(&self) -> Option<(Message, u64)>;
+    /// Messages sent to the server right after the ping task is started,
+    /// e.g., an unsolicited pong frame to warm up the connection.
+    fn on_connect_actions(&self) -> Vec<Message> {
+        Vec::new()
+    }
+    /// Whether the client must answer a ping frame from the server with a pong frame.
+    fn should_pong_on_ping(&self) -> bool {
+        false
+    }
+    /// How often (in seconds) to check unanswered pings and send a probing pong frame.
+    /// None disables this extra health check.
+    fn health_check_interval(&self) -> Option<u64> {
+        None
+    }
 }
@@ -1,6 +1,10 @@
 pub(crate) mod command_translator;
 pub(crate) mod connect_async;
+pub(crate) mod frame_decoder;
 pub(crate) mod message_handler;
+pub(crate) mod parse_pool;
+pub(crate) mod tls;
+pub(crate) mod verbosity;
 pub(super) mod utils;
 pub(crate) mod ws_client;
 pub(super) mod ws_client_internal;
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::*;
+use tokio::{
+    sync::{
+        Semaphore,
+        mpsc::{self, Receiver},
+    },
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use super::frame_decoder::{attach_raw_frame, decode_frame};
+
+/// Кадр на выходе пула декодирования
+pub(crate) enum Decoded {
+    /// Кадр без изменений или бинарный кадр, декодированный в `Message::Text`
+    Message(Message),
+    /// Бинарный кадр, который не удалось декодировать, и ошибка декодирования
+    Failed(Vec<u8>, std::io::Error),
+}
+
+// Кадр, ожидающий выдачи в исходном порядке
+enum Pending {
+    Ready(Message),
+    Decoding(JoinHandle<Decoded>),
+}
+
+/// Выносит декодирование бинарных кадров (protobuf, gzip, deflate) из цикла чтения
+/// сокета в пул из `workers` потоков.
+///
+/// Кадры больше `max_frame_size` байт не распаковываются и передаются как есть,
+/// чтобы `run()` отбросил их по размеру.
+///
+/// Кадры выдаются строго в порядке получения, поэтому порядок сообщений по каждому
+/// символу сохраняется. Успешно декодированный кадр превращается в `Message::Text`;
+/// при ошибке вместе с исходными байтами передаётся ошибка декодирования, чтобы
+/// `run()` обработал её так же, как без пула, не декодируя кадр повторно. При
+/// включенном `raw_frames` к тексту добавляется исходный кадр.
+pub(crate) fn spawn_parse_pool(
+    exchange: &'static str,
+    input: Receiver<Message>,
+    workers: usize,
+    max_frame_size: usize,
+    raw_frames: Arc<AtomicBool>,
+) -> Receiver<Decoded> {
+    spawn_pool_with(input, workers, move |binary| {
+        if binary.len() > max_frame_size {
+            return Decoded::Message(Message::Binary(binary));
+        }
+        match decode_frame(exchange, &binary) {
+            Ok(txt) if raw_frames.load(Ordering::Acquire) => {
+                Decoded::Message(Message::Text(attach_raw_frame(txt, &binary)))
+            }
+            Ok(txt) => Decoded::Message(Message::Text(txt)),
+            Err(err) => Decoded::Failed(binary, err),
+        }
+    })
+}
+
+fn spawn_pool_with<F>(mut input: Receiver<Message>, workers: usize, decode: F) -> Receiver<Decoded>
+where
+    F: Fn(Vec<u8>) -> Decoded + Clone + Send + 'static,
+{
+    let workers = workers.max(1);
+    let permits = Arc::new(Semaphore::new(workers));
+    // Очередь задач ограничена, чтобы при медленном потребителе давление
+    // передавалось обратно на сокет, а не копилось в памяти
+    let (pending_tx, mut pending_rx) = mpsc::channel::<Pending>(workers * 2);
+    let (output_tx, output_rx) = mpsc::channel::<Decoded>(workers * 2);
+
+    tokio::spawn(async move {
+        while let Some(msg) = input.recv().await {
+            let pending = match msg {
+                Message::Binary(binary) => {
+                    let Ok(permit) = permits.clone().acquire_owned().await else {
+                        break;
+                    };
+                    let decode = decode.clone();
+                    Pending::Decoding(tokio::task::spawn_blocking(move || {
+                        let _permit = permit;
+                        decode(binary)
+                    }))
+                }
+                other => Pending::Ready(other),
+            };
+            if pending_tx.send(pending).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(pending) = pending_rx.recv().await {
+            let decoded = match pending {
+                Pending::Ready(msg) => Decoded::Message(msg),
+                Pending::Decoding(handle) => match handle.await {
+                    Ok(decoded) => decoded,
+                    Err(err) => {
+                        error!("Parse worker failed: {}", err);
+                        continue;
+                    }
+                },
+            };
+            if output_tx.send(decoded).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    output_rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const WORKERS: usize = 4;
+    const PARSE_COST: Duration = Duration::from_millis(300);
+
+    fn text(decoded: Option<Decoded>) -> Option<String> {
+        match decoded? {
+            Decoded::Message(Message::Text(txt)) => Some(txt),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_pool_accepts_frames_while_decoding() {
+        // Как и сокет, вход пула почти не буферизуется
+        let (input_tx, input_rx) = mpsc::channel(1);
+        let mut output = spawn_pool_with(input_rx, WORKERS, |binary: Vec<u8>| {
+            std::thread::sleep(PARSE_COST);
+            Decoded::Message(Message::Text(binary[0].to_string()))
+        });
+
+        // Медленное декодирование не задерживает цикл чтения: кадры на каждый поток
+        // пула принимаются быстрее, чем декодируется хотя бы один из них
+        let started = Instant::now();
+        for i in 0..WORKERS as u8 {
+            input_tx.send(Message::Binary(vec![i])).await.unwrap();
+            input_tx.send(Message::Text(format!("text-{}", i))).await.unwrap();
+        }
+        let accepted = started.elapsed();
+        assert!(accepted < PARSE_COST / 2, "кадры приняты за {:?}", accepted);
+        drop(input_tx);
+
+        // Порядок кадров сохраняется независимо от того, какой воркер закончил первым
+        for i in 0..WORKERS {
+            assert_eq!(text(output.recv().await), Some(i.to_string()));
+            assert_eq!(text(output.recv().await), Some(format!("text-{}", i)));
+        }
+        assert!(output.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parse_pool_returns_decode_error() {
+        let (input_tx, input_rx) = mpsc::channel(1);
+        let mut output = spawn_parse_pool(
+            "mexc",
+            input_rx,
+            WORKERS,
+            usize::MAX,
+            Arc::new(AtomicBool::new(false)),
+        );
+        input_tx.send(Message::Binary(vec![0x0a, 0xff, 0xff])).await.unwrap();
+
+        match output.recv().await {
+            Some(Decoded::Failed(binary, _)) => assert_eq!(binary, [0x0a, 0xff, 0xff]),
+            _ => panic!("ожидалась ошибка декодирования"),
+        }
+    }
+}
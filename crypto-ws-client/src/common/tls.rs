@@ -0,0 +1,149 @@
+use std::{
+    io::{self, ErrorKind},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use rustls::{
+    Certificate, ClientConfig, RootCertStore, ServerName,
+    client::{ServerCertVerified, ServerCertVerifier},
+};
+use tokio_tungstenite::Connector;
+
+/// Настройки TLS для WebSocket соединений.
+///
+/// По умолчанию используется стандартная проверка сертификатов по системным
+/// корневым сертификатам, как и без конфигурации. Дополнительные корневые
+/// сертификаты нужны для работы через TLS-инспектирующие прокси в корпоративных сетях.
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig {
+    root_certs_pem: Vec<Vec<u8>>,
+    danger_accept_invalid_certs: bool,
+    alpn_protocols: Vec<Vec<u8>>,
+    disable_sni: bool,
+}
+
+impl TlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Доверять сертификатам из PEM в дополнение к системным
+    pub fn with_root_cert_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certs_pem.push(pem.into());
+        self
+    }
+
+    /// Отключить проверку сертификата сервера. Только для тестов!
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Протоколы ALPN, предлагаемые при handshake
+    pub fn with_alpn_protocols(mut self, protocols: &[&str]) -> Self {
+        self.alpn_protocols = protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+        self
+    }
+
+    /// Отправлять ли SNI при handshake (по умолчанию отправляется)
+    pub fn with_sni(mut self, enabled: bool) -> Self {
+        self.disable_sni = !enabled;
+        self
+    }
+
+    pub(crate) fn connector(&self) -> io::Result<Connector> {
+        Ok(Connector::Rustls(Arc::new(self.client_config()?)))
+    }
+
+    fn client_config(&self) -> io::Result<ClientConfig> {
+        let builder = ClientConfig::builder().with_safe_defaults();
+        let mut config = if self.danger_accept_invalid_certs {
+            builder
+                .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+                .with_no_client_auth()
+        } else {
+            builder.with_root_certificates(self.root_store()?).with_no_client_auth()
+        };
+        config.alpn_protocols = self.alpn_protocols.clone();
+        config.enable_sni = !self.disable_sni;
+        Ok(config)
+    }
+
+    fn root_store(&self) -> io::Result<RootCertStore> {
+        let mut roots = RootCertStore::empty();
+        for cert in rustls_native_certs::load_native_certs()? {
+            // Повреждённый системный сертификат не должен ломать подключение
+            let _ = roots.add(&Certificate(cert.0));
+        }
+        for pem in &self.root_certs_pem {
+            let certs = rustls_pemfile::certs(&mut pem.as_slice())?;
+            let (added, _) = roots.add_parsable_certificates(&certs);
+            if added == 0 {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "No valid certificate found in the root certificate PEM",
+                ));
+            }
+        }
+        Ok(roots)
+    }
+}
+
+// Принимает любой сертификат сервера, см. `with_danger_accept_invalid_certs`
+struct NoCertificateVerification;
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::connect_async::connect_async;
+
+    #[test]
+    fn test_tls_config_builds_client_config() {
+        let config = TlsConfig::new()
+            .with_danger_accept_invalid_certs(true)
+            .with_alpn_protocols(&["http/1.1"])
+            .with_sni(false)
+            .client_config()
+            .unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"http/1.1".to_vec()]);
+        assert!(!config.enable_sni);
+
+        let config = TlsConfig::new().client_config().unwrap();
+        assert!(config.alpn_protocols.is_empty());
+        assert!(config.enable_sni);
+    }
+
+    #[tokio::test]
+    async fn test_tls_config_threaded_to_connect_async() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        // Некорректный корневой сертификат отклоняется до установки соединения
+        let invalid = TlsConfig::new().with_root_cert_pem("not a certificate");
+        let err = connect_async(&url, None, Some(&invalid)).await.err().unwrap();
+        assert!(err.to_string().contains("root certificate PEM"), "{}", err);
+
+        let tls = TlsConfig::new().with_alpn_protocols(&["http/1.1"]);
+        assert!(connect_async(&url, None, Some(&tls)).await.is_ok());
+    }
+}
@@ -0,0 +1,57 @@
+//! Уровень подробности внутреннего логирования, задаваемый во время работы.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Насколько подробно клиенты логируют свою работу.
+///
+/// В отличие от `RUST_LOG`, уровень меняется на лету и действует только на логи,
+/// которые пишутся для каждого полученного кадра: разбор, декодирование, классификация.
+/// Ошибки, предупреждения и события соединения логируются всегда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Не логировать отдельные сообщения
+    Quiet,
+    /// Логировать каждое сообщение на уровнях `debug`/`info`
+    #[default]
+    Normal,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Задать уровень подробности для всех клиентов процесса
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Текущий уровень подробности
+pub fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        _ => Verbosity::Normal,
+    }
+}
+
+/// Писать ли логи для каждого сообщения (см. [`per_message_debug!`](crate::per_message_debug))
+pub fn per_message_logs() -> bool {
+    verbosity() > Verbosity::Quiet
+}
+
+/// `debug!` для логов на каждое сообщение; подавляется [`Verbosity::Quiet`]
+#[macro_export]
+macro_rules! per_message_debug {
+    ($($arg:tt)+) => {
+        if $crate::per_message_logs() {
+            ::log::debug!($($arg)+)
+        }
+    };
+}
+
+/// `info!` для логов на каждое сообщение; подавляется [`Verbosity::Quiet`]
+#[macro_export]
+macro_rules! per_message_info {
+    ($($arg:tt)+) => {
+        if $crate::per_message_logs() {
+            ::log::info!($($arg)+)
+        }
+    };
+}
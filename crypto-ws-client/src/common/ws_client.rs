@@ -1,5 +1,7 @@
 use async_trait::async_trait;
 
+use crate::common::ws_client_internal::{HealthStatus, ReconnectHook};
+
 /// The public interface of every WebSocket client.
 #[async_trait]
 pub trait WSClient {
@@ -158,4 +160,82 @@ pub trait WSClient {
 
     /// Close the connection and break the loop in Run().
     async fn close(&self);
+
+    /// Закрыть соединение кадром закрытия с кодом `code` и причиной `reason`
+    /// (например, 1001 Going Away).
+    ///
+    /// Клиенты, не поддерживающие код закрытия, закрываются как [`close`](Self::close).
+    async fn close_with(&self, _code: u16, _reason: &str) {
+        self.close().await;
+    }
+
+    /// Текущее состояние соединения и метрики переподключений.
+    ///
+    /// Возвращает `None`, если клиент не отслеживает состояние соединения.
+    fn health_status(&self) -> Option<HealthStatus> {
+        None
+    }
+
+    /// Сырые команды подписки, сохранённые транспортом для восстановления после
+    /// переподключения.
+    ///
+    /// Возвращает пустой список, если клиент не хранит подписки.
+    fn active_subscriptions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// WebSocket адрес, к которому подключен клиент, с учётом переопределения
+    /// при создании.
+    ///
+    /// Возвращает `None`, если клиент не раскрывает адрес подключения.
+    fn connection_url(&self) -> Option<&str> {
+        None
+    }
+
+    /// Декодировать бинарные кадры (protobuf, gzip) в пуле из `workers` потоков
+    /// вместо цикла чтения сокета. Порядок сообщений сохраняется.
+    ///
+    /// Вызывается до `run()`; 0 возвращает декодирование в цикл чтения. Клиенты
+    /// без такого транспорта игнорируют настройку.
+    fn set_parse_workers(&self, _workers: usize) {}
+
+    /// Максимальный размер входящего кадра в байтах; кадры большего размера
+    /// отбрасываются до распаковки и разбора. По умолчанию
+    /// [`DEFAULT_MAX_FRAME_SIZE`](crate::DEFAULT_MAX_FRAME_SIZE).
+    ///
+    /// Клиенты без такого транспорта игнорируют настройку.
+    fn set_max_frame_size(&self, _bytes: usize) {}
+
+    /// Включить или отключить автоматическое переподключение (по умолчанию включено).
+    ///
+    /// Без него `run()` завершается при разрыве соединения, что удобно для коротких
+    /// скриптов. Вызывается до `run()`.
+    fn set_auto_reconnect(&self, _enabled: bool) {}
+
+    /// Включить синтетические кадры `{"heartbeat":{"exchange":..,"latency_ms":..},"ts":..}`
+    /// на каждый pong (по умолчанию выключены). Позволяют потребителю убедиться,
+    /// что соединение живо, даже когда по подпискам нет данных.
+    fn set_heartbeat_messages(&self, _enabled: bool) {}
+
+    /// Добавлять к декодированным бинарным кадрам (protobuf, gzip) исходные байты
+    /// (по умолчанию выключено из-за расхода памяти). Потребитель извлекает их через
+    /// [`take_raw_frame`](crate::take_raw_frame).
+    fn set_raw_frames(&self, _enabled: bool) {}
+
+    /// Передавать бинарные кадры, которые не удалось декодировать, синтетическим кадром
+    /// `{"undecodable_frame":{"exchange":..,"error":..,"raw_frame":<base64>},"ts":..}`
+    /// вместо отбрасывания (по умолчанию выключено).
+    fn set_undecodable_passthrough(&self, _enabled: bool) {}
+
+    /// Учесть кадр, который потребитель не смог разобрать. Счётчик попадает в
+    /// [`HealthStatus::parse_failures`].
+    fn record_parse_failure(&self) {}
+
+    /// Задать хук, вызываемый после успешного переподключения до восстановления
+    /// подписок. Команды, которые он возвращает (например, кадр аутентификации
+    /// приватных каналов), отправляются первыми.
+    ///
+    /// Без хука переподключение работает как прежде. Клиенты без переподключения
+    /// игнорируют хук.
+    fn set_on_reconnect(&self, _hook: ReconnectHook) {}
 }
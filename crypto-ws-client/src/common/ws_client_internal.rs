@@ -1,20 +1,110 @@
 use std::{
-    io::prelude::*,
+    collections::VecDeque,
     num::NonZeroU32,
     sync::{
-        Arc, Mutex,
-        atomic::{AtomicBool, AtomicIsize, AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicIsize, AtomicU64, AtomicUsize, Ordering},
     },
     time::{Duration, Instant},
 };
 
-use flate2::read::{DeflateDecoder, GzDecoder};
 use log::*;
 use rand;
 use reqwest::StatusCode;
-use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_tungstenite::tungstenite::{
+    Error, Message,
+    protocol::{CloseFrame, frame::coding::CloseCode},
+};
+
+use crate::common::{
+    frame_decoder::{attach_raw_frame, decode_frame, undecodable_frame},
+    message_handler::{MessageHandler, MiscMessage},
+    parse_pool::{Decoded, spawn_parse_pool},
+    tls::TlsConfig,
+};
+
+/// Хук переподключения: возвращает сырые команды (например, кадр аутентификации),
+/// которые отправляются после переподключения до восстановления подписок
+pub type ReconnectHook = Arc<dyn Fn() -> Vec<String> + Send + Sync>;
+
+/// Приёмник кадров: получает кадр вместе с монотонным временем его получения в
+/// `run()` (см. [`monotonic_nanos`]). `false` означает, что получатель закрыт и
+/// `run()` пора завершить.
+pub type FrameSink = Arc<dyn Fn(u64, String) -> bool + Send + Sync>;
+
+/// Куда транспорт передаёт кадры; задаётся при создании клиента.
+///
+/// Конструкторы клиентов принимают `impl Into<FrameOutput>`, то есть и канал, и
+/// [`FrameSink`].
+#[derive(Clone)]
+pub enum FrameOutput {
+    /// Текст кадра в канал
+    Channel(std::sync::mpsc::Sender<String>),
+    /// Кадр вместе с временем получения
+    Sink(FrameSink),
+}
+
+impl FrameOutput {
+    /// Передать кадр потребителю; `false`, если получатель закрыт
+    fn deliver(&self, received_at_ns: u64, txt: String) -> bool {
+        match self {
+            FrameOutput::Channel(tx) => tx.send(txt).is_ok(),
+            FrameOutput::Sink(sink) => sink(received_at_ns, txt),
+        }
+    }
+}
+
+impl From<std::sync::mpsc::Sender<String>> for FrameOutput {
+    fn from(tx: std::sync::mpsc::Sender<String>) -> Self {
+        FrameOutput::Channel(tx)
+    }
+}
 
-use crate::common::message_handler::{MessageHandler, MiscMessage};
+impl From<FrameSink> for FrameOutput {
+    fn from(sink: FrameSink) -> Self {
+        FrameOutput::Sink(sink)
+    }
+}
+
+/// Монотонная метка времени в наносекундах от первого вызова в процессе.
+///
+/// Значения строго возрастают между вызовами, поэтому два сообщения,
+/// полученные подряд, никогда не получат одинаковую метку.
+pub fn monotonic_nanos() -> u64 {
+    static BASE: OnceLock<Instant> = OnceLock::new();
+    static LAST: AtomicU64 = AtomicU64::new(0);
+
+    let now = BASE.get_or_init(Instant::now).elapsed().as_nanos() as u64;
+    let mut prev = LAST.load(Ordering::Relaxed);
+    loop {
+        let next = now.max(prev + 1);
+        match LAST.compare_exchange_weak(prev, next, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(_) => return next,
+            Err(actual) => prev = actual,
+        }
+    }
+}
+
+// Входящие кадры: напрямую из сокета или через пул декодирования
+enum FrameReceiver {
+    Direct(tokio::sync::mpsc::Receiver<Message>),
+    Pooled(tokio::sync::mpsc::Receiver<Decoded>),
+}
+
+impl FrameReceiver {
+    async fn recv(&mut self) -> Option<Decoded> {
+        match self {
+            FrameReceiver::Direct(rx) => rx.recv().await.map(Decoded::Message),
+            FrameReceiver::Pooled(rx) => rx.recv().await,
+        }
+    }
+}
+
+/// Окно, за которое считается `messages_per_sec`
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Максимальный размер входящего кадра по умолчанию, 16 МиБ
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -33,6 +123,17 @@ pub struct ConnectionMetrics {
     pub reconnection_attempts: AtomicU64,
     pub ping_failures: AtomicU64,
     pub last_error: Mutex<Option<String>>,
+    /// Количество успешных WebSocket handshake и их суммарная длительность
+    pub handshake_count: AtomicU64,
+    pub total_handshake_ms: AtomicU64,
+    pub last_handshake_ms: AtomicU64,
+    /// Принятые кадры с данными (text и binary) и их суммарный размер в байтах
+    pub messages_received: AtomicU64,
+    pub bytes_received: AtomicU64,
+    /// Кадры, которые потребитель не смог разобрать (кроме служебных)
+    pub parse_failures: AtomicU64,
+    // Выборки (время, messages_received), снятые при чтении скорости
+    throughput_samples: Mutex<VecDeque<(Instant, u64)>>,
 }
 
 impl ConnectionMetrics {
@@ -56,9 +157,55 @@ impl ConnectionMetrics {
     pub fn record_ping_failure(&self) {
         self.ping_failures.fetch_add(1, Ordering::Relaxed);
     }
+
+    /// Учесть длительность установления соединения: от начала попытки до ответа на handshake
+    pub fn record_handshake(&self, elapsed: Duration) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        self.handshake_count.fetch_add(1, Ordering::Relaxed);
+        self.total_handshake_ms.fetch_add(elapsed_ms, Ordering::Relaxed);
+        self.last_handshake_ms.store(elapsed_ms, Ordering::Relaxed);
+    }
+
+    /// Учесть принятый кадр с данными
+    pub fn record_message(&self, bytes: usize) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Учесть кадр, который не удалось разобрать
+    pub fn record_parse_failure(&self) {
+        self.parse_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Скорость приёма сообщений за последние [`THROUGHPUT_WINDOW`].
+    ///
+    /// Выборки снимаются при каждом вызове, поэтому первый вызов возвращает 0.
+    pub fn messages_per_sec(&self) -> f64 {
+        let now = Instant::now();
+        let received = self.messages_received.load(Ordering::Relaxed);
+        let mut samples = self.throughput_samples.lock().unwrap();
+        samples.push_back((now, received));
+        // Оставляем последнюю выборку не позже начала окна как точку отсчёта
+        while samples.len() > 2 && now.duration_since(samples[1].0) >= THROUGHPUT_WINDOW {
+            samples.pop_front();
+        }
+
+        let (since, received_before) = samples[0];
+        let elapsed = now.duration_since(since).as_secs_f64();
+        if elapsed > 0.0 { (received - received_before) as f64 / elapsed } else { 0.0 }
+    }
+
+    /// Средняя длительность handshake в миллисекундах, 0 если соединений еще не было
+    pub fn avg_handshake_ms(&self) -> f64 {
+        match self.handshake_count.load(Ordering::Relaxed) {
+            0 => 0.0,
+            count => self.total_handshake_ms.load(Ordering::Relaxed) as f64 / count as f64,
+        }
+    }
 }
 
-#[derive(Debug)]
+/// Снимок состояния транспортного соединения
+#[derive(Debug, Clone)]
 pub struct HealthStatus {
     pub state: ConnectionState,
     pub total_connections: u64,
@@ -66,9 +213,20 @@ pub struct HealthStatus {
     pub failed_connections: u64,
     pub reconnection_attempts: u64,
     pub ping_failures: u64,
+    /// Unix-время (в секундах) последнего heartbeat: pong на наш ping или ping от сервера
     pub last_ping: i64,
     pub uptime: Duration,
     pub last_error: Option<String>,
+    /// Средняя длительность WebSocket handshake (мс) по всем подключениям
+    pub avg_handshake_ms: f64,
+    /// Длительность последнего WebSocket handshake (мс)
+    pub last_handshake_ms: u64,
+    pub messages_received: u64,
+    pub bytes_received: u64,
+    /// Скорость приёма сообщений за последние 10 секунд
+    pub messages_per_sec: f64,
+    /// Кадры, которые не удалось разобрать: новая схема, неизвестный канал
+    pub parse_failures: u64,
 }
 
 fn log_connection_event(exchange: &str, event: &str, details: &str) {
@@ -87,11 +245,7 @@ pub(crate) struct WSClientInternal<H: MessageHandler> {
     // pass parameters to run()
     #[allow(clippy::type_complexity)]
     params_rx: std::sync::Mutex<
-        tokio::sync::oneshot::Receiver<(
-            H,
-            tokio::sync::mpsc::Receiver<Message>,
-            std::sync::mpsc::Sender<String>,
-        )>,
+        tokio::sync::oneshot::Receiver<(H, tokio::sync::mpsc::Receiver<Message>, FrameOutput)>,
     >,
     command_tx: tokio::sync::mpsc::Sender<Message>,
     // Добавляем флаг для отслеживания состояния подключения
@@ -106,6 +260,24 @@ pub(crate) struct WSClientInternal<H: MessageHandler> {
     start_time: Instant,
     last_ping_time: AtomicU64,
     ping_shutdown_tx: Mutex<Option<tokio::sync::watch::Sender<bool>>>,
+    // Размер пула декодирования бинарных кадров, 0 - декодирование в цикле чтения
+    parse_workers: AtomicUsize,
+    // Кадры больше этого размера отбрасываются до распаковки и разбора
+    max_frame_size: AtomicUsize,
+    // false - run() завершается при разрыве соединения вместо переподключения
+    auto_reconnect: AtomicBool,
+    // Отправлять потребителю синтетический кадр heartbeat на каждый pong
+    heartbeat_messages: AtomicBool,
+    // Добавлять к декодированным бинарным кадрам исходные байты
+    raw_frames: Arc<AtomicBool>,
+    // Передавать потребителю нераспознанные бинарные кадры вместо отбрасывания
+    undecodable_passthrough: AtomicBool,
+    // Unix-время (мс) отправки последнего ping, 0 - ping еще не отправлялся
+    last_ping_sent_ms: Arc<AtomicU64>,
+    // Настройки TLS, используются и при переподключении
+    tls: Option<TlsConfig>,
+    // Вызывается после переподключения перед восстановлением подписок
+    on_reconnect: Mutex<Option<ReconnectHook>>,
 }
 
 impl<H: MessageHandler> WSClientInternal<H> {
@@ -117,6 +289,100 @@ impl<H: MessageHandler> WSClientInternal<H> {
         }
     }
     
+    fn record_heartbeat(&self) {
+        self.last_ping_time.store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
+    }
+
+    /// Кадр `{"heartbeat":{"exchange":..,"latency_ms":..},"ts":..}` для потребителя,
+    /// если он включен. `latency_ms` — время от отправки последнего ping до pong,
+    /// `null`, если клиент сам ping не отправлял.
+    fn heartbeat_frame(&self) -> Option<String> {
+        if !self.heartbeat_messages.load(Ordering::Acquire) {
+            return None;
+        }
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        let latency_ms = match self.last_ping_sent_ms.load(Ordering::Acquire) {
+            0 => None,
+            sent_ms => Some(now_ms.saturating_sub(sent_ms)),
+        };
+        let frame = serde_json::json!({
+            "heartbeat": {"exchange": self.exchange, "latency_ms": latency_ms},
+            "ts": now_ms,
+        });
+        Some(frame.to_string())
+    }
+
+    /// Команды подписки, которые будут повторно отправлены при переподключении
+    pub fn active_subscriptions(&self) -> Vec<String> {
+        self.active_subscriptions.lock().unwrap().clone()
+    }
+
+    /// Адрес, к которому фактически подключен клиент (с учётом переопределения)
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Задать размер пула декодирования бинарных кадров; действует с ближайшего
+    /// запуска `run()` или переподключения. 0 отключает пул.
+    pub fn set_parse_workers(&self, workers: usize) {
+        self.parse_workers.store(workers, Ordering::Release);
+    }
+
+    /// Задать максимальный размер входящего кадра в байтах. Кадры большего размера
+    /// отбрасываются до распаковки и разбора.
+    pub fn set_max_frame_size(&self, bytes: usize) {
+        self.max_frame_size.store(bytes, Ordering::Release);
+    }
+
+    /// Учесть кадр, который потребитель не смог разобрать
+    pub fn record_parse_failure(&self) {
+        self.metrics.record_parse_failure();
+    }
+
+    /// Включить или отключить кадры heartbeat для потребителя на каждый pong
+    /// (по умолчанию выключены)
+    pub fn set_heartbeat_messages(&self, enabled: bool) {
+        self.heartbeat_messages.store(enabled, Ordering::Release);
+    }
+
+    /// Добавлять к декодированным бинарным кадрам исходные байты в поле `raw_frame`
+    /// (по умолчанию выключено, см. [`take_raw_frame`](crate::take_raw_frame))
+    pub fn set_raw_frames(&self, enabled: bool) {
+        self.raw_frames.store(enabled, Ordering::Release);
+    }
+
+    /// Передавать бинарные кадры, которые не удалось декодировать, кадром
+    /// `{"undecodable_frame":{..}}` вместо отбрасывания (по умолчанию выключено)
+    pub fn set_undecodable_passthrough(&self, enabled: bool) {
+        self.undecodable_passthrough.store(enabled, Ordering::Release);
+    }
+
+    /// Включить или отключить автоматическое переподключение (по умолчанию включено).
+    /// Без него `run()` завершается при разрыве соединения.
+    pub fn set_auto_reconnect(&self, enabled: bool) {
+        self.auto_reconnect.store(enabled, Ordering::Release);
+    }
+
+    /// Задать хук, вызываемый после успешного переподключения перед
+    /// восстановлением подписок
+    pub fn set_on_reconnect(&self, hook: ReconnectHook) {
+        *self.on_reconnect.lock().unwrap() = Some(hook);
+    }
+
+    // Переносит декодирование бинарных кадров в пул, если он включён
+    fn with_parse_pool(&self, message_rx: tokio::sync::mpsc::Receiver<Message>) -> FrameReceiver {
+        match self.parse_workers.load(Ordering::Acquire) {
+            0 => FrameReceiver::Direct(message_rx),
+            workers => FrameReceiver::Pooled(spawn_parse_pool(
+                self.exchange,
+                message_rx,
+                workers,
+                self.max_frame_size.load(Ordering::Acquire),
+                self.raw_frames.clone(),
+            )),
+        }
+    }
+
     pub fn get_health_status(&self) -> HealthStatus {
         let state = self.connection_state.lock().unwrap().clone();
         let last_error = self.metrics.last_error.lock().unwrap().clone();
@@ -131,6 +397,12 @@ impl<H: MessageHandler> WSClientInternal<H> {
             last_ping: self.last_ping_time.load(Ordering::Relaxed) as i64,
             uptime: self.start_time.elapsed(),
             last_error,
+            avg_handshake_ms: self.metrics.avg_handshake_ms(),
+            last_handshake_ms: self.metrics.last_handshake_ms.load(Ordering::Relaxed),
+            messages_received: self.metrics.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.metrics.bytes_received.load(Ordering::Relaxed),
+            messages_per_sec: self.metrics.messages_per_sec(),
+            parse_failures: self.metrics.parse_failures.load(Ordering::Relaxed),
         }
     }
     
@@ -170,13 +442,26 @@ impl<H: MessageHandler> WSClientInternal<H> {
         url: &str,
         handler: H,
         uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
-        tx: std::sync::mpsc::Sender<String>,
+        output: impl Into<FrameOutput>,
     ) -> Self {
+        Self::connect_with_tls(exchange, url, handler, uplink_limit, None, output).await
+    }
+
+    /// То же, что [`connect`](Self::connect), но с собственными настройками TLS
+    pub async fn connect_with_tls(
+        exchange: &'static str,
+        url: &str,
+        handler: H,
+        uplink_limit: Option<(NonZeroU32, std::time::Duration)>,
+        tls: Option<TlsConfig>,
+        output: impl Into<FrameOutput>,
+    ) -> Self {
+        let output = output.into();
         // A channel to send parameters to run()
         let (params_tx, params_rx) = tokio::sync::oneshot::channel::<(
             H,
             tokio::sync::mpsc::Receiver<Message>,
-            std::sync::mpsc::Sender<String>,
+            FrameOutput,
         )>();
 
         // Максимальное количество попыток подключения
@@ -192,9 +477,12 @@ impl<H: MessageHandler> WSClientInternal<H> {
         for attempt in 1..=MAX_CONNECTION_ATTEMPTS {
             log_connection_event(exchange, "connection_attempt", &format!("Attempt {}/{}", attempt, MAX_CONNECTION_ATTEMPTS));
             
-            match super::connect_async::connect_async(url, uplink_limit).await {
+            let handshake_started = Instant::now();
+            match super::connect_async::connect_async(url, uplink_limit, tls.as_ref()).await {
                 Ok((message_rx, command_tx)) => {
-                    let _ = params_tx.send((handler, message_rx, tx));
+                    let metrics = ConnectionMetrics::default();
+                    metrics.record_handshake(handshake_started.elapsed());
+                    let _ = params_tx.send((handler, message_rx, output));
                     
                     log_connection_event(exchange, "connection_success", "WebSocket connected successfully");
 
@@ -207,10 +495,19 @@ impl<H: MessageHandler> WSClientInternal<H> {
                         active_subscriptions: std::sync::Mutex::new(Vec::new()),
                         ping_task_handle: std::sync::Mutex::new(None),
                         connection_state: Mutex::new(ConnectionState::Connected),
-                        metrics: ConnectionMetrics::default(),
+                        metrics,
                         start_time: Instant::now(),
                         last_ping_time: AtomicU64::new(chrono::Utc::now().timestamp() as u64),
                         ping_shutdown_tx: Mutex::new(None),
+                        parse_workers: AtomicUsize::new(0),
+                        max_frame_size: AtomicUsize::new(DEFAULT_MAX_FRAME_SIZE),
+                        auto_reconnect: AtomicBool::new(true),
+                        heartbeat_messages: AtomicBool::new(false),
+                        raw_frames: Arc::new(AtomicBool::new(false)),
+                        undecodable_passthrough: AtomicBool::new(false),
+                        last_ping_sent_ms: Arc::new(AtomicU64::new(0)),
+                        tls,
+                        on_reconnect: Mutex::new(None),
                     };
                 }
                 Err(err) => match err {
@@ -339,7 +636,7 @@ impl<H: MessageHandler> WSClientInternal<H> {
     async fn reconnect(
         &self,
         _handler: H,
-        _tx: std::sync::mpsc::Sender<String>,
+        _output: FrameOutput,
     ) -> Option<tokio::sync::mpsc::Receiver<Message>> {
         // Устанавливаем флаг, что переподключение в процессе
         self.reconnect_in_progress.store(true, Ordering::SeqCst);
@@ -379,8 +676,12 @@ impl<H: MessageHandler> WSClientInternal<H> {
             // Пытаемся переподключиться
                     self.metrics.record_reconnection_attempt();
                     
-                    match super::connect_async::connect_async(&self.url, None).await {
+                    let handshake_started = Instant::now();
+                    match super::connect_async::connect_async(&self.url, None, self.tls.as_ref())
+                        .await
+                    {
                         Ok((message_rx, new_command_tx)) => {
+                            self.metrics.record_handshake(handshake_started.elapsed());
                             // Обновляем command_tx
                             unsafe {
                                 // Это небезопасно, но необходимо для обновления command_tx
@@ -393,6 +694,19 @@ impl<H: MessageHandler> WSClientInternal<H> {
                             self.set_connection_state(ConnectionState::Connected);
                             log_connection_event(self.exchange, "reconnection_success", &format!("Reconnected after {} attempts", attempt));
 
+                            // Хук отправляет, например, повторную аутентификацию до подписок
+                            let hook = self.on_reconnect.lock().unwrap().clone();
+                            if let Some(hook) = hook {
+                                for command in hook() {
+                                    debug!("Sending reconnect hook command: {}", command);
+                                    if let Err(err) =
+                                        self.command_tx.send(Message::Text(command)).await
+                                    {
+                                        error!("Failed to send reconnect hook command: {}", err);
+                                    }
+                                }
+                            }
+
                             // Восстанавливаем подписки
                             let subscriptions = {
                                 let guard = self.active_subscriptions.lock().unwrap();
@@ -465,12 +779,14 @@ impl<H: MessageHandler> WSClientInternal<H> {
             // send heartbeat periodically
             let command_tx_clone = self.command_tx.clone();
             let num_unanswered_ping_clone = num_unanswered_ping.clone();
+            let last_ping_sent_ms = self.last_ping_sent_ms.clone();
 
             // Добавляем механизм проверки состояния соединения
             let url_clone = self.url.clone();
             let exchange_clone = self.exchange;
             // Переменная для будущего использования в мониторинге
             let _reconnect_in_progress_clone = self.reconnect_in_progress.clone();
+            let health_check_interval = handler.health_check_interval();
 
             let ping_task = tokio::task::spawn(async move {
                 let mut timer = {
@@ -482,14 +798,11 @@ impl<H: MessageHandler> WSClientInternal<H> {
                 let mut health_check_timer =
                     tokio::time::interval(Duration::from_secs(interval * 2));
 
-                // Специальный обработчик для Binance - более короткий интервал проверки
-                let is_binance = exchange_clone == "binance";
-                let mut binance_check_timer = if is_binance {
-                    tokio::time::interval(Duration::from_secs(30))
-                } else {
-                    // Для других бирж используем тот же интервал, но результат игнорируем
-                    tokio::time::interval(Duration::from_secs(60))
-                };
+                // Дополнительная проверка соединения, если ее запросил обработчик биржи
+                let mut extra_check_timer = tokio::time::interval(Duration::from_secs(
+                    // Без проверки используем интервал по умолчанию, но результат игнорируем
+                    health_check_interval.unwrap_or(60),
+                ));
 
                 loop {
                     tokio::select! {
@@ -503,6 +816,10 @@ impl<H: MessageHandler> WSClientInternal<H> {
                                 break;
                             } else {
                                 num_unanswered_ping_clone.fetch_add(1, Ordering::SeqCst);
+                                last_ping_sent_ms.store(
+                                    chrono::Utc::now().timestamp_millis() as u64,
+                                    Ordering::Release,
+                                );
                                 // Обновляем время последнего ping
                                 // last_ping_time.store(chrono::Utc::now().timestamp() as u64, Ordering::Relaxed);
                                 debug!("Ping sent successfully to {}", exchange_clone);
@@ -531,22 +848,21 @@ impl<H: MessageHandler> WSClientInternal<H> {
                             }
                         }
 
-                        _ = binance_check_timer.tick() => {
-                            // Проверка только для Binance
-                            if is_binance {
+                        _ = extra_check_timer.tick() => {
+                            if health_check_interval.is_some() {
                                 let unanswered = num_unanswered_ping_clone.load(Ordering::Acquire);
                                 if unanswered > 1 && !_reconnect_in_progress_clone.load(Ordering::Acquire) {
                                     warn!(
-                                        "Binance connection health check: {} unanswered pings for {}",
-                                        unanswered, url_clone
+                                        "{} connection health check: {} unanswered pings for {}",
+                                        exchange_clone, unanswered, url_clone
                                     );
 
-                                    // Для Binance отправляем Pong вместо Close для проверки соединения
+                                    // Отправляем Pong вместо Close для проверки соединения
                                     if let Err(err) = command_tx_clone.send(Message::Pong(Vec::new())).await {
-                                        error!("Failed to send pong message to Binance: {}", err);
+                                        error!("Failed to send pong message to {}: {}", exchange_clone, err);
                                         break;
                                     } else {
-                                        debug!("Sent pong message to Binance to check connection");
+                                        debug!("Sent pong message to {} to check connection", exchange_clone);
                                     }
                                 }
                             }
@@ -571,17 +887,20 @@ impl<H: MessageHandler> WSClientInternal<H> {
                 *guard = Some(ping_task);
             }
 
-            // Добавляем инициализирующий Pong сразу после создания пинг-задачи для Binance
-            // Это помогает установить корректное соединение сразу, особенно для user_data каналов
-            if self.exchange == "binance" {
+            // Инициализирующие сообщения обработчика биржи сразу после создания пинг-задачи
+            let on_connect_actions = handler.on_connect_actions();
+            if !on_connect_actions.is_empty() {
                 let cmd_tx = self.command_tx.clone();
+                let exchange = self.exchange;
                 tokio::spawn(async move {
                     // Даем немного времени на установление соединения
                     tokio::time::sleep(Duration::from_millis(500)).await;
-                    debug!("Sending initial pong to Binance after connection setup");
-                    // Отправляем пустой Pong фрейм для инициализации соединения
-                    if let Err(err) = cmd_tx.send(Message::Pong(Vec::new())).await {
-                        warn!("Failed to send initial pong to Binance: {}", err);
+                    for msg in on_connect_actions {
+                        debug!("Sending initial {:?} to {} after connection setup", msg, exchange);
+                        if let Err(err) = cmd_tx.send(msg).await {
+                            warn!("Failed to send initial message to {}: {}", exchange, err);
+                            break;
+                        }
                     }
                 });
             }
@@ -592,7 +911,7 @@ impl<H: MessageHandler> WSClientInternal<H> {
     }
 
     pub async fn run(&self) {
-        let (mut handler, mut message_rx, tx) = {
+        let (mut handler, message_rx, output) = {
             let mut guard = self.params_rx.lock().unwrap();
             match guard.try_recv() {
                 Ok(params) => params,
@@ -607,6 +926,8 @@ impl<H: MessageHandler> WSClientInternal<H> {
             }
         };
 
+        let mut message_rx = self.with_parse_pool(message_rx);
+        let deliver = |received_at_ns: u64, txt: String| output.deliver(received_at_ns, txt);
         let num_unanswered_ping = Arc::new(AtomicIsize::new(0)); // for debug only
 
         // Создаем клон handler для использования в переподключении
@@ -614,6 +935,7 @@ impl<H: MessageHandler> WSClientInternal<H> {
 
         // Запускаем пинг только один раз
         self.start_ping_task(&handler, num_unanswered_ping.clone());
+        let pong_on_ping = handler.should_pong_on_ping();
 
         // Для Binance добавляем дополнительную диагностику
         let is_binance = self.exchange == "binance";
@@ -623,220 +945,89 @@ impl<H: MessageHandler> WSClientInternal<H> {
 
         // Основной цикл с поддержкой переподключения
         'connection_loop: loop {
-            while let Some(msg) = message_rx.recv().await {
+            while let Some(frame) = message_rx.recv().await {
+                let received_at_ns = monotonic_nanos();
+                // Ошибка декодирования кадра в пуле; без пула кадр декодируется здесь
+                let (msg, decode_error) = match frame {
+                    Decoded::Message(msg) => (msg, None),
+                    Decoded::Failed(binary, err) => (Message::Binary(binary), Some(err)),
+                };
+                let frame_size = match &msg {
+                    Message::Text(txt) => txt.len(),
+                    Message::Binary(binary) => binary.len(),
+                    _ => 0,
+                };
+                let max_frame_size = self.max_frame_size.load(Ordering::Relaxed);
+                if frame_size > max_frame_size {
+                    warn!(
+                        "Dropping a {} byte frame from {}, exceeds max frame size {}",
+                        frame_size, self.url, max_frame_size
+                    );
+                    continue;
+                }
+                if frame_size > 0 {
+                    self.metrics.record_message(frame_size);
+                }
                 let txt = match msg {
                     Message::Text(txt) => Some(txt),
-                    Message::Binary(binary) => {
-                        let mut txt = String::new();
-                        let resp = match self.exchange {
-                            crate::clients::huobi::EXCHANGE_NAME
-                            | crate::clients::binance::EXCHANGE_NAME
-                            | "bitget"
-                            | "bitz" => {
-                                let mut decoder = GzDecoder::new(&binary[..]);
-                                decoder.read_to_string(&mut txt)
-                            }
-                            crate::clients::okx::EXCHANGE_NAME => {
-                                let mut decoder = DeflateDecoder::new(&binary[..]);
-                                decoder.read_to_string(&mut txt)
-                            }
-                            crate::clients::mexc::EXCHANGE_NAME => {
-                                // MEXC может использовать разные форматы, попробуем несколько вариантов
-                                if binary.len() > 0 {
-                                    // Попробуем определить формат данных по первым байтам
-                                    debug!(
-                                        "MEXC binary data - первые 10 байт: {:?}",
-                                        &binary[..std::cmp::min(10, binary.len())]
-                                    );
-
-                                    // Проверяем типичные заголовки сжатия СНАЧАЛА
-                                    let is_gzip =
-                                        binary.len() >= 2 && binary[0] == 0x1f && binary[1] == 0x8b;
-                                    let is_deflate_zlib = binary.len() >= 2
-                                        && binary[0] == 0x78
-                                        && (binary[1] == 0x01 || binary[1] == 0x9c || binary[1] == 0xda);
-
-                                    // Улучшенное определение Protocol Buffers
-                                    // Protobuf часто начинается с varint field number + wire type
-                                    // Первые байты [10, 30] = field 1, wire type 2 (length-delimited), length 30
-                                    let is_likely_protobuf = binary.len() >= 4 && 
-                                        !is_gzip && !is_deflate_zlib &&
-                                        (
-                                            // Типичные protobuf паттерны
-                                            (binary[0] == 0x08 && binary[1] < 0x80) || // field 1, varint
-                                            (binary[0] == 0x0a && binary[1] < 0x80) || // field 1, length-delimited
-                                            (binary[0] == 0x10 && binary[1] < 0x80) || // field 2, varint
-                                            (binary[0] == 0x12 && binary[1] < 0x80) || // field 2, length-delimited
-                                            // Специально для данного случая: [10, 30, "spot@private..."]
-                                            (binary[0] == 0x0a && binary.len() > 10 && 
-                                             binary[2..].starts_with(b"spot@"))
-                                        );
-
-                                    debug!(
-                                        "MEXC binary analysis: is_gzip={}, is_deflate_zlib={}, is_likely_protobuf={}",
-                                        is_gzip, is_deflate_zlib, is_likely_protobuf
-                                    );
-
-                                    if is_likely_protobuf {
-                                        // Определенно Protocol Buffers данные
-                                        info!("🔍 MEXC: Обнаружены Protocol Buffers данные (длина: {})", binary.len());
-                                        
-                                        // Попробуем декодировать protobuf данные
-                                        match crate::clients::mexc::decode_mexc_protobuf(&binary) {
-                                            Ok(json_string) => {
-                                                info!("✅ Успешно декодированы protobuf данные в JSON");
-                                                debug!("Декодированный JSON: {}", json_string);
-                                                txt = json_string;
-                                                Ok(txt.len())
-                                            }
-                                            Err(decode_err) => {
-                                                // Если декодирование не удалось, показываем диагностику
-                                                warn!("❌ Не удалось декодировать protobuf данные: {}", decode_err);
-                                                
-                                                // Попробуем извлечь информацию о канале из protobuf для диагностики
-                                                if binary.len() > 10 && binary[0] == 0x0a {
-                                                    let channel_length = binary[1] as usize;
-                                                    if binary.len() > 2 + channel_length {
-                                                        if let Ok(channel_name) = String::from_utf8(binary[2..2+channel_length].to_vec()) {
-                                                            warn!("📡 Канал протобуф: '{}'", channel_name);
-                                                            if channel_name.contains(".pb") {
-                                                                warn!("💡 Совет: возможно используется другая protobuf схема");
-                                                                warn!("   Рекомендация: используйте JSON канал '{}'", channel_name.replace(".pb", ""));
-                                                            }
-                                                        }
-                                                    }
-                                                }
-
-                                                warn!("📖 См. README_mexc_websocket_troubleshooting.md для подробностей");
-
-                                                Err(std::io::Error::new(
-                                                    std::io::ErrorKind::InvalidData,
-                                                    format!("Protocol Buffers decoding failed: {}", decode_err),
-                                                ))
-                                            }
-                                        }
-                                    } else if is_gzip {
-                                        // Данные сжаты gzip
-                                        debug!("Trying GZIP decompression for MEXC");
-                                        let mut gzip_decoder = GzDecoder::new(&binary[..]);
-                                        gzip_decoder.read_to_string(&mut txt)
-                                    } else if is_deflate_zlib {
-                                        // Данные сжаты deflate/zlib
-                                        debug!("Trying DEFLATE decompression for MEXC");
-                                        let mut deflate_decoder = DeflateDecoder::new(&binary[..]);
-                                        deflate_decoder.read_to_string(&mut txt)
-                                    } else {
-                                        // Возможно это несжатые JSON данные
-                                        debug!("Trying raw UTF-8 parsing for MEXC");
-                                        match String::from_utf8(binary.clone()) {
-                                            Ok(utf8_string) => {
-                                                if utf8_string.trim().starts_with('{')
-                                                    || utf8_string.trim().starts_with('[')
-                                                {
-                                                    // Это JSON данные
-                                                    txt = utf8_string;
-                                                    Ok(txt.len())
-                                                } else {
-                                                    // Не JSON и не protobuf - неизвестный формат
-                                                    warn!("MEXC: Неизвестный формат данных (длина: {})", binary.len());
-                                                    warn!("Первые 20 байт: {:?}", &binary[..std::cmp::min(20, binary.len())]);
-                                                    
-                                                    Err(std::io::Error::new(
-                                                        std::io::ErrorKind::InvalidData,
-                                                        "Unknown data format - not JSON, not protobuf, not compressed",
-                                                    ))
-                                                }
-                                            }
-                                            Err(utf8_error) => {
-                                                // Не UTF-8, последняя попытка - raw deflate
-                                                debug!("Trying raw DEFLATE decompression for MEXC");
-                                                txt.clear();
-
-                                                use flate2::read::DeflateDecoder;
-                                                use std::io::Cursor;
-
-                                                let cursor = Cursor::new(&binary);
-                                                let mut raw_deflate_decoder = DeflateDecoder::new(cursor);
-                                                match raw_deflate_decoder.read_to_string(&mut txt) {
-                                                    Ok(_) => {
-                                                        if !txt.is_empty()
-                                                            && (txt.trim().starts_with('{')
-                                                                || txt.trim().starts_with('['))
-                                                        {
-                                                            debug!("Successfully decompressed with raw DEFLATE");
-                                                            Ok(txt.len())
-                                                        } else {
-                                                            Err(std::io::Error::new(
-                                                                std::io::ErrorKind::InvalidData,
-                                                                "Raw DEFLATE produced non-JSON content",
-                                                            ))
-                                                        }
-                                                    }
-                                                    Err(_) => {
-                                                        // Все методы не сработали - возможно это протобуф, который мы не распознали
-                                                        warn!("MEXC: Все методы декомпрессии не сработали");
-                                                        warn!("Возможно это протобуф данные или неподдерживаемый формат");
-                                                        warn!("Данные: длина={}, UTF-8 ошибка: {}", binary.len(), utf8_error);
-                                                        
-                                                        Err(std::io::Error::new(
-                                                            std::io::ErrorKind::InvalidData,
-                                                            format!("All decompression methods failed: {}", utf8_error),
-                                                        ))
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    error!("MEXC received empty binary data");
-                                    Err(std::io::Error::new(
-                                        std::io::ErrorKind::InvalidData,
-                                        "Empty binary data from MEXC",
-                                    ))
-                                }
-                            }
-                            _ => {
-                                panic!("Unknown binary format from {}", self.url);
-                            }
-                        };
-
-                        match resp {
-                            Ok(_) => Some(txt),
-                            Err(err) => {
-                                error!("Decompression failed, {}", err);
-                                None
-                            }
+                    Message::Binary(binary) => match decode_error
+                        .map_or_else(|| decode_frame(self.exchange, &binary), Err)
+                    {
+                        Ok(txt) if self.raw_frames.load(Ordering::Acquire) => {
+                            Some(attach_raw_frame(txt, &binary))
                         }
-                    }
+                        Ok(txt) => Some(txt),
+                        Err(err) if err.kind() == std::io::ErrorKind::Unsupported => {
+                            panic!("Unknown binary format from {}", self.url);
+                        }
+                        Err(err) => {
+                            error!("Decompression failed, {}", err);
+                            self.undecodable_passthrough
+                                .load(Ordering::Acquire)
+                                .then(|| undecodable_frame(self.exchange, &binary, &err))
+                        }
+                    },
                     Message::Ping(resp) => {
+                        self.record_heartbeat();
                         // binance server will send a ping frame every 3 or 5 minutes
                         debug!(
                             "Received a ping frame: {} from {}",
                             std::str::from_utf8(&resp).unwrap_or("non-utf8"),
                             self.url,
                         );
-                        if self.exchange == "binance" {
+                        if pong_on_ping {
                             // send a pong frame
                             debug!("Sending a pong frame to {}", self.url);
-                            // Для Binance обязательно отправляем пустой Pong фрейм
+                            // Обязательно отправляем пустой Pong фрейм
                             // и сбрасываем счетчик неотвеченных пингов
                             if let Err(err) = self.command_tx.send(Message::Pong(Vec::new())).await
                             {
-                                error!("Failed to send pong response to Binance: {}", err);
+                                error!(
+                                    "Failed to send pong response to {}: {}",
+                                    self.exchange, err
+                                );
                                 // Если не можем отправить pong, соединение возможно мертво
-                                warn!("Could not send pong to Binance, connection might be dead");
+                                warn!(
+                                    "Could not send pong to {}, connection might be dead",
+                                    self.exchange
+                                );
                                 break; // Выходим из цикла, чтобы вызвать переподключение
                             } else {
                                 // Явно обнуляем счетчик при успешной отправке Pong
                                 num_unanswered_ping.store(0, Ordering::Release);
                                 debug!(
-                                    "Successfully sent pong response to Binance ping, reset unanswered count to 0"
+                                    "Successfully sent pong response to {} ping, reset unanswered count to 0",
+                                    self.exchange
                                 );
                             }
                         }
                         None
                     }
                     Message::Pong(resp) => {
+                        self.record_heartbeat();
+                        if let Some(frame) = self.heartbeat_frame() {
+                            deliver(received_at_ns, frame);
+                        }
                         num_unanswered_ping.store(0, Ordering::Release);
                         debug!(
                             "Received a pong frame: {} from {}, reset num_unanswered_ping to {}",
@@ -846,7 +1037,11 @@ impl<H: MessageHandler> WSClientInternal<H> {
                         );
                         None
                     }
-                    Message::Frame(_) => todo!(),
+                    Message::Frame(frame) => {
+                        // Сырые кадры не являются данными приложения, обработчики их не ожидают
+                        debug!("Ignoring a raw frame {} from {}", frame.header().opcode, self.url);
+                        None
+                    }
                     Message::Close(resp) => {
                         match resp {
                             Some(frame) => {
@@ -879,13 +1074,21 @@ impl<H: MessageHandler> WSClientInternal<H> {
                     match handler.handle_message(&txt) {
                         MiscMessage::Normal => {
                             // the receiver might get dropped earlier than this loop
-                            if tx.send(txt).is_err() {
+                            if !deliver(received_at_ns, txt) {
                                 break 'connection_loop; // break the loop if there is no receiver
                             }
                         }
-                        MiscMessage::Mutated(txt) => _ = tx.send(txt),
-                        MiscMessage::WebSocket(ws_msg) => _ = self.command_tx.send(ws_msg).await,
+                        MiscMessage::Mutated(txt) => _ = deliver(received_at_ns, txt),
+                        MiscMessage::WebSocket(ws_msg) => {
+                            // Ответ на heartbeat сервера (например, "Ping" у BingX)
+                            self.record_heartbeat();
+                            _ = self.command_tx.send(ws_msg).await
+                        }
                         MiscMessage::Pong => {
+                            self.record_heartbeat();
+                            if let Some(frame) = self.heartbeat_frame() {
+                                deliver(received_at_ns, frame);
+                            }
                             num_unanswered_ping.store(0, Ordering::Release);
                             debug!(
                                 "Received {} from {}, reset num_unanswered_ping to {}",
@@ -903,6 +1106,15 @@ impl<H: MessageHandler> WSClientInternal<H> {
                 }
             }
 
+            if !self.auto_reconnect.load(Ordering::Acquire) {
+                info!("Connection to {} closed, auto-reconnect is disabled, exiting...", self.url);
+                if let Some(handle) = self.ping_task_handle.lock().unwrap().take() {
+                    handle.abort();
+                }
+                self.set_connection_state(ConnectionState::Disconnected);
+                break 'connection_loop;
+            }
+
             // Проверяем, была ли закрыта очередь сообщений
             if !self.reconnect_in_progress.load(Ordering::SeqCst) {
                 info!("Message queue closed, attempting to reconnect...");
@@ -913,10 +1125,10 @@ impl<H: MessageHandler> WSClientInternal<H> {
 
                 // Пытаемся переподключиться
                 if let Some(new_message_rx) =
-                    self.reconnect(handler_clone_for_reconnect, tx.clone()).await
+                    self.reconnect(handler_clone_for_reconnect, output.clone()).await
                 {
                     // Если переподключение успешно, обновляем message_rx и продолжаем цикл
-                    message_rx = new_message_rx;
+                    message_rx = self.with_parse_pool(new_message_rx);
 
                     if is_binance {
                         info!("Successfully reconnected to Binance, continuing operations");
@@ -942,6 +1154,20 @@ impl<H: MessageHandler> WSClientInternal<H> {
     }
 
     pub async fn close(&self) {
+        self.close_frame(None).await;
+    }
+
+    /// Закрыть соединение, отправив кадр закрытия с кодом `code` и причиной `reason`
+    /// (например, 1001 Going Away).
+    pub async fn close_with(&self, code: u16, reason: &str) {
+        self.close_frame(Some(CloseFrame {
+            code: CloseCode::from(code),
+            reason: reason.to_string().into(),
+        }))
+        .await;
+    }
+
+    async fn close_frame(&self, frame: Option<CloseFrame<'static>>) {
         log_connection_event(self.exchange, "close_requested", "Closing WebSocket connection");
         self.set_connection_state(ConnectionState::Disconnected);
         
@@ -949,8 +1175,445 @@ impl<H: MessageHandler> WSClientInternal<H> {
         self.stop_ping_task_safely();
 
         // close the websocket connection and break the while loop in run()
-        _ = self.command_tx.send(Message::Close(None)).await;
+        _ = self.command_tx.send(Message::Close(frame)).await;
         
         log_connection_event(self.exchange, "close_completed", "WebSocket connection closed");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopHandler;
+
+    impl MessageHandler for NoopHandler {
+        fn handle_message(&mut self, _msg: &str) -> MiscMessage {
+            MiscMessage::Other
+        }
+
+        fn get_ping_msg_and_interval(&self) -> Option<(Message, u64)> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handshake_latency_metrics() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        // Сервер отвечает на handshake с задержкой, имитируя медленный регион или прокси
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = WSClientInternal::connect("test", &url, NoopHandler, None, tx).await;
+        let health = client.get_health_status();
+
+        assert!(health.last_handshake_ms >= 200, "handshake {} мс", health.last_handshake_ms);
+        assert!(health.last_handshake_ms < 2000, "handshake {} мс", health.last_handshake_ms);
+        assert_eq!(health.avg_handshake_ms, health.last_handshake_ms as f64);
+    }
+
+    struct EchoHandler;
+
+    impl MessageHandler for EchoHandler {
+        fn handle_message(&mut self, _msg: &str) -> MiscMessage {
+            MiscMessage::Normal
+        }
+
+        fn get_ping_msg_and_interval(&self) -> Option<(Message, u64)> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_raw_frame_does_not_stop_run_loop() {
+        use tokio_tungstenite::tungstenite::protocol::frame::Frame;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, EchoHandler, None, tx).await);
+
+        // Подменяем входящий поток, чтобы подать в цикл сырой кадр
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        message_tx.send(Message::Frame(Frame::ping(b"raw".to_vec()))).await.unwrap();
+        message_tx.send(Message::Text("after frame".to_string())).await.unwrap();
+
+        let received = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(received.unwrap(), "after frame");
+        assert!(!run.is_finished());
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_pong_emits_heartbeat_frame() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, EchoHandler, None, tx).await);
+        client.set_heartbeat_messages(true);
+
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        let ping_sent_ms = chrono::Utc::now().timestamp_millis() as u64 - 40;
+        client.last_ping_sent_ms.store(ping_sent_ms, Ordering::Release);
+        message_tx.send(Message::Pong(Vec::new())).await.unwrap();
+
+        let received = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap()
+            .unwrap();
+        let frame: serde_json::Value = serde_json::from_str(&received).unwrap();
+        assert_eq!(frame["heartbeat"]["exchange"], "test");
+        assert!(frame["heartbeat"]["latency_ms"].as_u64().unwrap() >= 40, "{}", received);
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_frame_sink_receives_receive_time() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (sink_tx, sink_rx) = std::sync::mpsc::channel();
+        let sink: FrameSink = Arc::new(move |received_at_ns, txt| {
+            sink_tx.send((received_at_ns, txt, monotonic_nanos())).is_ok()
+        });
+        let client = Arc::new(
+            WSClientInternal::connect("test", &url, EchoHandler, None, sink.clone()).await,
+        );
+
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Sink(sink))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        let sent_at_ns = monotonic_nanos();
+        message_tx.send(Message::Text("first".to_string())).await.unwrap();
+
+        let (received_at_ns, txt, delivered_at_ns) =
+            tokio::task::spawn_blocking(move || sink_rx.recv_timeout(Duration::from_secs(5)))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(txt, "first");
+        // Метка поставлена в цикле чтения, до передачи кадра потребителю
+        assert!(sent_at_ns < received_at_ns && received_at_ns < delivered_at_ns);
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_frame_carries_raw_bytes() {
+        use crate::clients::mexc::protobuf::mexc_proto::{
+            Deal, PublicDealsBatch, PushDataV3ApiWrapper, push_data_v3_api_wrapper::Body,
+        };
+        use prost::Message as _;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("mexc", &url, EchoHandler, None, tx).await);
+        client.set_raw_frames(true);
+
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        let wrapper = PushDataV3ApiWrapper {
+            channel: "spot@public.aggre.deals.v3.api.pb@100ms@BTCUSDT".to_string(),
+            body: Some(Body::PublicDealsBatch(PublicDealsBatch {
+                deals: vec![Deal {
+                    symbol: "BTCUSDT".to_string(),
+                    price: "50000.00".to_string(),
+                    quantity: "0.1".to_string(),
+                    time: 1736417034100,
+                    taker_order_side: 1,
+                }],
+            })),
+            symbol: Some("BTCUSDT".to_string()),
+            symbol_id: None,
+            create_time: None,
+            send_time: Some(1736417034332),
+        };
+        let binary = wrapper.encode_to_vec();
+        message_tx.send(Message::Binary(binary.clone())).await.unwrap();
+
+        let received = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap()
+            .unwrap();
+        let mut frame: serde_json::Value = serde_json::from_str(&received).unwrap();
+        assert_eq!(crate::take_raw_frame(&mut frame), Some(binary));
+        assert!(frame.get("raw_frame").is_none());
+        assert_eq!(frame["d"]["deals"][0]["p"], "50000.00");
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_protobuf_frame_passthrough() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("mexc", &url, EchoHandler, None, tx).await);
+
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        // Похоже на protobuf (поле 1, length-delimited), но длина больше данных
+        let binary = vec![0x0a, 0x20, 0xff, 0xfe, 0xfd];
+
+        // По умолчанию кадр отбрасывается: следующим приходит текстовый кадр
+        message_tx.send(Message::Binary(binary.clone())).await.unwrap();
+        message_tx.send(Message::Text(r#"{"marker":1}"#.to_string())).await.unwrap();
+        let rx = tokio::task::spawn_blocking(move || {
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), r#"{"marker":1}"#);
+            rx
+        })
+        .await
+        .unwrap();
+
+        client.set_undecodable_passthrough(true);
+        message_tx.send(Message::Binary(binary.clone())).await.unwrap();
+        let received = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap()
+            .unwrap();
+        let mut frame: serde_json::Value = serde_json::from_str(&received).unwrap();
+        assert_eq!(frame["undecodable_frame"]["exchange"], "mexc");
+        assert!(frame["undecodable_frame"]["error"].as_str().unwrap().contains("Protocol Buffers"));
+        assert_eq!(crate::take_raw_frame(&mut frame["undecodable_frame"]), Some(binary));
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_message_counters_and_throughput() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, EchoHandler, None, tx).await);
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(64);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        // Первая выборка задаёт начало окна
+        assert_eq!(client.get_health_status().messages_per_sec, 0.0);
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        const N: usize = 20;
+        let mut expected_bytes = 0;
+        for i in 0..N {
+            let txt = format!("{{\"seq\":{}}}", i);
+            expected_bytes += txt.len();
+            message_tx.send(Message::Text(txt)).await.unwrap();
+        }
+
+        let received = tokio::task::spawn_blocking(move || {
+            (0..N).map(|_| rx.recv_timeout(Duration::from_secs(5)).unwrap()).count()
+        })
+        .await
+        .unwrap();
+        assert_eq!(received, N);
+
+        let health = client.get_health_status();
+        assert_eq!(health.messages_received, N as u64);
+        assert_eq!(health.bytes_received, expected_bytes as u64);
+        assert!(health.messages_per_sec > 0.0);
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_is_dropped() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, EchoHandler, None, tx).await);
+        client.set_max_frame_size(16);
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let (params_tx, params_rx) = tokio::sync::oneshot::channel();
+        let (tx, rx) = std::sync::mpsc::channel();
+        params_tx.send((EchoHandler, message_rx, FrameOutput::Channel(tx))).ok().unwrap();
+        *client.params_rx.lock().unwrap() = params_rx;
+
+        let runner = client.clone();
+        let run = tokio::spawn(async move { runner.run().await });
+        message_tx.send(Message::Text("x".repeat(17))).await.unwrap();
+        // Бинарный кадр отбрасывается до распаковки, иначе неизвестный формат вызвал бы панику
+        message_tx.send(Message::Binary(vec![0; 1024])).await.unwrap();
+        message_tx.send(Message::Text("small".to_string())).await.unwrap();
+
+        let received = tokio::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(received.unwrap(), "small");
+        assert_eq!(client.get_health_status().messages_received, 1);
+        assert!(!run.is_finished());
+        run.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_hook_runs_before_subscription_restore() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (received_tx, received_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            // Первое соединение закрывается сервером после подписки
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            ws.read_message().unwrap();
+            ws.close(None).unwrap();
+            while ws.read_message().is_ok() {}
+
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while let Ok(msg) = ws.read_message() {
+                if let Message::Text(txt) = msg {
+                    received_tx.send(txt).unwrap();
+                }
+            }
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, NoopHandler, None, tx).await);
+        let subscribe = r#"{"op":"subscribe","args":["trades"]}"#.to_string();
+        client.send(std::slice::from_ref(&subscribe)).await;
+        client.set_on_reconnect(Arc::new(|| vec![r#"{"op":"login"}"#.to_string()]));
+
+        let runner = client.clone();
+        tokio::spawn(async move { runner.run().await });
+
+        let received = tokio::task::spawn_blocking(move || {
+            (0..2)
+                .map(|_| received_rx.recv_timeout(Duration::from_secs(10)).unwrap())
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap();
+        assert_eq!(received, vec![r#"{"op":"login"}"#.to_string(), subscribe]);
+        client.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_close_with_sends_close_code_and_reason() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let (frame_tx, frame_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            while let Ok(msg) = ws.read_message() {
+                if let Message::Close(frame) = msg {
+                    let frame =
+                        frame.map(|frame| (u16::from(frame.code), frame.reason.into_owned()));
+                    frame_tx.send(frame).unwrap();
+                    break;
+                }
+            }
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = WSClientInternal::connect("test", &url, NoopHandler, None, tx).await;
+        client.close_with(1001, "going away").await;
+
+        let received =
+            tokio::task::spawn_blocking(move || frame_rx.recv_timeout(Duration::from_secs(5)))
+                .await
+                .unwrap();
+        assert_eq!(received.unwrap(), Some((1001, "going away".to_string())));
+        assert_eq!(client.get_health_status().state, ConnectionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_run_exits_on_close_without_auto_reconnect() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut ws = tokio_tungstenite::tungstenite::accept(stream).unwrap();
+            ws.close(None).unwrap();
+            while ws.read_message().is_ok() {}
+        });
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let client = Arc::new(WSClientInternal::connect("test", &url, NoopHandler, None, tx).await);
+        client.set_auto_reconnect(false);
+
+        let runner = client.clone();
+        // С переподключением run() ждал бы не меньше 2.5 секунд до первой попытки
+        tokio::time::timeout(Duration::from_secs(2), runner.run()).await.unwrap();
+        assert_eq!(client.get_health_status().state, ConnectionState::Disconnected);
+    }
+}
@@ -62,7 +62,16 @@
 mod clients;
 mod common;
 
-pub use common::ws_client::WSClient;
+pub use common::{
+    frame_decoder::{decode_frame, take_raw_frame},
+    tls::TlsConfig,
+    verbosity::{Verbosity, per_message_logs, set_verbosity, verbosity},
+    ws_client::WSClient,
+    ws_client_internal::{
+        ConnectionState, DEFAULT_MAX_FRAME_SIZE, FrameOutput, FrameSink, HealthStatus, ReconnectHook,
+        monotonic_nanos,
+    },
+};
 
 pub use clients::{
     binance::*, binance_option::*, bingx::*, bitfinex::*, bitget::*, bithumb::*, bitmex::*,